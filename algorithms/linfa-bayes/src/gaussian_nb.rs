@@ -1,10 +1,13 @@
 use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
 use ndarray_stats::QuantileExt;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::error::{BayesError, Result};
 use linfa::dataset::{AsTargets, DatasetBase, Labels};
-use linfa::traits::{Fit, IncrementalFit, PredictRef};
+use linfa::metrics::{Accuracy, DefaultMetric};
+use linfa::traits::{Fit, IncrementalFit, PredictRef, ScoreSamples};
 use linfa::Float;
 
 /// Gaussian Naive Bayes (GaussianNB)
@@ -282,11 +285,21 @@ impl GaussianNbParams {
 }
 
 /// Fitted GaussianNB for predicting classes
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 #[derive(Debug, Clone)]
 pub struct GaussianNb<A> {
     class_info: HashMap<usize, ClassInfo<A>>,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 #[derive(Debug, Default, Clone)]
 struct ClassInfo<A> {
     class_count: usize,
@@ -329,6 +342,44 @@ where
     }
 }
 
+impl<F: Float> DefaultMetric for GaussianNb<F> {
+    type Metric = Accuracy;
+}
+
+impl<F: Float, D> ScoreSamples<ArrayBase<D, Ix2>, F> for GaussianNb<F>
+where
+    D: Data<Elem = F>,
+{
+    /// Log-likelihood `ln p(x)` of every sample, marginalized over the classes
+    fn score_samples(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let joint_log_likelihood = self.joint_log_likelihood(x.view());
+
+        let mut likelihood = Array2::zeros((joint_log_likelihood.len(), x.nrows()));
+        for (mut row, value) in likelihood
+            .genrows_mut()
+            .into_iter()
+            .zip(joint_log_likelihood.values())
+        {
+            row.assign(value);
+        }
+
+        // Sum the likelihoods of the classes, shifted by the maximum for numerical stability
+        likelihood.map_axis(Axis(0), |x| {
+            let max = x.fold(F::neg_infinity(), |a, b| a.max(*b));
+            max + x.mapv(|x| (x - max).exp()).sum().ln()
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<A: Float> linfa::persistence::SaveModel for GaussianNb<A> where Self: Serialize {}
+
+#[cfg(feature = "serde")]
+impl<A: Float> linfa::persistence::LoadModel for GaussianNb<A> where
+    Self: serde_crate::de::DeserializeOwned
+{
+}
+
 impl<A: Float> GaussianNb<A> {
     // Compute unnormalized posterior log probability
     fn joint_log_likelihood(&self, x: ArrayView2<A>) -> HashMap<&usize, Array1<A>> {
@@ -469,4 +520,72 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_score_samples() -> Result<()> {
+        let x = array![
+            [-2., -1.],
+            [-1., -1.],
+            [-1., -2.],
+            [1., 1.],
+            [1., 2.],
+            [2., 1.]
+        ];
+        let y = array![1, 1, 1, 2, 2, 2];
+
+        let data = DatasetView::new(x.view(), y.view());
+        let model = GaussianNbParams::params().fit(&data)?;
+
+        // the likelihood of a sample is the sum of the joint likelihoods of all classes
+        let jll = model.joint_log_likelihood(x.view());
+        let expected = jll[&&1usize].mapv(f64::exp) + jll[&&2usize].mapv(f64::exp);
+        let scores = model.score_samples(&x);
+        assert_abs_diff_eq!(scores, expected.mapv(f64::ln), epsilon = 1e-10);
+        assert_abs_diff_eq!(
+            model.mean_log_likelihood(&x),
+            scores.mean().unwrap(),
+            epsilon = 1e-10
+        );
+
+        // samples far away from the training data are less likely than all training samples
+        let outliers = array![[10., -10.], [0., 5.]];
+        let min_score = scores.fold(f64::INFINITY, |a, b| a.min(*b));
+        assert!(model
+            .score_samples(&outliers)
+            .iter()
+            .all(|x| *x < min_score));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_save_load_model() -> Result<()> {
+        use linfa::persistence::{LoadModel, SaveModel};
+
+        let x = array![
+            [-2., -1.],
+            [-1., -1.],
+            [-1., -2.],
+            [1., 1.],
+            [1., 2.],
+            [2., 1.]
+        ];
+        let y = array![1, 1, 1, 2, 2, 2];
+
+        let data = DatasetView::new(x.view(), y.view());
+        let model = GaussianNbParams::params().fit(&data)?;
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        model.save_model(file.path()).unwrap();
+        let loaded: GaussianNb<f64> = GaussianNb::load_model(file.path()).unwrap();
+
+        assert_eq!(
+            model.joint_log_likelihood(x.view()),
+            loaded.joint_log_likelihood(x.view())
+        );
+        assert_eq!(model.predict(&x), loaded.predict(&x));
+
+        Ok(())
+    }
 }
@@ -45,9 +45,59 @@ fn gaussian_mixture_bench(c: &mut Criterion) {
     benchmark.finish();
 }
 
+// Compare with `--features rayon` to measure the speedup of fitting the runs in parallel
+fn gaussian_mixture_n_runs_bench(c: &mut Criterion) {
+    let mut rng = Isaac64Rng::seed_from_u64(40);
+    let n_clusters = 4;
+    let n_features = 3;
+    let centroids =
+        Array2::random_using((n_clusters, n_features), Uniform::new(-30., 30.), &mut rng);
+    let dataset: DatasetBase<_, _> = (generate_blobs(1000, &centroids, &mut rng)).into();
+
+    let mut benchmark = c.benchmark_group("gaussian_mixture_n_runs");
+    for &n_runs in &[1, 10] {
+        benchmark.bench_with_input(
+            BenchmarkId::new("gaussian_mixture_n_runs", n_runs),
+            &n_runs,
+            |bencher, &n_runs| {
+                bencher.iter(|| {
+                    black_box(
+                        GaussianMixtureModel::params(n_clusters)
+                            .with_rng(rng.clone())
+                            .with_n_runs(n_runs)
+                            .with_tolerance(1e-3)
+                            .with_max_n_iterations(1000)
+                            .fit(&dataset)
+                            .expect("GMM fitting fail"),
+                    )
+                });
+            },
+        );
+        benchmark.bench_with_input(
+            BenchmarkId::new("gaussian_mixture_n_runs_early_abandon", n_runs),
+            &n_runs,
+            |bencher, &n_runs| {
+                bencher.iter(|| {
+                    black_box(
+                        GaussianMixtureModel::params(n_clusters)
+                            .with_rng(rng.clone())
+                            .with_n_runs(n_runs)
+                            .with_tolerance(1e-3)
+                            .with_max_n_iterations(1000)
+                            .with_early_abandon(3, 1.)
+                            .fit(&dataset)
+                            .expect("GMM fitting fail"),
+                    )
+                });
+            },
+        );
+    }
+    benchmark.finish();
+}
+
 criterion_group! {
   name = benches;
   config = Criterion::default();
-  targets = gaussian_mixture_bench
+  targets = gaussian_mixture_bench, gaussian_mixture_n_runs_bench
 }
 criterion_main!(benches);
@@ -0,0 +1,155 @@
+use criterion::{
+    black_box, criterion_group, criterion_main, AxisScale, BenchmarkId, Criterion,
+    PlotConfiguration,
+};
+use linfa::traits::Fit;
+use linfa::DatasetBase;
+use linfa_clustering::{
+    generate_blobs, GaussianMixtureModel, GmmWorkspace, KMeans, KMeansInit, KMeansWorkspace,
+};
+use ndarray::Array2;
+use ndarray_rand::RandomExt;
+use ndarray_rand::{rand::SeedableRng, rand_distr::Uniform};
+use rand_isaac::Isaac64Rng;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Global allocator which counts the number of allocations
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Number of fits performed in every benchmark iteration
+const N_FITS: usize = 20;
+
+/// Prints the average number of allocations of a single fit
+fn report_allocations(name: &str, mut fits: impl FnMut()) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..N_FITS {
+        fits();
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    println!(
+        "{}: {} allocations per fit",
+        name,
+        (after - before) / N_FITS
+    );
+}
+
+fn k_means_workspace_bench(c: &mut Criterion) {
+    let mut rng = Isaac64Rng::seed_from_u64(40);
+    let cluster_sizes = [(100, 4), (400, 10)];
+    let n_features = 3;
+
+    let mut benchmark = c.benchmark_group("k_means_workspace");
+    benchmark.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for &(cluster_size, n_clusters) in &cluster_sizes {
+        let rng = &mut rng;
+        let centroids =
+            Array2::random_using((n_clusters, n_features), Uniform::new(-30., 30.), rng);
+        let dataset = DatasetBase::from(generate_blobs(cluster_size, &centroids, rng));
+        let params = KMeans::params_with_rng(n_clusters, rng.clone())
+            .init_method(KMeansInit::KMeansPlusPlus)
+            .n_runs(1)
+            .tolerance(1e-3)
+            .build()
+            .unwrap();
+        let mut workspace = KMeansWorkspace::new();
+        let size = format!("{}x{}", n_clusters, cluster_size);
+
+        report_allocations(&format!("k_means fit {}", size), || {
+            params.fit(&dataset).unwrap();
+        });
+        report_allocations(&format!("k_means fit_with_workspace {}", size), || {
+            params.fit_with_workspace(&dataset, &mut workspace).unwrap();
+        });
+
+        benchmark.bench_function(BenchmarkId::new("fit", &size), |bencher| {
+            bencher.iter(|| {
+                for _ in 0..N_FITS {
+                    black_box(params.fit(&dataset).unwrap());
+                }
+            });
+        });
+        benchmark.bench_function(BenchmarkId::new("fit_with_workspace", &size), |bencher| {
+            bencher.iter(|| {
+                for _ in 0..N_FITS {
+                    black_box(params.fit_with_workspace(&dataset, &mut workspace).unwrap());
+                }
+            });
+        });
+    }
+
+    benchmark.finish();
+}
+
+fn gaussian_mixture_workspace_bench(c: &mut Criterion) {
+    let mut rng = Isaac64Rng::seed_from_u64(40);
+    let cluster_sizes = [(100, 4), (400, 10)];
+    let n_features = 3;
+
+    let mut benchmark = c.benchmark_group("gaussian_mixture_workspace");
+    benchmark.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for &(cluster_size, n_clusters) in &cluster_sizes {
+        let rng = &mut rng;
+        let centroids =
+            Array2::random_using((n_clusters, n_features), Uniform::new(-30., 30.), rng);
+        let dataset = DatasetBase::from(generate_blobs(cluster_size, &centroids, rng));
+        let params = GaussianMixtureModel::params_with_rng(n_clusters, rng.clone())
+            .with_n_runs(1)
+            .with_tolerance(1e-4)
+            .with_max_n_iterations(1000);
+        let mut workspace = GmmWorkspace::new();
+        let size = format!("{}x{}", n_clusters, cluster_size);
+
+        report_allocations(&format!("gmm fit {}", size), || {
+            params.fit(&dataset).unwrap();
+        });
+        report_allocations(&format!("gmm fit_with_workspace {}", size), || {
+            params.fit_with_workspace(&dataset, &mut workspace).unwrap();
+        });
+
+        benchmark.bench_function(BenchmarkId::new("fit", &size), |bencher| {
+            bencher.iter(|| {
+                for _ in 0..N_FITS {
+                    black_box(params.fit(&dataset).unwrap());
+                }
+            });
+        });
+        benchmark.bench_function(BenchmarkId::new("fit_with_workspace", &size), |bencher| {
+            bencher.iter(|| {
+                for _ in 0..N_FITS {
+                    black_box(params.fit_with_workspace(&dataset, &mut workspace).unwrap());
+                }
+            });
+        });
+    }
+
+    benchmark.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = k_means_workspace_bench, gaussian_mixture_workspace_bench
+}
+criterion_main!(benches);
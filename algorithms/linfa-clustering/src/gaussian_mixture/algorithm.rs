@@ -1,13 +1,18 @@
 use crate::gaussian_mixture::errors::{GmmError, Result};
-use crate::gaussian_mixture::hyperparameters::{GmmCovarType, GmmHyperParams, GmmInitMethod};
+use crate::gaussian_mixture::hyperparameters::{
+    GmmCovarType, GmmHyperParams, GmmInitMethod, Shrinkage,
+};
 use crate::k_means::KMeans;
 use linfa::{
+    covariance::weighted_ledoit_wolf,
     dataset::{WithLapack, WithoutLapack},
     traits::*,
     DatasetBase, Float,
 };
-use ndarray::{s, Array, Array1, Array2, Array3, ArrayBase, Axis, Data, Ix2, Ix3, Zip};
-use ndarray_linalg::{cholesky::*, triangular::*, Lapack, Scalar};
+#[cfg(feature = "rayon")]
+use ndarray::parallel::prelude::*;
+use ndarray::{s, Array, Array1, Array2, Array3, ArrayBase, ArrayView2, Axis, Data, Ix2, Ix3, Zip};
+use ndarray_linalg::{cholesky::*, triangular::*};
 use ndarray_rand::rand::Rng;
 use ndarray_rand::rand::SeedableRng;
 use ndarray_rand::rand_distr::Uniform;
@@ -50,6 +55,9 @@ use serde_crate::{Deserialize, Serialize};
 /// As the initialization of the algorithm is subject to randomness, several initializations are performed (controlled by
 /// the `n_runs` parameter).   
 ///
+/// If the dataset has weights, the responsibilities of every sample are scaled by its weight in
+/// the maximization step, such that doubling the weight of a sample is equivalent to duplicating it.
+///
 /// ## Tutorial
 ///
 /// Let's do a walkthrough of a training-predict-save example.
@@ -124,50 +132,65 @@ impl<F: Float> Clone for GaussianMixtureModel<F> {
 }
 
 impl<F: Float> GaussianMixtureModel<F> {
-    fn new<D: Data<Elem = F>, R: Rng + SeedableRng + Clone, T>(
-        hyperparameters: &GmmHyperParams<F, R>,
-        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+    fn new<R2: Rng, R: Rng + SeedableRng + Clone>(
+        hyperparameters: &GmmHyperParams<F, R2>,
+        observations: &ArrayView2<F>,
+        sample_weights: Option<&Array1<F>>,
         mut rng: R,
     ) -> Result<GaussianMixtureModel<F>> {
-        let observations = dataset.records().view();
         let n_samples = observations.nrows();
 
         // We initialize responsabilities (n_samples, n_clusters) of each clusters
         // that is, given a sample, the probabilities of a cluster being the source.
         // Responsabilities can be initialized either from a KMeans result or randomly.
-        let resp = match hyperparameters.init_method() {
+        let mut resp = match hyperparameters.init_method() {
             GmmInitMethod::KMeans => {
+                let mut dataset = DatasetBase::from(observations.view());
+                if let Some(sample_weights) = sample_weights {
+                    dataset = dataset.with_weights(sample_weights.mapv(|w| w.to_f32().unwrap()));
+                }
                 let model = KMeans::params_with_rng(hyperparameters.n_clusters(), rng)
                     .build()
                     .unwrap()
-                    .fit(dataset)?;
+                    .fit(&dataset)?;
                 let mut resp = Array::<F, Ix2>::zeros((n_samples, hyperparameters.n_clusters()));
-                for (k, idx) in model.predict(dataset.records()).iter().enumerate() {
+                for (k, idx) in model.predict(observations).iter().enumerate() {
                     resp[[k, *idx]] = F::cast(1.);
                 }
                 resp
             }
             GmmInitMethod::Random => {
-                let mut resp = Array2::<f64>::random_using(
+                // Responsabilities are sampled directly in the working float type and
+                // normalized for each sample
+                let mut resp = Array2::<F>::random_using(
                     (n_samples, hyperparameters.n_clusters()),
-                    Uniform::new(0., 1.),
+                    Uniform::new(F::zero(), F::one()),
                     &mut rng,
                 );
-                let totals = &resp.sum_axis(Axis(1)).insert_axis(Axis(0));
-                resp = (resp.reversed_axes() / totals).reversed_axes();
-                resp.mapv(F::cast)
+                let totals = resp.sum_axis(Axis(1)).insert_axis(Axis(1));
+                resp /= &totals;
+                resp
             }
         };
 
+        let total_weight = match sample_weights {
+            Some(sample_weights) => {
+                resp *= &sample_weights.view().insert_axis(Axis(1));
+                sample_weights.sum()
+            }
+            None => F::cast(n_samples),
+        };
+
         // We compute an initial GMM model from dataset and initial responsabilities wrt
         // to covariance specification.
         let (mut weights, means, covariances) = Self::estimate_gaussian_parameters(
-            &observations,
+            observations,
             &resp,
             hyperparameters.covariance_type(),
             hyperparameters.reg_covariance(),
+            hyperparameters.covariance_regularization(),
         )?;
-        weights /= F::cast(n_samples);
+        weights /= total_weight;
 
         // GmmCovarType = full
         let precisions_chol = Self::compute_precisions_cholesky_full(&covariances)?;
@@ -189,6 +212,10 @@ impl<F: Float> GaussianMixtureModel<F> {
         GmmHyperParams::new(n_clusters)
     }
 
+    pub fn params_with_rng<R: Rng + Clone>(n_clusters: usize, rng: R) -> GmmHyperParams<F, R> {
+        GmmHyperParams::new_with_rng(n_clusters, rng)
+    }
+
     pub fn weights(&self) -> &Array1<F> {
         &self.weights
     }
@@ -197,10 +224,21 @@ impl<F: Float> GaussianMixtureModel<F> {
         &self.means
     }
 
+    /// Covariance matrices of the components with shape `(n_clusters, n_features, n_features)`
+    ///
+    /// The covariances include the regularization added to their diagonal and are the inverses of
+    /// the matrices returned by [`precisions`](#method.precisions), i.e. `covariances[k]` is
+    /// `precisions[k]^-1`. They can be used to interpret the shape of the clusters, for example to
+    /// draw their confidence ellipses.
     pub fn covariances(&self) -> &Array3<F> {
         &self.covariances
     }
 
+    /// Precision matrices, the inverses of the [`covariances`](#method.covariances), with shape
+    /// `(n_clusters, n_features, n_features)`
+    ///
+    /// The precisions are computed from the Cholesky factors `L` of the inverse covariances as
+    /// `precisions[k] = L_k L_k^T`.
     pub fn precisions(&self) -> &Array3<F> {
         &self.precisions
     }
@@ -214,6 +252,7 @@ impl<F: Float> GaussianMixtureModel<F> {
         resp: &Array2<F>,
         _covar_type: &GmmCovarType,
         reg_covar: F,
+        shrinkage: Shrinkage,
     ) -> Result<(Array1<F>, Array2<F>, Array3<F>)> {
         let nk = resp.sum_axis(Axis(0));
         if nk.min()? < &(F::cast(10.) * F::epsilon()) {
@@ -226,8 +265,14 @@ impl<F: Float> GaussianMixtureModel<F> {
         let nk2 = nk.to_owned().insert_axis(Axis(1));
         let means = resp.t().dot(observations) / nk2;
         // GmmCovarType = Full
-        let covariances =
-            Self::estimate_gaussian_covariances_full(observations, resp, &nk, &means, reg_covar);
+        let covariances = Self::estimate_gaussian_covariances_full(
+            observations,
+            resp,
+            &nk,
+            &means,
+            reg_covar,
+            shrinkage,
+        );
         Ok((nk, means, covariances))
     }
 
@@ -237,15 +282,25 @@ impl<F: Float> GaussianMixtureModel<F> {
         nk: &Array1<F>,
         means: &Array2<F>,
         reg_covar: F,
+        shrinkage: Shrinkage,
     ) -> Array3<F> {
         let n_clusters = means.nrows();
         let n_features = means.ncols();
         let mut covariances = Array::zeros((n_clusters, n_features, n_features));
         for k in 0..n_clusters {
-            let diff = observations - &means.row(k);
-            let m = &diff.t() * &resp.index_axis(Axis(1), k);
-            let mut cov_k = m.dot(&diff) / nk[k];
-            cov_k.diag_mut().mapv_inplace(|x| x + reg_covar);
+            let cov_k = match shrinkage {
+                Shrinkage::RegCovariance => {
+                    let diff = observations - &means.row(k);
+                    let m = &diff.t() * &resp.index_axis(Axis(1), k);
+                    let mut cov_k = m.dot(&diff) / nk[k];
+                    cov_k.diag_mut().mapv_inplace(|x| x + reg_covar);
+                    cov_k
+                }
+                // the responsibilities are the frequencies of the observations in the cluster
+                Shrinkage::LedoitWolf => {
+                    weighted_ledoit_wolf(observations, &resp.index_axis(Axis(1), k)).0
+                }
+            };
             covariances.slice_mut(s![k, .., ..]).assign(&cov_k);
         }
         covariances
@@ -285,32 +340,56 @@ impl<F: Float> GaussianMixtureModel<F> {
         self.precisions = Self::compute_precisions_full(&self.precisions_chol);
     }
 
+    // The log responsibilities are written to `workspace.log_resp`
     fn e_step<D: Data<Elem = F>>(
         &self,
         observations: &ArrayBase<D, Ix2>,
-    ) -> Result<(F, Array2<F>)> {
-        let (log_prob_norm, log_resp) = self.estimate_log_prob_resp(observations);
-        let log_mean = log_prob_norm.mean().unwrap();
-        Ok((log_mean, log_resp))
+        sample_weights: Option<&Array1<F>>,
+        workspace: &mut GmmWorkspace<F>,
+    ) -> Result<F> {
+        self.estimate_log_prob_resp(observations, workspace);
+        let log_mean = match sample_weights {
+            Some(sample_weights) => {
+                (&workspace.log_prob_norm * sample_weights).sum() / sample_weights.sum()
+            }
+            None => workspace.log_prob_norm.mean().unwrap(),
+        };
+        Ok(log_mean)
     }
 
+    // Uses the log responsibilities of the previous e-step stored in `workspace.log_resp`
     fn m_step<D: Data<Elem = F>>(
         &mut self,
         reg_covar: F,
+        shrinkage: Shrinkage,
         observations: &ArrayBase<D, Ix2>,
-        log_resp: &Array2<F>,
+        sample_weights: Option<&Array1<F>>,
+        workspace: &mut GmmWorkspace<F>,
     ) -> Result<()> {
         let n_samples = observations.nrows();
+        Zip::from(&mut workspace.resp)
+            .and(&workspace.log_resp)
+            .apply(|r, &x| *r = x.exp());
+        // weighted samples contribute to the responsibilities in proportion to their weight
+        let total_weight = match sample_weights {
+            Some(sample_weights) => {
+                workspace.resp *= &sample_weights.view().insert_axis(Axis(1));
+                sample_weights.sum()
+            }
+            None => F::cast(n_samples),
+        };
         let (weights, means, covariances) = Self::estimate_gaussian_parameters(
             observations,
-            &log_resp.mapv(|x| x.exp()),
+            &workspace.resp,
             &self.covar_type,
             reg_covar,
+            shrinkage,
         )?;
         self.means = means;
-        self.weights = weights / F::cast(n_samples);
+        self.weights = weights / total_weight;
         // GmmCovarType = Full()
         self.precisions_chol = Self::compute_precisions_cholesky_full(&covariances)?;
+        self.covariances = covariances;
         Ok(())
     }
 
@@ -326,30 +405,49 @@ impl<F: Float> GaussianMixtureModel<F> {
     // Estimate log probabilities (log P(X)) and responsibilities for each sample.
     // Compute weighted log probabilities per component (log P(X)) and responsibilities
     // for each sample in X with respect to the current state of the model.
+    // The results are written to `workspace.log_prob_norm` and `workspace.log_resp`.
     fn estimate_log_prob_resp<D: Data<Elem = F>>(
         &self,
         observations: &ArrayBase<D, Ix2>,
-    ) -> (Array1<F>, Array2<F>) {
-        let weighted_log_prob = self.estimate_weighted_log_prob(observations);
-        let log_prob_norm = weighted_log_prob
-            .mapv(|x| x.exp())
-            .sum_axis(Axis(1))
-            .mapv(|x| x.ln());
-        let log_resp = weighted_log_prob - log_prob_norm.to_owned().insert_axis(Axis(1));
-        (log_prob_norm, log_resp)
+        workspace: &mut GmmWorkspace<F>,
+    ) {
+        workspace.prepare(observations, self.means.nrows());
+        let GmmWorkspace {
+            centered,
+            log_resp,
+            resp,
+            log_prob_norm,
+        } = workspace;
+
+        self.estimate_weighted_log_prob(observations, centered, log_resp);
+        Zip::from(&mut *resp)
+            .and(&*log_resp)
+            .apply(|r, &x| *r = x.exp());
+        Zip::from(&mut *log_prob_norm)
+            .and(resp.genrows())
+            .apply(|norm, row| *norm = row.sum().ln());
+        *log_resp -= &log_prob_norm.view().insert_axis(Axis(1));
     }
 
     // Estimate weighted log probabilities for each samples wrt to the model
     fn estimate_weighted_log_prob<D: Data<Elem = F>>(
         &self,
         observations: &ArrayBase<D, Ix2>,
-    ) -> Array2<F> {
-        self.estimate_log_prob(observations) + self.estimate_log_weights()
+        centered: &mut Array2<F>,
+        log_prob: &mut Array2<F>,
+    ) {
+        self.estimate_log_prob(observations, centered, log_prob);
+        *log_prob += &self.estimate_log_weights();
     }
 
     // Compute log probabilities for each samples wrt to the model which is gaussian
-    fn estimate_log_prob<D: Data<Elem = F>>(&self, observations: &ArrayBase<D, Ix2>) -> Array2<F> {
-        self.estimate_log_gaussian_prob(observations)
+    fn estimate_log_prob<D: Data<Elem = F>>(
+        &self,
+        observations: &ArrayBase<D, Ix2>,
+        centered: &mut Array2<F>,
+        log_prob: &mut Array2<F>,
+    ) {
+        self.estimate_log_gaussian_prob(observations, centered, log_prob)
     }
 
     // Compute the log likelihood in case of the gaussian probabilities
@@ -357,26 +455,29 @@ impl<F: Float> GaussianMixtureModel<F> {
     fn estimate_log_gaussian_prob<D: Data<Elem = F>>(
         &self,
         observations: &ArrayBase<D, Ix2>,
-    ) -> Array2<F> {
-        let n_samples = observations.nrows();
+        centered: &mut Array2<F>,
+        log_prob: &mut Array2<F>,
+    ) {
         let n_features = observations.ncols();
         let means = self.means();
-        let n_clusters = means.nrows();
         // GmmCovarType = full
         // det(precision_chol) is half of det(precision)
         let log_det = Self::compute_log_det_cholesky_full(&self.precisions_chol, n_features);
-        let mut log_prob: Array2<F> = Array::zeros((n_samples, n_clusters));
         Zip::indexed(means.genrows())
             .and(self.precisions_chol.outer_iter())
             .apply(|k, mu, prec_chol| {
-                let diff = (&observations.to_owned() - &mu).dot(&prec_chol);
+                centered.assign(observations);
+                *centered -= &mu;
+                let mut diff = centered.dot(&prec_chol);
+                diff.mapv_inplace(|v| v * v);
                 log_prob
                     .slice_mut(s![.., k])
-                    .assign(&diff.mapv(|v| v * v).sum_axis(Axis(1)))
+                    .assign(&diff.sum_axis(Axis(1)))
             });
-        log_prob.mapv(|v| {
+        log_prob.mapv_inplace(|v| {
             F::cast(-0.5) * (v + F::cast(n_features as f64 * f64::ln(2. * std::f64::consts::PI)))
-        }) + log_det
+        });
+        *log_prob += &log_det;
     }
 
     fn compute_log_det_cholesky_full<D: Data<Elem = F>>(
@@ -405,44 +506,97 @@ impl<F: Float, R: Rng + SeedableRng + Clone, D: Data<Elem = F>, T>
     type Object = GaussianMixtureModel<F>;
 
     fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        self.fit_with_workspace(dataset, &mut GmmWorkspace::new())
+    }
+}
+
+/// Scratch arrays of the expectation-maximization algorithm
+///
+/// Every EM iteration computes the log responsibilities of all samples and the centered
+/// observations of every cluster. When many models are fitted, for example in a hyperparameter
+/// search, a workspace can be passed to [`GmmHyperParams::fit_with_workspace`] to allocate these
+/// buffers only once. The buffers are resized if the shape of the data changes between fits.
+#[derive(Debug, Clone)]
+pub struct GmmWorkspace<F: Float> {
+    centered: Array2<F>,
+    log_resp: Array2<F>,
+    resp: Array2<F>,
+    log_prob_norm: Array1<F>,
+}
+
+impl<F: Float> Default for GmmWorkspace<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> GmmWorkspace<F> {
+    /// Create an empty workspace, the buffers are allocated by the first fit
+    pub fn new() -> Self {
+        GmmWorkspace {
+            centered: Array2::zeros((0, 0)),
+            log_resp: Array2::zeros((0, 0)),
+            resp: Array2::zeros((0, 0)),
+            log_prob_norm: Array1::zeros(0),
+        }
+    }
+
+    fn prepare<D: Data<Elem = F>>(&mut self, observations: &ArrayBase<D, Ix2>, n_clusters: usize) {
+        // keep the memory layout of the observations, such that the matrix products are
+        // computed exactly as without workspace
+        if self.centered.dim() != observations.dim()
+            || self.centered.strides() != observations.strides()
+        {
+            self.centered = observations.to_owned();
+        }
+        let n_samples = observations.nrows();
+        if self.log_resp.dim() != (n_samples, n_clusters) {
+            self.log_resp = Array2::zeros((n_samples, n_clusters));
+            self.resp = Array2::zeros((n_samples, n_clusters));
+            self.log_prob_norm = Array1::zeros(n_samples);
+        }
+    }
+}
+
+impl<F: Float, R: Rng + SeedableRng + Clone> GmmHyperParams<F, R> {
+    /// Fit the model like `fit`, but reuse the buffers of `workspace`
+    ///
+    /// The result is identical to `fit`, only the allocations of the buffers are saved. With the
+    /// `rayon` feature enabled the runs are fitted in parallel and every thread uses its own
+    /// buffers instead.
+    pub fn fit_with_workspace<D: Data<Elem = F>, T>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+        workspace: &mut GmmWorkspace<F>,
+    ) -> Result<GaussianMixtureModel<F>> {
         self.validate()?;
+        dataset.records().check_shape(self.n_clusters())?;
         let observations = dataset.records().view();
-        let mut gmm = GaussianMixtureModel::<F>::new(self, dataset, self.rng())?;
+        let sample_weights = dataset.weights().map(|_| {
+            Array1::from_shape_fn(observations.nrows(), |i| F::cast(dataset.weight_for(i)))
+        });
+        let n_runs = self.n_runs();
+        let runs = self.fit_runs(&observations, sample_weights.as_ref(), workspace)?;
 
+        // Keep the first run with the highest lower bound, which makes the result identical
+        // for sequential and parallel fitting
         let mut max_lower_bound = -F::infinity();
         let mut best_params = None;
         let mut best_iter = None;
-
-        let n_runs = self.n_runs();
-
-        for _ in 0..n_runs {
-            let mut lower_bound = -F::infinity();
-
-            let mut converged_iter: Option<u64> = None;
-            for n_iter in 0..self.max_n_iterations() {
-                let prev_lower_bound = lower_bound;
-                let (log_prob_norm, log_resp) = gmm.e_step(&observations)?;
-                gmm.m_step(self.reg_covariance(), &observations, &log_resp)?;
-                lower_bound =
-                    GaussianMixtureModel::<F>::compute_lower_bound(&log_resp, log_prob_norm);
-                let change = lower_bound - prev_lower_bound;
-                if change.abs() < self.tolerance() {
-                    converged_iter = Some(n_iter);
-                    break;
-                }
-            }
-
-            if lower_bound > max_lower_bound {
-                max_lower_bound = lower_bound;
-                gmm.refresh_precisions_full();
-                best_params = Some(gmm.clone());
-                best_iter = converged_iter;
+        for run in runs {
+            if run.lower_bound > max_lower_bound {
+                max_lower_bound = run.lower_bound;
+                best_params = Some(run.gmm);
+                best_iter = run.converged_iter;
             }
         }
 
         match best_iter {
             Some(_n_iter) => match best_params {
-                Some(gmm) => Ok(gmm),
+                Some(mut gmm) => {
+                    gmm.refresh_precisions_full();
+                    Ok(gmm)
+                }
                 _ => Err(GmmError::LowerBoundError(
                     "No lower bound improvement (-inf)".to_string(),
                 )),
@@ -456,17 +610,155 @@ impl<F: Float, R: Rng + SeedableRng + Clone, D: Data<Elem = F>, T>
     }
 }
 
-impl<F: Float + Lapack + Scalar, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<usize>>
+// Result of the EM algorithm from a single initialization
+struct GmmRun<F: Float> {
+    lower_bound: F,
+    converged_iter: Option<u64>,
+    n_iterations: u64,
+    gmm: GaussianMixtureModel<F>,
+}
+
+impl<F: Float, R: Rng + SeedableRng + Clone> GmmHyperParams<F, R> {
+    // Fit all runs. The first run is initialized with the RNG of the hyperparameters, such that a
+    // single run gives the same model as before the runs were independent. Every other run is
+    // initialized with its own RNG seeded from the RNG of the hyperparameters, such that the runs
+    // do not depend on the order of their execution.
+    fn fit_runs(
+        &self,
+        observations: &ArrayView2<F>,
+        sample_weights: Option<&Array1<F>>,
+        workspace: &mut GmmWorkspace<F>,
+    ) -> Result<Vec<GmmRun<F>>> {
+        let mut rng = self.rng();
+        let seeds = (1..self.n_runs())
+            .map(|_| rng.gen::<u64>())
+            .collect::<Vec<_>>();
+
+        // The first run is the reference for abandoning the other runs
+        let first = self.fit_run(observations, sample_weights, self.rng(), None, workspace)?;
+
+        #[cfg(feature = "rayon")]
+        {
+            // The RNG of the hyperparameters is not used by the other runs, replacing it allows
+            // to share the hyperparameters between threads for any type of RNG
+            let params = self.clone().with_rng(Isaac64Rng::seed_from_u64(0));
+            // The other runs are fitted in parallel with their own workspaces
+            let threshold = self.abandon_threshold(first.lower_bound);
+            let others = seeds
+                .par_iter()
+                .map_init(GmmWorkspace::new, |workspace, &seed| {
+                    params.fit_run(
+                        observations,
+                        sample_weights,
+                        R::seed_from_u64(seed),
+                        threshold,
+                        workspace,
+                    )
+                })
+                .collect::<Vec<_>>();
+            std::iter::once(Ok(first)).chain(others).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            let mut max_lower_bound = first.lower_bound;
+            let mut runs = Vec::with_capacity(self.n_runs());
+            runs.push(first);
+            for seed in seeds {
+                let run = self.fit_run(
+                    observations,
+                    sample_weights,
+                    R::seed_from_u64(seed),
+                    self.abandon_threshold(max_lower_bound),
+                    workspace,
+                )?;
+                if run.lower_bound > max_lower_bound {
+                    max_lower_bound = run.lower_bound;
+                }
+                runs.push(run);
+            }
+            Ok(runs)
+        }
+    }
+}
+
+impl<F: Float, R2: Rng + Clone> GmmHyperParams<F, R2> {
+    // Lower bound below which runs are abandoned, given the best lower bound so far
+    fn abandon_threshold(&self, max_lower_bound: F) -> Option<F> {
+        self.early_abandon()
+            .map(|(_, margin)| max_lower_bound - margin)
+    }
+
+    // Run the EM algorithm from a single initialization. The run is abandoned if its lower bound
+    // falls below `threshold` after the number of iterations given by `early_abandon`.
+    fn fit_run<R: Rng + SeedableRng + Clone>(
+        &self,
+        observations: &ArrayView2<F>,
+        sample_weights: Option<&Array1<F>>,
+        rng: R,
+        threshold: Option<F>,
+        workspace: &mut GmmWorkspace<F>,
+    ) -> Result<GmmRun<F>> {
+        let mut gmm = GaussianMixtureModel::<F>::new(self, observations, sample_weights, rng)?;
+        let mut lower_bound = -F::infinity();
+
+        let mut converged_iter: Option<u64> = None;
+        let mut n_iterations = 0;
+        for n_iter in 0..self.max_n_iterations() {
+            let prev_lower_bound = lower_bound;
+            let log_prob_norm = gmm.e_step(observations, sample_weights, workspace)?;
+            gmm.m_step(
+                self.reg_covariance(),
+                self.covariance_regularization(),
+                observations,
+                sample_weights,
+                workspace,
+            )?;
+            lower_bound =
+                GaussianMixtureModel::<F>::compute_lower_bound(&workspace.log_resp, log_prob_norm);
+            n_iterations = n_iter + 1;
+            let change = lower_bound - prev_lower_bound;
+            if change.abs() < self.tolerance() {
+                converged_iter = Some(n_iter);
+                break;
+            }
+            if let (Some(threshold), Some((abandon_after, _))) = (threshold, self.early_abandon()) {
+                if n_iterations >= abandon_after && lower_bound < threshold {
+                    break;
+                }
+            }
+        }
+
+        Ok(GmmRun {
+            lower_bound,
+            converged_iter,
+            n_iterations,
+            gmm,
+        })
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<usize>>
     for GaussianMixtureModel<F>
 {
     fn predict_ref(&self, observations: &ArrayBase<D, Ix2>) -> Array1<usize> {
-        let (_, log_resp) = self.estimate_log_prob_resp(observations);
-        log_resp
-            .mapv(Scalar::exp)
+        let mut workspace = GmmWorkspace::new();
+        self.estimate_log_prob_resp(observations, &mut workspace);
+        workspace
+            .log_resp
+            .mapv(|x| x.exp())
             .map_axis(Axis(1), |row| row.argmax().unwrap())
     }
 }
 
+impl<F: Float, D: Data<Elem = F>> ScoreSamples<ArrayBase<D, Ix2>, F> for GaussianMixtureModel<F> {
+    /// Log-likelihood `ln p(x)` of every observation under the mixture
+    fn score_samples(&self, observations: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let mut workspace = GmmWorkspace::new();
+        self.estimate_log_prob_resp(observations, &mut workspace);
+        workspace.log_prob_norm
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -619,6 +911,33 @@ mod tests {
         assert!(GaussianMixtureModel::params(1).fit(&dataset).is_ok());
     }
 
+    #[test]
+    fn test_ledoit_wolf_small_clusters() {
+        // clusters with fewer points than features have singular empirical covariances
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = Array2::random_using((2, 12), Uniform::new(-30., 30.), &mut rng);
+        let dataset = DatasetBase::from(generate_blobs(8, &centroids, &mut rng));
+
+        let gmm = GaussianMixtureModel::params(2)
+            .with_reg_covariance(0.)
+            .with_rng(rng.clone())
+            .fit(&dataset);
+        assert!(matches!(gmm, Err(GmmError::LinalgError(_))));
+
+        // shrunk covariances are well-conditioned without any diagonal term
+        let gmm = GaussianMixtureModel::params(2)
+            .with_reg_covariance(0.)
+            .with_covariance_regularization(Shrinkage::LedoitWolf)
+            .with_rng(rng)
+            .fit(&dataset)
+            .expect("GMM fitting with Ledoit-Wolf shrinkage");
+        assert!(gmm.covariances().iter().all(|x| x.is_finite()));
+
+        let memberships = gmm.predict(dataset.records());
+        assert!(memberships.iter().take(8).all(|&m| m == memberships[0]));
+        assert!(memberships.iter().skip(8).all(|&m| m != memberships[0]));
+    }
+
     #[test]
     fn test_centroids_prediction() {
         let mut rng = Isaac64Rng::seed_from_u64(42);
@@ -644,6 +963,230 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_outliers_have_low_score() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.]];
+        let blobs = DatasetBase::from(generate_blobs(1000, &centroids, &mut rng));
+
+        let gmm = GaussianMixtureModel::params(3)
+            .with_rng(rng)
+            .fit(&blobs)
+            .expect("GMM fitting");
+
+        let scores = gmm.score_samples(blobs.records());
+        assert_eq!(scores.len(), 3000);
+        assert_abs_diff_eq!(
+            gmm.mean_log_likelihood(blobs.records()),
+            scores.mean().unwrap(),
+            epsilon = 1e-10
+        );
+
+        // points between and far away from the clusters are less likely than every sample
+        let outliers = array![[-5., 5.], [20., -20.]];
+        let min_score = scores.fold(f64::INFINITY, |a, b| a.min(*b));
+        assert!(gmm.score_samples(&outliers).iter().all(|x| *x < min_score));
+    }
+
+    #[test]
+    fn test_covariances_are_inverse_precisions() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.]];
+        let dataset = DatasetBase::from(generate_blobs(100, &centroids, &mut rng));
+        let gmm = GaussianMixtureModel::params_with_rng(3, rng)
+            .fit(&dataset)
+            .unwrap();
+
+        assert_eq!(gmm.covariances().dim(), (3, 2, 2));
+        for (covariance, precision) in gmm
+            .covariances()
+            .outer_iter()
+            .zip(gmm.precisions().outer_iter())
+        {
+            assert_abs_diff_eq!(covariance.dot(&precision), Array2::eye(2), epsilon = 1e-8);
+            assert_abs_diff_eq!(covariance, covariance.t(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_n_runs_keep_best_run() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.]];
+        let observations = generate_blobs(100, &centroids, &mut rng);
+        let params = GaussianMixtureModel::params_with_rng(3, rng.clone())
+            .with_init_method(GmmInitMethod::Random)
+            .with_n_runs(4);
+        let gmm = params.fit(&DatasetBase::from(observations.view())).unwrap();
+
+        // the first run uses the RNG of the hyperparameters, every other run is initialized from
+        // its own seed, the first run with the best lower bound wins
+        let mut run_rngs = vec![rng.clone()];
+        run_rngs.extend((1..4).map(|_| Isaac64Rng::seed_from_u64(rng.gen::<u64>())));
+        let mut best: Option<(f64, GaussianMixtureModel<f64>)> = None;
+        for run_rng in run_rngs {
+            let run = params
+                .fit_run(
+                    &observations.view(),
+                    None,
+                    run_rng,
+                    None,
+                    &mut GmmWorkspace::new(),
+                )
+                .unwrap();
+            if best
+                .as_ref()
+                .map_or(true, |(max, _)| run.lower_bound > *max)
+            {
+                best = Some((run.lower_bound, run.gmm));
+            }
+        }
+        let mut expected = best.unwrap().1;
+        expected.refresh_precisions_full();
+        assert_eq!(gmm, expected);
+    }
+
+    #[test]
+    fn test_early_abandon_saves_iterations() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.], [8., 8.]];
+        let observations = generate_blobs(100, &centroids, &mut rng);
+        let params = GaussianMixtureModel::params_with_rng(4, rng).with_n_runs(10);
+        let abandoning = params.clone().with_early_abandon(1, 0.);
+
+        let mut workspace = GmmWorkspace::new();
+        let runs = params
+            .fit_runs(&observations.view(), None, &mut workspace)
+            .unwrap();
+        let abandoned_runs = abandoning
+            .fit_runs(&observations.view(), None, &mut workspace)
+            .unwrap();
+        let max_lower_bound = runs
+            .iter()
+            .map(|run| run.lower_bound)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        // runs are identical up to their abandonment, abandoned runs are never selected
+        for (run, abandoned) in runs.iter().zip(abandoned_runs.iter()) {
+            if abandoned.n_iterations == run.n_iterations {
+                assert_eq!(abandoned.gmm, run.gmm);
+            } else {
+                assert!(abandoned.n_iterations < run.n_iterations);
+                assert!(abandoned.lower_bound < max_lower_bound);
+                assert!(abandoned.converged_iter.is_none());
+            }
+        }
+        let n_iterations =
+            |runs: &[GmmRun<f64>]| runs.iter().map(|run| run.n_iterations).sum::<u64>();
+        assert!(n_iterations(&abandoned_runs) < n_iterations(&runs));
+    }
+
+    #[test]
+    fn test_early_abandon_keeps_best_model() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.], [8., 8.]];
+        let dataset = DatasetBase::from(generate_blobs(100, &centroids, &mut rng));
+        let params = GaussianMixtureModel::params_with_rng(4, rng).with_n_runs(10);
+
+        let expected = params.fit(&dataset).unwrap();
+        let gmm = params.with_early_abandon(3, 1.).fit(&dataset).unwrap();
+        assert_eq!(gmm, expected);
+    }
+
+    #[test]
+    fn test_weights_equal_duplicates() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.]];
+        let observations = generate_blobs(50, &centroids, &mut rng);
+
+        // duplicate every third observation
+        let duplicates = observations.select(Axis(0), &(0..150).step_by(3).collect::<Vec<_>>());
+        let duplicated = DatasetBase::from(
+            concatenate(Axis(0), &[observations.view(), duplicates.view()]).unwrap(),
+        );
+        let weights = (0..150)
+            .map(|i| if i % 3 == 0 { 2. } else { 1. })
+            .collect::<Array1<f32>>();
+        let weighted = DatasetBase::from(observations).with_weights(weights);
+
+        let params = GaussianMixtureModel::params_with_rng(3, rng).with_tolerance(1e-6);
+        let weighted_gmm = params.fit(&weighted).unwrap();
+        let duplicated_gmm = params.fit(&duplicated).unwrap();
+
+        // both models are the same up to a permutation of the clusters
+        for (mean, covariance) in weighted_gmm
+            .means()
+            .outer_iter()
+            .zip(weighted_gmm.covariances().outer_iter())
+        {
+            let k = duplicated_gmm
+                .means()
+                .outer_iter()
+                .position(|m| abs_diff_eq!(m, mean, epsilon = 1e-4))
+                .expect("weighted mean is a mean of the duplicated dataset");
+            assert_abs_diff_eq!(
+                covariance,
+                duplicated_gmm.covariances().index_axis(Axis(0), k),
+                epsilon = 1e-4
+            );
+        }
+        let mut weights = weighted_gmm.weights().to_vec();
+        let mut expected = duplicated_gmm.weights().to_vec();
+        weights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_abs_diff_eq!(
+            Array1::from(weights),
+            Array1::from(expected),
+            epsilon = 1e-4
+        );
+    }
+
+    #[test]
+    fn test_fit_with_workspace() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 1.], [-10., 20.], [-1., 10.]];
+        let small = DatasetBase::from(generate_blobs(50, &centroids, &mut rng));
+        let large = DatasetBase::from(generate_blobs(200, &centroids, &mut rng));
+
+        let mut workspace = GmmWorkspace::new();
+        for init in &[GmmInitMethod::KMeans, GmmInitMethod::Random] {
+            let params =
+                GaussianMixtureModel::params_with_rng(3, rng.clone()).with_init_method(*init);
+            // the workspace is resized when the shape of the data changes
+            for dataset in &[&small, &large, &small] {
+                let expected = params.fit(*dataset).unwrap();
+                let model = params.fit_with_workspace(*dataset, &mut workspace).unwrap();
+                assert_eq!(model, expected);
+                assert_eq!(
+                    model.predict(dataset.records()),
+                    expected.predict(dataset.records())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gmm_fit_f32_random_init() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let expected_centroids = array![[0., 1.], [-10., 20.], [-1., 10.]];
+        let blobs = generate_blobs(200, &expected_centroids, &mut rng).mapv(|x| x as f32);
+        let dataset = DatasetBase::from(blobs);
+
+        let gmm = GaussianMixtureModel::params_with_rng(3, rng)
+            .with_init_method(GmmInitMethod::Random)
+            .with_n_runs(5)
+            .fit(&dataset)
+            .expect("GMM fitting");
+
+        let gmm_centroids = gmm.centroids();
+        let memberships = gmm.predict(&expected_centroids.mapv(|x| x as f32));
+        for (i, expected_c) in expected_centroids.outer_iter().enumerate() {
+            let closest_c = gmm_centroids.index_axis(Axis(0), memberships[i]);
+            Zip::from(&closest_c)
+                .and(&expected_c)
+                .apply(|a, b| assert_abs_diff_eq!(*a as f64, *b, epsilon = 1.))
+        }
+    }
+
     #[test]
     fn test_invalid_n_runs() {
         assert!(
@@ -666,6 +1209,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_early_abandon_margin() {
+        assert!(
+            GaussianMixtureModel::params(1)
+                .with_early_abandon(2, -1.)
+                .fit(&DatasetBase::from(array![[0.]]))
+                .is_err(),
+            "early abandon margin must be positive"
+        );
+    }
+
     #[test]
     fn test_invalid_n_clusters() {
         assert!(
@@ -687,6 +1241,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_degenerate_records() {
+        let empty = DatasetBase::from(Array2::<f64>::zeros((0, 2)));
+        assert!(matches!(
+            GaussianMixtureModel::params(1).fit(&empty),
+            Err(GmmError::LinfaError(linfa::Error::Parameters(_)))
+        ));
+
+        let no_features = DatasetBase::from(Array2::<f64>::zeros((5, 0)));
+        assert!(matches!(
+            GaussianMixtureModel::params(1).fit(&no_features),
+            Err(GmmError::LinfaError(linfa::Error::Parameters(_)))
+        ));
+
+        let too_few = DatasetBase::from(array![[0., 1.], [1., 0.]]);
+        assert!(matches!(
+            GaussianMixtureModel::params(3).fit(&too_few),
+            Err(GmmError::LinfaError(linfa::Error::Parameters(_)))
+        ));
+    }
+
     #[test]
     fn test_invalid_max_n_iterations() {
         assert!(
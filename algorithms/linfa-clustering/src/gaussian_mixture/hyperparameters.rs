@@ -17,6 +17,22 @@ pub enum GmmCovarType {
     Full,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A specifier for the regularization of the components' covariances.
+pub enum Shrinkage {
+    /// the non-negative `reg_covar` value is added to the diagonal of the covariances
+    RegCovariance,
+    /// the covariances are estimated with Ledoit-Wolf shrinkage (see
+    /// [`ledoit_wolf`](linfa::covariance::ledoit_wolf)) instead of adding `reg_covar`,
+    /// which keeps them well-conditioned for clusters with few points in high dimensions
+    LedoitWolf,
+}
+
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -44,9 +60,11 @@ pub struct GmmHyperParams<F: Float, R: Rng> {
     covar_type: GmmCovarType,
     tolerance: F,
     reg_covar: F,
+    covariance_regularization: Shrinkage,
     n_runs: u64,
     max_n_iter: u64,
     init_method: GmmInitMethod,
+    early_abandon: Option<(u64, F)>,
     rng: R,
 }
 
@@ -58,15 +76,17 @@ impl<F: Float> GmmHyperParams<F, Isaac64Rng> {
 }
 
 impl<F: Float, R: Rng + Clone> GmmHyperParams<F, R> {
-    fn new_with_rng(n_clusters: usize, rng: R) -> GmmHyperParams<F, R> {
+    pub(crate) fn new_with_rng(n_clusters: usize, rng: R) -> GmmHyperParams<F, R> {
         GmmHyperParams {
             n_clusters,
             covar_type: GmmCovarType::Full,
             tolerance: F::cast(1e-3),
             reg_covar: F::cast(1e-6),
+            covariance_regularization: Shrinkage::RegCovariance,
             n_runs: 1,
             max_n_iter: 100,
             init_method: GmmInitMethod::KMeans,
+            early_abandon: None,
             rng,
         }
     }
@@ -87,6 +107,10 @@ impl<F: Float, R: Rng + Clone> GmmHyperParams<F, R> {
         self.reg_covar
     }
 
+    pub fn covariance_regularization(&self) -> Shrinkage {
+        self.covariance_regularization
+    }
+
     pub fn n_runs(&self) -> u64 {
         self.n_runs
     }
@@ -99,6 +123,10 @@ impl<F: Float, R: Rng + Clone> GmmHyperParams<F, R> {
         &self.init_method
     }
 
+    pub fn early_abandon(&self) -> Option<(u64, F)> {
+        self.early_abandon
+    }
+
     pub fn rng(&self) -> R {
         self.rng.clone()
     }
@@ -122,7 +150,17 @@ impl<F: Float, R: Rng + Clone> GmmHyperParams<F, R> {
         self
     }
 
+    /// Set the regularization of the covariances, either the fixed `reg_covar` diagonal term
+    /// (default) or Ledoit-Wolf shrinkage.
+    pub fn with_covariance_regularization(mut self, covariance_regularization: Shrinkage) -> Self {
+        self.covariance_regularization = covariance_regularization;
+        self
+    }
+
     /// Set the number of initializations to perform. The best results are kept.
+    ///
+    /// Every run is initialized with its own RNG derived from the RNG of the hyperparameters.
+    /// With the `rayon` feature enabled the runs are fitted in parallel, giving the same result.
     pub fn with_n_runs(mut self, n_runs: u64) -> Self {
         self.n_runs = n_runs;
         self
@@ -140,15 +178,28 @@ impl<F: Float, R: Rng + Clone> GmmHyperParams<F, R> {
         self
     }
 
+    /// Abandon runs which clearly underperform. After `n_iterations` EM iterations a run is
+    /// stopped when its lower bound is below the best lower bound of the previous runs by more
+    /// than `margin`. Abandoned runs are never selected.
+    ///
+    /// With the `rayon` feature enabled the first run is fitted on its own and its lower bound
+    /// is used as the reference for all other runs.
+    pub fn with_early_abandon(mut self, n_iterations: u64, margin: F) -> Self {
+        self.early_abandon = Some((n_iterations, margin));
+        self
+    }
+
     pub fn with_rng<R2: Rng + Clone>(self, rng: R2) -> GmmHyperParams<F, R2> {
         GmmHyperParams {
             n_clusters: self.n_clusters,
             covar_type: self.covar_type,
             tolerance: self.tolerance,
             reg_covar: self.reg_covar,
+            covariance_regularization: self.covariance_regularization,
             n_runs: self.n_runs,
             max_n_iter: self.max_n_iter,
             init_method: self.init_method,
+            early_abandon: self.early_abandon,
             rng,
         }
     }
@@ -178,6 +229,13 @@ impl<F: Float, R: Rng + Clone> GmmHyperParams<F, R> {
                 "`max_n_iterations` cannot be 0!".to_string(),
             ));
         }
+        if let Some((_, margin)) = self.early_abandon {
+            if margin.is_nan() || margin < F::zero() {
+                return Err(GmmError::InvalidValue(
+                    "`early_abandon` margin must be positive!".to_string(),
+                ));
+            }
+        }
         Ok(())
     }
 }
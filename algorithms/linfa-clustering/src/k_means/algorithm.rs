@@ -69,6 +69,13 @@ use serde_crate::{Deserialize, Serialize};
 ///
 /// More details on Mini-Batch K-means can be found [here](https://www.eecs.tufts.edu/~dsculley/papers/fastkmeans.pdf).
 ///
+/// ## Sample weights
+///
+/// If the dataset has weights, every observation contributes to the update of its centroid and
+/// to the inertia in proportion to its weight, such that doubling the weight of an observation
+/// is equivalent to duplicating it. The initialisation step and the incremental algorithm do not
+/// use the weights.
+///
 /// ## Parallelisation
 ///
 /// The work performed by the assignment step does not require any coordination:
@@ -214,7 +221,8 @@ impl<F: Float, D: Distance<F>> KMeans<F, D> {
         &self.centroids
     }
 
-    /// Return the number of training points belonging to each cluster
+    /// Return the number of training points belonging to each cluster, or their total weight if
+    /// the training dataset has weights
     pub fn cluster_count(&self) -> &Array1<F> {
         &self.cluster_count
     }
@@ -227,6 +235,15 @@ impl<F: Float, D: Distance<F>> KMeans<F, D> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<F: Float, D: Distance<F>> linfa::persistence::SaveModel for KMeans<F, D> where Self: Serialize {}
+
+#[cfg(feature = "serde")]
+impl<F: Float, D: Distance<F>> linfa::persistence::LoadModel for KMeans<F, D> where
+    Self: serde_crate::de::DeserializeOwned
+{
+}
+
 impl<F: Float, R: Rng + SeedableRng + Clone, DA: Data<Elem = F>, T, D: Distance<F>>
     Fit<ArrayBase<DA, Ix2>, T, KMeansError> for KMeansHyperParams<F, R, D>
 {
@@ -238,15 +255,89 @@ impl<F: Float, R: Rng + SeedableRng + Clone, DA: Data<Elem = F>, T, D: Distance<
     /// An instance of `KMeans` is returned.
     ///
     fn fit(&self, dataset: &DatasetBase<ArrayBase<DA, Ix2>, T>) -> Result<Self::Object> {
-        let mut rng = self.rng().clone();
+        self.fit_with_workspace(dataset, &mut KMeansWorkspace::new())
+    }
+}
+
+/// Scratch arrays of the K-means algorithm
+///
+/// Fitting allocates buffers for the cluster memberships, the distances and the updated
+/// centroids. When many models are fitted, for example in a hyperparameter search, a workspace
+/// can be passed to [`KMeansHyperParams::fit_with_workspace`] to allocate these buffers only
+/// once. The buffers are resized if the shape of the data changes between fits.
+#[derive(Debug, Clone)]
+pub struct KMeansWorkspace<F: Float> {
+    memberships: Array1<usize>,
+    dists: Array1<F>,
+    new_centroids: Array2<F>,
+    counts: Array1<F>,
+}
+
+impl<F: Float> Default for KMeansWorkspace<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> KMeansWorkspace<F> {
+    /// Create an empty workspace, the buffers are allocated by the first fit
+    pub fn new() -> Self {
+        KMeansWorkspace {
+            memberships: Array1::zeros(0),
+            dists: Array1::zeros(0),
+            new_centroids: Array2::zeros((0, 0)),
+            counts: Array1::zeros(0),
+        }
+    }
+
+    fn prepare(&mut self, n_samples: usize, n_features: usize, n_clusters: usize) {
+        if self.memberships.len() != n_samples {
+            self.memberships = Array1::zeros(n_samples);
+            self.dists = Array1::zeros(n_samples);
+        }
+        if self.new_centroids.dim() != (n_clusters, n_features) {
+            self.new_centroids = Array2::zeros((n_clusters, n_features));
+            self.counts = Array1::zeros(n_clusters);
+        }
+    }
+}
+
+impl<F: Float, R: Rng + SeedableRng + Clone, D: Distance<F>> KMeansHyperParams<F, R, D> {
+    /// Fit the model like `fit`, but reuse the buffers of `workspace`
+    ///
+    /// The result is identical to `fit`, only the allocations of the buffers are saved.
+    pub fn fit_with_workspace<DA: Data<Elem = F>, T>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<DA, Ix2>, T>,
+        workspace: &mut KMeansWorkspace<F>,
+    ) -> Result<KMeans<F, D>> {
+        dataset.records().check_shape(self.n_clusters())?;
         let observations = dataset.records().view();
+        // identical samples cannot be split into distinct clusters
+        if self.n_clusters() > 1 && observations.outer_iter().all(|x| x == observations.row(0)) {
+            return Err(linfa::Error::Parameters(format!(
+                "All samples are identical, cannot find {} distinct clusters",
+                self.n_clusters()
+            ))
+            .into());
+        }
+
+        let mut rng = self.rng().clone();
         let n_samples = dataset.nsamples();
+        let weights = dataset
+            .weights()
+            .map(|_| Array1::from_shape_fn(n_samples, |i| F::cast(dataset.weight_for(i))));
 
         let mut min_inertia = F::infinity();
         let mut best_centroids = None;
         let mut best_iter = None;
-        let mut memberships = Array1::zeros(n_samples);
-        let mut dists = Array1::zeros(n_samples);
+        workspace.prepare(n_samples, observations.ncols(), self.n_clusters());
+        let KMeansWorkspace {
+            memberships,
+            dists,
+            new_centroids,
+            counts,
+        } = workspace;
 
         let n_runs = self.n_runs();
 
@@ -261,15 +352,25 @@ impl<F: Float, R: Rng + SeedableRng + Clone, DA: Data<Elem = F>, T, D: Distance<
                     self.dist_fn(),
                     &centroids,
                     &observations,
-                    &mut memberships,
-                    &mut dists,
+                    memberships,
+                    dists,
+                );
+                compute_centroids_into(
+                    &centroids,
+                    &observations,
+                    memberships,
+                    weights.as_ref(),
+                    counts,
+                    new_centroids,
                 );
-                let new_centroids = compute_centroids(&centroids, &observations, &memberships);
-                inertia = dists.sum();
+                inertia = match &weights {
+                    Some(weights) => (&*dists * weights).sum(),
+                    None => dists.sum(),
+                };
                 let distance = self
                     .dist_fn()
                     .rdistance(centroids.view(), new_centroids.view());
-                centroids = new_centroids;
+                std::mem::swap(&mut centroids, new_centroids);
                 if distance < self.tolerance() {
                     converged_iter = Some(n_iter);
                     break;
@@ -290,13 +391,24 @@ impl<F: Float, R: Rng + SeedableRng + Clone, DA: Data<Elem = F>, T, D: Distance<
             Some(_n_iter) => match best_centroids {
                 Some(centroids) => {
                     let mut cluster_count = Array1::zeros(self.n_clusters());
-                    memberships
-                        .iter()
-                        .for_each(|&c| cluster_count[c] += F::one());
+                    let total_weight = match &weights {
+                        Some(weights) => {
+                            Zip::from(&*memberships)
+                                .and(weights)
+                                .apply(|&c, &w| cluster_count[c] += w);
+                            weights.sum()
+                        }
+                        None => {
+                            memberships
+                                .iter()
+                                .for_each(|&c| cluster_count[c] += F::one());
+                            F::cast(n_samples)
+                        }
+                    };
                     Ok(KMeans {
                         centroids,
                         cluster_count,
-                        inertia: min_inertia / F::cast(dataset.nsamples()),
+                        inertia: min_inertia / total_weight,
                         dist_fn: self.dist_fn().clone(),
                     })
                 }
@@ -450,6 +562,7 @@ impl<F: Float, DA: Data<Elem = F>, D: Distance<F>> PredictRef<ArrayBase<DA, Ix1>
 ///
 /// `compute_centroids` returns a 2-dimensional array,
 /// where the i-th row corresponds to the i-th cluster.
+#[cfg(test)]
 fn compute_centroids<F: Float>(
     old_centroids: &Array2<F>,
     // (n_observations, n_features)
@@ -458,23 +571,50 @@ fn compute_centroids<F: Float>(
     cluster_memberships: &ArrayBase<impl Data<Elem = usize>, Ix1>,
 ) -> Array2<F> {
     let n_clusters = old_centroids.nrows();
-    let mut counts: Array1<usize> = Array1::ones(n_clusters);
+    let mut counts = Array1::zeros(n_clusters);
     let mut centroids = Array2::zeros((n_clusters, observations.ncols()));
+    compute_centroids_into(
+        old_centroids,
+        observations,
+        cluster_memberships,
+        None,
+        &mut counts,
+        &mut centroids,
+    );
+    centroids
+}
 
-    Zip::from(observations.genrows())
+/// Update step of K-means, writing the new centroids into a preallocated array of shape
+/// `(n_clusters, n_features)` where the i-th row corresponds to the i-th cluster. Observations
+/// are weighted by `weights` if given. `counts` is used as scratch space for the cluster sizes.
+fn compute_centroids_into<F: Float>(
+    old_centroids: &Array2<F>,
+    // (n_observations, n_features)
+    observations: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    // (n_observations,)
+    cluster_memberships: &ArrayBase<impl Data<Elem = usize>, Ix1>,
+    // (n_observations,)
+    weights: Option<&Array1<F>>,
+    counts: &mut Array1<F>,
+    centroids: &mut Array2<F>,
+) {
+    counts.fill(F::one());
+    centroids.fill(F::zero());
+
+    Zip::indexed(observations.genrows())
         .and(cluster_memberships)
-        .apply(|observation, &cluster_membership| {
+        .apply(|i, observation, &cluster_membership| {
+            let weight = weights.map_or(F::one(), |weights| weights[i]);
             let mut centroid = centroids.row_mut(cluster_membership);
-            centroid += &observation;
-            counts[cluster_membership] += 1;
+            centroid.scaled_add(weight, &observation);
+            counts[cluster_membership] += weight;
         });
     // m_k-means: Treat the old centroid like another point in the cluster
-    centroids += old_centroids;
+    *centroids += old_centroids;
 
     Zip::from(centroids.genrows_mut())
-        .and(&counts)
-        .apply(|mut centroid, &cnt| centroid /= F::cast(cnt));
-    centroids
+        .and(&*counts)
+        .apply(|mut centroid, &cnt| centroid /= cnt);
 }
 
 /// Returns new centroids which has the moving average of all observations in each cluster added to
@@ -695,6 +835,105 @@ mod tests {
         test_n_runs(L1Dist);
     }
 
+    #[test]
+    fn test_weights_equal_duplicates() {
+        let observations = array![
+            [0., 0.],
+            [1., 0.],
+            [0., 1.],
+            [10., 10.],
+            [11., 10.],
+            [12., 12.]
+        ];
+        let duplicated = array![
+            [0., 0.],
+            [1., 0.],
+            [0., 1.],
+            [0., 1.],
+            [10., 10.],
+            [11., 10.],
+            [12., 12.],
+            [12., 12.],
+            [12., 12.]
+        ];
+        let weighted = DatasetBase::from(observations).with_weights(array![1., 1., 2., 1., 1., 3.]);
+        let duplicated = DatasetBase::from(duplicated);
+
+        let init = array![[0., 0.], [10., 10.]];
+        let params = KMeans::params(2)
+            .init_method(KMeansInit::Precomputed(init))
+            .n_runs(1)
+            .build()
+            .unwrap();
+        let weighted_model = params.fit(&weighted).unwrap();
+        let duplicated_model = params.fit(&duplicated).unwrap();
+
+        assert_abs_diff_eq!(
+            weighted_model.centroids(),
+            duplicated_model.centroids(),
+            epsilon = 1e-10
+        );
+        assert_abs_diff_eq!(weighted_model.cluster_count(), &array![4., 5.]);
+        assert_abs_diff_eq!(
+            weighted_model.inertia(),
+            duplicated_model.inertia(),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn test_fit_with_workspace() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let small = DatasetBase::from(Array::random_using(
+            (50, 3),
+            Uniform::new(-10., 10.),
+            &mut rng,
+        ));
+        let large = DatasetBase::from(Array::random_using(
+            (200, 2),
+            Uniform::new(-10., 10.),
+            &mut rng,
+        ));
+
+        let mut workspace = KMeansWorkspace::new();
+        for n_clusters in &[3, 5] {
+            let params = KMeans::params_with_rng(*n_clusters, rng.clone())
+                .build()
+                .unwrap();
+            // the workspace is resized when the shape of the data changes
+            for dataset in &[&small, &large, &small] {
+                let expected = params.fit(*dataset).unwrap();
+                let model = params.fit_with_workspace(*dataset, &mut workspace).unwrap();
+                assert_eq!(model, expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_save_load_model() {
+        use linfa::persistence::{LoadModel, SaveModel};
+
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let data = Array::random_using((100, 2), Uniform::new(-10., 10.), &mut rng);
+        let dataset = DatasetBase::from(data);
+        let model = KMeans::params_with_rng(3, rng)
+            .build()
+            .unwrap()
+            .fit(&dataset)
+            .expect("KMeans fitted");
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        model.save_model(file.path()).unwrap();
+        let loaded: KMeans<f64, L2Dist> = KMeans::load_model(file.path()).unwrap();
+
+        assert_eq!(model, loaded);
+        assert_eq!(
+            model.predict(dataset.records()),
+            loaded.predict(dataset.records())
+        );
+    }
+
     #[test]
     fn compute_centroids_works() {
         let cluster_size = 100;
@@ -796,6 +1035,31 @@ mod tests {
         assert_abs_diff_eq!(counts, array![5., 2., 1.]);
     }
 
+    #[test]
+    fn test_degenerate_records() {
+        let params = |n_clusters| KMeans::params(n_clusters).build().unwrap();
+        let is_parameters_error = |result: Result<KMeans<f64, L2Dist>>| {
+            matches!(
+                result,
+                Err(KMeansError::LinfaError(linfa::Error::Parameters(_)))
+            )
+        };
+
+        let empty = DatasetBase::from(Array2::<f64>::zeros((0, 2)));
+        assert!(is_parameters_error(params(1).fit(&empty)));
+
+        let no_features = DatasetBase::from(Array2::<f64>::zeros((5, 0)));
+        assert!(is_parameters_error(params(1).fit(&no_features)));
+
+        let too_few = DatasetBase::from(array![[0., 1.], [1., 0.]]);
+        assert!(is_parameters_error(params(3).fit(&too_few)));
+
+        // a single cluster of identical samples is fine, but it cannot be split
+        let identical = DatasetBase::from(Array2::<f64>::ones((10, 2)));
+        assert!(params(1).fit(&identical).is_ok());
+        assert!(is_parameters_error(params(2).fit(&identical)));
+    }
+
     #[test]
     fn test_incremental_kmeans() {
         let dataset1 = DatasetBase::from(array![[-1.0, -3.0], [0., 0.], [3., 5.], [5., 5.]]);
@@ -28,6 +28,59 @@ pub fn generate_blobs(
     blobs
 }
 
+/// Given an input matrix `blob_centroids`, with shape `(n_blobs, n_features)`, generate
+/// `blob_sizes[i]` data points around the `i`-th blob centroid.
+///
+/// Each blob is formed by points sampled from an isotropic normal distribution centered in
+/// the blob centroid with standard deviation `blob_stds[i]`. The blobs are stacked in the
+/// order of the centroids.
+///
+/// In contrast to [`generate_blobs`] this can be used to assemble imbalanced datasets or
+/// clusters with varying densities.
+///
+/// # Panics
+///
+/// If the number of sizes or standard deviations does not match the number of centroids.
+pub fn generate_blobs_with(
+    blob_sizes: &[usize],
+    blob_centroids: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    blob_stds: &[f64],
+    rng: &mut impl Rng,
+) -> Array2<f64> {
+    let (n_centroids, n_features) = blob_centroids.dim();
+    assert_eq!(
+        blob_sizes.len(),
+        n_centroids,
+        "number of blob sizes does not match number of centroids"
+    );
+    assert_eq!(
+        blob_stds.len(),
+        n_centroids,
+        "number of standard deviations does not match number of centroids"
+    );
+
+    let n_samples = blob_sizes.iter().sum();
+    let mut blobs: Array2<f64> = Array2::zeros((n_samples, n_features));
+
+    let mut start = 0;
+    for ((blob_centroid, &blob_size), &blob_std) in blob_centroids
+        .genrows()
+        .into_iter()
+        .zip(blob_sizes)
+        .zip(blob_stds)
+    {
+        let origin_blob: Array2<f64> =
+            Array::random_using((blob_size, n_features), StandardNormal, rng);
+        let blob = origin_blob * blob_std + &blob_centroid;
+
+        blobs
+            .slice_mut(s![start..start + blob_size, ..])
+            .assign(&blob);
+        start += blob_size;
+    }
+    blobs
+}
+
 /// Generate `blob_size` data points (a "blob") around `blob_centroid`.
 ///
 /// More specifically, the blob is formed by `blob_size` points sampled from a normal
@@ -43,3 +96,52 @@ pub fn generate_blob(
     let origin_blob: Array2<f64> = Array::random_using(shape, StandardNormal, rng);
     origin_blob + blob_centroid
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{array, Axis};
+    use ndarray_rand::rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    #[test]
+    fn generate_blobs_with_unequal_sizes() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let centroids = array![[0., 0.], [50., 50.], [-50., 50.]];
+        let sizes = [10, 200, 35];
+        let stds = [0.1, 1., 5.];
+
+        let blobs = generate_blobs_with(&sizes, &centroids, &stds, &mut rng);
+        assert_eq!(blobs.dim(), (245, 2));
+
+        // every sample lies closest to the centroid of its blob
+        let mut start = 0;
+        for (idx, &size) in sizes.iter().enumerate() {
+            let blob = blobs.slice(s![start..start + size, ..]);
+            for row in blob.genrows() {
+                let closest = centroids
+                    .genrows()
+                    .into_iter()
+                    .map(|c| (&c - &row).mapv(|x| x * x).sum())
+                    .enumerate()
+                    .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .unwrap()
+                    .0;
+                assert_eq!(closest, idx);
+            }
+
+            // the spread follows the requested standard deviation
+            let std = blob.std_axis(Axis(0), 1.).sum() / 2.;
+            assert!((std - stds[idx]).abs() < 0.5 * stds[idx]);
+
+            start += size;
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn generate_blobs_with_mismatched_sizes() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        generate_blobs_with(&[10], &array![[0., 0.], [1., 1.]], &[1., 1.], &mut rng);
+    }
+}
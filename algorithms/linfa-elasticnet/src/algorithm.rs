@@ -1,14 +1,15 @@
 use approx::{abs_diff_eq, abs_diff_ne};
-use ndarray::{s, Array1, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
 use ndarray_linalg::{Inverse, Lapack};
 
+use linfa::metrics::{DefaultMetric, R2};
 use linfa::traits::{Fit, PredictRef};
 use linfa::{
     dataset::{AsTargets, Records},
     DatasetBase, Float,
 };
 
-use super::{ElasticNet, ElasticNetParams, Error, Result};
+use super::{ElasticNet, ElasticNetParams, ElasticNetPath, Error, Result};
 
 impl<F, D, T> Fit<ArrayBase<D, Ix2>, T, crate::error::Error> for ElasticNetParams<F>
 where
@@ -36,6 +37,7 @@ where
         let (parameters, duality_gap, n_steps) = coordinate_descent(
             dataset.records().view(),
             y.view(),
+            Array1::zeros(dataset.nfeatures()),
             self.tolerance,
             self.max_iterations,
             self.l1_ratio,
@@ -66,6 +68,146 @@ impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<F>> for E
     }
 }
 
+impl<F: Float> DefaultMetric for ElasticNet<F> {
+    type Metric = R2;
+}
+
+impl<F: Float + Lapack> ElasticNetParams<F> {
+    /// Compute the regularization path of the elastic net
+    ///
+    /// Fits the model for every penalty in `alphas`, keeping all other hyper parameters fixed.
+    /// The penalties are visited in decreasing order and each fit is warm-started from the
+    /// solution of the previous one, which is considerably cheaper than fitting every penalty
+    /// independently. The `penalty` field of these parameters is ignored.
+    ///
+    /// Returns an `ElasticNetPath` containing the penalties, sorted in decreasing order, and the
+    /// coefficients found for each of them. Fails if no penalty is given or if a penalty is
+    /// negative, infinite or NaN.
+    pub fn path<D: Data<Elem = F>, T: AsTargets<Elem = F>>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+        alphas: &[F],
+    ) -> Result<ElasticNetPath<F>> {
+        self.validate_params()?;
+        if alphas.is_empty() {
+            return Err(
+                linfa::Error::Parameters("At least one penalty is required".to_string()).into(),
+            );
+        }
+        if let Some(alpha) = alphas
+            .iter()
+            .find(|alpha| !alpha.is_finite() || alpha.is_negative())
+        {
+            let msg = format!("Penalty should be positive and finite, but is {}", alpha);
+            return Err(linfa::Error::Parameters(msg).into());
+        }
+
+        let target = dataset.try_single_target()?;
+        let (intercept, y) = self.compute_intercept(target);
+
+        // all penalties are comparable, as they are finite
+        let mut alphas = alphas.to_vec();
+        alphas.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        let alphas = Array1::from(alphas);
+
+        let mut coefficients = Array2::zeros((alphas.len(), dataset.nfeatures()));
+        let mut duality_gaps = Array1::zeros(alphas.len());
+        let mut w = Array1::zeros(dataset.nfeatures());
+
+        for (i, alpha) in alphas.iter().enumerate() {
+            let (parameters, duality_gap, _) = coordinate_descent(
+                dataset.records().view(),
+                y.view(),
+                w,
+                self.tolerance,
+                self.max_iterations,
+                self.l1_ratio,
+                *alpha,
+            );
+
+            coefficients.row_mut(i).assign(&parameters);
+            duality_gaps[i] = duality_gap;
+            w = parameters;
+        }
+
+        Ok(ElasticNetPath {
+            alphas,
+            coefficients,
+            intercept,
+            duality_gaps,
+        })
+    }
+}
+
+impl<F: Float> ElasticNetParams<F> {
+    /// Solve the elastic net problem for dense records without an intercept
+    ///
+    /// The coordinate descent is warm-started from the parameters `initial`, which makes solving
+    /// a sequence of similar problems cheap. Unlike `fit`, the targets are not centered and no
+    /// variance of the parameters is estimated, so that other algorithms can use the elastic net
+    /// as inner solver, for example to update the loadings of a sparse matrix factorization. The
+    /// `with_intercept` field of these parameters is ignored.
+    ///
+    /// Returns the parameters and the duality gap at the end of the optimization.
+    pub fn solve(
+        &self,
+        records: ArrayView2<F>,
+        targets: ArrayView1<F>,
+        initial: Array1<F>,
+    ) -> Result<(Array1<F>, F)> {
+        self.validate_params()?;
+        if records.nrows() != targets.len() {
+            return Err(linfa::Error::MismatchedShapes(records.nrows(), targets.len()).into());
+        }
+        if records.ncols() != initial.len() {
+            return Err(linfa::Error::Parameters(format!(
+                "{} initial parameters given for {} features",
+                initial.len(),
+                records.ncols()
+            ))
+            .into());
+        }
+
+        let (parameters, duality_gap, _) = coordinate_descent(
+            records,
+            targets,
+            initial,
+            self.tolerance,
+            self.max_iterations,
+            self.l1_ratio,
+            self.penalty,
+        );
+
+        Ok((parameters, duality_gap))
+    }
+}
+
+impl<F: Float> ElasticNetPath<F> {
+    /// Get the penalties of the path, sorted in decreasing order
+    pub fn alphas(&self) -> &Array1<F> {
+        &self.alphas
+    }
+
+    /// Get the coefficients with shape `(n_alphas, n_features)`
+    ///
+    /// The i-th row contains the parameters fitted with the i-th penalty of `alphas()`.
+    pub fn coefficients(&self) -> &Array2<F> {
+        &self.coefficients
+    }
+
+    /// Get the intercept, 0. if no intercept was fitted
+    ///
+    /// The intercept is the mean of the targets and therefore shared by all penalties.
+    pub fn intercept(&self) -> F {
+        self.intercept
+    }
+
+    /// Get the duality gap at the end of the optimization for each penalty
+    pub fn duality_gaps(&self) -> &Array1<F> {
+        &self.duality_gaps
+    }
+}
+
 /// View the fitted parameters and make predictions with a fitted
 /// elastic net model
 impl<F: Float> ElasticNet<F> {
@@ -121,23 +263,83 @@ impl<F: Float> ElasticNet<F> {
     }
 }
 
-fn coordinate_descent<'a, F: Float>(
-    x: ArrayView2<'a, F>,
+/// Column-wise access to the records, as required by coordinate descent
+///
+/// This abstracts over dense and sparse records, so that a coordinate update only touches the
+/// stored entries of a column.
+pub(crate) trait Columns<F: Float> {
+    /// Number of rows in the records
+    fn n_samples(&self) -> usize;
+
+    /// Number of columns in the records
+    fn n_features(&self) -> usize;
+
+    /// Squared euclidean norm of each column
+    fn column_norms(&self) -> Array1<F>;
+
+    /// Dot product of the `j`-th column with `v`
+    fn column_dot(&self, j: usize, v: &Array1<F>) -> F;
+
+    /// Add the `j`-th column, scaled by `alpha`, to `v`
+    fn column_scaled_add(&self, j: usize, alpha: F, v: &mut Array1<F>);
+
+    /// Matrix-vector product `X*w`
+    fn mul_vec(&self, w: &Array1<F>) -> Array1<F>;
+
+    /// Transposed matrix-vector product `X^T*v`
+    fn t_mul_vec(&self, v: &Array1<F>) -> Array1<F> {
+        (0..self.n_features())
+            .map(|j| self.column_dot(j, v))
+            .collect()
+    }
+}
+
+impl<'a, F: Float> Columns<F> for ArrayView2<'a, F> {
+    fn n_samples(&self) -> usize {
+        self.nrows()
+    }
+
+    fn n_features(&self) -> usize {
+        self.ncols()
+    }
+
+    fn column_norms(&self) -> Array1<F> {
+        self.map_axis(Axis(0), |col| col.dot(&col))
+    }
+
+    fn column_dot(&self, j: usize, v: &Array1<F>) -> F {
+        self.slice(s![.., j]).dot(v)
+    }
+
+    fn column_scaled_add(&self, j: usize, alpha: F, v: &mut Array1<F>) {
+        v.scaled_add(alpha, &self.slice(s![.., j]));
+    }
+
+    fn mul_vec(&self, w: &Array1<F>) -> Array1<F> {
+        self.dot(w)
+    }
+
+    fn t_mul_vec(&self, v: &Array1<F>) -> Array1<F> {
+        self.t().dot(v)
+    }
+}
+
+pub(crate) fn coordinate_descent<'a, F: Float, X: Columns<F>>(
+    x: X,
     y: ArrayView1<'a, F>,
+    mut w: Array1<F>,
     tol: F,
     max_steps: u32,
     l1_ratio: F,
     penalty: F,
 ) -> (Array1<F>, F, u32) {
-    let n_samples = F::cast(x.shape()[0]);
-    let n_features = x.shape()[1];
-    // the parameters of the model
-    let mut w = Array1::<F>::zeros(n_features);
-    // the residuals: `y - X*w` (since w=0, this is just `y` for now),
+    let n_samples = F::cast(x.n_samples());
+    let n_features = x.n_features();
+    // the residuals: `y - X*w`, where `w` are the initial parameters of the model,
     // the residuals are updated during the algorithm as the parameters change
-    let mut r = y.to_owned();
+    let mut r = &y - &x.mul_vec(&w);
     let mut n_steps = 0u32;
-    let norm_cols_x = x.map_axis(Axis(0), |col| col.dot(&col));
+    let norm_cols_x = x.column_norms();
     let mut gap = F::one() + tol;
     let d_w_tol = tol;
     let tol = tol * y.dot(&y);
@@ -149,16 +351,14 @@ fn coordinate_descent<'a, F: Float>(
                 continue;
             }
             let w_ii = w[ii];
-            let x_slc: ArrayView1<F> = x.slice(s![.., ii]);
             if abs_diff_ne!(w_ii, F::zero()) {
-                // FIXME: direct addition with loop might be faster as it does not have to allocate
-                r += &(&x_slc * w_ii);
+                x.column_scaled_add(ii, w_ii, &mut r);
             }
-            let tmp: F = x_slc.dot(&r);
+            let tmp: F = x.column_dot(ii, &r);
             w[ii] = tmp.signum() * F::max(tmp.abs() - n_samples * l1_ratio * penalty, F::zero())
                 / (norm_cols_x[ii] + n_samples * (F::one() - l1_ratio) * penalty);
             if abs_diff_ne!(w[ii], F::zero()) {
-                r -= &(&x_slc * w[ii]);
+                x.column_scaled_add(ii, -w[ii], &mut r);
             }
             let d_w_ii = (w[ii] - w_ii).abs();
             d_w_max = F::max(d_w_max, d_w_ii);
@@ -169,7 +369,7 @@ fn coordinate_descent<'a, F: Float>(
         if n_steps == max_steps - 1 || abs_diff_eq!(w_max, F::zero()) || d_w_max / w_max < d_w_tol {
             // We've hit one potential stopping criteria
             // check duality gap for ultimate stopping criterion
-            gap = duality_gap(x.view(), y.view(), w.view(), r.view(), l1_ratio, penalty);
+            gap = duality_gap(&x, y.view(), w.view(), &r, l1_ratio, penalty);
             if gap < tol {
                 break;
             }
@@ -178,22 +378,22 @@ fn coordinate_descent<'a, F: Float>(
     (w, gap, n_steps)
 }
 
-fn duality_gap<'a, F: Float>(
-    x: ArrayView2<'a, F>,
+fn duality_gap<'a, F: Float, X: Columns<F>>(
+    x: &X,
     y: ArrayView1<'a, F>,
     w: ArrayView1<'a, F>,
-    r: ArrayView1<'a, F>,
+    r: &Array1<F>,
     l1_ratio: F,
     penalty: F,
 ) -> F {
     let half = F::cast(0.5);
-    let n_samples = F::cast(x.shape()[0]);
+    let n_samples = F::cast(x.n_samples());
     let l1_reg = l1_ratio * penalty * n_samples;
     let l2_reg = (F::one() - l1_ratio) * penalty * n_samples;
-    let xta = x.t().dot(&r) - &w * l2_reg;
+    let xta = x.t_mul_vec(r) - &w * l2_reg;
 
     let dual_norm_xta = xta.fold(F::zero(), |abs_max, &x| abs_max.max(x.abs()));
-    let r_norm2 = r.dot(&r);
+    let r_norm2 = r.dot(r);
     let w_norm2 = w.dot(&w);
     let (const_, mut gap) = if dual_norm_xta > l1_reg {
         let const_ = l1_reg / dual_norm_xta;
@@ -314,11 +514,35 @@ mod tests {
         let alpha = 0.8;
         let lambda = 0.001;
         let objective_start = elastic_net_objective(&x, &y, intercept, &beta, alpha, lambda);
-        let opt_result = coordinate_descent(x.view(), y.view(), 1e-4, 3, alpha, lambda);
+        let opt_result =
+            coordinate_descent(x.view(), y.view(), Array1::zeros(2), 1e-4, 3, alpha, lambda);
         let objective_end = elastic_net_objective(&x, &y, intercept, &opt_result.0, alpha, lambda);
         assert!(objective_start > objective_end);
     }
 
+    #[test]
+    fn solve_matches_fit_without_intercept() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let x = Array::random_using((50, 3), Uniform::new(-1., 1.), &mut rng);
+        let y = x.dot(&array![1., -2., 0.]);
+        let params = ElasticNet::lasso().penalty(0.1).with_intercept(false);
+
+        let model = params.fit(&Dataset::new(x.clone(), y.clone())).unwrap();
+        let (parameters, _) = params.solve(x.view(), y.view(), Array1::zeros(3)).unwrap();
+        assert_abs_diff_eq!(parameters.view(), model.parameters().view());
+
+        // warm starting from the solution stays there
+        let (warm, _) = params
+            .solve(x.view(), y.view(), parameters.clone())
+            .unwrap();
+        assert_abs_diff_eq!(warm, parameters, epsilon = 1e-4);
+
+        assert!(params
+            .solve(x.view(), y.slice(s![..10]), Array1::zeros(3))
+            .is_err());
+        assert!(params.solve(x.view(), y.view(), Array1::zeros(2)).is_err());
+    }
+
     #[test]
     fn lasso_zero_works() {
         let dataset = Dataset::from((array![[0.], [0.], [0.]], array![0., 0., 0.]));
@@ -413,6 +637,41 @@ mod tests {
         assert_abs_diff_eq!(model.parameters(), &array![0.5, -0.5], epsilon = 0.001);
     }
 
+    #[test]
+    fn intercept_toggle_on_centered_data() {
+        let x = array![[-3.0], [-1.0], [1.0], [3.0]];
+        let y = array![-5.0, -2.0, 2.0, 5.0];
+
+        // a single feature is solved by one soft-thresholding step
+        // w = S(x^T y, n * l1_ratio * penalty) / (x^T x + n * (1 - l1_ratio) * penalty)
+        let expected = (34.0 - 4.0 * 0.5) / (20.0 + 4.0 * 0.5);
+
+        let dataset = Dataset::new(x.clone(), y.clone());
+        let model = ElasticNet::params()
+            .penalty(1.0)
+            .with_intercept(true)
+            .fit(&dataset)
+            .unwrap();
+        assert_abs_diff_eq!(model.intercept(), 0.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(model.parameters(), &array![expected], epsilon = 1e-8);
+
+        let without = ElasticNet::params()
+            .penalty(1.0)
+            .with_intercept(false)
+            .fit(&dataset)
+            .unwrap();
+        assert_eq!(without.intercept(), 0.0);
+        assert_abs_diff_eq!(without.parameters(), &array![expected], epsilon = 1e-8);
+
+        // the intercept absorbs a shift of the targets and is not penalized
+        let shifted = ElasticNet::params()
+            .penalty(1.0)
+            .fit(&Dataset::new(x, y + 10.0))
+            .unwrap();
+        assert_abs_diff_eq!(shifted.intercept(), 10.0, epsilon = 1e-8);
+        assert_abs_diff_eq!(shifted.parameters(), &array![expected], epsilon = 1e-8);
+    }
+
     #[test]
     #[allow(clippy::excessive_precision)]
     fn elastic_net_diabetes_1_works_like_sklearn() {
@@ -567,6 +826,62 @@ mod tests {
         assert!(rms.unwrap() < 0.67);
     }
 
+    #[test]
+    fn path_gets_sparser_and_matches_independent_fits() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+
+        let mut w = Array::random_using(20, Uniform::new(1., 2.), &mut rng);
+        w.slice_mut(s![5..]).fill(0.0);
+
+        let x = Array::random_using((100, 20), Uniform::new(-1., 1.), &mut rng);
+        let y = x.dot(&w);
+        let dataset = Dataset::new(x, y);
+
+        let params = || {
+            ElasticNet::params()
+                .l1_ratio(0.9)
+                .max_iterations(10000)
+                .tolerance(1e-10)
+        };
+        let path = params().path(&dataset, &[0.001, 1.0, 0.01, 0.1]).unwrap();
+
+        // penalties are sorted in decreasing order
+        assert_abs_diff_eq!(path.alphas(), &array![1.0, 0.1, 0.01, 0.001]);
+        assert_eq!(path.coefficients().dim(), (4, 20));
+
+        // number of non-zero coefficients decreases with growing penalty
+        let non_zeros = path
+            .coefficients()
+            .genrows()
+            .into_iter()
+            .map(|row| row.iter().filter(|x| x.abs() > 1e-8).count())
+            .collect::<Vec<_>>();
+        assert!(non_zeros.windows(2).all(|x| x[0] <= x[1]));
+        assert!(non_zeros[0] < non_zeros[3]);
+
+        // warm-started solutions equal the independently fitted ones
+        for (alpha, coefficients) in path.alphas().iter().zip(path.coefficients().genrows()) {
+            let model = params().penalty(*alpha).fit(&dataset).unwrap();
+
+            assert_abs_diff_eq!(model.parameters(), &coefficients, epsilon = 1e-5);
+            assert_abs_diff_eq!(model.intercept(), path.intercept());
+        }
+    }
+
+    #[test]
+    fn path_rejects_invalid_alphas() {
+        let dataset = Dataset::new(array![[-1.0], [0.0], [1.0]], array![-1.0, 0.0, 1.0]);
+
+        assert!(ElasticNet::params().path(&dataset, &[]).is_err());
+        assert!(ElasticNet::params().path(&dataset, &[0.1, -0.1]).is_err());
+        assert!(ElasticNet::params()
+            .path(&dataset, &[0.1, f64::NAN])
+            .is_err());
+        assert!(ElasticNet::params()
+            .path(&dataset, &[f64::INFINITY])
+            .is_err());
+    }
+
     #[test]
     fn diabetes_z_score() {
         let dataset = linfa_datasets::diabetes();
@@ -17,6 +17,9 @@ pub enum Error {
     /// The input is singular
     #[error("the data is ill-conditioned")]
     IllConditioned,
+    /// The variance of the parameters is only estimated for dense records
+    #[error("the variance is not available for models fitted on sparse records")]
+    SparseRecords,
     #[error(transparent)]
     BaseCrate(#[from] linfa::Error),
 }
@@ -26,6 +26,44 @@
 //! }
 //! ```
 //!
+//! The coefficients for a whole sequence of penalties can be computed with warm starts:
+//!
+//! ```
+//! use linfa_elasticnet::{ElasticNet, Result};
+//!
+//! fn main() -> Result<()> {
+//!     let dataset = linfa_datasets::diabetes();
+//!
+//!     let path = ElasticNet::params()
+//!         .l1_ratio(0.8)
+//!         .path(&dataset, &[1.0, 0.1, 0.01])?;
+//!
+//!     // one row of coefficients per penalty
+//!     assert_eq!(path.coefficients().nrows(), path.alphas().len());
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Sparse records, for example from a text vectorizer, can be passed directly as a `sprs` matrix.
+//! The coordinate descent then only visits the stored entries of each feature:
+//!
+//! ```
+//! use linfa::{traits::Fit, DatasetBase};
+//! use linfa_elasticnet::{ElasticNet, Result};
+//! use ndarray::array;
+//! use sprs::CsMat;
+//!
+//! fn main() -> Result<()> {
+//!     let records = CsMat::csr_from_dense(array![[1., 0.], [0., 2.], [3., 0.]].view(), 0.);
+//!     let dataset = DatasetBase::new(records, array![1., 2., 3.]);
+//!
+//!     let model = ElasticNet::params().penalty(0.1).fit(&dataset)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## Implementation
 //!
 //! The coordinate descent algorithm is used to solve the lasso and ridge problem. It optimizes
@@ -38,7 +76,7 @@
 //! Descent](http://www.jstatsoft.org/v33/i01/paper)
 
 use linfa::Float;
-use ndarray::Array1;
+use ndarray::{Array1, Array2};
 
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
@@ -46,6 +84,7 @@ use serde_crate::{Deserialize, Serialize};
 mod algorithm;
 mod error;
 mod hyperparameters;
+mod sparse;
 
 pub use error::{Error, Result};
 pub use hyperparameters::ElasticNetParams;
@@ -68,6 +107,23 @@ pub struct ElasticNet<F> {
     variance: Result<Array1<F>>,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+/// Regularization path of an elastic net
+///
+/// This struct contains the coefficients of elastic net models fitted with a sequence of
+/// penalties, see `ElasticNetParams::path`. The penalties are stored in decreasing order, each row
+/// of the coefficient matrix corresponds to one of them.
+pub struct ElasticNetPath<F> {
+    alphas: Array1<F>,
+    coefficients: Array2<F>,
+    intercept: F,
+    duality_gaps: Array1<F>,
+}
+
 impl<F: Float> ElasticNet<F> {
     /// Create a default elastic net model
     ///
@@ -0,0 +1,187 @@
+use std::ops::Deref;
+
+use ndarray::Array1;
+use sprs::{CsMatBase, CsMatViewI, SpIndex};
+
+use linfa::traits::{Fit, PredictRef};
+use linfa::{dataset::AsTargets, DatasetBase, Float};
+
+use crate::algorithm::{coordinate_descent, Columns};
+use crate::{ElasticNet, ElasticNetParams, Error, Result};
+
+/// Column access for sparse records in CSC layout
+impl<'a, F: Float, I: SpIndex, Iptr: SpIndex> Columns<F> for CsMatViewI<'a, F, I, Iptr> {
+    fn n_samples(&self) -> usize {
+        self.rows()
+    }
+
+    fn n_features(&self) -> usize {
+        self.cols()
+    }
+
+    fn column_norms(&self) -> Array1<F> {
+        self.outer_iterator()
+            .map(|col| col.iter().map(|(_, &val)| val * val).sum::<F>())
+            .collect()
+    }
+
+    fn column_dot(&self, j: usize, v: &Array1<F>) -> F {
+        self.outer_view(j)
+            .unwrap()
+            .iter()
+            .map(|(i, &val)| val * v[i])
+            .sum()
+    }
+
+    fn column_scaled_add(&self, j: usize, alpha: F, v: &mut Array1<F>) {
+        for (i, &val) in self.outer_view(j).unwrap().iter() {
+            v[i] += alpha * val;
+        }
+    }
+
+    fn mul_vec(&self, w: &Array1<F>) -> Array1<F> {
+        let mut res = Array1::zeros(self.rows());
+        for (j, col) in self.outer_iterator().enumerate() {
+            for (i, &val) in col.iter() {
+                res[i] += val * w[j];
+            }
+        }
+        res
+    }
+}
+
+impl<F, I, IptrS, IndS, DataS, T> Fit<CsMatBase<F, I, IptrS, IndS, DataS>, T, Error>
+    for ElasticNetParams<F>
+where
+    F: Float,
+    I: SpIndex,
+    IptrS: Deref<Target = [I]>,
+    IndS: Deref<Target = [I]>,
+    DataS: Deref<Target = [F]>,
+    T: AsTargets<Elem = F>,
+{
+    type Object = ElasticNet<F>;
+
+    /// Fit an elastic net model given a sparse feature matrix `x` and a target
+    /// variable `y`.
+    ///
+    /// The feature matrix `x` must have shape `(n_samples, n_features)` and can be stored in
+    /// either CSR or CSC layout. CSR matrices are converted to CSC first, because the coordinate
+    /// descent iterates over the stored entries of each feature.
+    ///
+    /// The variance of the parameters is not estimated for sparse records, hence `z_score` and
+    /// `confidence_95th` of the fitted model return `Error::SparseRecords`.
+    fn fit(
+        &self,
+        dataset: &DatasetBase<CsMatBase<F, I, IptrS, IndS, DataS>, T>,
+    ) -> Result<Self::Object> {
+        self.validate_params()?;
+        let target = dataset.try_single_target()?;
+
+        let (intercept, y) = self.compute_intercept(target);
+
+        let records = dataset.records();
+        let csc;
+        let x = if records.is_csc() {
+            records.view()
+        } else {
+            csc = records.to_csc();
+            csc.view()
+        };
+
+        let (parameters, duality_gap, n_steps) = coordinate_descent(
+            x,
+            y.view(),
+            Array1::zeros(records.cols()),
+            self.tolerance,
+            self.max_iterations,
+            self.l1_ratio,
+            self.penalty,
+        );
+
+        Ok(ElasticNet {
+            intercept,
+            parameters,
+            duality_gap,
+            n_steps,
+            variance: Err(Error::SparseRecords),
+        })
+    }
+}
+
+impl<F, I, IptrS, IndS, DataS> PredictRef<CsMatBase<F, I, IptrS, IndS, DataS>, Array1<F>>
+    for ElasticNet<F>
+where
+    F: Float,
+    I: SpIndex,
+    IptrS: Deref<Target = [I]>,
+    IndS: Deref<Target = [I]>,
+    DataS: Deref<Target = [F]>,
+{
+    /// Given a sparse input matrix `X`, with shape `(n_samples, n_features)`,
+    /// `predict` returns the target variable according to elastic net
+    /// learned from the training data distribution.
+    fn predict_ref(&self, x: &CsMatBase<F, I, IptrS, IndS, DataS>) -> Array1<F> {
+        let mut y = Array1::from_elem(x.rows(), self.intercept);
+        for (&val, (row, col)) in x.iter() {
+            y[row.index()] += val * self.parameters[col.index()];
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{s, Array, Array2};
+    use ndarray_rand::rand::SeedableRng;
+    use ndarray_rand::rand_distr::Uniform;
+    use ndarray_rand::RandomExt;
+    use rand_isaac::Isaac64Rng;
+    use sprs::CsMat;
+
+    use linfa::{
+        traits::{Fit, Predict},
+        Dataset, DatasetBase,
+    };
+
+    use crate::{ElasticNet, Error};
+
+    fn sparse_records(rng: &mut Isaac64Rng) -> Array2<f64> {
+        // keep roughly a fifth of the entries
+        let mask = Array::random_using((100, 30), Uniform::new(0., 1.), rng).mapv(|x| x < 0.2);
+        let mut x = Array::random_using((100, 30), Uniform::new(-1., 1.), rng);
+        x.zip_mut_with(&mask, |x, &keep| {
+            if !keep {
+                *x = 0.0;
+            }
+        });
+        x
+    }
+
+    #[test]
+    fn sparse_fit_matches_dense_fit() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+
+        let mut w = Array::random_using(30, Uniform::new(1., 2.), &mut rng);
+        w.slice_mut(s![10..]).fill(0.0);
+
+        let x = sparse_records(&mut rng);
+        let y = x.dot(&w) + 0.5;
+
+        let params = || ElasticNet::params().penalty(0.01).tolerance(1e-10);
+        let dense = params().fit(&Dataset::new(x.clone(), y.clone())).unwrap();
+
+        let csr = DatasetBase::new(CsMat::csr_from_dense(x.view(), 0.), y.clone());
+        let csc = DatasetBase::new(CsMat::csc_from_dense(x.view(), 0.), y);
+
+        for dataset in &[csr, csc] {
+            let sparse = params().fit(dataset).unwrap();
+
+            assert_abs_diff_eq!(sparse.parameters(), dense.parameters(), epsilon = 1e-8);
+            assert_abs_diff_eq!(sparse.intercept(), dense.intercept(), epsilon = 1e-8);
+            assert_abs_diff_eq!(sparse.predict(dataset), dense.predict(&x), epsilon = 1e-8);
+            assert!(matches!(sparse.z_score(), Err(Error::SparseRecords)));
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use ndarray_linalg::error::LinalgError;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, GpError>;
+
+/// An error when fitting a Gaussian process
+#[derive(Error, Debug)]
+pub enum GpError {
+    /// When any of the hyperparameters are set the wrong value
+    #[error("Invalid value encountered: {0}")]
+    InvalidValue(String),
+    /// Errors encountered during linear algebra operations, for example when the kernel matrix is
+    /// not positive definite
+    #[error("Linalg Error: {0}")]
+    Linalg(#[from] LinalgError),
+    #[error(transparent)]
+    LinfaError(#[from] linfa::error::Error),
+}
@@ -0,0 +1,439 @@
+use linfa::dataset::{AsTargets, WithLapack, WithoutLapack};
+use linfa::metrics::{DefaultMetric, R2};
+use linfa::traits::{Fit, PredictRef};
+use linfa::{DatasetBase, Float};
+use linfa_kernel::KernelMethod;
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
+use ndarray_linalg::{cholesky::*, triangular::*};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use crate::error::{GpError, Result};
+
+/// Number of grid points per direction when searching the kernel hyperparameter
+const GRID_STEPS: i32 = 10;
+/// Number of golden-section iterations refining the best grid point
+const REFINE_STEPS: usize = 30;
+
+/// Gaussian process regression hyperparameters
+///
+/// The prior over functions is described by the kernel `method` and the observations are assumed
+/// to be corrupted by gaussian noise with variance `alpha`. If `optimize` is set, the bandwidth of
+/// the kernel is selected by maximizing the log marginal likelihood of the training data and the
+/// configured value only serves as starting point.
+#[derive(Debug, Clone)]
+pub struct GaussianProcessParams<F> {
+    method: KernelMethod<F>,
+    alpha: F,
+    optimize: bool,
+}
+
+impl<F: Float> Default for GaussianProcessParams<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> GaussianProcessParams<F> {
+    /// Create default Gaussian process hyperparameters
+    ///
+    /// By default an RBF kernel with the `Scale` heuristic, a noise variance of `1e-10` and no
+    /// hyperparameter optimization are used.
+    pub fn new() -> Self {
+        GaussianProcessParams {
+            method: KernelMethod::Rbf(linfa_kernel::Gamma::Scale),
+            alpha: F::cast(1e-10),
+            optimize: false,
+        }
+    }
+
+    /// Set the kernel function describing the covariance of the prior
+    pub fn method(mut self, method: KernelMethod<F>) -> Self {
+        self.method = method;
+
+        self
+    }
+
+    /// Set the variance of the observation noise
+    ///
+    /// The value is added to the diagonal of the kernel matrix. Apart from modelling noise, a
+    /// small value also ensures that the kernel matrix is positive definite.
+    pub fn alpha(mut self, alpha: F) -> Self {
+        self.alpha = alpha;
+
+        self
+    }
+
+    /// Select the bandwidth of the kernel by maximizing the log marginal likelihood
+    ///
+    /// This is supported for the RBF, Gaussian, Laplacian and Chi-squared kernel. The bandwidth
+    /// is searched on a logarithmic grid spanning five orders of magnitude around the configured
+    /// value and refined afterwards with a golden-section search.
+    pub fn optimize(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+
+        self
+    }
+
+    /// Validate the hyperparameters
+    pub fn validate(&self) -> Result<()> {
+        if self.alpha.is_negative() {
+            return Err(GpError::InvalidValue(format!(
+                "the noise variance should be non-negative, but is {}",
+                self.alpha
+            )));
+        }
+
+        // the RBF kernel is resolved to a Gaussian kernel before optimization
+        let has_scale =
+            matches!(self.method, KernelMethod::Rbf(_)) || kernel_scale(&self.method).is_some();
+        if self.optimize && !has_scale {
+            return Err(GpError::InvalidValue(format!(
+                "the kernel {:?} has no bandwidth to optimize",
+                self.method
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Search the bandwidth of `method` maximizing the log marginal likelihood
+    fn optimize_method(
+        &self,
+        method: KernelMethod<F>,
+        records: ArrayView2<F>,
+        targets: ArrayView1<F>,
+    ) -> KernelMethod<F> {
+        let scale = match kernel_scale(&method) {
+            Some(scale) => scale,
+            None => return method,
+        };
+
+        // the likelihood of parameters with a singular kernel matrix is treated as zero
+        let log_likelihood = |log_scale: F| {
+            let method = with_kernel_scale(&method, log_scale.exp());
+            Posterior::new(records, targets, &method, self.alpha)
+                .map(|posterior| posterior.log_marginal_likelihood)
+                .unwrap_or_else(|_| F::neg_infinity())
+        };
+
+        // search on a coarse grid with a resolution of half a magnitude
+        let step = F::cast(10f64.ln() / 2.);
+        let (center, best) = (-GRID_STEPS..=GRID_STEPS)
+            .map(|i| scale.ln() + F::cast(i) * step)
+            .map(|x| (x, log_likelihood(x)))
+            .fold((scale.ln(), F::neg_infinity()), |best, x| {
+                if x.1 > best.1 {
+                    x
+                } else {
+                    best
+                }
+            });
+
+        // refine the best grid point with a golden-section search in its neighbourhood
+        let inv_phi = F::cast((5f64.sqrt() - 1.) / 2.);
+        let (mut a, mut b) = (center - step, center + step);
+        let (mut c, mut d) = (b - (b - a) * inv_phi, a + (b - a) * inv_phi);
+        let (mut fc, mut fd) = (log_likelihood(c), log_likelihood(d));
+
+        for _ in 0..REFINE_STEPS {
+            if fc > fd {
+                b = d;
+                d = c;
+                fd = fc;
+                c = b - (b - a) * inv_phi;
+                fc = log_likelihood(c);
+            } else {
+                a = c;
+                c = d;
+                fc = fd;
+                d = a + (b - a) * inv_phi;
+                fd = log_likelihood(d);
+            }
+        }
+
+        let (refined, value) = if fc > fd { (c, fc) } else { (d, fd) };
+        if value > best {
+            with_kernel_scale(&method, refined.exp())
+        } else {
+            with_kernel_scale(&method, center.exp())
+        }
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets<Elem = F>> Fit<ArrayBase<D, Ix2>, T, GpError>
+    for GaussianProcessParams<F>
+{
+    type Object = GaussianProcess<F>;
+
+    /// Fit a Gaussian process to a dataset with a single target
+    ///
+    /// Fails if the kernel matrix of the records is not positive definite, in this case the
+    /// noise variance `alpha` should be increased.
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        self.validate()?;
+
+        let records = dataset.records().view();
+        let targets = dataset.try_single_target()?;
+
+        let mut method = self.method.resolve(&records);
+        if self.optimize {
+            method = self.optimize_method(method, records, targets.view());
+        }
+
+        let posterior = Posterior::new(records, targets.view(), &method, self.alpha)?;
+
+        Ok(GaussianProcess {
+            records: records.to_owned(),
+            method,
+            alpha: self.alpha,
+            cholesky: posterior.cholesky,
+            weights: posterior.weights,
+            log_marginal_likelihood: posterior.log_marginal_likelihood,
+        })
+    }
+}
+
+/// Fitted Gaussian process regression model
+///
+/// The model keeps the training records, the Cholesky factor of their kernel matrix and the
+/// weights of the posterior mean. Predictions with `Predict` return the posterior mean, use
+/// `predict_with_variance` to obtain the uncertainty as well.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct GaussianProcess<F> {
+    records: Array2<F>,
+    method: KernelMethod<F>,
+    alpha: F,
+    cholesky: Array2<F>,
+    weights: Array1<F>,
+    log_marginal_likelihood: F,
+}
+
+impl<F: Float> GaussianProcess<F> {
+    /// Create default Gaussian process hyperparameters
+    pub fn params() -> GaussianProcessParams<F> {
+        GaussianProcessParams::new()
+    }
+
+    /// Return the kernel function used by the model
+    ///
+    /// Data dependent or optimized parameters are already resolved.
+    pub fn method(&self) -> &KernelMethod<F> {
+        &self.method
+    }
+
+    /// Return the variance of the observation noise
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+
+    /// Return the log marginal likelihood of the training data
+    pub fn log_marginal_likelihood(&self) -> F {
+        self.log_marginal_likelihood
+    }
+
+    /// Predict the posterior mean and variance of new records
+    ///
+    /// The variance describes the uncertainty of the latent function and does not include the
+    /// observation noise `alpha`.
+    pub fn predict_with_variance<D: Data<Elem = F>>(
+        &self,
+        x: &ArrayBase<D, Ix2>,
+    ) -> Result<(Array1<F>, Array1<F>)> {
+        let cross = kernel_matrix(&self.method, x.view(), self.records.view());
+        let mean = cross.dot(&self.weights);
+
+        // solve L v = K(X, x) for the reduction of the prior variance
+        let v = self
+            .cholesky
+            .clone()
+            .with_lapack()
+            .solve_triangular(
+                UPLO::Lower,
+                Diag::NonUnit,
+                &cross.reversed_axes().with_lapack(),
+            )?
+            .without_lapack();
+
+        let prior = x
+            .genrows()
+            .into_iter()
+            .map(|row| self.method.distance(row, row))
+            .collect::<Array1<F>>();
+        let variance = (prior - v.mapv(|x| x * x).sum_axis(Axis(0))).mapv(|x| F::max(x, F::zero()));
+
+        Ok((mean, variance))
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<F>> for GaussianProcess<F> {
+    /// Predict the posterior mean of new records
+    fn predict_ref<'a>(&'a self, x: &'a ArrayBase<D, Ix2>) -> Array1<F> {
+        kernel_matrix(&self.method, x.view(), self.records.view()).dot(&self.weights)
+    }
+}
+
+impl<F: Float> DefaultMetric for GaussianProcess<F> {
+    type Metric = R2;
+}
+
+/// Posterior of a Gaussian process conditioned on training data
+struct Posterior<F> {
+    cholesky: Array2<F>,
+    weights: Array1<F>,
+    log_marginal_likelihood: F,
+}
+
+impl<F: Float> Posterior<F> {
+    fn new(
+        records: ArrayView2<F>,
+        targets: ArrayView1<F>,
+        method: &KernelMethod<F>,
+        alpha: F,
+    ) -> Result<Self> {
+        let mut kernel = kernel_matrix(method, records, records);
+        kernel.diag_mut().mapv_inplace(|x| x + alpha);
+
+        // solve L L^T w = y with two triangular systems
+        let cholesky = kernel.with_lapack().cholesky(UPLO::Lower)?;
+        let rhs = targets.to_owned().insert_axis(Axis(1)).with_lapack();
+        let z = cholesky.solve_triangular(UPLO::Lower, Diag::NonUnit, &rhs)?;
+        let weights = cholesky
+            .t()
+            .solve_triangular(UPLO::Upper, Diag::NonUnit, &z)?
+            .without_lapack()
+            .index_axis_move(Axis(1), 0);
+        let cholesky = cholesky.without_lapack();
+
+        // log p(y|X) = -1/2 y^T w - sum(log(diag(L))) - n/2 log(2 pi)
+        let nsamples = F::cast(records.nrows());
+        let log_marginal_likelihood = -F::cast(0.5) * targets.dot(&weights)
+            - cholesky.diag().mapv(|x| x.ln()).sum()
+            - nsamples / F::cast(2.) * F::cast(2. * std::f64::consts::PI).ln();
+
+        Ok(Posterior {
+            cholesky,
+            weights,
+            log_marginal_likelihood,
+        })
+    }
+}
+
+/// Evaluate the kernel function for all pairs of rows in `a` and `b`
+fn kernel_matrix<F: Float>(
+    method: &KernelMethod<F>,
+    a: ArrayView2<F>,
+    b: ArrayView2<F>,
+) -> Array2<F> {
+    Array2::from_shape_fn((a.nrows(), b.nrows()), |(i, j)| {
+        method.distance(a.row(i), b.row(j))
+    })
+}
+
+/// Return the bandwidth of kernels with a single positive scale parameter
+fn kernel_scale<F: Float>(method: &KernelMethod<F>) -> Option<F> {
+    match *method {
+        KernelMethod::Gaussian(eps) => Some(eps),
+        KernelMethod::Laplacian(gamma) | KernelMethod::ChiSquared(gamma) => Some(gamma),
+        _ => None,
+    }
+}
+
+/// Replace the bandwidth of a kernel, see `kernel_scale`
+fn with_kernel_scale<F: Float>(method: &KernelMethod<F>, scale: F) -> KernelMethod<F> {
+    match *method {
+        KernelMethod::Gaussian(_) => KernelMethod::Gaussian(scale),
+        KernelMethod::Laplacian(_) => KernelMethod::Laplacian(scale),
+        KernelMethod::ChiSquared(_) => KernelMethod::ChiSquared(scale),
+        ref method => method.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GaussianProcess;
+    use crate::GpError;
+    use approx::assert_abs_diff_eq;
+    use linfa::{
+        traits::{Fit, Predict},
+        Dataset,
+    };
+    use linfa_kernel::KernelMethod;
+    use ndarray::{array, Array, Array1, Axis};
+    use ndarray_rand::{rand::SeedableRng, rand_distr::Normal, RandomExt};
+    use rand_isaac::Isaac64Rng;
+
+    fn noisy_sine() -> Dataset<f64, f64> {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+
+        let records = Array::linspace(0., 5., 30).insert_axis(Axis(1));
+        let noise = Array1::random_using(30, Normal::new(0., 0.1).unwrap(), &mut rng);
+        let targets = records.column(0).mapv(f64::sin) + noise;
+
+        Dataset::new(records, targets)
+    }
+
+    #[test]
+    fn tracks_noisy_sine() {
+        let dataset = noisy_sine();
+        let model = GaussianProcess::params()
+            .method(KernelMethod::Gaussian(2.0))
+            .alpha(0.01)
+            .fit(&dataset)
+            .unwrap();
+
+        let test = Array::linspace(0.2, 4.8, 20).insert_axis(Axis(1));
+        let (mean, variance) = model.predict_with_variance(&test).unwrap();
+
+        assert_abs_diff_eq!(mean, test.column(0).mapv(f64::sin), epsilon = 0.15);
+        assert_abs_diff_eq!(model.predict(&test), mean, epsilon = 1e-10);
+        assert!(variance.iter().all(|x| *x < 0.01));
+
+        // the variance widens away from the training points towards the prior variance
+        let (_, variance) = model
+            .predict_with_variance(&array![[5.5], [7.0], [20.0]])
+            .unwrap();
+        assert!(variance[0] < variance[1] && variance[1] < variance[2]);
+        assert_abs_diff_eq!(variance[2], 1.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn optimizes_bandwidth() {
+        let dataset = noisy_sine();
+
+        // a far too small bandwidth interpolates the noise
+        let params = GaussianProcess::params()
+            .method(KernelMethod::Gaussian(1e-3))
+            .alpha(0.01);
+        let fixed = params.fit(&dataset).unwrap();
+        let optimized = params.optimize(true).fit(&dataset).unwrap();
+
+        assert!(optimized.log_marginal_likelihood() > fixed.log_marginal_likelihood());
+
+        let test = Array::linspace(0.1, 4.9, 20).insert_axis(Axis(1));
+        assert_abs_diff_eq!(
+            optimized.predict(&test),
+            test.column(0).mapv(f64::sin),
+            epsilon = 0.15
+        );
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = noisy_sine();
+
+        let res = GaussianProcess::params().alpha(-1.0).fit(&dataset);
+        assert!(matches!(res, Err(GpError::InvalidValue(_))));
+
+        let res = GaussianProcess::params()
+            .method(KernelMethod::Linear)
+            .optimize(true)
+            .fit(&dataset);
+        assert!(matches!(res, Err(GpError::InvalidValue(_))));
+    }
+}
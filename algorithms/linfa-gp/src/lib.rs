@@ -0,0 +1,64 @@
+//! # Gaussian Processes
+//!
+//! `linfa-gp` aims to provide pure Rust implementations of Gaussian process models.
+//!
+//! A Gaussian process places a prior over functions, described by a kernel function measuring the
+//! similarity of two points. Conditioning on noisy observations gives a posterior, which not only
+//! predicts a mean but also quantifies its uncertainty with a variance. The variance is small close
+//! to the training points and grows towards the prior variance far from them. This makes Gaussian
+//! processes attractive for regression tasks with few samples.
+//!
+//! ## The Big Picture
+//!
+//! `linfa-gp` is a crate in the [`linfa`](https://crates.io/crates/linfa) ecosystem,
+//! an effort to create a toolkit for classical Machine Learning implemented in pure Rust,
+//! akin to Python's `scikit-learn`.
+//!
+//! ## Current state
+//!
+//! `linfa-gp` currently provides an implementation of the following methods:
+//!
+//! - Gaussian process regression with kernels from `linfa-kernel` and optional maximization of
+//! the marginal likelihood
+//!
+//! ## Example
+//!
+//! ```
+//! use linfa::{traits::Fit, Dataset};
+//! use linfa_gp::{GaussianProcess, Result};
+//! use linfa_kernel::KernelMethod;
+//! use ndarray::{array, Array};
+//!
+//! fn main() -> Result<()> {
+//!     let records = Array::linspace(0., 5., 20).insert_axis(ndarray::Axis(1));
+//!     let targets = records.column(0).mapv(f64::sin);
+//!
+//!     let model = GaussianProcess::params()
+//!         .method(KernelMethod::Gaussian(2.0))
+//!         .alpha(1e-4)
+//!         .fit(&Dataset::new(records, targets))?;
+//!
+//!     let (mean, variance) = model.predict_with_variance(&array![[2.5], [10.0]])?;
+//!
+//!     // the prediction far from the training points is uncertain
+//!     assert!(variance[1] > variance[0]);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Implementation
+//!
+//! The kernel matrix of the training records, with the noise variance `alpha` added to its
+//! diagonal, is decomposed with a Cholesky factorization. The factor is kept in the model and
+//! used to compute the posterior mean and variance for new records. The hyperparameter of the
+//! kernel can optionally be chosen by maximizing the log marginal likelihood of the training data.
+//!
+//! See also:
+//! * [Gaussian Processes for Machine Learning, Chapter 2 and 5](http://gaussianprocess.org/gpml/chapters/)
+
+mod error;
+mod gaussian_process;
+
+pub use error::{GpError, Result};
+pub use gaussian_process::{GaussianProcess, GaussianProcessParams};
@@ -12,6 +12,9 @@ pub enum FastIcaError {
     /// When any of the hyperparameters are set the wrong value
     #[error("Invalid value encountered: {0}")]
     InvalidValue(String),
+    /// When the number of components is zero or exceeds the rank of the dataset
+    #[error("ncomponents must be between 1 and {max}, got {requested}")]
+    InvalidNComponents { requested: usize, max: usize },
     /// If we fail to compute any components of the SVD decomposition
     /// due to an Ill-Conditioned matrix
     #[error("SVD Decomposition failed, X could be an Ill-Conditioned matrix")]
@@ -28,6 +28,7 @@ pub struct FastIca<F: Float> {
     max_iter: usize,
     tol: F,
     random_state: Option<usize>,
+    n_init: usize,
 }
 
 impl<F: Float> Default for FastIca<F> {
@@ -45,6 +46,7 @@ impl<F: Float> FastIca<F> {
             max_iter: 200,
             tol: F::cast(1e-4),
             random_state: None,
+            n_init: 1,
         }
     }
 
@@ -77,6 +79,13 @@ impl<F: Float> FastIca<F> {
         self.random_state = Some(random_state);
         self
     }
+
+    /// Set the number of restarts from different random initializations of the de-mixing
+    /// matrix, the restart whose components have the largest total negentropy is kept
+    pub fn n_init(mut self, n_init: usize) -> Self {
+        self.n_init = n_init;
+        self
+    }
 }
 
 impl<F: Float, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, FastIcaError> for FastIca<F> {
@@ -86,12 +95,21 @@ impl<F: Float, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, FastIcaError> for
     ///
     /// # Errors
     ///
-    /// If the [`FastIca::ncomponents`] is set to a number greater than the minimum of
+    /// If the [`FastIca::ncomponents`] is set to zero or a number greater than the minimum of
     /// the number of rows and columns
     ///
     /// If the `alpha` value set for [`GFunc::Logcosh`] is not between 1 and 2
     /// inclusive
+    ///
+    /// If [`FastIca::n_init`] is set to zero
     fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        self.gfunc.validate()?;
+        if self.n_init == 0 {
+            return Err(FastIcaError::InvalidValue(
+                "n_init must be greater than zero".to_string(),
+            ));
+        }
+
         let x = &dataset.records;
         let (nsamples, nfeatures) = (x.nsamples(), x.nfeatures());
         if dataset.nsamples() == 0 {
@@ -100,15 +118,16 @@ impl<F: Float, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, FastIcaError> for
 
         // If the number of components is not set, we take the minimum of
         // the number of rows and columns
-        let ncomponents = self.ncomponents.unwrap_or_else(|| nsamples.min(nfeatures));
-
-        // The number of components cannot be greater than the minimum of
-        // the number of rows and columns
-        if ncomponents > nsamples.min(nfeatures) {
-            return Err(FastIcaError::InvalidValue(format!(
-                "ncomponents cannot be greater than the min({}, {}), got {}",
-                nsamples, nfeatures, ncomponents
-            )));
+        let max = nsamples.min(nfeatures);
+        let ncomponents = self.ncomponents.unwrap_or(max);
+
+        // The number of components has to be positive and cannot be greater
+        // than the minimum of the number of rows and columns
+        if ncomponents == 0 || ncomponents > max {
+            return Err(FastIcaError::InvalidNComponents {
+                requested: ncomponents,
+                max,
+            });
         }
 
         // We center the input by subtracting the mean of its features
@@ -140,18 +159,43 @@ impl<F: Float, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, FastIcaError> for
         let nsamples_sqrt = F::cast(nsamples).sqrt();
         xwhitened.mapv_inplace(|x| x * nsamples_sqrt);
 
-        // We initialize the de-mixing matrix with a uniform distribution
-        let w: Array2<f64>;
-        if let Some(seed) = self.random_state {
-            let mut rng = Isaac64Rng::seed_from_u64(seed as u64);
-            w = Array::random_using((ncomponents, ncomponents), Uniform::new(0., 1.), &mut rng);
-        } else {
-            w = Array::random((ncomponents, ncomponents), Uniform::new(0., 1.));
+        let mut rng = self
+            .random_state
+            .map(|seed| Isaac64Rng::seed_from_u64(seed as u64));
+        let gaussian = F::cast(self.gfunc.gaussian_expectation());
+
+        let mut best: Option<(Array2<F>, F, F, usize)> = None;
+        for init in 0..self.n_init {
+            // We initialize the de-mixing matrix with a uniform distribution
+            let w: Array2<f64> = match rng.as_mut() {
+                Some(rng) => {
+                    Array::random_using((ncomponents, ncomponents), Uniform::new(0., 1.), rng)
+                }
+                None => Array::random((ncomponents, ncomponents), Uniform::new(0., 1.)),
+            };
+            let w = w.mapv(F::cast);
+
+            // We find the optimized de-mixing matrix
+            let (w, lim) = self.ica_parallel(&xwhitened, &w)?;
+
+            // The recovered sources have unit variance, so their negentropy is
+            // approximated directly with the contrast function
+            let negentropy = self
+                .gfunc
+                .contrast(&w.dot(&xwhitened))
+                .mean_axis(Axis(1))
+                .unwrap()
+                .mapv(|x| (x - gaussian).powi(2))
+                .sum();
+
+            if best.as_ref().map_or(true, |(_, _, best_negentropy, _)| {
+                negentropy > *best_negentropy
+            }) {
+                best = Some((w, lim, negentropy, init));
+            }
         }
-        let mut w = w.mapv(F::cast);
-
-        // We find the optimized de-mixing matrix
-        w = self.ica_parallel(&xwhitened, &w)?;
+        // safe unwrap because there is at least one restart
+        let (w, convergence, _, best_init) = best.unwrap();
 
         // We whiten the de-mixing matrix
         let components = w.dot(&k);
@@ -159,19 +203,26 @@ impl<F: Float, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, FastIcaError> for
         Ok(FittedFastIca {
             mean: xmean,
             components,
+            whitening: k.mapv(|x| x * nsamples_sqrt),
+            gfunc: self.gfunc,
+            best_init,
+            convergence,
         })
     }
 }
 
 impl<F: Float> FastIca<F> {
     // Parallel FastICA, Optimization step
-    fn ica_parallel(&self, x: &Array2<F>, w: &Array2<F>) -> Result<Array2<F>> {
+    //
+    // Returns the de-mixing matrix and the convergence value of the last update
+    fn ica_parallel(&self, x: &Array2<F>, w: &Array2<F>) -> Result<(Array2<F>, F)> {
         let mut w = Self::sym_decorrelation(w)?;
+        let mut lim = F::infinity();
 
         let p = x.ncols() as f64;
 
         for _ in 0..self.max_iter {
-            let (gwtx, g_wtx) = self.gfunc.exec(&w.dot(x))?;
+            let (gwtx, g_wtx) = self.gfunc.exec(&w.dot(x));
 
             let lhs = gwtx.dot(&x.t()).mapv(|x| x / F::cast(p));
             let rhs = &w * &g_wtx.insert_axis(Axis(1));
@@ -179,7 +230,7 @@ impl<F: Float> FastIca<F> {
 
             // `lim` let us check for convergence between the old and
             // new weight values, we want their dot-product to almost equal one
-            let lim = *wnew
+            lim = *wnew
                 .outer_iter()
                 .zip(w.outer_iter())
                 .map(|(a, b)| a.dot(&b))
@@ -197,7 +248,7 @@ impl<F: Float> FastIca<F> {
             }
         }
 
-        Ok(w)
+        Ok((w, lim))
     }
 
     // Symmetric decorrelation
@@ -233,11 +284,69 @@ impl<F: Float> FastIca<F> {
 pub struct FittedFastIca<F> {
     mean: Array1<F>,
     components: Array2<F>,
+    whitening: Array2<F>,
+    gfunc: GFunc,
+    best_init: usize,
+    convergence: F,
 }
 
-impl<F: Float> PredictRef<Array2<F>, Array2<F>> for FittedFastIca<F> {
+impl<F: Float> FittedFastIca<F> {
+    /// Index of the restart which was kept, see [`FastIca::n_init`]
+    pub fn best_init(&self) -> usize {
+        self.best_init
+    }
+
+    /// Convergence value of the kept restart
+    ///
+    /// This is the largest deviation from one of the absolute dot-products between the rows of
+    /// the de-mixing matrix in the last two iterations. It is smaller than the tolerance if the
+    /// restart converged before reaching the maximum number of iterations.
+    pub fn convergence(&self) -> F {
+        self.convergence
+    }
+
+    /// Whitening matrix with shape `(ncomponents, nfeatures)`
+    ///
+    /// The whitening matrix projects centered records on their principal components and scales
+    /// them to unit variance (PCA whitening). It is the first step of the model, the sources are
+    /// recovered by rotating the whitened records.
+    pub fn whitening_matrix(&self) -> &Array2<F> {
+        &self.whitening
+    }
+
+    /// Center and whiten records without applying the ICA rotation
+    ///
+    /// The whitened training records have identity covariance, computed with a normalization by
+    /// the number of samples.
+    pub fn whiten<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let xcentered = x - &self.mean.view().insert_axis(Axis(0));
+        xcentered.dot(&self.whitening.t())
+    }
+
+    /// Approximate negentropy of every recovered component
+    ///
+    /// The sources recovered from `x` are standardized and their negentropy is approximated with
+    /// the contrast function of the [`GFunc`] used during fitting, as
+    /// `J(y) = (E[G(y)] - E[G(v)])^2` with `v` a standard Gaussian variable. Gaussian components
+    /// score close to zero, larger values indicate more non-Gaussian components.
+    pub fn component_negentropy<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let sources = self.predict_ref(x);
+        let std = sources.std_axis(Axis(0), F::zero());
+        let mean = sources.mean_axis(Axis(0)).unwrap();
+        let sources = (sources - &mean.insert_axis(Axis(0))) / &std.insert_axis(Axis(0));
+
+        let gaussian = F::cast(self.gfunc.gaussian_expectation());
+        self.gfunc
+            .contrast(&sources.reversed_axes())
+            .mean_axis(Axis(1))
+            .unwrap()
+            .mapv(|x| (x - gaussian).powi(2))
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>> for FittedFastIca<F> {
     /// Recover the sources
-    fn predict_ref(&self, x: &Array2<F>) -> Array2<F> {
+    fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array2<F> {
         let xcentered = x - &self.mean.view().insert_axis(Axis(0));
         xcentered.dot(&self.components.t())
     }
@@ -249,7 +358,7 @@ impl<F: Float> PredictRef<Array2<F>, Array2<F>> for FittedFastIca<F> {
     derive(Serialize, Deserialize),
     serde(crate = "serde_crate")
 )]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum GFunc {
     Logcosh(f64),
     Exp,
@@ -257,17 +366,55 @@ pub enum GFunc {
 }
 
 impl GFunc {
+    // Check that the parameters of the non-linear function are valid
+    fn validate(&self) -> Result<()> {
+        match self {
+            Self::Logcosh(alpha) if !(1.0..=2.0).contains(alpha) => {
+                Err(FastIcaError::InvalidValue(format!(
+                    "alpha must be between 1 and 2 inclusive, got {}",
+                    alpha
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
     // Function to select the correct non-linear function and execute it
     // returning a tuple, consisting of the first and second derivatives of the
     // non-linear function
-    fn exec<A: Float>(&self, x: &Array2<A>) -> Result<(Array2<A>, Array1<A>)> {
+    fn exec<A: Float>(&self, x: &Array2<A>) -> (Array2<A>, Array1<A>) {
         match self {
-            Self::Cube => Ok(Self::cube(x)),
-            Self::Exp => Ok(Self::exp(x)),
+            Self::Cube => Self::cube(x),
+            Self::Exp => Self::exp(x),
             Self::Logcosh(alpha) => Self::logcosh(x, *alpha),
         }
     }
 
+    // Contrast function `G` itself, whose first derivative is computed by `exec`
+    fn contrast<A: Float>(&self, x: &Array2<A>) -> Array2<A> {
+        match self {
+            Self::Cube => x.mapv(|x| x.powi(4) / A::from(4.).unwrap()),
+            Self::Exp => x.mapv(|x| -(-x.powi(2) / A::from(2.).unwrap()).exp()),
+            Self::Logcosh(alpha) => {
+                let alpha = A::from(*alpha).unwrap();
+                x.mapv(|x| (x * alpha).cosh().ln() / alpha)
+            }
+        }
+    }
+
+    // Expectation of the contrast function for a standard Gaussian variable, computed with the
+    // trapezoidal rule on [-10, 10]
+    fn gaussian_expectation(&self) -> f64 {
+        let npoints = 20001;
+        let grid = Array::linspace(-10., 10., npoints).insert_axis(Axis(0));
+        let step = 20. / (npoints - 1) as f64;
+        let density = grid.mapv(|x: f64| (-x * x / 2.).exp() / (2. * std::f64::consts::PI).sqrt());
+
+        let values = self.contrast(&grid) * density;
+        let values = values.row(0);
+        (values.sum() - (values[0] + values[npoints - 1]) / 2.) * step
+    }
+
     fn cube<A: Float>(x: &Array2<A>) -> (Array2<A>, Array1<A>) {
         (
             x.mapv(|x| x.powi(3)),
@@ -287,21 +434,13 @@ impl GFunc {
         )
     }
 
-    //#[allow(clippy::manual_range_contains)]
-    fn logcosh<A: Float>(x: &Array2<A>, alpha: f64) -> Result<(Array2<A>, Array1<A>)> {
-        //if alpha < 1.0 || alpha > 2.0 {
-        if !(1.0..=2.0).contains(&alpha) {
-            return Err(FastIcaError::InvalidValue(format!(
-                "alpha must be between 1 and 2 inclusive, got {}",
-                alpha
-            )));
-        }
+    fn logcosh<A: Float>(x: &Array2<A>, alpha: f64) -> (Array2<A>, Array1<A>) {
         let alpha = A::from(alpha).unwrap();
 
         let gx = x.mapv(|x| (x * alpha).tanh());
         let g_x = gx.mapv(|x| alpha * (A::from(1.).unwrap() - x.powi(2)));
 
-        Ok((gx, g_x.mean_axis(Axis(1)).unwrap()))
+        (gx, g_x.mean_axis(Axis(1)).unwrap())
     }
 }
 
@@ -310,7 +449,7 @@ mod tests {
     use super::*;
     use linfa::traits::{Fit, Predict};
 
-    use ndarray_rand::rand_distr::StudentT;
+    use ndarray_rand::rand_distr::{Exp, Normal, StudentT};
 
     // Test to make sure the number of components set cannot be greater
     // that the minimum of the number of rows and columns of the input
@@ -319,7 +458,19 @@ mod tests {
         let input = DatasetBase::from(Array::random((4, 4), Uniform::new(0.0, 1.0)));
         let ica = FastIca::new().ncomponents(100);
         let ica = ica.fit(&input);
-        assert!(ica.is_err());
+        assert!(matches!(
+            ica,
+            Err(FastIcaError::InvalidNComponents {
+                requested: 100,
+                max: 4
+            })
+        ));
+
+        let ica = FastIca::new().ncomponents(0).fit(&input);
+        assert!(matches!(
+            ica,
+            Err(FastIcaError::InvalidNComponents { requested: 0, .. })
+        ));
     }
 
     // Test to make sure the alpha value of the `GFunc::Logcosh` is between
@@ -332,6 +483,182 @@ mod tests {
         assert!(ica.is_err());
     }
 
+    // Test that the whitened training data has identity covariance
+    #[test]
+    fn test_whitening() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let mixing = array![[1., 0.5, 0.2], [0.3, 2., 0.1], [0., 0.4, 3.]];
+        let records = Array::random_using((500, 3), Uniform::new(-1., 1.), &mut rng).dot(&mixing);
+        let dataset = DatasetBase::from(records.view());
+
+        for &ncomponents in &[3, 2] {
+            let ica = FastIca::new()
+                .ncomponents(ncomponents)
+                .random_state(42)
+                .fit(&dataset)
+                .unwrap();
+            assert_eq!(ica.whitening_matrix().dim(), (ncomponents, 3));
+
+            let whitened = ica.whiten(&records);
+            assert_eq!(whitened.dim(), (500, ncomponents));
+            let covariance = whitened.t().dot(&whitened) / 500.;
+            let max_diff = (covariance - Array2::<f64>::eye(ncomponents))
+                .mapv(f64::abs)
+                .fold(0., |a: f64, &b| a.max(b));
+            assert!(max_diff < 1e-8);
+        }
+    }
+
+    // Test that fitting on a non-contiguous view gives the same model as on an owned copy
+    #[test]
+    fn test_non_contiguous_view() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let mixing = array![[1., 0.5], [0.3, 2.]];
+        let records = Array::random_using((1000, 2), Uniform::new(-1., 1.), &mut rng).dot(&mixing);
+
+        let view = records.slice(s![..;2, ..]);
+        assert!(!view.is_standard_layout());
+        let owned = view.to_owned();
+
+        let ica = FastIca::new().ncomponents(2).random_state(42);
+        let from_view = ica.fit(&DatasetBase::from(view)).unwrap();
+        let from_owned = ica.fit(&DatasetBase::from(owned.clone())).unwrap();
+
+        let max_diff = (from_view.predict(&view) - from_owned.predict(&owned))
+            .mapv(f64::abs)
+            .fold(0., |a: f64, &b| a.max(b));
+        assert!(max_diff < 1e-8);
+
+        let max_diff = (from_view.component_negentropy(&view)
+            - from_owned.component_negentropy(&owned))
+        .mapv(f64::abs)
+        .fold(0., |a: f64, &b| a.max(b));
+        assert!(max_diff < 1e-8);
+    }
+
+    // Test that a non-Gaussian source has a larger negentropy than a Gaussian one
+    #[test]
+    fn test_component_negentropy() {
+        let nsamples = 2000;
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let uniform = Array::random_using(
+            (nsamples, 1),
+            Uniform::new(-(3f64.sqrt()), 3f64.sqrt()),
+            &mut rng,
+        );
+        let gaussian = Array::random_using((nsamples, 1), Normal::new(0., 1.).unwrap(), &mut rng);
+        let sources = concatenate![Axis(1), uniform, gaussian];
+
+        let mixing = array![[1., 0.5], [0.3, 2.]];
+        let records = sources.dot(&mixing);
+
+        let ica = FastIca::new()
+            .ncomponents(2)
+            .random_state(42)
+            .fit(&DatasetBase::from(records.view()))
+            .unwrap();
+        let negentropy = ica.component_negentropy(&records);
+        assert_eq!(negentropy.len(), 2);
+        assert!(negentropy.iter().all(|x| *x >= 0.));
+
+        // find the recovered component matching the uniform source
+        let output = ica.predict(&records);
+        let correlation = |i: usize| {
+            let col = output.column(i);
+            let col = &col - col.mean().unwrap();
+            (col.dot(&uniform.column(0)) / col.dot(&col).sqrt()).abs()
+        };
+        let (non_gaussian, near_gaussian) = if correlation(0) > correlation(1) {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+
+        assert!(negentropy[non_gaussian] > 10. * negentropy[near_gaussian]);
+    }
+
+    #[test]
+    fn test_n_init_err() {
+        let input = DatasetBase::from(Array::random((4, 4), Uniform::new(0.0, 1.0)));
+        let ica = FastIca::new().n_init(0).fit(&input);
+        assert!(ica.is_err());
+    }
+
+    // Mean absolute correlation between the true and the recovered sources, for the best matching
+    // permutation of the recovered sources
+    fn source_recovery(sources: &Array2<f64>, recovered: &Array2<f64>) -> f64 {
+        let correlation = |i: usize, j: usize| {
+            let (a, b) = (sources.column(i), recovered.column(j));
+            let a = &a - a.mean().unwrap();
+            let b = &b - b.mean().unwrap();
+            (a.dot(&b) / (a.dot(&a) * b.dot(&b)).sqrt()).abs()
+        };
+
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        permutations
+            .iter()
+            .map(|perm| (0..3).map(|i| correlation(i, perm[i])).sum::<f64>() / 3.)
+            .fold(0., f64::max)
+    }
+
+    // Test that multiple restarts recover sources at least as well as a single one
+    #[test]
+    fn test_n_init() {
+        let nsamples = 1000;
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        // the difference of two exponential variables is Laplace distributed
+        let laplace = Array::random_using((nsamples, 1), Exp::new(1.).unwrap(), &mut rng)
+            - Array::random_using((nsamples, 1), Exp::new(1.).unwrap(), &mut rng);
+        let sources = concatenate![
+            Axis(1),
+            Array::random_using((nsamples, 1), Uniform::new(-1., 1.), &mut rng),
+            laplace,
+            Array::random_using((nsamples, 1), Uniform::new(-1f64, 1.), &mut rng)
+                .mapv(|x| x.powi(3))
+        ];
+        let mixing = array![[1., 0.9, 0.8], [0.9, 1., 0.9], [0.8, 0.9, 1.]];
+        let records = sources.dot(&mixing);
+        let dataset = DatasetBase::from(records.view());
+
+        // a single iteration makes the result depend on the initialization, the first restart
+        // uses the same initialization as the single run
+        let single = FastIca::new()
+            .max_iter(1)
+            .random_state(42)
+            .fit(&dataset)
+            .unwrap();
+        let restarts = FastIca::new()
+            .max_iter(1)
+            .n_init(10)
+            .random_state(42)
+            .fit(&dataset)
+            .unwrap();
+
+        assert_eq!(single.best_init(), 0);
+        assert!(restarts.best_init() < 10);
+        assert!(restarts.convergence().is_finite());
+
+        let single_recovery = source_recovery(&sources, &single.predict(&records));
+        let restarts_recovery = source_recovery(&sources, &restarts.predict(&records));
+        assert!(restarts_recovery > 0.9);
+        assert!(restarts_recovery >= single_recovery - 0.01);
+
+        // with enough iterations every restart converges
+        let converged = FastIca::new()
+            .n_init(3)
+            .random_state(42)
+            .fit(&dataset)
+            .unwrap();
+        assert!(converged.convergence() < 1e-4);
+    }
+
     // Helper macro that produces test-cases with the pattern test_fast_ica_*
     macro_rules! fast_ica_tests {
         ($($name:ident: $gfunc:expr,)*) => {
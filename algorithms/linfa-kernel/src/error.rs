@@ -0,0 +1,17 @@
+use ndarray_linalg::error::LinalgError;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, KernelError>;
+
+/// An error when approximating a kernel
+#[derive(Error, Debug)]
+pub enum KernelError {
+    /// When any of the hyperparameters are set the wrong value
+    #[error("Invalid value encountered: {0}")]
+    InvalidValue(String),
+    /// Errors encountered during linear algebra operations
+    #[error("Linalg Error: {0}")]
+    Linalg(#[from] LinalgError),
+    #[error(transparent)]
+    LinfaError(#[from] linfa::error::Error),
+}
@@ -9,17 +9,29 @@
 //!
 //! linfa-kernel currently provides an implementation of kernel methods for RBF and polynomial kernels,
 //! with sparse or dense representation. Further a k-neighbour approximation allows to reduce the kernel
-//! matrix size.
+//! matrix size. The bandwidth of the RBF kernel can be estimated from the training data with the
+//! [`Gamma`](enum.Gamma.html) heuristics.
 //!
-//! Low-rank kernel approximation are currently missing, but are on the roadmap. Examples for these are the
-//! [Nyström approximation](https://www.jmlr.org/papers/volume6/drineas05a/drineas05a.pdf) or [Quasi Random Fourier Features](http://www-personal.umich.edu/~aniketde/processed_md/Stats608_Aniketde.pdf).
-
+//! For large datasets the [Nyström approximation](https://www.jmlr.org/papers/volume6/drineas05a/drineas05a.pdf)
+//! constructs a low-rank feature map, which can be consumed by linear models. It depends on
+//! `ndarray-linalg` and is only available with the `nystroem` feature. Other low-rank
+//! approximations, like [Quasi Random Fourier Features](http://www-personal.umich.edu/~aniketde/processed_md/Stats608_Aniketde.pdf),
+//! are on the roadmap.
+
+#[cfg(feature = "nystroem")]
+pub mod error;
 pub mod inner;
+#[cfg(feature = "nystroem")]
+pub mod nystroem;
 mod sparse;
 
+#[cfg(feature = "nystroem")]
+pub use error::KernelError;
 pub use inner::{Inner, KernelInner};
 use ndarray::prelude::*;
 use ndarray::Data;
+#[cfg(feature = "nystroem")]
+pub use nystroem::{Nystroem, NystroemParams};
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
 use sprs::{CsMat, CsMatView};
@@ -202,6 +214,10 @@ impl<F: Float, K1: Inner<Elem = F>, K2: Inner<Elem = F>> KernelBase<K1, K2> {
 
 impl<'a, F: Float> Kernel<F> {
     pub fn new(dataset: ArrayView2<'a, F>, method: KernelMethod<F>, kind: KernelType) -> Kernel<F> {
+        // data dependent parameters, like the bandwidth heuristics of the RBF kernel, are
+        // resolved once from the training data and then stored with the kernel
+        let method = method.resolve(&dataset);
+
         let inner = match kind {
             KernelType::Dense => KernelInner::Dense(dense_from_fn(&dataset, &method)),
             KernelType::Sparse(k) => KernelInner::Sparse(sparse_from_fn(&dataset, k, &method)),
@@ -246,13 +262,65 @@ impl<F: Float, K1: Inner<Elem = F>, K2: Inner<Elem = F>> Records for KernelBase<
     }
 }
 
+/// Bandwidth selection for the RBF kernel
+///
+/// The RBF kernel `exp(-gamma * norm(x - x'))` is sensitive to the choice of `gamma`. Apart
+/// from an explicit value, two heuristics are available which derive `gamma` from the training
+/// data when the kernel is constructed:
+///
+/// - Scale: `gamma = 1 / (nfeatures * var(X))`, where the variance is taken over all elements
+/// - Auto: `gamma = 1 / nfeatures`
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gamma<F> {
+    /// `1 / (nfeatures * var(X))`, falls back to `1` for constant data
+    Scale,
+    /// `1 / nfeatures`
+    Auto,
+    /// An explicit value for `gamma`
+    Value(F),
+}
+
+impl<F: Float> Gamma<F> {
+    /// Computes the value of `gamma` for a matrix of records (#records, #features)
+    ///
+    /// ## Panics
+    ///
+    /// If `Scale` or `Auto` are used with records without any features
+    pub fn value<D: Data<Elem = F>>(&self, records: &ArrayBase<D, Ix2>) -> F {
+        match *self {
+            Gamma::Value(gamma) => gamma,
+            Gamma::Auto => F::one() / F::cast(records.ncols()),
+            Gamma::Scale => {
+                let nelems = F::cast(records.len());
+                let mean = records.sum() / nelems;
+                let var = records.iter().map(|x| (*x - mean) * (*x - mean)).sum::<F>() / nelems;
+
+                if var > F::zero() {
+                    F::one() / (F::cast(records.ncols()) * var)
+                } else {
+                    F::one()
+                }
+            }
+        }
+    }
+}
+
 /// The inner product definition used by a kernel.
 ///
-/// There are three methods available:
+/// There are seven methods available:
 ///
 /// - Gaussian(eps):  `d(x, x') = exp(-norm(x - x')/eps) `
+/// - Rbf(gamma): `d(x, x') = exp(-gamma * norm(x - x'))`, with `gamma` optionally estimated from the data
 /// - Linear: `d(x, x') = <x, x'>`
 /// - Polynomial(constant, degree):  `d(x, x') = (<x, x'> + costant)^(degree)`
+/// - Sigmoid(gamma, constant): `d(x, x') = tanh(gamma * <x, x'> + constant)`
+/// - Laplacian(gamma): `d(x, x') = exp(-gamma * sum_i |x_i - x'_i|)`
+/// - ChiSquared(gamma): `d(x, x') = exp(-gamma * sum_i (x_i - x'_i)^2 / (x_i + x'_i))`
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -262,26 +330,86 @@ impl<F: Float, K1: Inner<Elem = F>, K2: Inner<Elem = F>> Records for KernelBase<
 pub enum KernelMethod<F> {
     /// Gaussian(eps): exp(-norm(x - x')/eps)
     Gaussian(F),
+    /// Rbf(gamma): exp(-gamma * norm(x - x')), resolved to `Gaussian(1/gamma)` when a kernel
+    /// is constructed from training data
+    Rbf(Gamma<F>),
     /// Euclidean inner product
     Linear,
     /// Polynomial(constant, degree):  ` (<x, x'> + costant)^(degree)`
     Polynomial(F, F),
+    /// Sigmoid(gamma, constant): `tanh(gamma * <x, x'> + constant)`
+    Sigmoid(F, F),
+    /// Laplacian(gamma): `exp(-gamma * sum_i |x_i - x'_i|)`
+    Laplacian(F),
+    /// ChiSquared(gamma): `exp(-gamma * sum_i (x_i - x'_i)^2 / (x_i + x'_i))`, requires
+    /// non-negative features, like histograms, and panics when built from negative records
+    ChiSquared(F),
 }
 
 impl<F: Float> KernelMethod<F> {
+    /// Computes the inner product between two points
+    ///
+    /// An RBF kernel with a data dependent `gamma`, which was not resolved with `resolve`, falls
+    /// back to `Gamma::Auto`, as it only depends on the number of features.
+    ///
+    /// ## Panics
+    ///
+    /// In debug builds, if a chi-squared kernel encounters negative features
     pub fn distance(&self, a: ArrayView1<F>, b: ArrayView1<F>) -> F {
         match *self {
-            KernelMethod::Gaussian(eps) => {
+            KernelMethod::Gaussian(eps) => (-squared_distance(a, b) / eps).exp(),
+            KernelMethod::Rbf(Gamma::Value(gamma)) => (-squared_distance(a, b) * gamma).exp(),
+            KernelMethod::Rbf(_) => (-squared_distance(a, b) / F::cast(a.len())).exp(),
+            KernelMethod::Linear => a.mul(&b).sum(),
+            KernelMethod::Polynomial(c, d) => (a.mul(&b).sum() + c).powf(d),
+            KernelMethod::Sigmoid(gamma, c) => (a.mul(&b).sum() * gamma + c).tanh(),
+            KernelMethod::Laplacian(gamma) => {
                 let distance = a
                     .iter()
                     .zip(b.iter())
-                    .map(|(x, y)| (*x - *y) * (*x - *y))
+                    .map(|(x, y)| (*x - *y).abs())
                     .sum::<F>();
 
-                (-distance / eps).exp()
+                (-distance * gamma).exp()
             }
-            KernelMethod::Linear => a.mul(&b).sum(),
-            KernelMethod::Polynomial(c, d) => (a.mul(&b).sum() + c).powf(d),
+            KernelMethod::ChiSquared(gamma) => {
+                debug_assert!(
+                    a.iter().chain(b.iter()).all(|x| *x >= F::zero()),
+                    "the chi-squared kernel requires non-negative features"
+                );
+
+                // terms where both entries are zero don't contribute to the distance
+                let distance = a
+                    .iter()
+                    .zip(b.iter())
+                    .filter(|(x, y)| **x != F::zero() || **y != F::zero())
+                    .map(|(x, y)| (*x - *y) * (*x - *y) / (*x + *y))
+                    .sum::<F>();
+
+                (-distance * gamma).exp()
+            }
+        }
+    }
+
+    /// Resolves data dependent parameters with the training records
+    ///
+    /// An RBF kernel is turned into a Gaussian kernel with `eps = 1/gamma`, where `gamma` is
+    /// computed from `records`. All other methods are returned unchanged.
+    ///
+    /// ## Panics
+    ///
+    /// If the method is a chi-squared kernel and `records` contains negative features
+    pub fn resolve<D: Data<Elem = F>>(&self, records: &ArrayBase<D, Ix2>) -> KernelMethod<F> {
+        match self {
+            KernelMethod::Rbf(gamma) => KernelMethod::Gaussian(F::one() / gamma.value(records)),
+            KernelMethod::ChiSquared(_) => {
+                assert!(
+                    records.iter().all(|x| *x >= F::zero()),
+                    "the chi-squared kernel requires non-negative features"
+                );
+                self.clone()
+            }
+            method => method.clone(),
         }
     }
 
@@ -290,6 +418,13 @@ impl<F: Float> KernelMethod<F> {
     }
 }
 
+fn squared_distance<F: Float>(a: ArrayView1<F>, b: ArrayView1<F>) -> F {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y) * (*x - *y))
+        .sum::<F>()
+}
+
 /// Defines the set of parameters needed to build a kernel
 pub struct KernelParams<F> {
     /// Whether to construct a dense or sparse kernel
@@ -552,8 +687,9 @@ fn sparse_from_fn<F: Float, D: Data<Elem = F>>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_abs_diff_eq;
     use linfa::Dataset;
-    use ndarray::{Array1, Array2};
+    use ndarray::{array, Array1, Array2};
     use std::f64::consts;
 
     #[test]
@@ -662,6 +798,52 @@ mod tests {
         assert!(((distance - expected) as f64).abs() <= f64::EPSILON);
     }
 
+    #[test]
+    fn gamma_test() {
+        // all elements have mean 2.0 and variance 4.0
+        let records = array![[0., 4.], [4., 0.], [0., 4.], [4., 0.]];
+
+        assert_abs_diff_eq!(Gamma::Scale.value(&records), 1. / (2. * 4.));
+        assert_abs_diff_eq!(Gamma::Auto.value(&records), 1. / 2.);
+        assert_abs_diff_eq!(Gamma::Value(0.3).value(&records), 0.3);
+
+        // constant data falls back to unit bandwidth
+        let records = Array2::<f64>::ones((3, 2));
+        assert_abs_diff_eq!(Gamma::Scale.value(&records), 1.);
+    }
+
+    #[test]
+    fn rbf_resolve_test() {
+        let records = array![[0., 4.], [4., 0.], [0., 4.], [4., 0.]];
+
+        let kernel = Kernel::params()
+            .method(KernelMethod::Rbf(Gamma::Scale))
+            .transform(&records);
+
+        // the kernel stores the resolved method, which is then used at prediction time
+        match kernel.method {
+            KernelMethod::Gaussian(eps) => assert_abs_diff_eq!(eps, 8.),
+            _ => panic!("RBF kernel should be resolved to a Gaussian kernel"),
+        }
+
+        // an explicit gamma is the inverse of the Gaussian bandwidth
+        let p1 = array![1., 1.];
+        let p2 = array![2., 2.];
+        assert_abs_diff_eq!(
+            KernelMethod::Rbf(Gamma::Value(10.)).distance(p1.view(), p2.view()),
+            KernelMethod::Gaussian(0.1).distance(p1.view(), p2.view())
+        );
+        assert_abs_diff_eq!(kernel.column(0)[1], (-32.0f64 / 8.).exp());
+
+        // unresolved heuristics fall back to `gamma = 1 / nfeatures` instead of panicking
+        for gamma in &[Gamma::Scale, Gamma::Auto] {
+            assert_abs_diff_eq!(
+                KernelMethod::Rbf(gamma.clone()).distance(p1.view(), p2.view()),
+                (-1.0f64).exp()
+            );
+        }
+    }
+
     #[test]
     fn poly2_test() {
         let pol_0 = KernelMethod::Polynomial(0., 2.);
@@ -696,6 +878,66 @@ mod tests {
         assert!(((distance - expected) as f64).abs() <= f64::EPSILON);
     }
 
+    #[test]
+    fn sigmoid_test() {
+        let sigmoid = KernelMethod::Sigmoid(0.5, 1.);
+
+        let p1 = array![1., 2.];
+        let p2 = array![3., -1.];
+        // tanh(0.5 * (3 - 2) + 1)
+        assert_abs_diff_eq!(sigmoid.distance(p1.view(), p2.view()), 1.5f64.tanh());
+
+        let p1 = array![0., 0.];
+        // tanh(0.5 * 0 + 1)
+        assert_abs_diff_eq!(sigmoid.distance(p1.view(), p2.view()), 1f64.tanh());
+    }
+
+    #[test]
+    fn laplacian_test() {
+        let laplacian = KernelMethod::Laplacian(0.5);
+
+        let p1 = array![1., 2.];
+        assert_abs_diff_eq!(laplacian.distance(p1.view(), p1.view()), 1.);
+
+        let p2 = array![3., -1.];
+        // exp(-0.5 * (|1 - 3| + |2 + 1|))
+        assert_abs_diff_eq!(
+            laplacian.distance(p1.view(), p2.view()),
+            (-2.5f64).exp(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn chi_squared_test() {
+        let chi2 = KernelMethod::ChiSquared(1.);
+
+        let p1 = array![1., 0., 3.];
+        assert_abs_diff_eq!(chi2.distance(p1.view(), p1.view()), 1.);
+
+        let p2 = array![3., 0., 1.];
+        // exp(-((1 - 3)^2 / 4 + (3 - 1)^2 / 4)), the second entry is skipped
+        assert_abs_diff_eq!(
+            chi2.distance(p1.view(), p2.view()),
+            (-2f64).exp(),
+            epsilon = 1e-12
+        );
+
+        // the kernel matrix uses the same inner product
+        let records = array![[1., 0., 3.], [3., 0., 1.]];
+        let kernel = Kernel::params().method(chi2).transform(&records);
+        assert_abs_diff_eq!(kernel.column(0)[1], (-2f64).exp(), epsilon = 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "the chi-squared kernel requires non-negative features")]
+    fn chi_squared_rejects_negative_records() {
+        let records = array![[1., -1., 3.], [3., 1., 1.]];
+        Kernel::params()
+            .method(KernelMethod::ChiSquared(1.))
+            .transform(&records);
+    }
+
     #[test]
     fn test_kernel_dot() {
         let input_vec: Vec<f64> = (0..100).map(|v| v as f64 * 0.1).collect();
@@ -888,6 +1130,9 @@ mod tests {
             KernelMethod::Linear,
             KernelMethod::Gaussian(0.1),
             KernelMethod::Polynomial(1., 2.),
+            KernelMethod::Sigmoid(0.1, 1.),
+            KernelMethod::Laplacian(0.1),
+            KernelMethod::ChiSquared(0.1),
         ];
         for method in methods {
             let kernel_ref = Kernel::new(input.records().view(), method.clone(), k_type.clone());
@@ -919,6 +1164,9 @@ mod tests {
             KernelMethod::Linear,
             KernelMethod::Gaussian(0.1),
             KernelMethod::Polynomial(1., 2.),
+            KernelMethod::Sigmoid(0.1, 1.),
+            KernelMethod::Laplacian(0.1),
+            KernelMethod::ChiSquared(0.1),
         ];
         for method in methods {
             let kernel_ref = Kernel::new(*input.records(), method.clone(), k_type.clone());
@@ -944,6 +1192,9 @@ mod tests {
             KernelMethod::Linear,
             KernelMethod::Gaussian(0.1),
             KernelMethod::Polynomial(1., 2.),
+            KernelMethod::Sigmoid(0.1, 1.),
+            KernelMethod::Laplacian(0.1),
+            KernelMethod::ChiSquared(0.1),
         ];
         for method in methods {
             let kernel_ref = Kernel::new(input.view(), method.clone(), k_type.clone());
@@ -969,6 +1220,9 @@ mod tests {
             KernelMethod::Linear,
             KernelMethod::Gaussian(0.1),
             KernelMethod::Polynomial(1., 2.),
+            KernelMethod::Sigmoid(0.1, 1.),
+            KernelMethod::Laplacian(0.1),
+            KernelMethod::ChiSquared(0.1),
         ];
         for method in methods {
             let kernel_ref = Kernel::new(input, method.clone(), k_type.clone());
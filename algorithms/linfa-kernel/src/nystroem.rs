@@ -0,0 +1,248 @@
+//! Nyström approximation of kernel matrices
+//!
+//! The kernel matrix of a dataset with `n` samples has `n²` entries and gets quickly too large for
+//! exact kernel methods. The [Nyström method](https://www.jmlr.org/papers/volume6/drineas05a/drineas05a.pdf)
+//! samples `m ≪ n` landmark points and constructs a feature map `phi` with `m` dimensions, such
+//! that the inner product `<phi(x), phi(x')>` approximates the kernel function `d(x, x')`.
+//! Linear models trained on the transformed features then approximate their kernelized
+//! counterparts at a fraction of the cost.
+//!
+//! # Example
+//!
+//! ```rust, ignore
+//! use linfa::traits::{Fit, Predict};
+//! use linfa_kernel::{Gamma, KernelMethod, Nystroem};
+//!
+//! let features = Nystroem::params(100)
+//!     .method(KernelMethod::Rbf(Gamma::Scale))
+//!     .fit(&dataset)?
+//!     .predict(&dataset);
+//! ```
+use linfa::dataset::{WithLapack, WithoutLapack};
+use linfa::{
+    dataset::Records,
+    traits::{Fit, PredictRef, Transformer},
+    DatasetBase, Float,
+};
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use ndarray_linalg::{eigh::EighInto, Scalar, UPLO};
+use ndarray_rand::rand::{seq::index, Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use crate::error::{KernelError, Result};
+use crate::{dense_from_fn, KernelMethod};
+
+/// Nyström approximation hyperparameters
+///
+/// The number of landmark points `n_components` trades approximation quality against the
+/// dimensionality of the feature map. The landmarks are sampled uniformly without replacement
+/// from the training records with the random number generator `rng`.
+pub struct NystroemParams<F, R> {
+    n_components: usize,
+    method: KernelMethod<F>,
+    rng: R,
+}
+
+impl<F: Float, R: Rng + Clone> NystroemParams<F, R> {
+    /// Set the kernel function which is approximated
+    pub fn method(mut self, method: KernelMethod<F>) -> Self {
+        self.method = method;
+
+        self
+    }
+
+    /// Set the random number generator used to sample landmark points
+    pub fn with_rng<R2: Rng + Clone>(self, rng: R2) -> NystroemParams<F, R2> {
+        NystroemParams {
+            n_components: self.n_components,
+            method: self.method,
+            rng,
+        }
+    }
+
+    /// Validates the parameters against the number of training samples
+    pub fn validate(&self, nsamples: usize) -> Result<()> {
+        if self.n_components == 0 || self.n_components > nsamples {
+            return Err(KernelError::InvalidValue(format!(
+                "`n_components` should be between 1 and the number of samples {}, but is {}",
+                nsamples, self.n_components
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Fitted Nyström feature map
+///
+/// The model contains the sampled landmark points and the normalization of their kernel
+/// matrix. Predicting on records with `n` samples returns the approximated feature map with
+/// dimensionality `(n, n_components)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct Nystroem<F> {
+    components: Array2<F>,
+    normalization: Array2<F>,
+    method: KernelMethod<F>,
+}
+
+impl<F: Float> Nystroem<F> {
+    /// Create default parameter set
+    ///
+    /// The RBF kernel with `Gamma::Scale` is used by default and landmark points are sampled
+    /// with a seeded random number generator.
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the number of landmark points and dimensionality of the feature map
+    pub fn params(n_components: usize) -> NystroemParams<F, Isaac64Rng> {
+        Self::params_with_rng(n_components, Isaac64Rng::seed_from_u64(42))
+    }
+
+    /// Create default parameter set with a custom random number generator
+    pub fn params_with_rng<R: Rng + Clone>(n_components: usize, rng: R) -> NystroemParams<F, R> {
+        NystroemParams {
+            n_components,
+            method: KernelMethod::Rbf(crate::Gamma::Scale),
+            rng,
+        }
+    }
+
+    /// Return the sampled landmark points with dimensionality (n_components, n_features)
+    pub fn components(&self) -> &Array2<F> {
+        &self.components
+    }
+
+    /// Return the kernel method, with data dependent parameters resolved from the training data
+    pub fn method(&self) -> &KernelMethod<F> {
+        &self.method
+    }
+}
+
+impl<F: Float, R: Rng + Clone, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, KernelError>
+    for NystroemParams<F, R>
+{
+    type Object = Nystroem<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Nystroem<F>> {
+        self.validate(dataset.nsamples())?;
+
+        let records = dataset.records();
+        let method = self.method.resolve(records);
+
+        // sample landmark points without replacement
+        let mut rng = self.rng.clone();
+        let indices = index::sample(&mut rng, records.nsamples(), self.n_components).into_vec();
+        let components = records.select(Axis(0), &indices);
+
+        // the feature map is `K_nm K_mm^{-1/2}`, calculate the inverse square root of the
+        // symmetric kernel matrix between the landmark points
+        let kernel = dense_from_fn(&components, &method).with_lapack();
+        let (vals, vecs) = kernel.eigh_into(UPLO::Lower)?;
+        let vals: Array1<F::Lapack> = vals.mapv(Scalar::from_real);
+        let (vals, vecs): (Array1<F>, Array2<F>) = (vals.without_lapack(), vecs.without_lapack());
+
+        // cut small eigenvalues to avoid numerical problems
+        let vals = vals.mapv(|x| x.max(F::cast(1e-12)).sqrt().recip());
+        let normalization = (&vecs * &vals.insert_axis(Axis(0))).dot(&vecs.t());
+
+        Ok(Nystroem {
+            components,
+            normalization,
+            method,
+        })
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>> for Nystroem<F> {
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let kernel = Array2::from_shape_fn(
+            (records.nsamples(), self.components.nsamples()),
+            |(i, j)| self.method.distance(records.row(i), self.components.row(j)),
+        );
+
+        kernel.dot(&self.normalization.t())
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>> for Nystroem<F>
+{
+    fn transform(&self, ds: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let DatasetBase {
+            records,
+            targets,
+            weights,
+            ..
+        } = ds;
+
+        let new_records = self.predict_ref(&records);
+
+        DatasetBase::new(new_records, targets).with_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gamma, Kernel};
+    use approx::assert_abs_diff_eq;
+    use linfa::traits::Predict;
+    use ndarray_rand::{rand_distr::Uniform, RandomExt};
+
+    #[test]
+    fn reconstructs_kernel_with_all_samples() -> Result<()> {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let records = Array2::random_using((30, 3), Uniform::new(-1., 1.), &mut rng);
+        let dataset = DatasetBase::from(records.clone());
+
+        // with all samples as landmark points the approximation is exact
+        let features = Nystroem::params(30)
+            .method(KernelMethod::Rbf(Gamma::Value(0.5)))
+            .fit(&dataset)?
+            .predict(&records);
+        assert_eq!(features.dim(), (30, 30));
+
+        let kernel = Kernel::params()
+            .method(KernelMethod::Rbf(Gamma::Value(0.5)))
+            .transform(&records);
+
+        for i in 0..30 {
+            for (j, val) in kernel.column(i).into_iter().enumerate() {
+                assert_abs_diff_eq!(features.row(i).dot(&features.row(j)), val, epsilon = 1e-6);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reduces_dimensionality() -> Result<()> {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let records = Array2::random_using((100, 3), Uniform::new(-1., 1.), &mut rng);
+        let dataset = DatasetBase::from(records);
+
+        let model = Nystroem::params_with_rng(10, rng).fit(&dataset)?;
+        assert_eq!(model.components().dim(), (10, 3));
+        assert!(matches!(model.method(), KernelMethod::Gaussian(_)));
+
+        let dataset = model.transform(dataset);
+        assert_eq!(dataset.records().dim(), (100, 10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_n_components() {
+        let dataset = DatasetBase::from(Array2::<f64>::zeros((5, 2)));
+
+        assert!(Nystroem::params(0).fit(&dataset).is_err());
+        assert!(Nystroem::params(6).fit(&dataset).is_err());
+    }
+}
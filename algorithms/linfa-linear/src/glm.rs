@@ -12,11 +12,15 @@ use argmin::core::{ArgminOp, Executor};
 use argmin::solver::linesearch::MoreThuenteLineSearch;
 use argmin::solver::quasinewton::LBFGS;
 use ndarray::{array, concatenate, s};
-use ndarray::{Array, Array1, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
+use ndarray::{Array, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
+use ndarray_linalg::solveh::InverseH;
 use serde::{Deserialize, Serialize};
 
+use linfa::dataset::{AsTargets, WithLapack, WithoutLapack};
+use linfa::distribution::normal_quantile;
+use linfa::metrics::{DefaultMetric, R2};
 use linfa::traits::*;
-use linfa::{dataset::AsTargets, DatasetBase};
+use linfa::DatasetBase;
 
 /// Generalized Linear Model (GLM) with a Tweedie distribution
 ///
@@ -85,12 +89,23 @@ impl TweedieRegressor {
         self
     }
 
-    /// Specifies whether a bias or intercept should be added to the model
-    pub fn fit_intercept(mut self, fit_intercept: bool) -> Self {
-        self.fit_intercept = fit_intercept;
+    /// Configure the regressor to fit an intercept, defaults to `true`.
+    ///
+    /// The intercept is never penalized by `alpha`. Disabling it forces the linear predictor
+    /// through the origin, which is useful when the data is already centered.
+    pub fn with_intercept(mut self, with_intercept: bool) -> Self {
+        self.fit_intercept = with_intercept;
         self
     }
 
+    /// Specifies whether a bias or intercept should be added to the model
+    ///
+    /// Same as [`with_intercept`](#method.with_intercept), which is consistent with the other
+    /// linear models.
+    pub fn fit_intercept(self, fit_intercept: bool) -> Self {
+        self.with_intercept(fit_intercept)
+    }
+
     /// The power determines the underlying target distribution
     pub fn power(mut self, power: f64) -> Self {
         self.power = power;
@@ -185,27 +200,34 @@ impl<A: Float, D: Data<Elem = A>, T: AsTargets<Elem = A>> Fit<ArrayBase<D, Ix2>,
         // For our problem we set m as 7
         let solver = LBFGS::new(linesearch, 7).with_tol_grad(A::from(self.tol).unwrap());
 
-        let result = Executor::new(problem, solver, ArgminParam(coef))
+        let result = Executor::new(problem.clone(), solver, ArgminParam(coef))
             .max_iters(self.max_iter as u64)
             .run()?;
         coef = result.state.get_best_param().as_array().to_owned();
 
+        let (information, dispersion) = problem.fisher_information(&coef);
+
         if self.fit_intercept {
             Ok(FittedTweedieRegressor {
                 coef: coef.slice(s![1..]).to_owned(),
                 intercept: *coef.get(0).unwrap(),
                 link,
+                information,
+                dispersion,
             })
         } else {
             Ok(FittedTweedieRegressor {
                 coef: coef.to_owned(),
                 intercept: A::from(0.).unwrap(),
                 link,
+                information,
+                dispersion,
             })
         }
     }
 }
 
+#[derive(Clone)]
 struct TweedieProblem<'a, A: Float> {
     x: ArrayView2<'a, A>,
     y: ArrayView1<'a, A>,
@@ -232,6 +254,43 @@ impl<'a, A: Float> TweedieProblem<'a, A> {
 
         (self.link.inverse(&lin_pred), lin_pred, offset)
     }
+
+    // Computes the Fisher information of the penalized objective at `p`, together with the
+    // estimated dispersion of the distribution
+    //
+    // The information is `X^T W X + alpha * I` with the weights `W = h'(lin_pred)^2 / V(ypred)`
+    // given by the derivative of the inverse link and the variance function of the Tweedie
+    // distribution. The dispersion is one for Poisson distributed targets and estimated from the
+    // Pearson residuals otherwise.
+    fn fisher_information(&self, p: &Array1<A>) -> (Array2<A>, A) {
+        let (ypred, lin_pred, offset) = self.ypred(p);
+        let nsamples = self.x.nrows();
+
+        let der = self.link.inverse_derviative(&lin_pred);
+        let variance = self.dist.unit_variance(ypred.view());
+        let weights = &der * &der / &variance;
+
+        let design = if self.fit_intercept {
+            concatenate![Axis(1), Array2::ones((nsamples, 1)), self.x]
+        } else {
+            self.x.to_owned()
+        };
+
+        let mut information = (&design.t() * &weights).dot(&design);
+        for i in offset..information.nrows() {
+            information[(i, i)] += A::from(self.alpha).unwrap();
+        }
+
+        let dispersion = if self.dist.fixed_dispersion() {
+            A::one()
+        } else {
+            let dof = nsamples.saturating_sub(design.ncols()).max(1);
+            let pearson = (&self.y - &ypred).mapv(|x| x * x) / &variance;
+            pearson.sum() / A::from(dof).unwrap()
+        };
+
+        (information, dispersion)
+    }
 }
 
 impl<'a, A: Float> ArgminOp for TweedieProblem<'a, A> {
@@ -298,6 +357,80 @@ pub struct FittedTweedieRegressor<A> {
     /// Intercept or bias added to the linear model
     pub intercept: A,
     link: Link,
+    information: Array2<A>,
+    dispersion: A,
+}
+
+impl<A: Float> FittedTweedieRegressor<A> {
+    /// Estimated covariance matrix of the parameters
+    ///
+    /// The covariance is the scaled inverse of the Fisher information at the solution. If an
+    /// intercept was fitted, it corresponds to the first row and column. For a penalized model
+    /// (`alpha` > 0) the penalty is included in the information matrix.
+    pub fn covariance(&self) -> Result<Array2<A>> {
+        let covariance = self.information.clone().with_lapack().invh()?;
+
+        Ok(covariance.without_lapack() * self.dispersion)
+    }
+
+    /// Standard errors of the estimated coefficients
+    pub fn coef_standard_errors(&self) -> Result<Array1<A>> {
+        let offset = self.information.nrows() - self.coef.len();
+
+        Ok(self
+            .covariance()?
+            .diag()
+            .slice(s![offset..])
+            .mapv(num_traits::Float::sqrt))
+    }
+
+    /// Standard error of the estimated intercept, zero if no intercept was fitted
+    pub fn intercept_standard_error(&self) -> Result<A> {
+        if self.information.nrows() == self.coef.len() {
+            return Ok(A::zero());
+        }
+
+        Ok(num_traits::Float::sqrt(self.covariance()?[(0, 0)]))
+    }
+
+    /// Confidence interval of the predicted mean at significance level `alpha`
+    ///
+    /// The interval is constructed on the scale of the linear predictor from the normal
+    /// approximation of the parameter estimates, and then mapped through the inverse link
+    /// function. Returns the lower and upper bounds for each sample, for example `alpha = 0.05`
+    /// gives a 95% interval.
+    pub fn predict_interval<D: Data<Elem = A>>(
+        &self,
+        x: &ArrayBase<D, Ix2>,
+        alpha: f64,
+    ) -> Result<(Array1<A>, Array1<A>)> {
+        if alpha <= 0. || alpha >= 1. {
+            return Err(linfa::Error::Parameters(format!(
+                "Significance level must be between 0 and 1, got: {}",
+                alpha
+            ))
+            .into());
+        }
+
+        let covariance = self.covariance()?;
+        let design = if self.information.nrows() == self.coef.len() {
+            x.to_owned()
+        } else {
+            concatenate![Axis(1), Array2::ones((x.nrows(), 1)), x.view()]
+        };
+
+        // standard error of the linear predictor for each sample
+        let std_err = (design.dot(&covariance) * &design)
+            .sum_axis(Axis(1))
+            .mapv(num_traits::Float::sqrt);
+
+        let z = A::from(normal_quantile(1. - alpha / 2.)).unwrap();
+        let lin_pred = x.dot(&self.coef) + self.intercept;
+        let lower = self.link.inverse(&(&lin_pred - &(&std_err * z)));
+        let upper = self.link.inverse(&(&lin_pred + &(&std_err * z)));
+
+        Ok((lower, upper))
+    }
 }
 
 impl<A: Float, D: Data<Elem = A>> PredictRef<ArrayBase<D, Ix2>, Array1<A>>
@@ -310,6 +443,10 @@ impl<A: Float, D: Data<Elem = A>> PredictRef<ArrayBase<D, Ix2>, Array1<A>>
     }
 }
 
+impl<A: Float> DefaultMetric for FittedTweedieRegressor<A> {
+    type Metric = R2;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,7 +468,7 @@ mod tests {
                         .power($power)
                         .link(Link::Log)
                         .tol(1e-7)
-                        .fit_intercept($intercept);
+                        .with_intercept($intercept);
 
                     if $intercept {
                         x = x.slice(s![.., 1..]).to_owned();
@@ -351,6 +488,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_poisson_interval_coverage() {
+        use ndarray_rand::rand::{rngs::SmallRng, SeedableRng};
+        use ndarray_rand::rand_distr::{Distribution, Poisson, Uniform};
+        use ndarray_rand::RandomExt;
+
+        let mut rng = SmallRng::seed_from_u64(42);
+        let (intercept, coef) = (0.5, array![1.0]);
+        let x_test = array![[0.1], [0.5], [0.9]];
+        let mean_test = x_test.dot(&coef).mapv(|x: f64| (x + intercept).exp());
+
+        let (nruns, mut covered) = (200, 0);
+        for _ in 0..nruns {
+            let x = Array2::random_using((100, 1), Uniform::new(0., 1.), &mut rng);
+            let y = x.dot(&coef).mapv(|x: f64| {
+                Poisson::new((x + intercept).exp())
+                    .unwrap()
+                    .sample(&mut rng)
+            });
+
+            let glm = TweedieRegressor::new()
+                .alpha(0.)
+                .power(1.)
+                .tol(1e-8)
+                .fit(&Dataset::new(x, y))
+                .unwrap();
+
+            assert_eq!(glm.coef_standard_errors().unwrap().len(), 1);
+
+            let (lower, upper) = glm.predict_interval(&x_test, 0.05).unwrap();
+            covered += mean_test
+                .iter()
+                .zip(lower.iter().zip(upper.iter()))
+                .filter(|(m, (l, u))| l <= m && m <= u)
+                .count();
+        }
+
+        let coverage = covered as f64 / (3 * nruns) as f64;
+        assert!((0.9..=0.99).contains(&coverage), "coverage {}", coverage);
+    }
+
+    #[test]
+    fn test_invalid_interval_level() {
+        let x = array![[0.], [1.], [2.], [3.]];
+        let y = array![1., 2., 4., 8.];
+        let glm = TweedieRegressor::new()
+            .power(1.)
+            .fit(&Dataset::new(x.clone(), y))
+            .unwrap();
+
+        assert!(glm.predict_interval(&x, 0.).is_err());
+        assert!(glm.predict_interval(&x, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_intercept_toggle() {
+        // centered records and targets on a line through the origin
+        let x = array![[-1.5], [-0.5], [0.5], [1.5]];
+        let y = array![-3., -1., 1., 3.];
+        let dataset = Dataset::new(x.clone(), y.clone());
+        let params = TweedieRegressor::new().power(0.).alpha(0.).tol(1e-8);
+
+        let glm = params.with_intercept(true).fit(&dataset).unwrap();
+        assert_abs_diff_eq!(glm.intercept, 0., epsilon = 1e-4);
+        assert_abs_diff_eq!(glm.coef, array![2.], epsilon = 1e-4);
+
+        // with the log link of the Poisson distribution, constant counts are fitted by the
+        // intercept alone, which is the logarithm of the count
+        let counts = Dataset::new(x.clone(), array![2., 2., 2., 2.]);
+        let glm = TweedieRegressor::new()
+            .power(1.)
+            .alpha(0.)
+            .tol(1e-8)
+            .fit(&counts)
+            .unwrap();
+        assert_abs_diff_eq!(glm.intercept, 2f64.ln(), epsilon = 1e-4);
+        assert_abs_diff_eq!(glm.coef, array![0.], epsilon = 1e-4);
+
+        let params = TweedieRegressor::new().power(0.).alpha(0.).tol(1e-8);
+        let glm = params.with_intercept(false).fit(&dataset).unwrap();
+        assert_eq!(glm.intercept, 0.);
+        assert_abs_diff_eq!(glm.coef, array![2.], epsilon = 1e-4);
+
+        // the penalty shrinks the coefficients, but not the intercept
+        let dataset = Dataset::new(x, y + 10.);
+        let glm = TweedieRegressor::new()
+            .power(0.)
+            .alpha(100.)
+            .tol(1e-8)
+            .fit(&dataset)
+            .unwrap();
+        assert_abs_diff_eq!(glm.intercept, 10., epsilon = 1e-4);
+        assert!(glm.coef[0] < 1.);
+    }
+
     test_tweedie! {
         test_glm_normal1: {
             power: 0.,
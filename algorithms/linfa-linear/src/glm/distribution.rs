@@ -4,6 +4,7 @@ use ndarray::{Array1, ArrayView1};
 
 use crate::error::Result;
 
+#[derive(Clone)]
 pub struct TweedieDistribution {
     power: f64,
     lower_bound: f64,
@@ -50,7 +51,12 @@ impl TweedieDistribution {
         y.iter().all(|&x| x > F::cast(self.lower_bound))
     }
 
-    fn unit_variance<F: Float>(&self, ypred: ArrayView1<F>) -> Array1<F> {
+    // Returns `true` if the dispersion is fixed to one, as for the Poisson distribution
+    pub fn fixed_dispersion(&self) -> bool {
+        (self.power - 1.).abs() < 1e-6
+    }
+
+    pub fn unit_variance<F: Float>(&self, ypred: ArrayView1<F>) -> Array1<F> {
         // ypred ^ power
         ypred.mapv(|x| x.powf(F::cast(self.power)))
     }
@@ -7,6 +7,7 @@ use ndarray_stats::SummaryStatisticsExt;
 use serde::{Deserialize, Serialize};
 
 use linfa::dataset::{AsTargets, DatasetBase};
+use linfa::metrics::{DefaultMetric, R2};
 use linfa::traits::{Fit, PredictRef};
 
 pub trait Float: linfa::Float + Lapack + Scalar {}
@@ -235,6 +236,10 @@ impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<F>>
     }
 }
 
+impl<F: Float> DefaultMetric for FittedLinearRegression<F> {
+    type Metric = R2;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +270,30 @@ mod tests {
         assert_abs_diff_eq!(result, &array![0., 1.], epsilon = 1e-12);
     }
 
+    /// On centered data the fitted intercept vanishes, so fitting with
+    /// and without intercept gives the same parameters.
+    #[test]
+    fn intercept_toggle_on_centered_data() {
+        let dataset = Dataset::new(
+            array![[-2.], [-1.], [0.], [1.], [2.]],
+            array![-3.9, -2.1, 0.1, 1.9, 4.0],
+        );
+
+        let model = LinearRegression::new()
+            .with_intercept(true)
+            .fit(&dataset)
+            .unwrap();
+        assert_abs_diff_eq!(model.intercept(), 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(model.params(), &array![1.98], epsilon = 1e-12);
+
+        let model = LinearRegression::new()
+            .with_intercept(false)
+            .fit(&dataset)
+            .unwrap();
+        assert_eq!(model.intercept(), 0.);
+        assert_abs_diff_eq!(model.params(), &array![1.98], epsilon = 1e-12);
+    }
+
     /// We can't fit a line through two points without fitting the
     /// intercept in general. In this case we should find the solution
     /// that minimizes the squares. Fitting a line with intercept through
@@ -373,4 +402,46 @@ mod tests {
         assert_eq!(model1.params(), model2.params());
         assert_abs_diff_eq!(model1.intercept(), model2.intercept());
     }
+
+    #[test]
+    fn predict_one_matches_batch_prediction() {
+        use linfa::traits::PredictOne;
+
+        let dataset = linfa_datasets::diabetes();
+        let model = LinearRegression::new().fit(&dataset).unwrap();
+
+        for row in dataset.records().genrows().into_iter().take(5) {
+            let prediction = model.predict(&row.insert_axis(Axis(0)));
+            assert_abs_diff_eq!(model.predict_one(row), prediction[0]);
+        }
+    }
+
+    #[test]
+    fn log_transformed_target_beats_raw_target() {
+        use linfa::composing::{TargetTransform, TransformedTargetRegressor};
+        use ndarray_rand::rand::{rngs::SmallRng, SeedableRng};
+        use ndarray_rand::rand_distr::{Normal, Uniform};
+        use ndarray_rand::RandomExt;
+
+        let mut rng = SmallRng::seed_from_u64(42);
+        let x = Array2::random_using((200, 1), Uniform::new(0., 2.), &mut rng);
+        let noise = Array1::random_using(200, Normal::new(0., 0.1).unwrap(), &mut rng);
+        let y = (x.column(0).mapv(|x| 1. + 1.5 * x) + noise).mapv(f64::exp);
+        let dataset = Dataset::new(x, y);
+
+        let raw = LinearRegression::new().fit(&dataset).unwrap();
+        let transformed =
+            TransformedTargetRegressor::new(LinearRegression::new(), TargetTransform::Log)
+                .fit(&dataset)
+                .unwrap();
+
+        let mse = |prediction: Array1<f64>| {
+            (prediction - dataset.targets()).mapv(|e| e * e).sum()
+                / dataset.records().nrows() as f64
+        };
+        let raw_mse = mse(raw.predict(dataset.records()));
+        let transformed_mse = mse(transformed.predict(dataset.records()));
+
+        assert!(transformed_mse < raw_mse);
+    }
 }
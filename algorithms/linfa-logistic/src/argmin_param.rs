@@ -9,9 +9,10 @@
 use crate::float::Float;
 use argmin::prelude::*;
 use ndarray::Array1;
-use serde::{Deserialize, Serialize};
+use serde_crate::{Deserialize, Serialize};
 
 #[derive(Serialize, Clone, Deserialize, Debug, Default)]
+#[serde(crate = "serde_crate")]
 pub struct ArgminParam<F>(pub Array1<F>);
 
 impl<F> ArgminParam<F> {
@@ -7,6 +7,8 @@ pub enum Error {
     LinfaError(#[from] linfa::Error),
     #[error("Expected exactly two classes for logistic regression")]
     WrongNumberOfClasses,
+    #[error("Expected at least two classes for ordinal regression")]
+    NotEnoughClasses,
     #[error(transparent)]
     ArgMinError(#[from] argmin::core::Error),
     #[error("Expected `x` and `y` to have same number of rows, got {0} != {1}")]
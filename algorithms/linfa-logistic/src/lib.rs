@@ -5,7 +5,8 @@
 //! `linfa-logistic` is a crate in the [`linfa`](https://crates.io/crates/linfa) ecosystem, an effort to create a toolkit for classical Machine Learning implemented in pure Rust, akin to Python's `scikit-learn`.
 //!
 //! ## Current state
-//! `linfa-logistic` provides a pure Rust implementation of a two class [logistic regression model](struct.LogisticRegression.html).
+//! `linfa-logistic` provides a pure Rust implementation of a two class [logistic regression model](struct.LogisticRegression.html)
+//! and of an [ordinal regression model](struct.OrdinalRegression.html) for ordered classes.
 //!
 //! ## Examples
 //!
@@ -22,16 +23,21 @@ use crate::error::{Error, Result};
 use argmin::prelude::*;
 use argmin::solver::linesearch::MoreThuenteLineSearch;
 use argmin::solver::quasinewton::lbfgs::LBFGS;
-use linfa::prelude::{AsTargets, DatasetBase};
+use linfa::metrics::{Accuracy, DefaultMetric};
+use linfa::prelude::{AsTargets, DatasetBase, Records};
 use linfa::traits::{Fit, PredictRef};
-use ndarray::{s, Array, Array1, ArrayBase, Data, Ix1, Ix2};
+use ndarray::{s, Array, Array1, Array2, ArrayBase, Data, Ix1, Ix2};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
 use std::default::Default;
 
 mod argmin_param;
 mod float;
+mod ordinal;
 
 use argmin_param::ArgminParam;
 use float::Float;
+pub use ordinal::{FittedOrdinalRegression, OrdinalRegression};
 
 /// A two-class logistic regression model.
 ///
@@ -99,6 +105,9 @@ impl<F: Float> LogisticRegression<F> {
     }
 
     /// Configure if an intercept should be fitted, defaults to `true`.
+    ///
+    /// The intercept is never penalized by `alpha`. Disabling it forces the
+    /// decision boundary through the origin.
     pub fn with_intercept(mut self, fit_intercept: bool) -> LogisticRegression<F> {
         self.fit_intercept = fit_intercept;
         self
@@ -148,6 +157,7 @@ impl<F: Float> LogisticRegression<F> {
         T: AsTargets<Elem = C>,
         C: PartialOrd + Clone,
     {
+        x.check_shape(1)?;
         let (labels, target) = label_classes(y)?;
         self.validate_data(x, &target)?;
         let problem = self.setup_problem(x, target);
@@ -303,6 +313,107 @@ impl<'a, C: 'a + PartialOrd + Clone, F: Float, D: Data<Elem = F>, T: AsTargets<E
     }
 }
 
+impl<F: Float> LogisticRegression<F> {
+    /// Compute the regularization path of the logistic regression
+    ///
+    /// Fits the model for every penalty in `alphas`, keeping all other hyper parameters fixed.
+    /// The penalties are visited in decreasing order and each fit is warm-started from the
+    /// solution of the previous one, which is considerably cheaper than fitting every penalty
+    /// independently. The `alpha` of these parameters is ignored and the initial parameters, if
+    /// configured, are only used for the first penalty.
+    ///
+    /// Returns a `LogisticRegressionPath` containing the penalties, sorted in decreasing order,
+    /// and the coefficients and intercepts found for each of them.
+    pub fn path<D, T, C>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+        alphas: &[F],
+    ) -> Result<LogisticRegressionPath<F>>
+    where
+        D: Data<Elem = F>,
+        T: AsTargets<Elem = C>,
+        C: PartialOrd + Clone,
+    {
+        if alphas.is_empty() {
+            return Err(
+                linfa::Error::Parameters("At least one penalty is required".to_string()).into(),
+            );
+        }
+        if let Some(alpha) = alphas
+            .iter()
+            .find(|alpha| !alpha.is_finite() || **alpha < F::zero())
+        {
+            let msg = format!("Penalty should be positive and finite, but is {}", alpha);
+            return Err(linfa::Error::Parameters(msg).into());
+        }
+
+        let x = dataset.records();
+        x.check_shape(1)?;
+        let (_, target) = label_classes(dataset.targets())?;
+        self.validate_data(x, &target)?;
+
+        let mut alphas = alphas.to_vec();
+        alphas.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        let alphas = Array1::from(alphas);
+
+        let n_features = x.ncols();
+        let mut coefficients = Array2::zeros((alphas.len(), n_features));
+        let mut intercepts = Array1::zeros(alphas.len());
+        let mut w = self.setup_init_params(x);
+
+        for (i, alpha) in alphas.iter().enumerate() {
+            let problem = LogisticRegressionProblem {
+                x,
+                target: target.clone(),
+                alpha: *alpha,
+            };
+            let result = self.run_solver(problem, self.setup_solver(), w)?;
+            w = result.state().best_param.as_array().clone();
+
+            let (params, intercept) = convert_params(n_features, &w);
+            coefficients.row_mut(i).assign(&params);
+            intercepts[i] = intercept;
+        }
+
+        Ok(LogisticRegressionPath {
+            alphas,
+            coefficients,
+            intercepts,
+        })
+    }
+}
+
+/// Regularization path of a logistic regression
+///
+/// This struct contains the coefficients of logistic regression models fitted with a sequence of
+/// penalties, see `LogisticRegression::path`. The penalties are stored in decreasing order, each
+/// row of the coefficient matrix corresponds to one of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogisticRegressionPath<F> {
+    alphas: Array1<F>,
+    coefficients: Array2<F>,
+    intercepts: Array1<F>,
+}
+
+impl<F: Float> LogisticRegressionPath<F> {
+    /// Get the penalties of the path, sorted in decreasing order
+    pub fn alphas(&self) -> &Array1<F> {
+        &self.alphas
+    }
+
+    /// Get the coefficients with shape `(n_alphas, n_features)`
+    ///
+    /// The i-th row contains the parameters fitted with the i-th penalty of `alphas()`.
+    pub fn coefficients(&self) -> &Array2<F> {
+        &self.coefficients
+    }
+
+    /// Get the intercepts fitted with each penalty, zero if no intercept was fitted
+    pub fn intercepts(&self) -> &Array1<F> {
+        &self.intercepts
+    }
+}
+
 /// Identify the distinct values of the classes  `y` and associate
 /// the target labels `-1.0` and `1.0` to it. -1.0 always labels the
 /// smaller class (by PartialOrd) and 1.0 always labels the larger
@@ -446,6 +557,11 @@ fn logistic_grad<F: Float, A: Data<Elem = F>>(
 }
 
 /// A fitted logistic regression which can make predictions
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 #[derive(PartialEq, Debug)]
 pub struct FittedLogisticRegression<F: Float, C: PartialOrd + Clone> {
     threshold: F,
@@ -486,11 +602,19 @@ impl<F: Float, C: PartialOrd + Clone> FittedLogisticRegression<F, C> {
         &self.params
     }
 
+    /// Given a feature matrix, compute the logit of every sample, the log-odds
+    /// that it should be classified as the larger of the two classes learned
+    /// when the model was fitted. Positive values correspond to probabilities
+    /// larger than 0.5.
+    pub fn decision_function<A: Data<Elem = F>>(&self, x: &ArrayBase<A, Ix2>) -> Array1<F> {
+        x.dot(&self.params) + self.intercept
+    }
+
     /// Given a feature matrix, predict the probabilities that a sample
     /// should be classified as the larger of the two classes learned when the
     /// model was fitted.
     pub fn predict_probabilities<A: Data<Elem = F>>(&self, x: &ArrayBase<A, Ix2>) -> Array1<F> {
-        let mut probs = x.dot(&self.params) + self.intercept;
+        let mut probs = self.decision_function(x);
         probs.mapv_inplace(logistic);
         probs
     }
@@ -520,6 +644,31 @@ impl<C: PartialOrd + Clone, F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D,
     }
 }
 
+impl<F: Float, C: PartialOrd + Clone> DefaultMetric for FittedLogisticRegression<F, C> {
+    type Metric = Accuracy;
+}
+
+#[cfg(feature = "serde")]
+impl<F: Float, C: PartialOrd + Clone> linfa::persistence::SaveModel
+    for FittedLogisticRegression<F, C>
+where
+    Self: Serialize,
+{
+}
+
+#[cfg(feature = "serde")]
+impl<F: Float, C: PartialOrd + Clone> linfa::persistence::LoadModel
+    for FittedLogisticRegression<F, C>
+where
+    Self: serde_crate::de::DeserializeOwned,
+{
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 #[derive(PartialEq, Debug, Clone)]
 struct ClassLabel<F: Float, C: PartialOrd> {
     class: C,
@@ -753,6 +902,128 @@ mod test {
         assert_eq!(res.predict(&x), y);
     }
 
+    #[test]
+    fn intercept_toggle_on_centered_data() {
+        // the data is symmetric under flipping the sign of x and the class
+        let x = array![[-2.0], [-1.0], [1.0], [2.0]];
+        let y = array![0.0, 1.0, 0.0, 1.0];
+
+        let res = LogisticRegression::default()
+            .with_intercept(true)
+            .gradient_tolerance(1e-8)
+            .fit(&x, &y)
+            .unwrap();
+        assert_abs_diff_eq!(res.intercept(), 0.0, epsilon = 1e-6);
+
+        let without = LogisticRegression::default()
+            .with_intercept(false)
+            .gradient_tolerance(1e-8)
+            .fit(&x, &y)
+            .unwrap();
+        assert_eq!(without.intercept(), 0.0);
+        assert!(without.params().abs_diff_eq(res.params(), 1e-6));
+    }
+
+    #[test]
+    fn path_shrinks_and_matches_independent_fits() {
+        let x = array![
+            [0.0, 1.0],
+            [1.0, 0.0],
+            [2.0, 2.0],
+            [3.0, 1.0],
+            [4.0, 3.0],
+            [5.0, 2.0],
+            [6.0, 4.0],
+            [7.0, 3.0],
+            [3.0, 1.0]
+        ];
+        let y = array![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 0.0];
+        let dataset = DatasetBase::new(x.clone(), y.clone());
+
+        let params = || {
+            LogisticRegression::default()
+                .gradient_tolerance(1e-8)
+                .max_iterations(1000)
+        };
+        let path = params()
+            .path(&dataset, &[0.01, 10.0, 0.1, 100.0, 1.0])
+            .unwrap();
+        assert_abs_diff_eq!(path.alphas(), &array![100.0, 10.0, 1.0, 0.1, 0.01]);
+        assert_eq!(path.coefficients().dim(), (5, 2));
+        assert_eq!(path.intercepts().len(), 5);
+
+        // the norm of the coefficients grows as the penalty decreases
+        let norms = path
+            .coefficients()
+            .genrows()
+            .into_iter()
+            .map(|row| row.dot(&row))
+            .collect::<Vec<_>>();
+        assert!(norms.windows(2).all(|w| w[0] < w[1]));
+
+        for ((alpha, coefficients), intercept) in path
+            .alphas()
+            .iter()
+            .zip(path.coefficients().genrows())
+            .zip(path.intercepts())
+        {
+            let model = params().alpha(*alpha).fit(&x, &y).unwrap();
+            assert!(model.params().abs_diff_eq(&coefficients, 1e-4));
+            assert_abs_diff_eq!(model.intercept(), *intercept, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn path_rejects_invalid_alphas() {
+        let dataset = DatasetBase::new(array![[0.0], [1.0]], array![0.0, 1.0]);
+
+        assert!(LogisticRegression::default().path(&dataset, &[]).is_err());
+        assert!(LogisticRegression::default()
+            .path(&dataset, &[1.0, -1.0])
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_degenerate_records() {
+        let log_reg = LogisticRegression::default();
+        let is_parameters_error = |result: Result<FittedLogisticRegression<f64, usize>>| {
+            matches!(result, Err(Error::LinfaError(linfa::Error::Parameters(_))))
+        };
+
+        let x = Array2::<f64>::zeros((0, 2));
+        let y = Array1::<usize>::zeros(0);
+        assert!(is_parameters_error(log_reg.fit(&x, &y)));
+        let dataset = DatasetBase::new(x, y);
+        assert!(log_reg.path(&dataset, &[1.0]).is_err());
+
+        let x = Array2::<f64>::zeros((4, 0));
+        let y = array![0, 0, 1, 1];
+        assert!(is_parameters_error(log_reg.fit(&x, &y)));
+        let dataset = DatasetBase::new(x, y);
+        assert!(log_reg.path(&dataset, &[1.0]).is_err());
+    }
+
+    #[test]
+    fn decision_function_matches_predictions() {
+        let log_reg = LogisticRegression::default().alpha(1.0);
+        let x = array![[0.0], [1.0], [2.0], [3.0], [4.0], [5.0], [6.0], [7.0]];
+        let y = array![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0];
+        let res = log_reg.fit(&x, &y).unwrap();
+
+        let logits = res.decision_function(&x);
+        assert!(logits.abs_diff_eq(
+            &(x.column(0).to_owned() * res.params()[0] + res.intercept()),
+            1e-12
+        ));
+        assert!(res
+            .predict_probabilities(&x)
+            .abs_diff_eq(&logits.mapv(|l| 1.0 / (1.0 + (-l).exp())), 1e-12));
+        assert_eq!(
+            res.predict(&x),
+            logits.mapv(|l| if l >= 0.0 { 1.0 } else { 0.0 })
+        );
+    }
+
     #[test]
     fn rejects_multi_target() {
         let log_reg = LogisticRegression::default();
@@ -865,4 +1136,25 @@ mod test {
         assert!(res.params().abs_diff_eq(&array![0.682_f32], 1e-3));
         assert_eq!(res.predict(&x), y);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn save_load_model() {
+        use linfa::persistence::{LoadModel, SaveModel};
+
+        let x = array![[-1.0], [-0.01], [0.01], [1.0]];
+        let y = array![3, 3, 7, 7];
+        let model = LogisticRegression::default()
+            .fit(&x, &y)
+            .unwrap()
+            .set_threshold(0.3);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        model.save_model(file.path()).unwrap();
+        let loaded: FittedLogisticRegression<f64, i32> =
+            FittedLogisticRegression::load_model(file.path()).unwrap();
+
+        assert_eq!(model, loaded);
+        assert_eq!(model.predict(&x), loaded.predict(&x));
+    }
 }
@@ -0,0 +1,587 @@
+//! Ordinal regression with the proportional odds model
+
+use crate::argmin_param::ArgminParam;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::{logistic, LBFGSType};
+use argmin::prelude::*;
+use argmin::solver::linesearch::MoreThuenteLineSearch;
+use argmin::solver::quasinewton::lbfgs::LBFGS;
+use linfa::metrics::{Accuracy, DefaultMetric};
+use linfa::prelude::{AsTargets, DatasetBase};
+use linfa::traits::{Fit, PredictRef};
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2};
+
+/// An ordinal regression model for ordered classes.
+///
+/// Ordinal regression, also known as proportional odds logistic regression, models targets which
+/// are ordered categories, like ratings or quality scores. A single linear model `x^T params`
+/// is learned for all classes, together with a set of increasing thresholds `t_0 < t_1 < ..`
+/// which divide the real line into one interval per class. The cumulative probability of
+/// observing a class up to `k` is then given by
+///
+/// `P(y <= k | x) = sigm(t_k - x^T params)`
+///
+/// In contrast to a nominal classifier, the model respects the ordering of the classes and a
+/// single set of coefficients is shared by them. The classes are ordered by `PartialOrd` of the
+/// targets.
+///
+/// l2 regularization of the coefficients is weighted by parameter `alpha`, the thresholds are
+/// not regularized.
+///
+/// ## Examples
+///
+/// Here's an example on how to train an ordinal regression model on the `winequality` dataset
+/// ```rust
+/// use linfa::traits::{Fit, Predict};
+/// use linfa_logistic::OrdinalRegression;
+///
+/// let dataset = linfa_datasets::winequality();
+/// let model = OrdinalRegression::default().fit(&dataset).unwrap();
+/// let prediction = model.predict(&dataset);
+/// let probabilities = model.predict_proba(dataset.records());
+/// ```
+pub struct OrdinalRegression<F: Float> {
+    alpha: F,
+    max_iterations: u64,
+    gradient_tolerance: F,
+}
+
+impl<F: Float> Default for OrdinalRegression<F> {
+    fn default() -> OrdinalRegression<F> {
+        OrdinalRegression::new()
+    }
+}
+
+impl<F: Float> OrdinalRegression<F> {
+    /// Creates a new OrdinalRegression with default configuration.
+    pub fn new() -> OrdinalRegression<F> {
+        OrdinalRegression {
+            alpha: F::cast(1.0),
+            max_iterations: 100,
+            gradient_tolerance: F::cast(1e-4),
+        }
+    }
+
+    /// Set the normalization parameter `alpha` used for L2 normalization,
+    /// defaults to `1.0`.
+    pub fn alpha(mut self, alpha: F) -> OrdinalRegression<F> {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Configure the maximum number of iterations that the solver should perform,
+    /// defaults to `100`.
+    pub fn max_iterations(mut self, max_iterations: u64) -> OrdinalRegression<F> {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Configure the minimum change to the gradient to continue the solver,
+    /// defaults to `1e-4`.
+    pub fn gradient_tolerance(mut self, gradient_tolerance: F) -> OrdinalRegression<F> {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Ensure that `x` and `y` have the right shape and that all data and
+    /// configuration parameters are finite.
+    fn validate_data<A: Data<Elem = F>>(&self, x: &ArrayBase<A, Ix2>, y: &[usize]) -> Result<()> {
+        if x.nrows() != y.len() {
+            return Err(Error::MismatchedShapes(x.nrows(), y.len()));
+        }
+        if x.iter().any(|x| !x.is_finite()) || !self.alpha.is_finite() {
+            return Err(Error::InvalidValues);
+        }
+        if !self.gradient_tolerance.is_finite() || self.gradient_tolerance <= F::zero() {
+            return Err(Error::InvalidGradientTolerance);
+        }
+        Ok(())
+    }
+
+    /// Start with zero coefficients and the thresholds matching the cumulative class
+    /// frequencies.
+    fn setup_init_params(&self, n_features: usize, y: &[usize], n_classes: usize) -> Array1<F> {
+        let mut counts = vec![0usize; n_classes];
+        for &k in y {
+            counts[k] += 1;
+        }
+
+        let mut thresholds = Vec::with_capacity(n_classes - 1);
+        let mut seen = 0;
+        for count in &counts[..n_classes - 1] {
+            seen += count;
+            let p = F::cast(seen) / F::cast(y.len());
+            thresholds.push(logit(p));
+        }
+
+        let mut params = Array1::zeros(n_features + n_classes - 1);
+        params
+            .slice_mut(s![n_features..])
+            .assign(&thresholds_to_raw(&thresholds));
+        params
+    }
+}
+
+impl<C: PartialOrd + Clone, F: Float, D: Data<Elem = F>, T: AsTargets<Elem = C>>
+    Fit<ArrayBase<D, Ix2>, T, Error> for OrdinalRegression<F>
+{
+    type Object = FittedOrdinalRegression<F, C>;
+
+    /// Given a 2-dimensional feature matrix array `x` with shape
+    /// (n_samples, n_features) and an array of ordered target classes, create
+    /// a `FittedOrdinalRegression` object which allows making predictions.
+    ///
+    /// The targets must contain at least two distinct classes, which are
+    /// ordered by `PartialOrd`.
+    ///
+    /// This method returns an error if any of the preconditions are violated,
+    /// i.e. any values are `Inf` or `NaN`, `y` doesn't have as many items as
+    /// `x` has rows, or if other parameters (gradient_tolerance, alpha) have
+    /// been set to inalid values.
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        let x = dataset.records();
+        let (classes, y) = ordered_classes(dataset.try_single_target()?)?;
+        self.validate_data(x, &y)?;
+
+        let n_features = x.ncols();
+        let problem = OrdinalRegressionProblem {
+            x,
+            y: &y,
+            n_classes: classes.len(),
+            alpha: self.alpha,
+        };
+        let solver: LBFGSType<F> =
+            LBFGS::new(MoreThuenteLineSearch::new(), 10).with_tol_grad(self.gradient_tolerance);
+        let init_params = self.setup_init_params(n_features, &y, classes.len());
+
+        let result = Executor::new(problem, solver, ArgminParam(init_params))
+            .max_iters(self.max_iterations)
+            .run()?;
+        let w = result.state().best_param.as_array();
+
+        Ok(FittedOrdinalRegression {
+            params: w.slice(s![..n_features]).to_owned(),
+            thresholds: raw_to_thresholds(w.slice(s![n_features..])),
+            classes,
+        })
+    }
+}
+
+/// Sort the distinct classes and map each target to the index of its class.
+///
+/// Fails if a target can't be ordered, like `NaN`, which is not even comparable to itself.
+fn ordered_classes<C: PartialOrd + Clone>(y: ArrayView1<C>) -> Result<(Vec<C>, Vec<usize>)> {
+    let mut classes: Vec<C> = Vec::new();
+    for item in y.iter() {
+        if item.partial_cmp(item).is_none() {
+            return Err(Error::InvalidValues);
+        }
+        if !classes.contains(item) {
+            classes.push(item.clone());
+        }
+    }
+    if classes.len() < 2 {
+        return Err(Error::NotEnoughClasses);
+    }
+    // all classes are comparable to themselves, which is assumed to make them comparable to each
+    // other
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let indices = y
+        .iter()
+        .map(|item| classes.iter().position(|c| c == item).unwrap())
+        .collect();
+
+    Ok((classes, indices))
+}
+
+/// The thresholds are parametrized by the first threshold and the logarithms of the gaps
+/// between consecutive thresholds, so that they stay ordered during the optimization.
+fn raw_to_thresholds<F: linfa::Float>(raw: ArrayView1<F>) -> Array1<F> {
+    let mut thresholds: Vec<F> = Vec::with_capacity(raw.len());
+    for (i, &r) in raw.iter().enumerate() {
+        let threshold = if i == 0 {
+            r
+        } else {
+            thresholds[i - 1] + r.exp()
+        };
+        thresholds.push(threshold);
+    }
+    Array1::from(thresholds)
+}
+
+fn thresholds_to_raw<F: linfa::Float>(thresholds: &[F]) -> Array1<F> {
+    thresholds
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| {
+            if i == 0 {
+                t
+            } else {
+                // keep a minimal gap for classes with tiny frequencies
+                (t - thresholds[i - 1]).max(F::cast(1e-3)).ln()
+            }
+        })
+        .collect()
+}
+
+fn logit<F: linfa::Float>(p: F) -> F {
+    (p / (F::one() - p)).ln()
+}
+
+/// Computes the negative log-likelihood of the proportional odds model and its gradient with
+/// respect to the coefficients and the raw threshold parameters
+fn ordinal_loss_grad<F: linfa::Float, A: Data<Elem = F>>(
+    x: &ArrayBase<A, Ix2>,
+    y: &[usize],
+    n_classes: usize,
+    alpha: F,
+    w: &Array1<F>,
+) -> (F, Array1<F>) {
+    let n_features = x.ncols();
+    let params = w.slice(s![..n_features]);
+    let raw = w.slice(s![n_features..]);
+    let thresholds = raw_to_thresholds(raw);
+
+    let linear = x.dot(&params);
+    let mut loss = F::zero();
+    let mut grad_linear = Array1::zeros(y.len());
+    let mut grad_thresholds = Array1::<F>::zeros(n_classes - 1);
+
+    for (i, (&k, &eta)) in y.iter().zip(linear.iter()).enumerate() {
+        // cumulative probabilities and their densities at the upper and lower boundary
+        let (upper, d_upper) = if k < n_classes - 1 {
+            cumulative(thresholds[k] - eta)
+        } else {
+            (F::one(), F::zero())
+        };
+        let (lower, d_lower) = if k > 0 {
+            cumulative(thresholds[k - 1] - eta)
+        } else {
+            (F::zero(), F::zero())
+        };
+
+        let prob = (upper - lower).max(F::epsilon());
+        loss -= prob.ln();
+
+        grad_linear[i] = (d_upper - d_lower) / prob;
+        if k < n_classes - 1 {
+            grad_thresholds[k] -= d_upper / prob;
+        }
+        if k > 0 {
+            grad_thresholds[k - 1] += d_lower / prob;
+        }
+    }
+
+    loss += F::cast(0.5) * alpha * params.dot(&params);
+
+    let mut grad = Array1::zeros(w.len());
+    grad.slice_mut(s![..n_features])
+        .assign(&(x.t().dot(&grad_linear) + &params.mapv(|p| p * alpha)));
+
+    // chain rule for the threshold parametrization, every gap shifts all following thresholds
+    let mut tail_sum = F::zero();
+    for j in (0..n_classes - 1).rev() {
+        tail_sum += grad_thresholds[j];
+        grad[n_features + j] = if j == 0 {
+            tail_sum
+        } else {
+            tail_sum * raw[j].exp()
+        };
+    }
+
+    (loss, grad)
+}
+
+/// The logistic function and its derivative
+fn cumulative<F: linfa::Float>(z: F) -> (F, F) {
+    let p = logistic(z);
+    (p, p * (F::one() - p))
+}
+
+/// A fitted ordinal regression which can make predictions
+#[derive(PartialEq, Debug)]
+pub struct FittedOrdinalRegression<F: Float, C: PartialOrd + Clone> {
+    params: Array1<F>,
+    thresholds: Array1<F>,
+    classes: Vec<C>,
+}
+
+impl<F: Float, C: PartialOrd + Clone> FittedOrdinalRegression<F, C> {
+    /// The coefficients of the linear model shared by all classes
+    pub fn params(&self) -> &Array1<F> {
+        &self.params
+    }
+
+    /// The increasing thresholds between consecutive classes
+    pub fn thresholds(&self) -> &Array1<F> {
+        &self.thresholds
+    }
+
+    /// The classes in ascending order
+    pub fn classes(&self) -> &[C] {
+        &self.classes
+    }
+
+    /// Given a feature matrix, predict the probability of each class
+    ///
+    /// The returned matrix has shape (n_samples, n_classes) with the columns in the order of
+    /// [`classes`](FittedOrdinalRegression::classes).
+    pub fn predict_proba<A: Data<Elem = F>>(&self, x: &ArrayBase<A, Ix2>) -> Array2<F> {
+        let n_classes = self.classes.len();
+        let linear = x.dot(&self.params);
+
+        let mut probs = Array2::zeros((x.nrows(), n_classes));
+        for (mut row, &eta) in probs.genrows_mut().into_iter().zip(linear.iter()) {
+            let mut lower = F::zero();
+            for (k, prob) in row.iter_mut().enumerate() {
+                let upper = if k < n_classes - 1 {
+                    logistic(self.thresholds[k] - eta)
+                } else {
+                    F::one()
+                };
+                *prob = upper - lower;
+                lower = upper;
+            }
+        }
+        probs
+    }
+}
+
+impl<C: PartialOrd + Clone, F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<C>>
+    for FittedOrdinalRegression<F, C>
+{
+    /// Given a feature matrix, predict the most probable class
+    fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<C> {
+        self.predict_proba(x).map_axis(Axis(1), |row| {
+            let (best, _) =
+                row.iter()
+                    .enumerate()
+                    .skip(1)
+                    .fold(
+                        (0, row[0]),
+                        |(best, max), (k, &p)| if p > max { (k, p) } else { (best, max) },
+                    );
+            self.classes[best].clone()
+        })
+    }
+}
+
+impl<F: Float, C: PartialOrd + Clone> DefaultMetric for FittedOrdinalRegression<F, C> {
+    type Metric = Accuracy;
+}
+
+/// Internal representation of an ordinal regression problem.
+/// This data structure exists to be handed to Argmin.
+struct OrdinalRegressionProblem<'a, F: Float, A: Data<Elem = F>> {
+    x: &'a ArrayBase<A, Ix2>,
+    y: &'a [usize],
+    n_classes: usize,
+    alpha: F,
+}
+
+impl<'a, F: Float, A: Data<Elem = F>> ArgminOp for OrdinalRegressionProblem<'a, F, A> {
+    type Param = ArgminParam<F>;
+    type Output = F;
+    type Hessian = ();
+    type Jacobian = Array1<F>;
+    type Float = F;
+
+    fn apply(&self, p: &Self::Param) -> std::result::Result<Self::Output, argmin::core::Error> {
+        let (loss, _) = ordinal_loss_grad(self.x, self.y, self.n_classes, self.alpha, p.as_array());
+        Ok(loss)
+    }
+
+    fn gradient(&self, p: &Self::Param) -> std::result::Result<Self::Param, argmin::core::Error> {
+        let (_, grad) = ordinal_loss_grad(self.x, self.y, self.n_classes, self.alpha, p.as_array());
+        Ok(ArgminParam(grad))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LogisticRegression;
+    use approx::assert_abs_diff_eq;
+    use linfa::{metrics::worst_misclassifications, traits::Predict, Dataset};
+    use ndarray::{array, Array};
+
+    fn mean_class_distance(pred: &Array1<usize>, truth: ArrayView1<usize>) -> f64 {
+        pred.iter()
+            .zip(truth.iter())
+            .map(|(a, b)| (*a as f64 - *b as f64).abs())
+            .sum::<f64>()
+            / pred.len() as f64
+    }
+
+    #[test]
+    fn gradient_matches_finite_differences() {
+        let x = array![
+            [0.5, -1.0],
+            [1.5, 0.3],
+            [-0.7, 2.0],
+            [0.1, 0.1],
+            [2.0, -0.5]
+        ];
+        let y = [0, 2, 1, 1, 2];
+        let w = array![0.3, -0.2, -0.5, 0.1];
+
+        let (_, grad) = ordinal_loss_grad(&x, &y, 3, 0.5, &w);
+        for i in 0..w.len() {
+            let mut w_plus = w.clone();
+            let mut w_minus = w.clone();
+            w_plus[i] += 1e-6;
+            w_minus[i] -= 1e-6;
+            let numeric = (ordinal_loss_grad(&x, &y, 3, 0.5, &w_plus).0
+                - ordinal_loss_grad(&x, &y, 3, 0.5, &w_minus).0)
+                / 2e-6;
+            assert_abs_diff_eq!(grad[i], numeric, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn beats_one_vs_rest_on_ordered_classes() {
+        // five ordered classes along a single latent direction
+        let x = Array::linspace(-4., 4., 400).insert_axis(Axis(1));
+        let y = x.column(0).mapv(|v| match v {
+            v if v < -2. => 0usize,
+            v if v < -0.75 => 1,
+            v if v < 0.75 => 2,
+            v if v < 2. => 3,
+            _ => 4,
+        });
+        let dataset = Dataset::new(x.clone(), y.clone());
+
+        let model = OrdinalRegression::default()
+            .alpha(1e-3)
+            .max_iterations(500)
+            .fit(&dataset)
+            .unwrap();
+
+        let probs = model.predict_proba(dataset.records());
+        for row in probs.genrows() {
+            assert_abs_diff_eq!(row.sum(), 1.0, epsilon = 1e-10);
+        }
+        assert!(model
+            .thresholds()
+            .windows(2)
+            .into_iter()
+            .all(|w| w[0] < w[1]));
+
+        // a binary logistic regression for each class, predicting the most probable one
+        let one_vs_rest = (0..5usize)
+            .map(|class| {
+                LogisticRegression::default()
+                    .fit(&Dataset::new(x.clone(), y.mapv(|y| y == class)))
+                    .unwrap()
+                    .predict_probabilities(&x)
+            })
+            .collect::<Vec<_>>();
+        let ovr_pred = Array1::from_shape_fn(y.len(), |i| {
+            (0..5)
+                .max_by(|a, b| one_vs_rest[*a][i].partial_cmp(&one_vs_rest[*b][i]).unwrap())
+                .unwrap()
+        });
+
+        let targets = dataset.try_single_target().unwrap();
+        let ordinal_dist = mean_class_distance(&model.predict(&dataset), targets);
+        let ovr_dist = mean_class_distance(&ovr_pred, targets);
+
+        assert!(ordinal_dist < 0.05);
+        assert!(ordinal_dist < ovr_dist);
+    }
+
+    #[test]
+    fn winequality_respects_ordering() {
+        let (train, valid) = linfa_datasets::winequality().split_with_ratio(0.8);
+
+        let model = OrdinalRegression::default()
+            .max_iterations(300)
+            .fit(&train)
+            .unwrap();
+        assert_eq!(model.classes(), &[3, 4, 5, 6, 7, 8]);
+
+        // predicting the most frequent quality for every wine is the baseline, it is correct for
+        // about 43% of the wines with a mean class distance of about 0.68
+        let targets = valid.try_single_target().unwrap();
+        let majority = Array1::from_elem(targets.len(), 5usize);
+        let pred = model.predict(&valid);
+        let dist = mean_class_distance(&pred, targets);
+        let accuracy = pred
+            .iter()
+            .zip(targets.iter())
+            .filter(|(a, b)| a == b)
+            .count() as f64
+            / pred.len() as f64;
+
+        // a binary logistic regression for each quality, predicting the most probable one
+        let one_vs_rest = (3..=8usize)
+            .map(|class| {
+                LogisticRegression::default()
+                    .max_iterations(300)
+                    .fit(&Dataset::new(
+                        train.records().clone(),
+                        train.try_single_target().unwrap().mapv(|y| y == class),
+                    ))
+                    .unwrap()
+                    .predict_probabilities(valid.records())
+            })
+            .collect::<Vec<_>>();
+        let ovr_pred = Array1::from_shape_fn(targets.len(), |i| {
+            (0..6)
+                .max_by(|a, b| one_vs_rest[*a][i].partial_cmp(&one_vs_rest[*b][i]).unwrap())
+                .unwrap()
+                + 3
+        });
+
+        assert!(dist < mean_class_distance(&majority, targets));
+        assert!(dist < mean_class_distance(&ovr_pred, targets));
+        assert!(dist < 0.55);
+        assert!(accuracy > 0.5);
+    }
+
+    #[test]
+    fn surfaces_mislabeled_samples() {
+        // three separated clusters of ordered classes, with one sample of each outer cluster
+        // labeled as the opposite class
+        let x = Array::from_shape_fn((90, 1), |(i, _)| {
+            (i / 30) as f64 * 4. - 5. + (i % 30) as f64 * 2. / 29.
+        });
+        let mut y = Array1::from_shape_fn(90, |i| i / 30);
+        y[0] = 2;
+        y[89] = 0;
+        let dataset = Dataset::new(x, y);
+
+        let model = OrdinalRegression::default()
+            .alpha(1e-3)
+            .fit(&dataset)
+            .unwrap();
+        let probs = model.predict_proba(dataset.records());
+
+        let worst = worst_misclassifications(&dataset, probs.view(), None).unwrap();
+        let mut indices = worst.iter().map(|m| m.index).collect::<Vec<_>>();
+        indices.sort_unstable();
+        assert_eq!(indices, vec![0, 89]);
+        for m in &worst {
+            assert_eq!(m.predicted, 2 - m.expected);
+            assert!(m.confidence > 0.5);
+        }
+    }
+
+    #[test]
+    fn rejects_nan_targets() {
+        let dataset = Dataset::new(array![[1.0], [2.0], [3.0]], array![1.0, f64::NAN, 2.0]);
+        let res = OrdinalRegression::<f64>::default().fit(&dataset);
+
+        assert!(matches!(res, Err(Error::InvalidValues)));
+    }
+
+    #[test]
+    fn rejects_single_class() {
+        let dataset = Dataset::new(array![[1.0], [2.0]], array![1usize, 1]);
+        let res = OrdinalRegression::<f64>::default().fit(&dataset);
+
+        assert!(matches!(res, Err(Error::NotEnoughClasses)));
+    }
+}
@@ -0,0 +1,100 @@
+use linfa::Float;
+use ndarray::{ArrayBase, CowArray, Data, Ix2};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use crate::{
+    distance::Distance, BuildError, CommonNearestNeighbour, NearestNeighbour,
+    NearestNeighbourIndex, DEFAULT_LEAF_SIZE,
+};
+
+/// Nearest neighbour algorithm which selects the index structure from the shape of the batch of
+/// points. Calling `from_batch` builds the index chosen by
+/// [`select`](struct.AutoNearestNeighbour.html#method.select):
+///
+/// * [`LinearSearch`](struct.LinearSearch.html) if there are fewer points than the brute force
+///   threshold (default 100), because building a tree does not pay off for small batches
+/// * [`KdTree`](struct.KdTree.html) if the points have at most the maximal KD tree dimension
+///   (default 15) and the distance is a
+///   [Minkowski distance](distance/trait.Distance.html#method.is_minkowski)
+/// * [`BallTree`](struct.BallTree.html) otherwise, as KD trees degrade to a linear scan in higher
+///   dimensions
+///
+/// The thresholds can be overridden with the builder methods.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct AutoNearestNeighbour {
+    brute_force_threshold: usize,
+    kdtree_max_dimension: usize,
+    leaf_size: usize,
+}
+
+impl Default for AutoNearestNeighbour {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AutoNearestNeighbour {
+    /// Creates an instance of `AutoNearestNeighbour` with the default heuristics
+    pub fn new() -> Self {
+        Self {
+            brute_force_threshold: 100,
+            kdtree_max_dimension: 15,
+            leaf_size: DEFAULT_LEAF_SIZE,
+        }
+    }
+
+    /// Set the number of points below which a linear search is used
+    pub fn brute_force_threshold(mut self, brute_force_threshold: usize) -> Self {
+        self.brute_force_threshold = brute_force_threshold;
+        self
+    }
+
+    /// Set the maximal dimension of the points for which a KD tree is used
+    pub fn kdtree_max_dimension(mut self, kdtree_max_dimension: usize) -> Self {
+        self.kdtree_max_dimension = kdtree_max_dimension;
+        self
+    }
+
+    /// Set the number of points in the leaf nodes, used by `from_batch`
+    pub fn leaf_size(mut self, leaf_size: usize) -> Self {
+        self.leaf_size = leaf_size;
+        self
+    }
+
+    /// Returns the algorithm used for a batch of points and a distance function
+    pub fn select<F: Float, DT: Data<Elem = F>, D: Distance<F>>(
+        &self,
+        batch: &ArrayBase<DT, Ix2>,
+        dist_fn: &D,
+    ) -> CommonNearestNeighbour {
+        if batch.nrows() < self.brute_force_threshold {
+            CommonNearestNeighbour::LinearSearch
+        } else if batch.ncols() <= self.kdtree_max_dimension && dist_fn.is_minkowski() {
+            CommonNearestNeighbour::KdTree
+        } else {
+            CommonNearestNeighbour::BallTree
+        }
+    }
+}
+
+impl NearestNeighbour for AutoNearestNeighbour {
+    fn default_leaf_size(&self) -> usize {
+        self.leaf_size
+    }
+
+    fn from_cow_with_leaf_size<'a, F: Float, D: 'a + Distance<F>>(
+        &self,
+        batch: CowArray<'a, F, Ix2>,
+        leaf_size: usize,
+        dist_fn: D,
+    ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
+        self.select(&batch, &dist_fn)
+            .from_cow_with_leaf_size(batch, leaf_size, dist_fn)
+    }
+}
@@ -2,7 +2,7 @@
 use std::{cmp::Reverse, collections::BinaryHeap};
 
 use linfa::Float;
-use ndarray::{Array1, ArrayBase, Data, Ix2};
+use ndarray::{Array1, ArrayBase, ArrayView2, CowArray, Data, Ix2};
 use noisy_float::{checkers::FiniteChecker, NoisyFloat};
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
@@ -10,13 +10,17 @@ use serde_crate::{Deserialize, Serialize};
 use crate::{
     distance::Distance,
     heap_elem::{MaxHeapElem, MinHeapElem},
-    BuildError, NearestNeighbour, NearestNeighbourIndex, NnError, Point,
+    BuildError, NearestNeighbour, NearestNeighbourIndex, NnError, Point, DEFAULT_LEAF_SIZE,
 };
 
 // Partition the points using median value
 fn partition<F: Float>(
     mut points: Vec<(Point<F>, usize)>,
-) -> (Vec<(Point<F>, usize)>, Point<F>, Vec<(Point<F>, usize)>) {
+) -> (
+    Vec<(Point<F>, usize)>,
+    (Point<F>, usize),
+    Vec<(Point<F>, usize)>,
+) {
     debug_assert!(points.len() >= 2);
 
     // Spread of a dimension is measured using range, which is suceptible to skew. It may be better
@@ -39,13 +43,14 @@ fn partition<F: Float>(
 
     let mid = points.len() / 2;
     // Compute median on the chosen dimension in linear time
-    let median = order_stat::kth_by(&mut points, mid, |p1, p2| {
-        p1.0[max_spread_dim]
-            .partial_cmp(&p2.0[max_spread_dim])
-            .expect("NaN in data")
-    })
-    .0
-    .reborrow();
+    let (median, median_pos) = {
+        let median = order_stat::kth_by(&mut points, mid, |p1, p2| {
+            p1.0[max_spread_dim]
+                .partial_cmp(&p2.0[max_spread_dim])
+                .expect("NaN in data")
+        });
+        (median.0.reborrow(), median.1)
+    };
 
     let (mut left, mut right): (Vec<_>, Vec<_>) = points
         .into_iter()
@@ -56,7 +61,7 @@ fn partition<F: Float>(
     if left.is_empty() {
         left.push(right.pop().unwrap());
     }
-    (left, median, right)
+    (left, (median, median_pos), right)
 }
 
 // Calculates radius of a bounding sphere
@@ -73,29 +78,26 @@ fn calc_radius<'a, F: Float, D: Distance<F>>(
     dist_fn.rdist_to_dist(r_rad)
 }
 
+// Nodes refer to the indexed points by their positions, so that the tree does not borrow them
 #[derive(Debug, PartialEq)]
-enum BallTreeInner<'a, F: Float> {
+enum BallTreeInner<F: Float> {
     // Leaf node sphere
     Leaf {
         center: Array1<F>,
         radius: F,
-        points: Vec<(Point<'a, F>, usize)>,
+        points: Vec<usize>,
     },
-    // Sphere that encompasses both children
+    // Sphere centered at one of the points that encompasses both children
     Branch {
-        center: Point<'a, F>,
+        center: usize,
         radius: F,
-        left: Box<BallTreeInner<'a, F>>,
-        right: Box<BallTreeInner<'a, F>>,
+        left: Box<BallTreeInner<F>>,
+        right: Box<BallTreeInner<F>>,
     },
 }
 
-impl<'a, F: Float> BallTreeInner<'a, F> {
-    fn new<D: Distance<F>>(
-        points: Vec<(Point<'a, F>, usize)>,
-        leaf_size: usize,
-        dist_fn: &D,
-    ) -> Self {
+impl<F: Float> BallTreeInner<F> {
+    fn new<D: Distance<F>>(points: Vec<(Point<F>, usize)>, leaf_size: usize, dist_fn: &D) -> Self {
         if points.len() <= leaf_size {
             // Leaf node
             if let Some(dim) = points.first().map(|p| p.0.len()) {
@@ -114,19 +116,19 @@ impl<'a, F: Float> BallTreeInner<'a, F> {
                 BallTreeInner::Leaf {
                     center,
                     radius,
-                    points,
+                    points: points.into_iter().map(|(_, pos)| pos).collect(),
                 }
             } else {
                 // In case of an empty tree
                 BallTreeInner::Leaf {
                     center: Array1::zeros(0),
-                    points,
+                    points: Vec::new(),
                     radius: F::zero(),
                 }
             }
         } else {
             // Non-leaf node
-            let (aps, center, bps) = partition(points);
+            let (aps, (center, center_pos), bps) = partition(points);
             debug_assert!(!aps.is_empty() && !bps.is_empty());
             let radius = calc_radius(
                 aps.iter().chain(bps.iter()).map(|p| p.0.reborrow()),
@@ -136,7 +138,7 @@ impl<'a, F: Float> BallTreeInner<'a, F> {
             let a_tree = BallTreeInner::new(aps, leaf_size, dist_fn);
             let b_tree = BallTreeInner::new(bps, leaf_size, dist_fn);
             BallTreeInner::Branch {
-                center,
+                center: center_pos,
                 radius,
                 left: Box::new(a_tree),
                 right: Box::new(b_tree),
@@ -144,10 +146,10 @@ impl<'a, F: Float> BallTreeInner<'a, F> {
         }
     }
 
-    fn rdistance<D: Distance<F>>(&self, p: Point<F>, dist_fn: &D) -> F {
+    fn rdistance<D: Distance<F>>(&self, p: Point<F>, points: ArrayView2<F>, dist_fn: &D) -> F {
         let (center, radius) = match self {
             BallTreeInner::Leaf { center, radius, .. } => (center.view(), radius),
-            BallTreeInner::Branch { center, radius, .. } => (center.reborrow(), radius),
+            BallTreeInner::Branch { center, radius, .. } => (points.row(*center), radius),
         };
 
         // The distance to a sphere is the distance to its edge, so the distance between a point
@@ -161,7 +163,8 @@ impl<'a, F: Float> BallTreeInner<'a, F> {
 /// Spatial indexing structure created by [`BallTree`](struct.BallTree.html)
 #[derive(Debug)]
 pub struct BallTreeIndex<'a, F: Float, D: Distance<F>> {
-    tree: BallTreeInner<'a, F>,
+    tree: BallTreeInner<F>,
+    points: CowArray<'a, F, Ix2>,
     dist_fn: D,
     dim: usize,
     len: usize,
@@ -173,6 +176,15 @@ impl<'a, F: Float, D: Distance<F>> BallTreeIndex<'a, F, D> {
         batch: &'a ArrayBase<DT, Ix2>,
         leaf_size: usize,
         dist_fn: D,
+    ) -> Result<Self, BuildError> {
+        Self::from_cow(batch.view().into(), leaf_size, dist_fn)
+    }
+
+    /// Creates a `BallTreeIndex` from borrowed or owned points
+    pub fn from_cow(
+        batch: CowArray<'a, F, Ix2>,
+        leaf_size: usize,
+        dist_fn: D,
     ) -> Result<Self, BuildError> {
         let dim = batch.ncols();
         let len = batch.nrows();
@@ -187,8 +199,10 @@ impl<'a, F: Float, D: Distance<F>> BallTreeIndex<'a, F, D> {
                 .enumerate()
                 .map(|(i, pt)| (pt, i))
                 .collect();
+            let tree = BallTreeInner::new(points, leaf_size, &dist_fn);
             Ok(BallTreeIndex {
-                tree: BallTreeInner::new(points, leaf_size, &dist_fn),
+                tree,
+                points: batch,
                 dist_fn,
                 dim,
                 len,
@@ -209,8 +223,9 @@ impl<'a, F: Float, D: Distance<F>> BallTreeIndex<'a, F, D> {
         } else {
             let mut out: BinaryHeap<MaxHeapElem<_, _>> = BinaryHeap::new();
             let mut queue = BinaryHeap::new();
+            let points = self.points.view();
             queue.push(MinHeapElem::new(
-                self.tree.rdistance(point, &self.dist_fn),
+                self.tree.rdistance(point, points, &self.dist_fn),
                 &self.tree,
             ));
 
@@ -224,13 +239,13 @@ impl<'a, F: Float, D: Distance<F>> BallTreeIndex<'a, F, D> {
                 }
 
                 match elem {
-                    BallTreeInner::Leaf { points, .. } => {
-                        for p in points {
-                            let dist = self.dist_fn.rdistance(point, p.0.reborrow());
+                    BallTreeInner::Leaf { points: leaf, .. } => {
+                        for &pos in leaf {
+                            let dist = self.dist_fn.rdistance(point, points.row(pos));
                             if dist < max_radius
                                 && (out.len() < k || out.peek().unwrap().dist > dist)
                             {
-                                out.push(MaxHeapElem::new(dist, p));
+                                out.push(MaxHeapElem::new(dist, pos));
                                 if out.len() > k {
                                     out.pop();
                                 }
@@ -238,8 +253,8 @@ impl<'a, F: Float, D: Distance<F>> BallTreeIndex<'a, F, D> {
                         }
                     }
                     BallTreeInner::Branch { left, right, .. } => {
-                        let dl = left.rdistance(point, &self.dist_fn);
-                        let dr = right.rdistance(point, &self.dist_fn);
+                        let dl = left.rdistance(point, points, &self.dist_fn);
+                        let dr = right.rdistance(point, points, &self.dist_fn);
 
                         if dl <= max_radius {
                             queue.push(MinHeapElem::new(dl, left));
@@ -253,8 +268,7 @@ impl<'a, F: Float, D: Distance<F>> BallTreeIndex<'a, F, D> {
             Ok(out
                 .into_sorted_vec()
                 .into_iter()
-                .map(|e| e.elem)
-                .map(|(pt, i)| (pt.reborrow(), *i))
+                .map(|e| (self.points.row(e.elem), e.elem))
                 .collect())
         }
     }
@@ -277,6 +291,10 @@ impl<'a, F: Float, D: Distance<F>> NearestNeighbourIndex<F> for BallTreeIndex<'a
         let range = self.dist_fn.dist_to_rdist(range);
         self.nn_helper(point, self.len, range)
     }
+
+    fn points(&self) -> ArrayView2<F> {
+        self.points.view()
+    }
 }
 
 /// Implementation of ball tree, a space partitioning data structure that partitions its points
@@ -286,29 +304,49 @@ impl<'a, F: Float, D: Distance<F>> NearestNeighbourIndex<F> for BallTreeIndex<'a
 ///
 /// More details can be found [here](https://en.wikipedia.org/wiki/Ball_tree). This implementation
 /// is based off of the [ball_tree](https://docs.rs/ball-tree/0.2.0/ball_tree/) crate.
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
     serde(crate = "serde_crate")
 )]
-pub struct BallTree;
+pub struct BallTree {
+    leaf_size: usize,
+}
+
+impl Default for BallTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl BallTree {
-    /// Creates an instance of `BallTree`
+    /// Creates an instance of `BallTree` with the default leaf size
     pub fn new() -> Self {
-        Self
+        Self {
+            leaf_size: DEFAULT_LEAF_SIZE,
+        }
+    }
+
+    /// Set the number of points in the leaf nodes, used by `from_batch`
+    pub fn leaf_size(mut self, leaf_size: usize) -> Self {
+        self.leaf_size = leaf_size;
+        self
     }
 }
 
 impl NearestNeighbour for BallTree {
-    fn from_batch_with_leaf_size<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
+    fn default_leaf_size(&self) -> usize {
+        self.leaf_size
+    }
+
+    fn from_cow_with_leaf_size<'a, F: Float, D: 'a + Distance<F>>(
         &self,
-        batch: &'a ArrayBase<DT, Ix2>,
+        batch: CowArray<'a, F, Ix2>,
         leaf_size: usize,
         dist_fn: D,
     ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
-        BallTreeIndex::new(batch, leaf_size, dist_fn)
+        BallTreeIndex::from_cow(batch, leaf_size, dist_fn)
             .map(|v| Box::new(v) as Box<dyn NearestNeighbourIndex<F>>)
     }
 }
@@ -335,7 +373,7 @@ mod test {
             .enumerate()
             .map(|(i, p)| (p, i))
             .collect();
-        let (l, mid, r) = partition(vec.clone());
+        let (l, (mid, _), r) = partition(vec.clone());
         let l: Vec<_> = l.into_iter().map(|(p, _)| p).collect();
         let r: Vec<_> = r.into_iter().map(|(p, _)| p).collect();
         assert_abs_diff_eq!(stack(Axis(0), &l).unwrap(), exp_left);
@@ -1,5 +1,5 @@
 use linfa::Float;
-use ndarray::{ArrayView, Dimension, Zip};
+use ndarray::{Array2, ArrayView, ArrayView2, Dimension, Zip};
 use ndarray_stats::DeviationExt;
 
 /// A distance function that can be used in spatial algorithms such as nearest neighbour.
@@ -30,6 +30,15 @@ pub trait Distance<F: Float>: Clone + Send + Sync {
     fn dist_to_rdist(&self, dist: F) -> F {
         dist
     }
+
+    /// Whether the distance is a [Minkowski](https://en.wikipedia.org/wiki/Minkowski_distance)
+    /// distance of the coordinates. Only these distances are bounded by the axis-aligned boxes of
+    /// [`KdTree`](../struct.KdTree.html), which rejects any other distance. Distances like
+    /// [`HaversineDist`](struct.HaversineDist.html) have to return `false`.
+    #[inline]
+    fn is_minkowski(&self) -> bool {
+        true
+    }
 }
 
 /// L1 or [Manhattan](https://en.wikipedia.org/wiki/Taxicab_geometry) distance
@@ -90,10 +99,94 @@ impl<F: Float> Distance<F> for LpDist<F> {
     }
 }
 
+/// [Haversine](https://en.wikipedia.org/wiki/Haversine_formula) or great-circle distance
+///
+/// Points are two-dimensional `(latitude, longitude)` pairs in radians and the distance is the
+/// central angle between them on the unit sphere, multiply it with the radius of the sphere (about
+/// 6371 km for the earth) to get the arc length. The distance satisfies the triangle inequality,
+/// so it can be used with [`BallTree`](../struct.BallTree.html) and
+/// [`LinearSearch`](../struct.LinearSearch.html). [`KdTree`](../struct.KdTree.html) prunes with
+/// axis-aligned bounding boxes and returns an error when built with it.
+///
+/// Panics if the points are not two-dimensional.
+#[derive(Debug, Clone)]
+pub struct HaversineDist;
+impl<F: Float> Distance<F> for HaversineDist {
+    #[inline]
+    fn distance<D: Dimension>(&self, a: ArrayView<F, D>, b: ArrayView<F, D>) -> F {
+        self.rdist_to_dist(self.rdistance(a, b))
+    }
+
+    /// Computes the haversine of the central angle
+    #[inline]
+    fn rdistance<D: Dimension>(&self, a: ArrayView<F, D>, b: ArrayView<F, D>) -> F {
+        assert!(
+            a.len() == 2 && b.len() == 2,
+            "haversine distance requires (latitude, longitude) points"
+        );
+        let mut a = a.iter();
+        let mut b = b.iter();
+        let (lat1, lon1) = (*a.next().unwrap(), *a.next().unwrap());
+        let (lat2, lon2) = (*b.next().unwrap(), *b.next().unwrap());
+
+        let two = F::cast(2.);
+        let hav = ((lat2 - lat1) / two).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / two).sin().powi(2);
+        // rounding errors can push antipodal points out of the domain of `asin`
+        hav.min(F::one())
+    }
+
+    #[inline]
+    fn rdist_to_dist(&self, rdist: F) -> F {
+        F::cast(2.) * rdist.sqrt().asin()
+    }
+
+    #[inline]
+    fn dist_to_rdist(&self, dist: F) -> F {
+        // distances beyond half a great circle include every point
+        let dist = dist.min(F::cast(std::f64::consts::PI));
+        (dist / F::cast(2.)).sin().powi(2)
+    }
+
+    /// Longitudes wrap around at ±180°, so bounding boxes in `(latitude, longitude)` space
+    /// don't bound the distance
+    #[inline]
+    fn is_minkowski(&self) -> bool {
+        false
+    }
+}
+
+/// Computes the distances between all pairs of rows in `a` and `b`
+///
+/// Returns a matrix with shape `(a.nrows(), b.nrows())`, whose entry `(i, j)` is the distance
+/// between the `i`-th row of `a` and the `j`-th row of `b`. If `b` is `None`, the distances
+/// between the rows of `a` are computed instead. With the `rayon` feature enabled, the entries
+/// are computed in parallel.
+///
+/// Panics if the rows of `a` and `b` have different dimensions.
+pub fn pairwise_distances<F: Float, D: Distance<F>>(
+    a: &ArrayView2<F>,
+    b: Option<&ArrayView2<F>>,
+    dist_fn: &D,
+) -> Array2<F> {
+    let b = b.unwrap_or(a);
+    assert_eq!(a.ncols(), b.ncols());
+
+    let mut distances = Array2::zeros((a.nrows(), b.nrows()));
+    let zip = Zip::indexed(&mut distances);
+
+    #[cfg(feature = "rayon")]
+    zip.par_apply(|(i, j), dist| *dist = dist_fn.distance(a.row(i), b.row(j)));
+    #[cfg(not(feature = "rayon"))]
+    zip.apply(|(i, j), dist| *dist = dist_fn.distance(a.row(i), b.row(j)));
+
+    distances
+}
+
 #[cfg(test)]
 mod test {
     use approx::assert_abs_diff_eq;
-    use ndarray::arr1;
+    use ndarray::{arr1, arr2, Array1};
 
     use super::*;
 
@@ -142,4 +235,64 @@ mod test {
     fn lp_dist() {
         dist_test(LpDist(3.3), 4.635);
     }
+
+    #[test]
+    fn haversine_dist() {
+        let city = |lat: f64, lon: f64| arr1(&[lat.to_radians(), lon.to_radians()]);
+        let paris = city(48.8566, 2.3522);
+        let london = city(51.5074, -0.1278);
+        let new_york = city(40.7128, -74.0060);
+        let los_angeles = city(34.0522, -118.2437);
+        let sydney = city(-33.8688, 151.2093);
+
+        // reference distances in km on a sphere with the mean radius of the earth
+        let earth_radius = 6371.;
+        let km = |a: &Array1<f64>, b: &Array1<f64>| {
+            HaversineDist.distance(a.view(), b.view()) * earth_radius
+        };
+        assert_abs_diff_eq!(km(&paris, &london), 343.56, epsilon = 0.1);
+        assert_abs_diff_eq!(km(&new_york, &los_angeles), 3935.75, epsilon = 0.1);
+        assert_abs_diff_eq!(km(&london, &new_york), 5570.22, epsilon = 0.1);
+        assert_abs_diff_eq!(km(&paris, &sydney), 16960.50, epsilon = 0.1);
+        assert_abs_diff_eq!(km(&paris, &paris), 0.);
+
+        // antipodal points are half a great circle apart
+        let antipode = arr1(&[-paris[0], paris[1] - std::f64::consts::PI]);
+        assert_abs_diff_eq!(
+            HaversineDist.distance(paris.view(), antipode.view()),
+            std::f64::consts::PI,
+            epsilon = 1e-6
+        );
+
+        let dist = HaversineDist.distance(paris.view(), sydney.view());
+        assert_abs_diff_eq!(
+            HaversineDist.rdist_to_dist(HaversineDist.dist_to_rdist(dist)),
+            dist,
+            epsilon = 1e-12
+        );
+
+        // Triangle inequality
+        assert!(km(&paris, &new_york) <= km(&paris, &london) + km(&london, &new_york));
+    }
+
+    #[test]
+    fn pairwise() {
+        let a = arr2(&[[0.5, 6.6], [4.4, 3.0], [-4.5, 3.3]]);
+        let b = arr2(&[[1.0, 1.0], [0.0, -2.0]]);
+
+        let distances = pairwise_distances(&a.view(), Some(&b.view()), &L1Dist);
+        assert_eq!(distances.dim(), (3, 2));
+        for (i, row) in distances.genrows().into_iter().enumerate() {
+            for (j, dist) in row.iter().enumerate() {
+                assert_abs_diff_eq!(*dist, L1Dist.distance(a.row(i), b.row(j)));
+            }
+        }
+
+        // self-distances are symmetric with a zero diagonal
+        let distances = pairwise_distances(&a.view(), None, &L2Dist);
+        assert_eq!(distances.dim(), (3, 3));
+        assert_abs_diff_eq!(distances, distances.t());
+        assert_abs_diff_eq!(distances.diag(), arr1(&[0., 0., 0.]));
+        assert_abs_diff_eq!(distances[(0, 1)], 5.3075, epsilon = 1e-3);
+    }
 }
@@ -0,0 +1,413 @@
+use linfa::{
+    traits::{Fit, ScoreSamples},
+    DatasetBase, Float,
+};
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix2};
+use ndarray_rand::rand::Rng;
+use ndarray_rand::rand_distr::StandardNormal;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    distance::{Distance, L2Dist},
+    BuildError, CommonNearestNeighbour, NearestNeighbour, NearestNeighbourIndex,
+};
+
+/// Kernel values of the gaussian kernel below this fraction of its maximum are ignored
+const GAUSSIAN_CUTOFF: f64 = 1e-8;
+
+/// Error returned when fitting or evaluating a kernel density estimate
+#[derive(Error, Debug)]
+pub enum KdeError {
+    #[error("invalid value encountered: {0}")]
+    InvalidValue(String),
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    #[error(transparent)]
+    LinfaError(#[from] linfa::Error),
+}
+
+/// Smoothing kernel placed on every sample of a kernel density estimate
+///
+/// For a distance `u` between two points, relative to the bandwidth, the kernels are proportional
+/// to:
+///  * Gaussian: `exp(-u²/2)`
+///  * Epanechnikov: `1 - u²` for `u < 1`
+///  * Tophat: `1` for `u < 1`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub enum KdeKernel {
+    Gaussian,
+    Epanechnikov,
+    Tophat,
+}
+
+impl KdeKernel {
+    /// Distance, relative to the bandwidth, beyond which the kernel is considered zero
+    fn support<F: Float>(&self) -> F {
+        match self {
+            KdeKernel::Gaussian => F::cast((-2. * GAUSSIAN_CUTOFF.ln()).sqrt()),
+            KdeKernel::Epanechnikov | KdeKernel::Tophat => F::one(),
+        }
+    }
+
+    /// Unnormalized kernel value for a relative distance `u`
+    fn value<F: Float>(&self, u: F) -> F {
+        match self {
+            KdeKernel::Gaussian => (-u * u / F::cast(2.)).exp(),
+            KdeKernel::Epanechnikov if u < F::one() => F::one() - u * u,
+            KdeKernel::Tophat if u <= F::one() => F::one(),
+            _ => F::zero(),
+        }
+    }
+
+    /// Logarithm of the constant normalizing the kernel to unit mass in `d` dimensions
+    fn log_norm<F: Float>(&self, d: usize) -> F {
+        let norm = match self {
+            KdeKernel::Gaussian => (2. * std::f64::consts::PI).powf(-(d as f64) / 2.),
+            KdeKernel::Epanechnikov => (d as f64 + 2.) / (2. * unit_ball_volume(d)),
+            KdeKernel::Tophat => 1. / unit_ball_volume(d),
+        };
+
+        F::cast(norm.ln())
+    }
+}
+
+/// Volume of the unit ball in `d` dimensions
+fn unit_ball_volume(d: usize) -> f64 {
+    match d {
+        0 => 1.,
+        1 => 2.,
+        d => unit_ball_volume(d - 2) * 2. * std::f64::consts::PI / d as f64,
+    }
+}
+
+/// Kernel density estimation hyperparameters
+///
+/// The estimate places a `kernel` with width `bandwidth` on every training sample. Neighbouring
+/// samples of a query point are found with the nearest neighbour algorithm `nn_algo`.
+#[derive(Debug, Clone)]
+pub struct KernelDensityParams<F> {
+    kernel: KdeKernel,
+    bandwidth: F,
+    nn_algo: CommonNearestNeighbour,
+}
+
+impl<F: Float> KernelDensityParams<F> {
+    /// Set the smoothing kernel, defaults to `KdeKernel::Gaussian`
+    pub fn kernel(mut self, kernel: KdeKernel) -> Self {
+        self.kernel = kernel;
+        self
+    }
+
+    /// Set the width of the kernel, defaults to `1`
+    pub fn bandwidth(mut self, bandwidth: F) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// Set the nearest neighbour algorithm, defaults to `CommonNearestNeighbour::KdTree`
+    pub fn nn_algo(mut self, nn_algo: CommonNearestNeighbour) -> Self {
+        self.nn_algo = nn_algo;
+        self
+    }
+
+    /// Validate the hyperparameters
+    pub fn validate(&self) -> Result<(), KdeError> {
+        if self.bandwidth <= F::zero() {
+            return Err(KdeError::InvalidValue(format!(
+                "bandwidth should be positive, but is {}",
+                self.bandwidth
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T> Fit<ArrayBase<D, Ix2>, T, KdeError>
+    for KernelDensityParams<F>
+{
+    type Object = KernelDensity<F>;
+
+    /// Fit a kernel density estimate to the records of a dataset, targets are ignored
+    ///
+    /// The spatial index over the samples is built once here and reused by every call to
+    /// `score_samples`.
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object, KdeError> {
+        self.validate()?;
+
+        if dataset.records().nrows() == 0 {
+            return Err(linfa::Error::NotEnoughSamples.into());
+        }
+
+        // the index keeps the only copy of the samples
+        let index = self
+            .nn_algo
+            .from_owned_batch(dataset.records().to_owned(), L2Dist)?;
+
+        Ok(KernelDensity {
+            index,
+            kernel: self.kernel,
+            bandwidth: self.bandwidth,
+        })
+    }
+}
+
+/// Kernel density estimate
+///
+/// Estimates the probability density of a set of samples by the normalized sum of kernels
+/// centered at the samples:
+///
+/// `p(x) = 1 / (n h^d) * sum_i K(|x - x_i| / h)`
+///
+/// where `n` is the number of samples, `h` the bandwidth and `d` the number of features. Only
+/// samples within the support of the kernel are summed up, which are found with a spatial index.
+/// As the gaussian kernel has unbounded support, it is truncated where its value drops below
+/// `1e-8` of the maximum.
+///
+/// ## Example
+///
+/// ```rust
+/// use linfa::{traits::{Fit, ScoreSamples}, DatasetBase};
+/// use linfa_nn::{KdeKernel, KernelDensity};
+/// use ndarray::array;
+///
+/// let dataset = DatasetBase::from(array![[0.0], [0.5], [1.0]]);
+/// let kde = KernelDensity::params()
+///     .kernel(KdeKernel::Epanechnikov)
+///     .bandwidth(0.5)
+///     .fit(&dataset)
+///     .unwrap();
+///
+/// let log_density = kde.score_samples(&array![[0.5], [10.0]]);
+/// assert!(log_density[0] > log_density[1]);
+/// ```
+pub struct KernelDensity<F: Float> {
+    index: Box<dyn NearestNeighbourIndex<F>>,
+    kernel: KdeKernel,
+    bandwidth: F,
+}
+
+impl<F: Float> std::fmt::Debug for KernelDensity<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KernelDensity")
+            .field("points", &self.index.points())
+            .field("kernel", &self.kernel)
+            .field("bandwidth", &self.bandwidth)
+            .finish()
+    }
+}
+
+impl<F: Float> KernelDensity<F> {
+    /// Create default kernel density hyperparameters
+    pub fn params() -> KernelDensityParams<F> {
+        KernelDensityParams {
+            kernel: KdeKernel::Gaussian,
+            bandwidth: F::one(),
+            nn_algo: CommonNearestNeighbour::KdTree,
+        }
+    }
+
+    /// Return the smoothing kernel
+    pub fn kernel(&self) -> KdeKernel {
+        self.kernel
+    }
+
+    /// Return the width of the kernel
+    pub fn bandwidth(&self) -> F {
+        self.bandwidth
+    }
+
+    /// Draw `n` random samples from the estimated density
+    ///
+    /// A sample is generated by picking a training sample uniformly at random and adding noise
+    /// distributed like the kernel.
+    pub fn sample<R: Rng>(&self, n: usize, rng: &mut R) -> Array2<F> {
+        let points = self.index.points();
+        let nfeatures = points.ncols();
+        let mut samples = Array2::zeros((n, nfeatures));
+
+        for mut sample in samples.genrows_mut() {
+            let center = points.row(rng.gen_range(0..points.nrows()));
+
+            let noise = match self.kernel {
+                KdeKernel::Gaussian => random_normal(nfeatures, rng),
+                KdeKernel::Tophat => random_in_ball(nfeatures, rng),
+                // rejection sampling from the uniform distribution in the unit ball
+                KdeKernel::Epanechnikov => loop {
+                    let candidate = random_in_ball(nfeatures, rng);
+                    let norm = candidate.dot(&candidate);
+                    if rng.gen::<f64>() < 1. - norm {
+                        break candidate;
+                    }
+                },
+            };
+
+            sample.assign(&(&center + &noise.mapv(|x| F::cast(x) * self.bandwidth)));
+        }
+
+        samples
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> ScoreSamples<ArrayBase<D, Ix2>, F> for KernelDensity<F> {
+    /// Evaluate the logarithm of the estimated density for each row of `x`
+    ///
+    /// Points outside the support of all kernels have a log-density of negative infinity.
+    ///
+    /// # Panics
+    ///
+    /// If `x` has a different number of features than the training samples.
+    fn score_samples(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let points = self.index.points();
+        let nfeatures = points.ncols();
+        let range = self.kernel.support::<F>() * self.bandwidth;
+        let log_norm = self.kernel.log_norm::<F>(nfeatures)
+            - F::cast(points.nrows()).ln()
+            - F::cast(nfeatures) * self.bandwidth.ln();
+
+        x.genrows()
+            .into_iter()
+            .map(|point| {
+                let point = point.as_standard_layout();
+                let sum = self
+                    .index
+                    .within_range(point.view(), range)
+                    .expect("query points should have the dimension of the training samples")
+                    .into_iter()
+                    .map(|(neighbour, _)| {
+                        let dist = L2Dist.distance(point.view(), neighbour);
+                        self.kernel.value(dist / self.bandwidth)
+                    })
+                    .sum::<F>();
+
+                sum.ln() + log_norm
+            })
+            .collect()
+    }
+}
+
+fn random_normal<R: Rng>(d: usize, rng: &mut R) -> Array1<f64> {
+    (0..d)
+        .map(|_| rng.sample::<f64, _>(StandardNormal))
+        .collect()
+}
+
+/// Draw a point uniformly from the unit ball in `d` dimensions
+fn random_in_ball<R: Rng>(d: usize, rng: &mut R) -> Array1<f64> {
+    let direction = random_normal(d, rng);
+    let norm = direction.dot(&direction).sqrt();
+    let radius = rng.gen::<f64>().powf(1. / d as f64);
+
+    direction * (radius / norm)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_abs_diff_eq;
+    use linfa::{
+        traits::{Fit, ScoreSamples},
+        DatasetBase,
+    };
+    use ndarray::{array, Array, Array2, Axis};
+    use ndarray_rand::{rand::SeedableRng, rand_distr::StandardNormal, RandomExt};
+    use ndarray_stats::QuantileExt;
+    use rand_isaac::Isaac64Rng;
+
+    use super::*;
+
+    fn normal_samples() -> DatasetBase<Array2<f64>, Array2<()>> {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        Array2::random_using((500, 1), StandardNormal, &mut rng).into()
+    }
+
+    #[test]
+    fn density_integrates_to_one() {
+        let dataset = normal_samples();
+        let grid = Array::range(-6., 6., 0.01).insert_axis(Axis(1));
+
+        for kernel in &[
+            KdeKernel::Gaussian,
+            KdeKernel::Epanechnikov,
+            KdeKernel::Tophat,
+        ] {
+            let kde = KernelDensity::params()
+                .kernel(*kernel)
+                .bandwidth(0.4)
+                .fit(&dataset)
+                .unwrap();
+
+            let density = kde.score_samples(&grid).mapv(f64::exp);
+            assert_abs_diff_eq!(density.sum() * 0.01, 1.0, epsilon = 1e-2);
+
+            // the density peaks close to the mode of the standard normal distribution
+            let mode = grid[(density.argmax().unwrap(), 0)];
+            assert!(mode.abs() < 0.5);
+        }
+    }
+
+    #[test]
+    fn density_is_independent_of_index() {
+        let dataset = normal_samples();
+        let grid = Array::range(-3., 3., 0.1).insert_axis(Axis(1));
+
+        let reference = KernelDensity::params()
+            .nn_algo(CommonNearestNeighbour::LinearSearch)
+            .fit(&dataset)
+            .unwrap()
+            .score_samples(&grid);
+
+        for nn_algo in &[
+            CommonNearestNeighbour::KdTree,
+            CommonNearestNeighbour::BallTree,
+        ] {
+            let log_density = KernelDensity::params()
+                .nn_algo(*nn_algo)
+                .fit(&dataset)
+                .unwrap()
+                .score_samples(&grid);
+
+            assert_abs_diff_eq!(log_density, reference, epsilon = 1e-8);
+        }
+    }
+
+    #[test]
+    fn samples_follow_density() {
+        let dataset = normal_samples();
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+
+        for kernel in &[
+            KdeKernel::Gaussian,
+            KdeKernel::Epanechnikov,
+            KdeKernel::Tophat,
+        ] {
+            let kde = KernelDensity::params()
+                .kernel(*kernel)
+                .bandwidth(0.2)
+                .fit(&dataset)
+                .unwrap();
+
+            let samples = kde.sample(5000, &mut rng);
+            assert_eq!(samples.dim(), (5000, 1));
+            // the variance of the samples is the sum of the data and kernel variance
+            let mean = samples.mean().unwrap();
+            let var = samples.mapv(|x| (x - mean) * (x - mean)).mean().unwrap();
+            assert_abs_diff_eq!(mean, 0.0, epsilon = 0.1);
+            assert_abs_diff_eq!(var, 1.0, epsilon = 0.15);
+        }
+    }
+
+    #[test]
+    fn invalid_bandwidth() {
+        let res = KernelDensity::params()
+            .bandwidth(0.0)
+            .fit(&DatasetBase::from(array![[0.0]]));
+        assert!(matches!(res, Err(KdeError::InvalidValue(_))));
+    }
+}
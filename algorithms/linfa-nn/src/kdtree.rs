@@ -1,23 +1,53 @@
 use linfa::Float;
-use ndarray::{aview1, ArrayBase, Data, Ix2};
+use ndarray::{aview1, ArcArray, ArrayBase, ArrayView2, CowArray, Data, Ix2};
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
 
 use crate::{
     distance::Distance, BuildError, NearestNeighbour, NearestNeighbourIndex, NnError, Point,
+    DEFAULT_LEAF_SIZE,
 };
 
+/// Key of a point in the KD tree, which refers to a row of the indexed points instead of holding
+/// a copy of it
+#[derive(Debug, Clone, PartialEq)]
+struct Row<F> {
+    points: ArcArray<F, Ix2>,
+    pos: usize,
+}
+
+impl<F: Float> AsRef<[F]> for Row<F> {
+    fn as_ref(&self) -> &[F] {
+        let dim = self.points.ncols();
+        // the points are stored in standard layout, so every row is a contiguous slice
+        &self.points.as_slice().unwrap()[self.pos * dim..(self.pos + 1) * dim]
+    }
+}
+
 /// Spatial indexing structure created by [`KdTree`](struct.KdTree.html)
 #[derive(Debug)]
-pub struct KdTreeIndex<'a, F: Float, D: Distance<F>>(
-    kdtree::KdTree<F, (Point<'a, F>, usize), &'a [F]>,
-    D,
-);
+pub struct KdTreeIndex<F: Float, D: Distance<F>> {
+    tree: kdtree::KdTree<F, usize, Row<F>>,
+    points: ArcArray<F, Ix2>,
+    dist_fn: D,
+}
 
-impl<'a, F: Float, D: Distance<F>> KdTreeIndex<'a, F, D> {
+impl<F: Float, D: Distance<F>> KdTreeIndex<F, D> {
     /// Creates a new `KdTreeIndex`
     pub fn new<DT: Data<Elem = F>>(
-        batch: &'a ArrayBase<DT, Ix2>,
+        batch: &ArrayBase<DT, Ix2>,
+        leaf_size: usize,
+        dist_fn: D,
+    ) -> Result<Self, BuildError> {
+        Self::from_cow(batch.view().into(), leaf_size, dist_fn)
+    }
+
+    /// Creates a new `KdTreeIndex` from borrowed or owned points
+    ///
+    /// The tree shares the points with the index. Owned points in standard layout are taken
+    /// without copying them, all others are copied once.
+    pub fn from_cow(
+        batch: CowArray<F, Ix2>,
         leaf_size: usize,
         dist_fn: D,
     ) -> Result<Self, BuildError> {
@@ -25,16 +55,28 @@ impl<'a, F: Float, D: Distance<F>> KdTreeIndex<'a, F, D> {
             Err(BuildError::EmptyLeaf)
         } else if batch.ncols() == 0 {
             Err(BuildError::ZeroDimension)
+        } else if !dist_fn.is_minkowski() {
+            Err(BuildError::UnsupportedDistance)
         } else {
-            let mut tree = kdtree::KdTree::with_capacity(batch.ncols().max(1), leaf_size);
-            for (i, point) in batch.genrows().into_iter().enumerate() {
-                tree.add(
-                    point.to_slice().expect("views should be contiguous"),
-                    (point, i),
-                )
-                .unwrap();
+            let points = if batch.is_standard_layout() {
+                batch.into_owned().into_shared()
+            } else {
+                batch.as_standard_layout().into_owned().into_shared()
+            };
+
+            let mut tree = kdtree::KdTree::with_capacity(points.ncols(), leaf_size);
+            for pos in 0..points.nrows() {
+                let row = Row {
+                    points: points.clone(),
+                    pos,
+                };
+                tree.add(row, pos).unwrap();
             }
-            Ok(Self(tree, dist_fn))
+            Ok(Self {
+                tree,
+                points,
+                dist_fn,
+            })
         }
     }
 }
@@ -49,21 +91,21 @@ impl From<kdtree::ErrorKind> for NnError {
     }
 }
 
-impl<'a, F: Float, D: Distance<F>> NearestNeighbourIndex<F> for KdTreeIndex<'a, F, D> {
+impl<F: Float, D: Distance<F>> NearestNeighbourIndex<F> for KdTreeIndex<F, D> {
     fn k_nearest<'b>(
         &self,
         point: Point<'b, F>,
         k: usize,
     ) -> Result<Vec<(Point<F>, usize)>, NnError> {
         Ok(self
-            .0
+            .tree
             .nearest(
                 point.to_slice().expect("views should be contiguous"),
                 k,
-                &|a, b| self.1.rdistance(aview1(a), aview1(b)),
+                &|a, b| self.dist_fn.rdistance(aview1(a), aview1(b)),
             )?
             .into_iter()
-            .map(|(_, (pt, pos))| (pt.reborrow(), *pos))
+            .map(|(_, pos)| (self.points.row(*pos), *pos))
             .collect())
     }
 
@@ -72,18 +114,22 @@ impl<'a, F: Float, D: Distance<F>> NearestNeighbourIndex<F> for KdTreeIndex<'a,
         point: Point<'b, F>,
         range: F,
     ) -> Result<Vec<(Point<F>, usize)>, NnError> {
-        let range = self.1.dist_to_rdist(range);
+        let range = self.dist_fn.dist_to_rdist(range);
         Ok(self
-            .0
+            .tree
             .within(
                 point.to_slice().expect("views should be contiguous"),
                 range,
-                &|a, b| self.1.rdistance(aview1(a), aview1(b)),
+                &|a, b| self.dist_fn.rdistance(aview1(a), aview1(b)),
             )?
             .into_iter()
-            .map(|(_, (pt, pos))| (pt.reborrow(), *pos))
+            .map(|(_, pos)| (self.points.row(*pos), *pos))
             .collect())
     }
+
+    fn points(&self) -> ArrayView2<F> {
+        self.points.view()
+    }
 }
 
 /// Implementation of K-D tree, a fast space-partitioning data structure.  For each parent node,
@@ -93,31 +139,53 @@ impl<'a, F: Float, D: Distance<F>> NearestNeighbourIndex<F> for KdTreeIndex<'a,
 ///
 /// More details can be found [here](https://en.wikipedia.org/wiki/K-d_tree).
 ///
-/// Unlike other `NearestNeighbour` implementations, `KdTree` requires that points be laid out
-/// contiguously in memory and will panic otherwise.
-#[derive(Default, Clone, Debug)]
+/// Unlike other `NearestNeighbour` implementations, `KdTree` requires that query points be laid
+/// out contiguously in memory and will panic otherwise. It only supports
+/// [Minkowski distances](distance/trait.Distance.html#method.is_minkowski) and returns an error
+/// for other distance functions.
+#[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
     serde(crate = "serde_crate")
 )]
-pub struct KdTree;
+pub struct KdTree {
+    leaf_size: usize,
+}
+
+impl Default for KdTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl KdTree {
-    /// Creates an instance of `KdTree`
+    /// Creates an instance of `KdTree` with the default leaf size
     pub fn new() -> Self {
-        Self
+        Self {
+            leaf_size: DEFAULT_LEAF_SIZE,
+        }
+    }
+
+    /// Set the number of points in the leaf nodes, used by `from_batch`
+    pub fn leaf_size(mut self, leaf_size: usize) -> Self {
+        self.leaf_size = leaf_size;
+        self
     }
 }
 
 impl NearestNeighbour for KdTree {
-    fn from_batch_with_leaf_size<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
+    fn default_leaf_size(&self) -> usize {
+        self.leaf_size
+    }
+
+    fn from_cow_with_leaf_size<'a, F: Float, D: 'a + Distance<F>>(
         &self,
-        batch: &'a ArrayBase<DT, Ix2>,
+        batch: CowArray<'a, F, Ix2>,
         leaf_size: usize,
         dist_fn: D,
     ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
-        KdTreeIndex::new(batch, leaf_size, dist_fn)
+        KdTreeIndex::from_cow(batch, leaf_size, dist_fn)
             .map(|v| Box::new(v) as Box<dyn NearestNeighbourIndex<F>>)
     }
 }
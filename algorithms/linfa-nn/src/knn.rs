@@ -0,0 +1,549 @@
+use linfa::{
+    dataset::{AsTargets, DatasetBase},
+    metrics::{Accuracy, DefaultMetric, R2},
+    traits::{Fit, PredictRef},
+    Float, Label,
+};
+use ndarray::{Array1, ArrayBase, Data, Ix2};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use thiserror::Error;
+
+use crate::{
+    distance::{Distance, L2Dist},
+    BuildError, CommonNearestNeighbour, NearestNeighbour, NearestNeighbourIndex, NnError,
+};
+
+/// Error returned when fitting a k-nearest neighbours model
+#[derive(Error, Debug)]
+pub enum KnnError {
+    #[error("invalid value encountered: {0}")]
+    InvalidValue(String),
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    #[error(transparent)]
+    Nn(#[from] NnError),
+    #[error(transparent)]
+    LinfaError(#[from] linfa::Error),
+}
+
+/// Weighting of the `k` nearest neighbours of a query point
+///
+/// For a distance `d` between the query point and a neighbour, the weights are:
+///  * Uniform: `1`, every neighbour counts the same
+///  * Inverse: `1 / d`, neighbours at distance zero outweigh all others
+///  * Gaussian: `exp(-d²/(2h²))` with bandwidth `h`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub enum KnnWeights<F> {
+    Uniform,
+    Inverse,
+    Gaussian(F),
+}
+
+impl<F: Float> KnnWeights<F> {
+    /// Weights of neighbours sorted by ascending distance
+    fn weights(&self, dists: &[F]) -> Vec<F> {
+        match self {
+            KnnWeights::Uniform => vec![F::one(); dists.len()],
+            KnnWeights::Inverse if dists.iter().any(|d| *d == F::zero()) => dists
+                .iter()
+                .map(|d| if *d == F::zero() { F::one() } else { F::zero() })
+                .collect(),
+            KnnWeights::Inverse => dists.iter().map(|d| d.recip()).collect(),
+            KnnWeights::Gaussian(bandwidth) => {
+                // relative to the nearest neighbour, so that far away query points don't
+                // underflow all weights to zero
+                let nearest = dists.first().map_or(F::zero(), |d| *d / *bandwidth);
+                dists
+                    .iter()
+                    .map(|d| {
+                        let u = *d / *bandwidth;
+                        (-(u * u - nearest * nearest) / F::cast(2.)).exp()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Hyperparameters shared by the k-nearest neighbours models
+#[derive(Debug, Clone)]
+struct KnnConfig<F, D> {
+    k: usize,
+    weights: KnnWeights<F>,
+    dist_fn: D,
+    nn_algo: CommonNearestNeighbour,
+}
+
+impl<F: Float, D: 'static + Distance<F>> KnnConfig<F, D> {
+    fn new(k: usize, dist_fn: D) -> Self {
+        KnnConfig {
+            k,
+            weights: KnnWeights::Uniform,
+            dist_fn,
+            nn_algo: CommonNearestNeighbour::KdTree,
+        }
+    }
+
+    fn validate(&self) -> Result<(), KnnError> {
+        if self.k == 0 {
+            return Err(KnnError::InvalidValue(
+                "number of neighbours should be positive".to_string(),
+            ));
+        }
+        if let KnnWeights::Gaussian(bandwidth) = self.weights {
+            if bandwidth <= F::zero() {
+                return Err(KnnError::InvalidValue(format!(
+                    "bandwidth should be positive, but is {}",
+                    bandwidth
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the hyperparameters and build the spatial index over the training points
+    fn build_index<DT: Data<Elem = F>>(
+        &self,
+        records: &ArrayBase<DT, Ix2>,
+    ) -> Result<Box<dyn NearestNeighbourIndex<F>>, KnnError> {
+        self.validate()?;
+
+        if records.nrows() == 0 {
+            return Err(linfa::Error::NotEnoughSamples.into());
+        }
+
+        let index = self
+            .nn_algo
+            .from_owned_batch(records.to_owned(), self.dist_fn.clone())?;
+
+        Ok(index)
+    }
+
+    /// Calls `aggregate` with the positions and weights of the nearest neighbours of every row
+    /// of `x`
+    ///
+    /// Panics if `x` has a different number of features than the training points.
+    fn predict_with<DT: Data<Elem = F>, T>(
+        &self,
+        index: &dyn NearestNeighbourIndex<F>,
+        x: &ArrayBase<DT, Ix2>,
+        aggregate: impl Fn(&[usize], &[F]) -> T,
+    ) -> Array1<T> {
+        x.genrows()
+            .into_iter()
+            .map(|point| {
+                let point = point.as_standard_layout();
+                let (positions, dists): (Vec<_>, Vec<_>) = index
+                    .k_nearest(point.view(), self.k)
+                    .expect("query points should have the dimension of the training points")
+                    .into_iter()
+                    .map(|(neighbour, pos)| (pos, self.dist_fn.distance(point.view(), neighbour)))
+                    .unzip();
+
+                aggregate(&positions, &self.weights.weights(&dists))
+            })
+            .collect()
+    }
+}
+
+macro_rules! impl_knn_params {
+    ($params:ident $(, $label:ident)?) => {
+        impl<F: Float, $($label,)? D: 'static + Distance<F>> $params<F, $($label,)? D> {
+            /// Set the number of neighbours
+            pub fn k(mut self, k: usize) -> Self {
+                self.0.k = k;
+                self
+            }
+
+            /// Set the weighting of the neighbours, defaults to `KnnWeights::Uniform`
+            pub fn weights(mut self, weights: KnnWeights<F>) -> Self {
+                self.0.weights = weights;
+                self
+            }
+
+            /// Set the distance metric
+            ///
+            /// Distances which are not
+            /// [Minkowski distances](distance/trait.Distance.html#method.is_minkowski) are
+            /// rejected by the default `CommonNearestNeighbour::KdTree` when fitting.
+            pub fn dist_fn(mut self, dist_fn: D) -> Self {
+                self.0.dist_fn = dist_fn;
+                self
+            }
+
+            /// Set the nearest neighbour algorithm, defaults to `CommonNearestNeighbour::KdTree`
+            pub fn nn_algo(mut self, nn_algo: CommonNearestNeighbour) -> Self {
+                self.0.nn_algo = nn_algo;
+                self
+            }
+
+            /// Validate the hyperparameters
+            pub fn validate(&self) -> Result<(), KnnError> {
+                self.0.validate()
+            }
+        }
+    };
+}
+
+/// k-nearest neighbours classification hyperparameters
+#[derive(Debug, Clone)]
+pub struct KnnClassifierParams<F, L, D = L2Dist>(KnnConfig<F, D>, PhantomData<L>);
+
+impl_knn_params!(KnnClassifierParams, L);
+
+/// k-nearest neighbours regression hyperparameters
+#[derive(Debug, Clone)]
+pub struct KnnRegressorParams<F, D = L2Dist>(KnnConfig<F, D>);
+
+impl_knn_params!(KnnRegressorParams);
+
+impl<F: Float, L: Label, DT: Data<Elem = F>, T: AsTargets<Elem = L>, D: 'static + Distance<F>>
+    Fit<ArrayBase<DT, Ix2>, T, KnnError> for KnnClassifierParams<F, L, D>
+{
+    type Object = KnnClassifier<F, L, D>;
+
+    /// Index the records and store the labels of a dataset with a single target
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<DT, Ix2>, T>) -> Result<Self::Object, KnnError> {
+        let labels = dataset.try_single_target()?.to_owned();
+
+        Ok(KnnClassifier {
+            config: self.0.clone(),
+            index: self.0.build_index(dataset.records())?,
+            labels,
+        })
+    }
+}
+
+impl<F: Float, DT: Data<Elem = F>, T: AsTargets<Elem = F>, D: 'static + Distance<F>>
+    Fit<ArrayBase<DT, Ix2>, T, KnnError> for KnnRegressorParams<F, D>
+{
+    type Object = KnnRegressor<F, D>;
+
+    /// Index the records and store the targets of a dataset with a single target
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<DT, Ix2>, T>) -> Result<Self::Object, KnnError> {
+        let targets = dataset.try_single_target()?.to_owned();
+
+        Ok(KnnRegressor {
+            config: self.0.clone(),
+            index: self.0.build_index(dataset.records())?,
+            targets,
+        })
+    }
+}
+
+/// k-nearest neighbours classifier
+///
+/// Predicts the label with the largest sum of weights among the `k` training samples closest to
+/// a query point. Ties are broken in favour of the label of the closer neighbour.
+///
+/// ## Example
+///
+/// ```rust
+/// use linfa::{traits::{Fit, Predict}, Dataset};
+/// use linfa_nn::{KnnClassifier, KnnWeights};
+/// use ndarray::array;
+///
+/// let dataset = Dataset::new(array![[0.0], [0.2], [1.0], [1.2]], array![0usize, 0, 1, 1]);
+/// let knn = KnnClassifier::params(3)
+///     .weights(KnnWeights::Inverse)
+///     .fit(&dataset)
+///     .unwrap();
+///
+/// assert_eq!(knn.predict(&array![[0.1], [0.9]]), array![0, 1]);
+/// ```
+pub struct KnnClassifier<F: Float, L, D = L2Dist> {
+    config: KnnConfig<F, D>,
+    index: Box<dyn NearestNeighbourIndex<F>>,
+    labels: Array1<L>,
+}
+
+impl<F: Float, L: std::fmt::Debug, D: std::fmt::Debug> std::fmt::Debug for KnnClassifier<F, L, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KnnClassifier")
+            .field("config", &self.config)
+            .field("labels", &self.labels)
+            .finish()
+    }
+}
+
+impl<F: Float, L: Label> KnnClassifier<F, L> {
+    /// Create classification hyperparameters with `k` neighbours and the euclidean distance
+    pub fn params(k: usize) -> KnnClassifierParams<F, L> {
+        Self::params_with(k, L2Dist)
+    }
+
+    /// Create classification hyperparameters with `k` neighbours and a custom distance metric
+    pub fn params_with<D: 'static + Distance<F>>(
+        k: usize,
+        dist_fn: D,
+    ) -> KnnClassifierParams<F, L, D> {
+        KnnClassifierParams(KnnConfig::new(k, dist_fn), PhantomData)
+    }
+}
+
+impl<F: Float, L: Label, DT: Data<Elem = F>, D: 'static + Distance<F>>
+    PredictRef<ArrayBase<DT, Ix2>, Array1<L>> for KnnClassifier<F, L, D>
+{
+    /// Predict the label of every row of `x`
+    ///
+    /// Panics if `x` has a different number of features than the training samples.
+    fn predict_ref(&self, x: &ArrayBase<DT, Ix2>) -> Array1<L> {
+        self.config
+            .predict_with(self.index.as_ref(), x, |positions, weights| {
+                // neighbours are visited by ascending distance, so ties keep the closer label
+                let mut votes: Vec<(&L, F)> = Vec::new();
+                for (pos, weight) in positions.iter().zip(weights) {
+                    let label = &self.labels[*pos];
+                    match votes.iter_mut().find(|(l, _)| *l == label) {
+                        Some((_, sum)) => *sum += *weight,
+                        None => votes.push((label, *weight)),
+                    }
+                }
+
+                let mut best = &votes[0];
+                for vote in &votes[1..] {
+                    if vote.1 > best.1 {
+                        best = vote;
+                    }
+                }
+                best.0.clone()
+            })
+    }
+}
+
+impl<F: Float, L: Label, D> DefaultMetric for KnnClassifier<F, L, D> {
+    type Metric = Accuracy;
+}
+
+/// k-nearest neighbours regressor
+///
+/// Predicts the weighted mean of the targets of the `k` training samples closest to a query
+/// point.
+///
+/// ## Example
+///
+/// ```rust
+/// use linfa::{traits::{Fit, Predict}, Dataset};
+/// use linfa_nn::{KnnRegressor, KnnWeights};
+/// use ndarray::array;
+///
+/// let dataset = Dataset::new(array![[0.0], [1.0], [2.0]], array![0.0, 1.0, 2.0]);
+/// let knn = KnnRegressor::params(2)
+///     .weights(KnnWeights::Gaussian(1.0))
+///     .fit(&dataset)
+///     .unwrap();
+///
+/// assert!((knn.predict(&array![[0.5]])[0] - 0.5).abs() < 1e-10);
+/// ```
+pub struct KnnRegressor<F: Float, D = L2Dist> {
+    config: KnnConfig<F, D>,
+    index: Box<dyn NearestNeighbourIndex<F>>,
+    targets: Array1<F>,
+}
+
+impl<F: Float, D: std::fmt::Debug> std::fmt::Debug for KnnRegressor<F, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KnnRegressor")
+            .field("config", &self.config)
+            .field("targets", &self.targets)
+            .finish()
+    }
+}
+
+impl<F: Float> KnnRegressor<F> {
+    /// Create regression hyperparameters with `k` neighbours and the euclidean distance
+    pub fn params(k: usize) -> KnnRegressorParams<F> {
+        Self::params_with(k, L2Dist)
+    }
+
+    /// Create regression hyperparameters with `k` neighbours and a custom distance metric
+    pub fn params_with<D: 'static + Distance<F>>(k: usize, dist_fn: D) -> KnnRegressorParams<F, D> {
+        KnnRegressorParams(KnnConfig::new(k, dist_fn))
+    }
+}
+
+impl<F: Float, DT: Data<Elem = F>, D: 'static + Distance<F>>
+    PredictRef<ArrayBase<DT, Ix2>, Array1<F>> for KnnRegressor<F, D>
+{
+    /// Predict the target of every row of `x`
+    ///
+    /// Panics if `x` has a different number of features than the training samples.
+    fn predict_ref(&self, x: &ArrayBase<DT, Ix2>) -> Array1<F> {
+        self.config
+            .predict_with(self.index.as_ref(), x, |positions, weights| {
+                let weighted_sum = positions
+                    .iter()
+                    .zip(weights)
+                    .map(|(pos, weight)| self.targets[*pos] * *weight)
+                    .sum::<F>();
+
+                weighted_sum / weights.iter().copied().sum::<F>()
+            })
+    }
+}
+
+impl<F: Float, D> DefaultMetric for KnnRegressor<F, D> {
+    type Metric = R2;
+}
+
+#[cfg(test)]
+mod test {
+    use linfa::{traits::Predict, Dataset};
+    use ndarray::{array, Array, Axis};
+    use ndarray_rand::{
+        rand::SeedableRng,
+        rand_distr::{Normal, Uniform},
+        RandomExt,
+    };
+    use rand_isaac::Isaac64Rng;
+
+    use super::*;
+    use crate::distance::{HaversineDist, L1Dist};
+
+    #[test]
+    fn weights() {
+        let dists = [0.5, 1.0, 2.0];
+        assert_eq!(KnnWeights::Uniform.weights(&dists), vec![1.0, 1.0, 1.0]);
+        assert_eq!(KnnWeights::Inverse.weights(&dists), vec![2.0, 1.0, 0.5]);
+        assert_eq!(
+            KnnWeights::Inverse.weights(&[0.0, 0.0, 1.0]),
+            vec![1.0, 1.0, 0.0]
+        );
+
+        // only the ratios between gaussian weights matter
+        let gaussian = KnnWeights::Gaussian(0.5).weights(&dists);
+        assert_eq!(gaussian[0], 1.0);
+        assert!((gaussian[1] - (-1.5f64).exp()).abs() < 1e-12);
+        assert!((gaussian[2] - (-7.5f64).exp()).abs() < 1e-12);
+        let far = KnnWeights::Gaussian(0.5).weights(&[100.0, 100.1]);
+        assert!(far.iter().all(|w| *w > 0.0));
+    }
+
+    #[test]
+    fn classification() {
+        let records = array![[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [5.0, 5.0], [5.0, 6.0]];
+        let dataset = Dataset::new(records, array![0usize, 0, 0, 1, 1]);
+        let x = array![[0.2, 0.2], [5.0, 5.0], [4.0, 4.0]];
+
+        let knn = KnnClassifier::params(3).fit(&dataset).unwrap();
+        assert_eq!(knn.predict(&x), array![0, 1, 1]);
+
+        // with all samples as neighbours, uniform votes go to the majority
+        let knn = KnnClassifier::params(5).fit(&dataset).unwrap();
+        assert_eq!(knn.predict(&x), array![0, 0, 0]);
+
+        // closer neighbours outweigh the majority
+        let knn = KnnClassifier::params(5)
+            .weights(KnnWeights::Inverse)
+            .fit(&dataset)
+            .unwrap();
+        assert_eq!(knn.predict(&x), array![0, 1, 1]);
+    }
+
+    #[test]
+    fn custom_distance() {
+        let dataset = Dataset::new(array![[3.0, 0.0], [2.0, 2.0]], array![0usize, 1]);
+        let x = array![[0.0, 0.0]];
+
+        // the second sample is closer in euclidean distance, the first one in manhattan distance
+        let knn = KnnClassifier::params(1).fit(&dataset).unwrap();
+        assert_eq!(knn.predict(&x), array![1]);
+        let knn = KnnClassifier::params_with(1, L1Dist).fit(&dataset).unwrap();
+        assert_eq!(knn.predict(&x), array![0]);
+
+        // KD trees only support Minkowski distances
+        let res = KnnClassifier::params_with(1, HaversineDist).fit(&dataset);
+        assert!(matches!(
+            res,
+            Err(KnnError::Build(BuildError::UnsupportedDistance))
+        ));
+        assert!(KnnClassifier::params_with(1, HaversineDist)
+            .nn_algo(CommonNearestNeighbour::BallTree)
+            .fit(&dataset)
+            .is_ok());
+    }
+
+    #[test]
+    fn gaussian_weights_smooth_regression() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let records = Array::random_using((200, 1), Uniform::new(0., 1.), &mut rng);
+        let noise = Array::random_using(200, Normal::new(0., 0.3).unwrap(), &mut rng);
+        let targets = records
+            .column(0)
+            .mapv(|x| (2. * std::f64::consts::PI * x).sin())
+            + noise;
+        let dataset = Dataset::new(records, targets);
+        let grid = Array::linspace(0.1, 0.9, 2000).insert_axis(Axis(1));
+
+        // sum of the squared changes between neighbouring grid points, which stays large for
+        // predictions jumping whenever the set of neighbours changes
+        let roughness = |weights: KnnWeights<f64>| {
+            let prediction = KnnRegressor::params(20)
+                .weights(weights)
+                .fit(&dataset)
+                .unwrap()
+                .predict(&grid);
+            prediction
+                .windows(2)
+                .into_iter()
+                .map(|w| (w[1] - w[0]).powi(2))
+                .sum::<f64>()
+        };
+
+        let uniform = roughness(KnnWeights::Uniform);
+        let gaussian = roughness(KnnWeights::Gaussian(0.02));
+        assert!(gaussian < 0.5 * uniform);
+    }
+
+    #[test]
+    fn independent_of_index() {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let records = Array::random_using((500, 3), Uniform::new(-1., 1.), &mut rng);
+        let targets = records.sum_axis(Axis(1));
+        let dataset = Dataset::new(records, targets);
+        let x = Array::random_using((50, 3), Uniform::new(-1., 1.), &mut rng);
+
+        let params = KnnRegressor::params(7).weights(KnnWeights::Inverse);
+        let reference = params
+            .clone()
+            .nn_algo(CommonNearestNeighbour::LinearSearch)
+            .fit(&dataset)
+            .unwrap()
+            .predict(&x);
+        for nn_algo in &[
+            CommonNearestNeighbour::KdTree,
+            CommonNearestNeighbour::BallTree,
+        ] {
+            let prediction = params
+                .clone()
+                .nn_algo(*nn_algo)
+                .fit(&dataset)
+                .unwrap()
+                .predict(&x);
+            assert!(prediction
+                .iter()
+                .zip(reference.iter())
+                .all(|(a, b)| (a - b).abs() < 1e-10));
+        }
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = Dataset::new(array![[0.0], [1.0]], array![0.0, 1.0]);
+        let res = KnnRegressor::params(0).fit(&dataset);
+        assert!(matches!(res, Err(KnnError::InvalidValue(_))));
+
+        let res = KnnRegressor::params(1)
+            .weights(KnnWeights::Gaussian(0.0))
+            .fit(&dataset);
+        assert!(matches!(res, Err(KnnError::InvalidValue(_))));
+    }
+}
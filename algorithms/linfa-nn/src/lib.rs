@@ -18,27 +18,40 @@
 //! * [KD Tree](struct.KdTree.html)
 //! * [Ball Tree](struct.BallTree.html)
 //!
+//! [`AutoNearestNeighbour`](struct.AutoNearestNeighbour.html) picks one of them based on the
+//! number and dimension of the points.
+//!
+//! On top of the spatial indices, [`KernelDensity`](struct.KernelDensity.html) estimates the
+//! probability density of a set of samples, while [`KnnClassifier`](struct.KnnClassifier.html)
+//! and [`KnnRegressor`](struct.KnnRegressor.html) predict from the `k` nearest training samples.
+//!
 //! The [`CommonNearestNeighbour`](struct.CommonNearestNeighbour) enum should be used to dispatch
 //! between all of the above algorithms flexibly.
 
 use distance::Distance;
 use linfa::Float;
-use ndarray::{ArrayBase, ArrayView1, Data, Ix2};
+use ndarray::{Array2, ArrayBase, ArrayView1, ArrayView2, CowArray, Data, Ix2};
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
 use thiserror::Error;
 
+mod auto;
 mod balltree;
 mod heap_elem;
+mod kde;
 mod kdtree;
+mod knn;
 mod linear;
 
 pub mod distance;
 
-pub use crate::{balltree::*, kdtree::*, linear::*};
+pub use crate::{auto::*, balltree::*, kde::*, kdtree::*, knn::*, linear::*};
 
 pub(crate) type Point<'a, F> = ArrayView1<'a, F>;
 
+/// Leaf size used by `from_batch` unless the algorithm is configured otherwise
+pub(crate) const DEFAULT_LEAF_SIZE: usize = 16;
+
 /// Error returned when building nearest neighbour indices
 #[derive(Error, Debug)]
 pub enum BuildError {
@@ -46,6 +59,8 @@ pub enum BuildError {
     ZeroDimension,
     #[error("leaf size is 0")]
     EmptyLeaf,
+    #[error("distance function is not supported by the index")]
+    UnsupportedDistance,
 }
 
 /// Error returned when performing spatial queries on nearest neighbour indices
@@ -61,33 +76,61 @@ pub enum NnError {
 /// computing of nearest neighbour and range queries.
 pub trait NearestNeighbour: std::fmt::Debug {
     /// Builds a spatial index using a MxN two-dimensional array representing M points with N
-    /// dimensions. Also takes `leaf_size`, which specifies the number of elements in the leaf
-    /// nodes of tree-like index structures.
+    /// dimensions, which is either borrowed or owned by the index. Also takes `leaf_size`, which
+    /// specifies the number of elements in the leaf nodes of tree-like index structures.
     ///
     /// Returns an error if the points have dimensionality of 0 or if the leaf size is 0. If any
     /// value in the batch is NaN or infinite, the behaviour is unspecified.
-    fn from_batch_with_leaf_size<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
+    fn from_cow_with_leaf_size<'a, F: Float, D: 'a + Distance<F>>(
         &self,
-        batch: &'a ArrayBase<DT, Ix2>,
+        batch: CowArray<'a, F, Ix2>,
         leaf_size: usize,
         dist_fn: D,
     ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError>;
 
-    /// Builds a spatial index using a default leaf size. See `from_batch_with_leaf_size` for more
+    /// Leaf size used by `from_batch`. Algorithms with a configurable leaf size, like
+    /// [`KdTree::leaf_size`](struct.KdTree.html#method.leaf_size), return the configured value.
+    fn default_leaf_size(&self) -> usize {
+        DEFAULT_LEAF_SIZE
+    }
+
+    /// Builds a spatial index borrowing a batch of points. See `from_cow_with_leaf_size` for more
     /// information.
+    fn from_batch_with_leaf_size<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
+        &self,
+        batch: &'a ArrayBase<DT, Ix2>,
+        leaf_size: usize,
+        dist_fn: D,
+    ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
+        self.from_cow_with_leaf_size(batch.view().into(), leaf_size, dist_fn)
+    }
+
+    /// Builds a spatial index using the default leaf size of the algorithm. See
+    /// `from_cow_with_leaf_size` for more information.
     fn from_batch<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
         &self,
         batch: &'a ArrayBase<DT, Ix2>,
         dist_fn: D,
     ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
-        self.from_batch_with_leaf_size(batch, 2usize.pow(4), dist_fn)
+        self.from_batch_with_leaf_size(batch, self.default_leaf_size(), dist_fn)
+    }
+
+    /// Builds a spatial index which takes ownership of the points, using the default leaf size of
+    /// the algorithm. Unlike `from_batch`, the index does not borrow from the caller and can be
+    /// stored next to other data, for example in a fitted model.
+    fn from_owned_batch<F: Float, D: 'static + Distance<F>>(
+        &self,
+        batch: Array2<F>,
+        dist_fn: D,
+    ) -> Result<Box<dyn NearestNeighbourIndex<F>>, BuildError> {
+        self.from_cow_with_leaf_size(batch.into(), self.default_leaf_size(), dist_fn)
     }
 }
 
 /// A spatial index structure over a set of points, created by `NearestNeighbour`. Allows efficient
 /// computation of nearest neighbour and range queries over the set of points. Individual points
 /// are represented as one-dimensional array views.
-pub trait NearestNeighbourIndex<F: Float> {
+pub trait NearestNeighbourIndex<F: Float>: Send + Sync {
     /// Returns the `k` points in the index that are the closest to the provided point, along with
     /// their positions in the original dataset. Points are returned in ascending order of the
     /// distance away from the provided points, and less than `k` points will be returned if the
@@ -112,6 +155,47 @@ pub trait NearestNeighbourIndex<F: Float> {
         point: Point<'b, F>,
         range: F,
     ) -> Result<Vec<(Point<F>, usize)>, NnError>;
+
+    /// Returns all the points in the index, with each point in the row of its position in the
+    /// original dataset.
+    fn points(&self) -> ArrayView2<F>;
+}
+
+/// Queries on boxed indices, as returned by [`NearestNeighbour`](trait.NearestNeighbour.html),
+/// accept any point convertible into an array view, such as `&Array1` or `&[F]`.
+///
+/// ```rust
+/// use ndarray::{array, Array2};
+/// use linfa_nn::{distance::L2Dist, CommonNearestNeighbour, NearestNeighbour};
+///
+/// let points: Array2<f64> = array![[0., 1.], [2., 3.], [4., 5.]];
+/// let nn = CommonNearestNeighbour::KdTree.from_batch(&points, L2Dist).unwrap();
+///
+/// let pt = array![1.8, 3.1];
+/// assert_eq!(nn.k_nearest(&pt, 1).unwrap()[0].1, 1);
+/// assert_eq!(nn.within_range(&[0., 0.][..], 1.5).unwrap()[0].1, 0);
+/// ```
+impl<'a, F: Float> dyn NearestNeighbourIndex<F> + 'a {
+    /// Returns the `k` points in the index that are the closest to the provided point, see
+    /// [`NearestNeighbourIndex::k_nearest`](trait.NearestNeighbourIndex.html#tymethod.k_nearest).
+    pub fn k_nearest<'b, P: Into<Point<'b, F>>>(
+        &self,
+        point: P,
+        k: usize,
+    ) -> Result<Vec<(Point<F>, usize)>, NnError> {
+        NearestNeighbourIndex::k_nearest(self, point.into(), k)
+    }
+
+    /// Returns all the points in the index that are within the specified distance to the provided
+    /// point, see
+    /// [`NearestNeighbourIndex::within_range`](trait.NearestNeighbourIndex.html#tymethod.within_range).
+    pub fn within_range<'b, P: Into<Point<'b, F>>>(
+        &self,
+        point: P,
+        range: F,
+    ) -> Result<Vec<(Point<F>, usize)>, NnError> {
+        NearestNeighbourIndex::within_range(self, point.into(), range)
+    }
 }
 
 /// Enum that dispatches to one of the crate's [`NearestNeighbour`](trait.NearestNeighbour.html)
@@ -138,12 +222,12 @@ pub trait NearestNeighbourIndex<F: Float> {
 ///
 /// let pt = Array1::random_using(n_features, distr, &mut rng);
 /// // Compute the 10 nearest points to `pt` in the index
-/// let nearest = nn.k_nearest(pt.view(), 10).unwrap();
+/// let nearest = nn.k_nearest(&pt, 10).unwrap();
 /// // Compute all points within 100 units of `pt`
 /// let range = nn.within_range(pt.view(), 100.0).unwrap();
 /// ```
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -159,16 +243,16 @@ pub enum CommonNearestNeighbour {
 }
 
 impl NearestNeighbour for CommonNearestNeighbour {
-    fn from_batch_with_leaf_size<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
+    fn from_cow_with_leaf_size<'a, F: Float, D: 'a + Distance<F>>(
         &self,
-        batch: &'a ArrayBase<DT, Ix2>,
+        batch: CowArray<'a, F, Ix2>,
         leaf_size: usize,
         dist_fn: D,
     ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
         match self {
-            Self::LinearSearch => LinearSearch.from_batch_with_leaf_size(batch, leaf_size, dist_fn),
-            Self::KdTree => KdTree.from_batch_with_leaf_size(batch, leaf_size, dist_fn),
-            Self::BallTree => BallTree.from_batch_with_leaf_size(batch, leaf_size, dist_fn),
+            Self::LinearSearch => LinearSearch.from_cow_with_leaf_size(batch, leaf_size, dist_fn),
+            Self::KdTree => KdTree::new().from_cow_with_leaf_size(batch, leaf_size, dist_fn),
+            Self::BallTree => BallTree::new().from_cow_with_leaf_size(batch, leaf_size, dist_fn),
         }
     }
 }
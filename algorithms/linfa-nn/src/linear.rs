@@ -1,7 +1,7 @@
 use std::{cmp::Reverse, collections::BinaryHeap};
 
 use linfa::Float;
-use ndarray::{ArrayBase, ArrayView2, Data, Ix2};
+use ndarray::{ArrayBase, ArrayView2, CowArray, Data, Ix2};
 use noisy_float::NoisyFloat;
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
@@ -13,7 +13,7 @@ use crate::{
 
 /// Spatial indexing structure created by [`LinearSearch`](struct.LinearSearch.html)
 #[derive(Debug)]
-pub struct LinearSearchIndex<'a, F: Float, D: Distance<F>>(ArrayView2<'a, F>, D);
+pub struct LinearSearchIndex<'a, F: Float, D: Distance<F>>(CowArray<'a, F, Ix2>, D);
 
 impl<'a, F: Float, D: Distance<F>> LinearSearchIndex<'a, F, D> {
     /// Creates a new `LinearSearchIndex`
@@ -21,10 +21,15 @@ impl<'a, F: Float, D: Distance<F>> LinearSearchIndex<'a, F, D> {
         batch: &'a ArrayBase<DT, Ix2>,
         dist_fn: D,
     ) -> Result<Self, BuildError> {
+        Self::from_cow(batch.view().into(), dist_fn)
+    }
+
+    /// Creates a new `LinearSearchIndex` from borrowed or owned points
+    pub fn from_cow(batch: CowArray<'a, F, Ix2>, dist_fn: D) -> Result<Self, BuildError> {
         if batch.ncols() == 0 {
             Err(BuildError::ZeroDimension)
         } else {
-            Ok(Self(batch.view(), dist_fn))
+            Ok(Self(batch, dist_fn))
         }
     }
 }
@@ -72,6 +77,10 @@ impl<'a, F: Float, D: Distance<F>> NearestNeighbourIndex<F> for LinearSearchInde
                 .collect())
         }
     }
+
+    fn points(&self) -> ArrayView2<F> {
+        self.0.view()
+    }
 }
 
 /// Implementation of linear search, which is the simplest nearest neighbour algorithm. All queries
@@ -93,16 +102,16 @@ impl LinearSearch {
 }
 
 impl NearestNeighbour for LinearSearch {
-    fn from_batch_with_leaf_size<'a, F: Float, DT: Data<Elem = F>, D: 'a + Distance<F>>(
+    fn from_cow_with_leaf_size<'a, F: Float, D: 'a + Distance<F>>(
         &self,
-        batch: &'a ArrayBase<DT, Ix2>,
+        batch: CowArray<'a, F, Ix2>,
         leaf_size: usize,
         dist_fn: D,
     ) -> Result<Box<dyn 'a + NearestNeighbourIndex<F>>, BuildError> {
         if leaf_size == 0 {
             return Err(BuildError::EmptyLeaf);
         }
-        LinearSearchIndex::new(batch, dist_fn)
+        LinearSearchIndex::from_cow(batch, dist_fn)
             .map(|v| Box::new(v) as Box<dyn NearestNeighbourIndex<F>>)
     }
 }
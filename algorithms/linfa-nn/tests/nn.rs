@@ -5,7 +5,10 @@ use ndarray_stats::DeviationExt;
 use noisy_float::{checkers::FiniteChecker, NoisyFloat};
 use rand_isaac::Isaac64Rng;
 
-use linfa_nn::{distance::*, CommonNearestNeighbour, LinearSearch, NearestNeighbour};
+use linfa_nn::{
+    distance::*, AutoNearestNeighbour, BallTree, BuildError, CommonNearestNeighbour, KdTree,
+    LinearSearch, NearestNeighbour,
+};
 
 fn sort_by_dist<'a>(
     mut vec: Vec<(ArrayView1<'a, f64>, usize)>,
@@ -43,6 +46,35 @@ fn nn_test_empty(builder: &CommonNearestNeighbour) {
     assert_eq!(out, Vec::<_>::new());
 }
 
+fn nn_test_owned(builder: &CommonNearestNeighbour) {
+    let points = arr2(&[[0.0, 2.0], [10.0, 4.0], [4.0, 5.0], [7.0, 1.0], [1.0, 7.2]]);
+    let nn = builder.from_batch(&points, L2Dist).unwrap();
+
+    // query points computed on the fly are owned
+    let pt = arr1(&[3.0, 4.0]) + 1.0;
+    assert_query(nn.k_nearest(&pt, 2).unwrap(), &points, vec![2, 4]);
+    let out = sort_by_dist(nn.within_range(&pt, 4.0).unwrap(), pt.view());
+    assert_query(out, &points, vec![2, 4]);
+
+    let pt = vec![0.5, 1.5];
+    assert_query(nn.k_nearest(&pt[..], 1).unwrap(), &points, vec![0]);
+    let out = sort_by_dist(nn.within_range(pt.as_slice(), 6.0).unwrap(), aview1(&pt));
+    assert_query(out, &points, vec![0, 2, 4]);
+
+    assert!(nn.k_nearest(&[1.0][..], 1).is_err());
+}
+
+fn nn_test_owned_batch(builder: &CommonNearestNeighbour) {
+    let points = arr2(&[[0.0, 2.0], [10.0, 4.0], [4.0, 5.0], [7.0, 1.0], [1.0, 7.2]]);
+    // the index takes ownership of a copy of the points
+    let nn = builder.from_owned_batch(points.clone(), L2Dist).unwrap();
+
+    let pt = aview1(&[4.0, 4.0]);
+    assert_query(nn.k_nearest(pt, 3).unwrap(), &points, vec![2, 3, 4]);
+    let out = sort_by_dist(nn.within_range(pt, 4.3).unwrap(), pt);
+    assert_query(out, &points, vec![2, 3]);
+}
+
 fn nn_test_error(builder: &CommonNearestNeighbour) {
     let points = Array2::<f64>::zeros((4, 0));
     assert!(builder.from_batch(&points, L2Dist).is_err());
@@ -166,6 +198,171 @@ fn nn_test_random<D: 'static + Distance<f64> + Clone>(
     );
 }
 
+fn nn_test_leaf_size<N: NearestNeighbour>(builder: N, leaf_size: usize) {
+    assert_eq!(builder.default_leaf_size(), leaf_size);
+
+    let mut rng = Isaac64Rng::seed_from_u64(40);
+    let points = Array2::random_using((1000, 3), Uniform::new(-50., 50.), &mut rng);
+    let linear = LinearSearch::new().from_batch(&points, L2Dist).unwrap();
+    let nn = builder.from_batch(&points, L2Dist).unwrap();
+
+    let pt = arr1(&[-3.4, 10., 0.95]);
+    assert_eq_queries(
+        nn.k_nearest(pt.view(), 30).unwrap(),
+        linear.k_nearest(pt.view(), 30).unwrap(),
+    );
+}
+
+#[test]
+fn leaf_size() {
+    assert_eq!(LinearSearch::new().default_leaf_size(), 16);
+    nn_test_leaf_size(KdTree::new(), 16);
+    nn_test_leaf_size(KdTree::new().leaf_size(32), 32);
+    nn_test_leaf_size(BallTree::new().leaf_size(32), 32);
+    nn_test_leaf_size(BallTree::new().leaf_size(2), 2);
+
+    // the configured leaf size is validated when building
+    let points = arr2(&[[0.0, 2.0]]);
+    assert!(BallTree::new()
+        .leaf_size(0)
+        .from_batch(&points, L2Dist)
+        .is_err());
+    assert!(KdTree::new()
+        .leaf_size(0)
+        .from_batch(&points, L2Dist)
+        .is_err());
+}
+
+#[test]
+fn haversine() {
+    let mut rng = Isaac64Rng::seed_from_u64(40);
+    let lat = Array2::random_using((2000, 1), Uniform::new(-1.5, 1.5), &mut rng);
+    let lon = Array2::random_using((2000, 1), Uniform::new(-3.1, 3.1), &mut rng);
+    let points = ndarray::concatenate(Axis(1), &[lat.view(), lon.view()]).unwrap();
+
+    let linear = LinearSearch::new()
+        .from_batch(&points, HaversineDist)
+        .unwrap();
+    let balltree = BallTree::new().from_batch(&points, HaversineDist).unwrap();
+
+    for pt in &[arr1(&[0.2, 3.0]), arr1(&[-1.2, -0.4]), arr1(&[1.45, 1.0])] {
+        assert_eq_queries(
+            balltree.k_nearest(pt.view(), 10).unwrap(),
+            linear.k_nearest(pt.view(), 10).unwrap(),
+        );
+        assert_eq_queries(
+            sort_by_dist(balltree.within_range(pt.view(), 0.2).unwrap(), pt.view()),
+            sort_by_dist(linear.within_range(pt.view(), 0.2).unwrap(), pt.view()),
+        );
+    }
+
+    assert!(matches!(
+        KdTree::new().from_batch(&points, HaversineDist),
+        Err(BuildError::UnsupportedDistance)
+    ));
+}
+
+#[test]
+fn haversine_antimeridian() {
+    use std::f64::consts::PI;
+
+    // points on both sides of the antimeridian, which are close on the sphere but far apart in
+    // `(latitude, longitude)` space
+    let points = arr2(&[
+        [0.1, PI - 0.01],
+        [0.1, -PI + 0.02],
+        [0.1, 0.5],
+        [-0.2, PI - 0.3],
+        [0.12, -PI + 0.005],
+    ]);
+    let pt = arr1(&[0.1, PI - 0.005]);
+
+    // without the threshold for linear search the auto selection builds a tree
+    let auto = AutoNearestNeighbour::new()
+        .brute_force_threshold(1)
+        .from_batch(&points, HaversineDist)
+        .unwrap();
+    let balltree = BallTree::new().from_batch(&points, HaversineDist).unwrap();
+    for nn in &[auto, balltree] {
+        let out = nn.k_nearest(pt.view(), 3).unwrap();
+        assert_query(out, &points, vec![0, 4, 1]);
+
+        let mut out = nn.within_range(pt.view(), 0.05).unwrap();
+        out.sort_by_key(|(_, i)| *i);
+        assert_query(out, &points, vec![0, 1, 4]);
+    }
+}
+
+#[test]
+fn auto() {
+    let mut rng = Isaac64Rng::seed_from_u64(40);
+    let auto = AutoNearestNeighbour::new();
+    let cases = [
+        ((50, 3), CommonNearestNeighbour::LinearSearch),
+        ((2000, 3), CommonNearestNeighbour::KdTree),
+        ((2000, 20), CommonNearestNeighbour::BallTree),
+    ];
+
+    for &(shape, expected) in &cases {
+        let points = Array2::random_using(shape, Uniform::new(-50., 50.), &mut rng);
+        assert_eq!(auto.select(&points, &L2Dist), expected);
+
+        let nn = auto.from_batch(&points, L2Dist).unwrap();
+        let pt = points.row(7).mapv(|x| x + 0.5);
+        for builder in &[
+            CommonNearestNeighbour::LinearSearch,
+            CommonNearestNeighbour::KdTree,
+            CommonNearestNeighbour::BallTree,
+        ] {
+            let other = builder.from_batch(&points, L2Dist).unwrap();
+            assert_eq_queries(
+                nn.k_nearest(pt.view(), 10).unwrap(),
+                other.k_nearest(pt.view(), 10).unwrap(),
+            );
+        }
+    }
+
+    // points which are not contiguous in memory are copied into the KD tree
+    let points = Array2::random_using((3, 2000), Uniform::new(-50., 50.), &mut rng);
+    let points = points.t();
+    assert_eq!(
+        auto.select(&points, &L2Dist),
+        CommonNearestNeighbour::KdTree
+    );
+
+    let nn = auto.from_batch(&points, L2Dist).unwrap();
+    let other = CommonNearestNeighbour::BallTree
+        .from_batch(&points, L2Dist)
+        .unwrap();
+    let pt = points.row(7).mapv(|x| x + 0.5);
+    assert_eq_queries(
+        nn.k_nearest(pt.view(), 10).unwrap(),
+        other.k_nearest(pt.view(), 10).unwrap(),
+    );
+
+    // the heuristics can be overridden
+    let auto = AutoNearestNeighbour::new()
+        .brute_force_threshold(10)
+        .kdtree_max_dimension(30);
+    let points = Array2::random_using((50, 20), Uniform::new(-50., 50.), &mut rng);
+    assert_eq!(
+        auto.select(&points, &L2Dist),
+        CommonNearestNeighbour::KdTree
+    );
+
+    // KD trees don't support the haversine distance
+    let points = Array2::random_using((2000, 2), Uniform::new(-1.5, 1.5), &mut rng);
+    assert_eq!(
+        auto.select(&points, &HaversineDist),
+        CommonNearestNeighbour::BallTree
+    );
+    let points = Array2::random_using((50, 2), Uniform::new(-1.5, 1.5), &mut rng);
+    assert_eq!(
+        auto.select(&points, &HaversineDist),
+        CommonNearestNeighbour::LinearSearch
+    );
+}
+
 macro_rules! nn_tests {
     ($mod:ident, $builder:ident, $sort:expr $(, $_u:ident)?) => {
         mod $mod {
@@ -181,6 +378,16 @@ macro_rules! nn_tests {
                 nn_test_error(&CommonNearestNeighbour::$builder);
             }
 
+            #[test]
+            fn owned() {
+                nn_test_owned(&CommonNearestNeighbour::$builder);
+            }
+
+            #[test]
+            fn owned_batch() {
+                nn_test_owned_batch(&CommonNearestNeighbour::$builder);
+            }
+
             #[test]
             fn normal() {
                 nn_test(&CommonNearestNeighbour::$builder, $sort);
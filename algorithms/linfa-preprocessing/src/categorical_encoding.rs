@@ -0,0 +1,452 @@
+//! Encoders for categorical features
+//!
+//! Categorical features take values from a fixed set of categories, for example codes of colors or
+//! countries. Since the records of a dataset are floating point numbers, categories are given by
+//! their (exact) values. The encoders learn the sorted set of categories of each feature during
+//! fitting:
+//!
+//! * [OrdinalEncoder](struct.OrdinalEncoder.html) replaces each category by its index
+//! * [OneHotEncoder](struct.OneHotEncoder.html) replaces each feature by one indicator column per category
+
+use crate::error::{Error, Result};
+use linfa::dataset::{AsTargets, DatasetBase, Float};
+use linfa::traits::{Fit, Transformer};
+use ndarray::{s, Array2, ArrayBase, Data, Ix2, Zip};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Policy for categories which have not been seen during fitting
+///
+/// * Error: transforming a value of an unknown category returns an error
+/// * Ignore: unknown categories are encoded as NaN by the ordinal encoder and as all-zero columns by the one-hot encoder
+pub enum HandleUnknown {
+    Error,
+    Ignore,
+}
+
+/// Learns the sorted categories of each feature
+fn learn_categories<F: Float, D: Data<Elem = F>>(
+    records: &ArrayBase<D, Ix2>,
+) -> Result<Vec<Vec<F>>> {
+    if records.nrows() == 0 {
+        return Err(Error::NotEnoughSamples);
+    }
+    if records.iter().any(|x| !x.is_finite()) {
+        return Err(Error::InvalidFloat);
+    }
+
+    Ok(records
+        .gencolumns()
+        .into_iter()
+        .map(|col| {
+            let mut categories = col.to_vec();
+            // safe unwrap because all values are finite
+            categories.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            categories.dedup();
+            categories
+        })
+        .collect())
+}
+
+/// Index of a value in the sorted categories of a feature
+fn category_index<F: Float>(categories: &[F], x: F) -> Option<usize> {
+    // comparisons with NaN are treated as a mismatch
+    categories
+        .binary_search_by(|c| c.partial_cmp(&x).unwrap_or(std::cmp::Ordering::Less))
+        .ok()
+}
+
+/// Ordinal encoder: learns the categories of each feature, producing a
+/// [fitted ordinal encoder](struct.FittedOrdinalEncoder.html) which replaces every value by the
+/// index of its category.
+///
+/// ### Example
+///
+/// ```rust
+/// use linfa::traits::{Fit, Transformer};
+/// use linfa::DatasetBase;
+/// use linfa_preprocessing::categorical_encoding::OrdinalEncoder;
+/// use ndarray::array;
+///
+/// let dataset = DatasetBase::from(array![[10., 1.], [20., 3.], [10., 2.]]);
+/// // Learn the categories of each feature
+/// let encoder = OrdinalEncoder::new().fit(&dataset).unwrap();
+/// // replace each value by the index of its category
+/// let encoded = encoder.transform(dataset).unwrap();
+/// assert_eq!(encoded.records(), &array![[0., 0.], [1., 2.], [0., 1.]]);
+/// ```
+pub struct OrdinalEncoder {
+    handle_unknown: HandleUnknown,
+}
+
+impl Default for OrdinalEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrdinalEncoder {
+    /// Initializes an ordinal encoder which returns an error for unknown categories
+    pub fn new() -> Self {
+        Self {
+            handle_unknown: HandleUnknown::Error,
+        }
+    }
+
+    /// Setter for the policy for categories which have not been seen during fitting
+    pub fn handle_unknown(mut self, handle_unknown: HandleUnknown) -> Self {
+        self.handle_unknown = handle_unknown;
+        self
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets> Fit<ArrayBase<D, Ix2>, T, Error>
+    for OrdinalEncoder
+{
+    type Object = FittedOrdinalEncoder<F>;
+
+    /// Learns the categories of each feature. Will return an error if the dataset does not
+    /// contain any samples or contains values which are not finite.
+    fn fit(&self, x: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        Ok(FittedOrdinalEncoder {
+            categories: learn_categories(x.records())?,
+            handle_unknown: self.handle_unknown,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The result of fitting an [ordinal encoder](struct.OrdinalEncoder.html).
+/// Encodes datasets with the categories learned during fitting.
+pub struct FittedOrdinalEncoder<F: Float> {
+    categories: Vec<Vec<F>>,
+    handle_unknown: HandleUnknown,
+}
+
+impl<F: Float> FittedOrdinalEncoder<F> {
+    /// The sorted categories of each feature
+    pub fn categories(&self) -> &[Vec<F>] {
+        &self.categories
+    }
+
+    /// Maps category indices back to the categories. Will return an error if an index does not
+    /// belong to a category, for example NaN values produced for unknown categories.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    pub fn inverse_transform(&self, x: Array2<F>) -> Result<Array2<F>> {
+        assert_eq!(x.ncols(), self.categories.len());
+        let mut x = x;
+        for (col_idx, (categories, mut col)) in
+            self.categories.iter().zip(x.gencolumns_mut()).enumerate()
+        {
+            for value in col.iter_mut() {
+                let idx = value.round();
+                if idx.is_nan() || idx < F::zero() || idx >= F::cast(categories.len()) {
+                    return Err(Error::UnknownCategory(col_idx, value.to_string()));
+                }
+                let idx: usize = idx.as_();
+                *value = categories[idx];
+            }
+        }
+        Ok(x)
+    }
+}
+
+impl<F: Float> Transformer<Array2<F>, Result<Array2<F>>> for FittedOrdinalEncoder<F> {
+    /// Replaces every value of an array of size (nsamples, nfeatures) by the index of its category.
+    /// Will return an error for unknown categories if the policy is [HandleUnknown::Error](enum.HandleUnknown.html).
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: Array2<F>) -> Result<Array2<F>> {
+        assert_eq!(x.ncols(), self.categories.len());
+        let mut x = x;
+        for (col_idx, (categories, mut col)) in
+            self.categories.iter().zip(x.gencolumns_mut()).enumerate()
+        {
+            for value in col.iter_mut() {
+                *value = match (category_index(categories, *value), self.handle_unknown) {
+                    (Some(idx), _) => F::cast(idx),
+                    (None, HandleUnknown::Ignore) => F::nan(),
+                    (None, HandleUnknown::Error) => {
+                        return Err(Error::UnknownCategory(col_idx, value.to_string()))
+                    }
+                };
+            }
+        }
+        Ok(x)
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, Result<DatasetBase<Array2<F>, T>>>
+    for FittedOrdinalEncoder<F>
+{
+    /// Substitutes the records of the dataset with their encoded version.
+    /// Panics if the shape of the records is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<DatasetBase<Array2<F>, T>> {
+        let feature_names = x.feature_names();
+        let (records, targets, weights) = (x.records, x.targets, x.weights);
+        let records = self.transform(records.to_owned())?;
+        Ok(DatasetBase::new(records, targets)
+            .with_weights(weights)
+            .with_feature_names(feature_names))
+    }
+}
+
+/// One-hot encoder: learns the categories of each feature, producing a
+/// [fitted one-hot encoder](struct.FittedOneHotEncoder.html) which replaces every feature by one
+/// indicator column per category.
+///
+/// ### Example
+///
+/// ```rust
+/// use linfa::traits::{Fit, Transformer};
+/// use linfa::DatasetBase;
+/// use linfa_preprocessing::categorical_encoding::OneHotEncoder;
+/// use ndarray::array;
+///
+/// let dataset = DatasetBase::from(array![[10.], [20.], [10.]]).with_feature_names(vec!["size"]);
+/// // Learn the categories of each feature
+/// let encoder = OneHotEncoder::new().fit(&dataset).unwrap();
+/// // expand each feature into indicator columns
+/// let encoded = encoder.transform(dataset).unwrap();
+/// assert_eq!(encoded.records(), &array![[1., 0.], [0., 1.], [1., 0.]]);
+/// assert_eq!(encoded.feature_names(), vec!["size_10", "size_20"]);
+/// ```
+pub struct OneHotEncoder {
+    handle_unknown: HandleUnknown,
+}
+
+impl Default for OneHotEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OneHotEncoder {
+    /// Initializes a one-hot encoder which returns an error for unknown categories
+    pub fn new() -> Self {
+        Self {
+            handle_unknown: HandleUnknown::Error,
+        }
+    }
+
+    /// Setter for the policy for categories which have not been seen during fitting
+    pub fn handle_unknown(mut self, handle_unknown: HandleUnknown) -> Self {
+        self.handle_unknown = handle_unknown;
+        self
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets> Fit<ArrayBase<D, Ix2>, T, Error> for OneHotEncoder {
+    type Object = FittedOneHotEncoder<F>;
+
+    /// Learns the categories of each feature. Will return an error if the dataset does not
+    /// contain any samples or contains values which are not finite.
+    fn fit(&self, x: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        Ok(FittedOneHotEncoder {
+            categories: learn_categories(x.records())?,
+            input_names: x.feature_names(),
+            handle_unknown: self.handle_unknown,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The result of fitting a [one-hot encoder](struct.OneHotEncoder.html).
+/// Encodes datasets with the categories learned during fitting.
+pub struct FittedOneHotEncoder<F: Float> {
+    categories: Vec<Vec<F>>,
+    input_names: Vec<String>,
+    handle_unknown: HandleUnknown,
+}
+
+impl<F: Float> FittedOneHotEncoder<F> {
+    /// The sorted categories of each feature
+    pub fn categories(&self) -> &[Vec<F>] {
+        &self.categories
+    }
+
+    /// Names of the indicator columns, given by the name of the feature seen during fitting and the category
+    pub fn feature_names(&self) -> Vec<String> {
+        self.input_names
+            .iter()
+            .zip(self.categories.iter())
+            .flat_map(|(name, categories)| {
+                categories
+                    .iter()
+                    .map(move |category| format!("{}_{}", name, category))
+            })
+            .collect()
+    }
+
+    /// Maps indicator columns back to the categories. Will return an error if the indicator columns
+    /// of a feature are all zero, for example for unknown categories.
+    /// Panics if the shape of the input array is not compatible with the shape of the encoded dataset.
+    pub fn inverse_transform(&self, x: Array2<F>) -> Result<Array2<F>> {
+        let n_columns: usize = self.categories.iter().map(Vec::len).sum();
+        assert_eq!(x.ncols(), n_columns);
+
+        let mut result = Array2::zeros((x.nrows(), self.categories.len()));
+        let mut offset = 0;
+        for (col_idx, (categories, mut col)) in self
+            .categories
+            .iter()
+            .zip(result.gencolumns_mut())
+            .enumerate()
+        {
+            let indicators = x.slice(s![.., offset..offset + categories.len()]);
+            for (row, value) in indicators.genrows().into_iter().zip(col.iter_mut()) {
+                let idx = row
+                    .iter()
+                    .position(|&x| x > F::zero())
+                    .ok_or_else(|| Error::UnknownCategory(col_idx, "all-zero".to_string()))?;
+                *value = categories[idx];
+            }
+            offset += categories.len();
+        }
+        Ok(result)
+    }
+}
+
+impl<F: Float> Transformer<Array2<F>, Result<Array2<F>>> for FittedOneHotEncoder<F> {
+    /// Expands an array of size (nsamples, nfeatures) into one indicator column per category.
+    /// Will return an error for unknown categories if the policy is [HandleUnknown::Error](enum.HandleUnknown.html).
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: Array2<F>) -> Result<Array2<F>> {
+        assert_eq!(x.ncols(), self.categories.len());
+        let n_columns: usize = self.categories.iter().map(Vec::len).sum();
+
+        let mut result = Array2::zeros((x.nrows(), n_columns));
+        let mut offset = 0;
+        for (col_idx, (categories, col)) in self.categories.iter().zip(x.gencolumns()).enumerate() {
+            let mut indicators = result.slice_mut(s![.., offset..offset + categories.len()]);
+            Zip::from(indicators.genrows_mut())
+                .and(&col)
+                .apply(|mut row, &value| {
+                    if let Some(idx) = category_index(categories, value) {
+                        row[idx] = F::one();
+                    }
+                });
+
+            if self.handle_unknown == HandleUnknown::Error {
+                if let Some(value) = col
+                    .iter()
+                    .find(|&&value| category_index(categories, value).is_none())
+                {
+                    return Err(Error::UnknownCategory(col_idx, value.to_string()));
+                }
+            }
+            offset += categories.len();
+        }
+        Ok(result)
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, Result<DatasetBase<Array2<F>, T>>>
+    for FittedOneHotEncoder<F>
+{
+    /// Substitutes the records of the dataset with their encoded version and names the indicator
+    /// columns after the features and categories.
+    /// Panics if the shape of the records is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<DatasetBase<Array2<F>, T>> {
+        let (records, targets, weights) = (x.records, x.targets, x.weights);
+        let records = self.transform(records.to_owned())?;
+        Ok(DatasetBase::new(records, targets)
+            .with_weights(weights)
+            .with_feature_names(self.feature_names()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HandleUnknown, OneHotEncoder, OrdinalEncoder};
+    use crate::error::Error;
+    use approx::assert_abs_diff_eq;
+    use linfa::dataset::DatasetBase;
+    use linfa::traits::{Fit, Transformer};
+    use ndarray::{array, Array2};
+
+    fn categorical() -> DatasetBase<Array2<f64>, Array2<()>> {
+        DatasetBase::from(array![[1., 10.], [2., 20.], [1., 30.], [3., 10.]])
+            .with_feature_names(vec!["a", "b"])
+    }
+
+    #[test]
+    fn test_ordinal_round_trip() {
+        let dataset = categorical();
+        let encoder = OrdinalEncoder::new().fit(&dataset).unwrap();
+        assert_eq!(
+            encoder.categories(),
+            &[vec![1., 2., 3.], vec![10., 20., 30.]]
+        );
+
+        let encoded = encoder.transform(dataset).unwrap();
+        assert_eq!(encoded.feature_names(), vec!["a", "b"]);
+        assert_abs_diff_eq!(
+            *encoded.records(),
+            array![[0., 0.], [1., 1.], [0., 2.], [2., 0.]]
+        );
+
+        let decoded = encoder.inverse_transform(encoded.records).unwrap();
+        assert_abs_diff_eq!(decoded, categorical().records);
+    }
+
+    #[test]
+    fn test_one_hot_round_trip() {
+        let dataset = categorical();
+        let encoder = OneHotEncoder::new().fit(&dataset).unwrap();
+
+        let encoded = encoder.transform(dataset).unwrap();
+        assert_eq!(
+            encoded.feature_names(),
+            vec!["a_1", "a_2", "a_3", "b_10", "b_20", "b_30"]
+        );
+        assert_abs_diff_eq!(
+            *encoded.records(),
+            array![
+                [1., 0., 0., 1., 0., 0.],
+                [0., 1., 0., 0., 1., 0.],
+                [1., 0., 0., 0., 0., 1.],
+                [0., 0., 1., 1., 0., 0.]
+            ]
+        );
+
+        let decoded = encoder.inverse_transform(encoded.records).unwrap();
+        assert_abs_diff_eq!(decoded, categorical().records);
+    }
+
+    #[test]
+    fn test_unknown_categories() {
+        let dataset = categorical();
+        let unseen = array![[1., 10.], [4., 20.]];
+
+        let encoder = OrdinalEncoder::new().fit(&dataset).unwrap();
+        assert!(matches!(
+            encoder.transform(unseen.clone()),
+            Err(Error::UnknownCategory(0, _))
+        ));
+        let encoder = OneHotEncoder::new().fit(&dataset).unwrap();
+        assert!(matches!(
+            encoder.transform(unseen.clone()),
+            Err(Error::UnknownCategory(0, _))
+        ));
+
+        let encoder = OrdinalEncoder::new()
+            .handle_unknown(HandleUnknown::Ignore)
+            .fit(&dataset)
+            .unwrap();
+        let encoded = encoder.transform(unseen.clone()).unwrap();
+        assert!(encoded[[1, 0]].is_nan());
+        assert_abs_diff_eq!(encoded[[1, 1]], 1.);
+
+        let encoder = OneHotEncoder::new()
+            .handle_unknown(HandleUnknown::Ignore)
+            .fit(&dataset)
+            .unwrap();
+        let encoded = encoder.transform(unseen).unwrap();
+        assert_abs_diff_eq!(
+            encoded,
+            array![[1., 0., 0., 1., 0., 0.], [0., 0., 0., 0., 1., 0.]]
+        );
+        assert!(encoder.inverse_transform(encoded).is_err());
+    }
+}
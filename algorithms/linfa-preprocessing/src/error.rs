@@ -12,6 +12,14 @@ pub enum Error {
     NotEnoughSamples,
     #[error("not a valid float")]
     InvalidFloat,
+    #[error("Box-Cox transformation requires strictly positive data")]
+    NonPositiveBoxCox,
+    #[error("number of quantiles has to be at least 2, got {0}")]
+    InvalidNQuantiles(usize),
+    #[error("number of bins has to be at least 2, got {0}")]
+    InvalidNBins(usize),
+    #[error("unknown category {1} in column {0}")]
+    UnknownCategory(usize, String),
     #[error("minimum value for MinMax scaler cannot be greater than the maximum")]
     FlippedMinMaxRange,
     #[error("n_gram boundaries cannot be zero (min = {0}, max = {1})")]
@@ -33,5 +41,7 @@ pub enum Error {
     #[error(transparent)]
     NdarrayStatsEmptyError(#[from] ndarray_stats::errors::EmptyInput),
     #[error(transparent)]
+    KMeansError(#[from] linfa_clustering::KMeansError),
+    #[error(transparent)]
     LinfaError(#[from] linfa::error::Error),
 }
@@ -0,0 +1,452 @@
+//! Discretization of continuous features
+//!
+//! The discretizer partitions the range of each feature into a number of bins and replaces every
+//! value with the index of the bin it falls in. The bin edges are learned from the data with one
+//! of several [strategies](enum.BinningStrategy.html).
+
+use crate::error::{Error, Result};
+use linfa::dataset::{AsTargets, DatasetBase, Float};
+use linfa::traits::{Fit, Transformer};
+use linfa_clustering::{KMeans, KMeansError, KMeansInit};
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2};
+
+/// Bins whose width is below this threshold are merged with their neighbours
+const MIN_BIN_WIDTH: f64 = 1e-8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Possible strategies for computing the bin edges of the [KBinsDiscretizer](struct.KBinsDiscretizer.html)
+///
+/// * Uniform: all bins of a feature have identical widths
+/// * Quantile: all bins of a feature contain approximately the same number of samples
+/// * KMeans: the values in each bin have the same nearest center of a one-dimensional K-Means clustering
+pub enum BinningStrategy {
+    Uniform,
+    Quantile,
+    KMeans,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Possible encodings of the bins returned by the [KBinsDiscretizer](struct.KBinsDiscretizer.html)
+///
+/// * Ordinal: every feature is replaced by its bin index
+/// * OneHot: every feature is replaced by one column per bin, containing one for the bin of the value and zero otherwise
+pub enum BinEncoding {
+    Ordinal,
+    OneHot,
+}
+
+/// K-bins discretizer: learns the bin edges of each feature according to the specified
+/// [strategy](enum.BinningStrategy.html), producing a [fitted discretizer](struct.FittedKBinsDiscretizer.html)
+/// that can be used to discretize different datasets using the same bins.
+///
+/// By default bins contain approximately the same number of samples and are encoded by their index.
+///
+/// ### Example
+///
+/// ```rust
+/// use linfa::traits::{Fit, Transformer};
+/// use linfa_preprocessing::kbins_discretization::{BinEncoding, KBinsDiscretizer};
+///
+/// // Load dataset
+/// let dataset = linfa_datasets::diabetes();
+/// // Learn the bin edges of each feature
+/// let discretizer = KBinsDiscretizer::new(5).encode(BinEncoding::OneHot).fit(&dataset).unwrap();
+/// // replace every feature with the one-hot encoding of its bin
+/// let dataset = discretizer.transform(dataset);
+/// ```
+pub struct KBinsDiscretizer {
+    n_bins: usize,
+    strategy: BinningStrategy,
+    encode: BinEncoding,
+}
+
+impl KBinsDiscretizer {
+    /// Initializes a discretizer with `n_bins` bins per feature. Fitting will return an error if
+    /// less than two bins are requested.
+    pub fn new(n_bins: usize) -> Self {
+        Self {
+            n_bins,
+            strategy: BinningStrategy::Quantile,
+            encode: BinEncoding::Ordinal,
+        }
+    }
+
+    /// Setter for the number of bins per feature
+    pub fn n_bins(mut self, n_bins: usize) -> Self {
+        self.n_bins = n_bins;
+        self
+    }
+
+    /// Setter for the strategy used to compute the bin edges
+    pub fn strategy(mut self, strategy: BinningStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Setter for the encoding of the bins
+    pub fn encode(mut self, encode: BinEncoding) -> Self {
+        self.encode = encode;
+        self
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets> Fit<ArrayBase<D, Ix2>, T, Error>
+    for KBinsDiscretizer
+{
+    type Object = FittedKBinsDiscretizer<F>;
+
+    /// Computes the bin edges of each feature. Will return an error if the dataset does not
+    /// contain any samples, contains values which are not finite, if less than two bins are
+    /// requested or if the K-Means clustering of a feature fails.
+    ///
+    /// Bins which are narrower than `1e-8` are removed, such that features may end up with less
+    /// than `n_bins` bins.
+    fn fit(&self, x: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        if self.n_bins < 2 {
+            return Err(Error::InvalidNBins(self.n_bins));
+        }
+        let records = x.records();
+        if records.nrows() == 0 {
+            return Err(Error::NotEnoughSamples);
+        }
+        if records.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidFloat);
+        }
+
+        let bin_edges = records
+            .gencolumns()
+            .into_iter()
+            .map(|col| {
+                let min = col.fold(F::infinity(), |a, &b| a.min(b));
+                let max = col.fold(F::neg_infinity(), |a, &b| a.max(b));
+                if min == max {
+                    // constant features are put into a single bin
+                    return Ok(Array1::from(vec![min, max]));
+                }
+
+                let edges = match self.strategy {
+                    BinningStrategy::Uniform => Array1::linspace(min, max, self.n_bins + 1),
+                    BinningStrategy::Quantile => quantile_edges(col, self.n_bins),
+                    BinningStrategy::KMeans => kmeans_edges(col, self.n_bins, min, max)?,
+                };
+
+                Ok(remove_narrow_bins(edges))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(FittedKBinsDiscretizer {
+            bin_edges,
+            encode: self.encode,
+        })
+    }
+}
+
+/// Bin edges at the evenly spaced percentiles of a feature
+fn quantile_edges<F: Float>(col: ArrayView1<F>, n_bins: usize) -> Array1<F> {
+    let mut sorted = col.to_vec();
+    // safe unwrap because all values are finite
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let last = F::cast(sorted.len() - 1);
+    Array1::linspace(F::zero(), F::one(), n_bins + 1).mapv(|r| {
+        let pos = r * last;
+        let lower = pos.floor();
+        let idx: usize = lower.as_();
+        let idx = idx.min(sorted.len() - 1);
+        if idx + 1 < sorted.len() {
+            sorted[idx] + (pos - lower) * (sorted[idx + 1] - sorted[idx])
+        } else {
+            sorted[idx]
+        }
+    })
+}
+
+/// Bin edges halfway between the sorted centers of a one-dimensional K-Means clustering
+///
+/// The clustering is initialized with the centers of uniform bins, which makes it deterministic.
+fn kmeans_edges<F: Float>(col: ArrayView1<F>, n_bins: usize, min: F, max: F) -> Result<Array1<F>> {
+    let width = (max - min) / F::cast(n_bins);
+    let init = Array1::linspace(min + width / F::cast(2.), max - width / F::cast(2.), n_bins)
+        .insert_axis(Axis(1));
+
+    let observations = DatasetBase::from(col.insert_axis(Axis(1)));
+    let model = KMeans::params(n_bins)
+        .n_runs(1)
+        .init_method(KMeansInit::Precomputed(init))
+        .build()
+        .map_err(KMeansError::from)?
+        .fit(&observations)?;
+
+    let mut centers = model
+        .centroids()
+        .iter()
+        .copied()
+        .filter(|c| c.is_finite())
+        .collect::<Vec<_>>();
+    // safe unwrap because all centers are finite
+    centers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut edges = vec![min];
+    edges.extend(
+        centers
+            .windows(2)
+            .map(|pair| (pair[0] + pair[1]) / F::cast(2.)),
+    );
+    edges.push(max);
+
+    Ok(Array1::from(edges))
+}
+
+fn remove_narrow_bins<F: Float>(edges: Array1<F>) -> Array1<F> {
+    let mut kept = vec![edges[0]];
+    for &edge in edges.iter().skip(1) {
+        // safe unwrap because `kept` contains at least the first edge
+        if edge - *kept.last().unwrap() > F::cast(MIN_BIN_WIDTH) {
+            kept.push(edge);
+        }
+    }
+    if kept.len() == 1 {
+        kept.push(edges[edges.len() - 1]);
+    }
+
+    Array1::from(kept)
+}
+
+#[derive(Debug, Clone)]
+/// The result of fitting a [K-bins discretizer](struct.KBinsDiscretizer.html).
+/// Discretizes datasets with the bins learned during fitting.
+///
+/// Values outside of the range seen during fitting are put into the first or last bin.
+pub struct FittedKBinsDiscretizer<F: Float> {
+    bin_edges: Vec<Array1<F>>,
+    encode: BinEncoding,
+}
+
+impl<F: Float> FittedKBinsDiscretizer<F> {
+    /// The edges of the bins of each feature, including the minimum and maximum value seen during fitting
+    pub fn bin_edges(&self) -> &[Array1<F>] {
+        &self.bin_edges
+    }
+
+    /// The number of bins of each feature
+    pub fn n_bins(&self) -> Vec<usize> {
+        self.bin_edges.iter().map(|edges| edges.len() - 1).collect()
+    }
+
+    /// Returns the encoding of the bins
+    pub fn encode(&self) -> BinEncoding {
+        self.encode
+    }
+
+    /// Index of the bin that contains `x`
+    fn bin_index(edges: &Array1<F>, x: F) -> usize {
+        // only the inner edges separate bins, such that values out of range are clipped
+        edges
+            .slice(s![1..edges.len() - 1])
+            .iter()
+            .take_while(|&&edge| edge <= x)
+            .count()
+    }
+
+    /// Maps discretized data back to the original feature space, replacing every bin by its center.
+    /// The input has to be in the encoding of the discretizer.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    pub fn inverse_transform(&self, x: Array2<F>) -> Array2<F> {
+        let mut offset = 0;
+        let mut result = Array2::zeros((x.nrows(), self.bin_edges.len()));
+        for (edges, mut col) in self.bin_edges.iter().zip(result.gencolumns_mut()) {
+            let n_bins = edges.len() - 1;
+            for (row, value) in x.genrows().into_iter().zip(col.iter_mut()) {
+                let bin = match self.encode {
+                    BinEncoding::Ordinal => {
+                        let bin: usize = row[offset].max(F::zero()).round().as_();
+                        bin.min(n_bins - 1)
+                    }
+                    BinEncoding::OneHot => {
+                        row.slice(s![offset..offset + n_bins])
+                            .iter()
+                            .enumerate()
+                            .fold((0, F::neg_infinity()), |(best, max), (i, &v)| {
+                                if v > max {
+                                    (i, v)
+                                } else {
+                                    (best, max)
+                                }
+                            })
+                            .0
+                    }
+                };
+                *value = (edges[bin] + edges[bin + 1]) / F::cast(2.);
+            }
+            offset += match self.encode {
+                BinEncoding::Ordinal => 1,
+                BinEncoding::OneHot => n_bins,
+            };
+        }
+        result
+    }
+}
+
+impl<F: Float> Transformer<Array2<F>, Array2<F>> for FittedKBinsDiscretizer<F> {
+    /// Discretizes an array of size (nsamples, nfeatures). The result has one column per feature
+    /// for the ordinal encoding and one column per bin for the one-hot encoding.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: Array2<F>) -> Array2<F> {
+        assert_eq!(x.ncols(), self.bin_edges.len());
+        match self.encode {
+            BinEncoding::Ordinal => {
+                let mut x = x;
+                for (edges, mut col) in self.bin_edges.iter().zip(x.gencolumns_mut()) {
+                    col.mapv_inplace(|x| F::cast(Self::bin_index(edges, x)));
+                }
+                x
+            }
+            BinEncoding::OneHot => {
+                let n_columns = self.n_bins().iter().sum();
+                let mut result = Array2::zeros((x.nrows(), n_columns));
+                let mut offset = 0;
+                for (edges, col) in self.bin_edges.iter().zip(x.gencolumns()) {
+                    for (mut row, &x) in result.genrows_mut().into_iter().zip(col.iter()) {
+                        row[offset + Self::bin_index(edges, x)] = F::one();
+                    }
+                    offset += edges.len() - 1;
+                }
+                result
+            }
+        }
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>>
+    for FittedKBinsDiscretizer<F>
+{
+    /// Substitutes the records of the dataset with their discretized version. The feature names
+    /// are only kept for the ordinal encoding.
+    /// Panics if the shape of the records is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let feature_names = x.feature_names();
+        let (records, targets, weights) = (x.records, x.targets, x.weights);
+        let records = self.transform(records.to_owned());
+        let dataset = DatasetBase::new(records, targets).with_weights(weights);
+        match self.encode {
+            BinEncoding::Ordinal => dataset.with_feature_names(feature_names),
+            BinEncoding::OneHot => dataset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinEncoding, BinningStrategy, KBinsDiscretizer};
+    use crate::error::Error;
+    use approx::assert_abs_diff_eq;
+    use linfa::dataset::DatasetBase;
+    use linfa::traits::{Fit, Transformer};
+    use ndarray::{array, Array1, Array2};
+    use ndarray_rand::{rand::rngs::SmallRng, rand::SeedableRng, rand_distr::LogNormal, RandomExt};
+
+    #[test]
+    fn test_quantile_equal_counts() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = Array2::random_using((1000, 2), LogNormal::new(0., 1.).unwrap(), &mut rng);
+
+        let dataset: DatasetBase<_, _> = records.into();
+        let discretizer = KBinsDiscretizer::new(4).fit(&dataset).unwrap();
+        assert_eq!(discretizer.n_bins(), vec![4, 4]);
+
+        let transformed = discretizer.transform(dataset);
+        for col in transformed.records().gencolumns() {
+            let mut counts = Array1::<f64>::zeros(4);
+            for &bin in col.iter() {
+                counts[bin as usize] += 1.;
+            }
+            assert_abs_diff_eq!(counts, Array1::from_elem(4, 250.), epsilon = 2.);
+        }
+    }
+
+    #[test]
+    fn test_uniform_edges() {
+        let dataset = array![[0., 5.], [1., 5.], [4., 5.], [10., 5.]].into();
+        let discretizer = KBinsDiscretizer::new(5)
+            .strategy(BinningStrategy::Uniform)
+            .fit(&dataset)
+            .unwrap();
+
+        assert_abs_diff_eq!(discretizer.bin_edges()[0], array![0., 2., 4., 6., 8., 10.]);
+        // constant features have a single bin
+        assert_eq!(discretizer.n_bins(), vec![5, 1]);
+
+        let transformed = discretizer.transform(array![[-3., 5.], [3.9, 1.], [4., 5.], [12., 9.]]);
+        assert_abs_diff_eq!(transformed, array![[0., 0.], [1., 0.], [2., 0.], [4., 0.]]);
+    }
+
+    #[test]
+    fn test_kmeans_bins() {
+        let dataset = array![
+            [0.],
+            [0.5],
+            [1.],
+            [10.],
+            [10.5],
+            [11.],
+            [20.],
+            [20.5],
+            [21.]
+        ]
+        .into();
+        let discretizer = KBinsDiscretizer::new(3)
+            .strategy(BinningStrategy::KMeans)
+            .fit(&dataset)
+            .unwrap();
+
+        assert_abs_diff_eq!(discretizer.bin_edges()[0], array![0., 5.5, 15.5, 21.]);
+        let transformed = discretizer.transform(array![[0.7], [9.5], [20.1]]);
+        assert_abs_diff_eq!(transformed, array![[0.], [1.], [2.]]);
+    }
+
+    #[test]
+    fn test_one_hot_inverse_transform() {
+        let records = array![[0., 1.], [1., 3.], [4., 2.], [10., 5.]];
+        for encode in &[BinEncoding::Ordinal, BinEncoding::OneHot] {
+            let dataset = records.clone().into();
+            let discretizer = KBinsDiscretizer::new(2)
+                .strategy(BinningStrategy::Uniform)
+                .encode(*encode)
+                .fit(&dataset)
+                .unwrap();
+
+            let transformed = discretizer.transform(records.clone());
+            if *encode == BinEncoding::OneHot {
+                assert_abs_diff_eq!(
+                    transformed,
+                    array![
+                        [1., 0., 1., 0.],
+                        [1., 0., 0., 1.],
+                        [1., 0., 1., 0.],
+                        [0., 1., 0., 1.]
+                    ]
+                );
+            }
+
+            assert_abs_diff_eq!(
+                discretizer.inverse_transform(transformed),
+                array![[2.5, 2.], [2.5, 4.], [2.5, 2.], [7.5, 4.]]
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_n_bins() {
+        let dataset = array![[1.], [2.]].into();
+        let res = KBinsDiscretizer::new(1).fit(&dataset);
+        assert!(matches!(res, Err(Error::InvalidNBins(1))));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let dataset: DatasetBase<Array2<f64>, _> = Array2::zeros((0, 2)).into();
+        let res = KBinsDiscretizer::new(3).fit(&dataset);
+        assert!(matches!(res, Err(Error::NotEnoughSamples)));
+    }
+}
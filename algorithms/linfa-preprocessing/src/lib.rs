@@ -12,11 +12,19 @@
 //! * Count vectorization
 //! * Term frequency - inverse document frequency count vectorization
 //! * Whitening
+//! * Power transformations (Box-Cox and Yeo-Johnson)
+//! * Quantile transformation
+//! * K-bins discretization
+//! * Ordinal and one-hot encoding of categorical features
 
+pub mod categorical_encoding;
 pub mod count_vectorization;
 pub mod error;
 mod helpers;
+pub mod kbins_discretization;
 pub mod linear_scaling;
 pub mod norm_scaling;
+pub mod power_transform;
+pub mod quantile_transform;
 pub mod tf_idf_vectorization;
 pub mod whitening;
@@ -2,10 +2,9 @@
 
 use crate::error::{Error, Result};
 use approx::abs_diff_eq;
-use linfa::dataset::{AsTargets, DatasetBase, Float, WithLapack};
-use linfa::traits::{Fit, Transformer};
+use linfa::dataset::{AsTargets, DatasetBase, Float};
+use linfa::traits::{Fit, IncrementalFit, Transformer};
 use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix2, Zip};
-use ndarray_linalg::norm::Norm;
 
 #[derive(Clone, Debug)]
 /// Possible scaling methods for [LinearScaler](struct.LinearScaler.html)
@@ -131,43 +130,158 @@ impl<F: Float, D: Data<Elem = F>, T: AsTargets> Fit<ArrayBase<D, Ix2>, T, Error>
     }
 }
 
+impl<'a, F: Float, D: Data<Elem = F>, T: AsTargets> IncrementalFit<'a, ArrayBase<D, Ix2>, T>
+    for LinearScaler<F>
+{
+    type ObjectIn = Option<FittedLinearScaler<F>>;
+    type ObjectOut = Result<FittedLinearScaler<F>>;
+
+    /// Incrementally fits the scaler on a chunk of samples. If no fitted scaler is given, a new one is fitted on the chunk,
+    /// otherwise the given scaler is updated with [partial_fit](struct.FittedLinearScaler.html#method.partial_fit).
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use linfa::traits::{IncrementalFit, Transformer};
+    /// use linfa::DatasetBase;
+    /// use linfa_preprocessing::linear_scaling::LinearScaler;
+    /// use ndarray::Axis;
+    ///
+    /// let dataset = linfa_datasets::diabetes();
+    /// let params = LinearScaler::standard();
+    ///
+    /// let mut scaler = None;
+    /// for chunk in dataset.records().axis_chunks_iter(Axis(0), 100) {
+    ///     scaler = Some(params.fit_with(scaler, &DatasetBase::from(chunk)).unwrap());
+    /// }
+    ///
+    /// let dataset = scaler.unwrap().transform(dataset);
+    /// ```
+    fn fit_with(
+        &self,
+        model: Self::ObjectIn,
+        dataset: &'a DatasetBase<ArrayBase<D, Ix2>, T>,
+    ) -> Self::ObjectOut {
+        match model {
+            Some(mut scaler) => {
+                scaler.partial_fit(dataset.records())?;
+                Ok(scaler)
+            }
+            None => self.fit(dataset),
+        }
+    }
+}
+
 #[derive(Debug)]
 /// The result of fitting a [linear scaler](struct.LinearScaler.html).
 /// Scales datasets with the parameters learned during fitting.
+///
+/// The scaler keeps running statistics of all samples seen so far, such that it can be updated
+/// incrementally with [partial_fit](struct.FittedLinearScaler.html#method.partial_fit). Fitting
+/// a scaler over several chunks of data gives the same parameters as fitting it over their
+/// concatenation.
 pub struct FittedLinearScaler<F: Float> {
     offsets: Array1<F>,
     scales: Array1<F>,
     method: ScalingMethod<F>,
+    stats: RunningStatistics<F>,
+}
+
+/// Per-feature statistics of all samples seen by a scaler
+///
+/// Means and sums of squared deviations are merged with the parallel variant of Welford's
+/// algorithm, which is numerically stable for long streams of data.
+#[derive(Debug, Clone)]
+struct RunningStatistics<F: Float> {
+    n_samples: usize,
+    means: Array1<F>,
+    m2: Array1<F>,
+    mins: Array1<F>,
+    maxs: Array1<F>,
+}
+
+impl<F: Float> RunningStatistics<F> {
+    fn new(nfeatures: usize) -> Self {
+        Self {
+            n_samples: 0,
+            means: Array1::zeros(nfeatures),
+            m2: Array1::zeros(nfeatures),
+            mins: Array1::from_elem(nfeatures, F::infinity()),
+            maxs: Array1::from_elem(nfeatures, F::neg_infinity()),
+        }
+    }
+
+    fn update<D: Data<Elem = F>>(&mut self, records: &ArrayBase<D, Ix2>) {
+        let nsamples = records.dim().0;
+        // safe unwrap because callers check for empty records
+        let means = records.mean_axis(Axis(0)).unwrap();
+        let m2 = (records - &means).mapv(|x| x * x).sum_axis(Axis(0));
+
+        if self.n_samples == 0 {
+            self.means = means;
+            self.m2 = m2;
+        } else {
+            let (n_a, n_b) = (F::cast(self.n_samples), F::cast(nsamples));
+            let n = n_a + n_b;
+            let delta = &means - &self.means;
+            Zip::from(&mut self.means)
+                .and(&mut self.m2)
+                .and(&delta)
+                .and(&m2)
+                .apply(|mean, m2_a, &delta, &m2_b| {
+                    *mean += delta * n_b / n;
+                    *m2_a += m2_b + delta * delta * n_a * n_b / n;
+                });
+        }
+
+        let mins = records.fold_axis(
+            Axis(0),
+            F::infinity(),
+            |&x, &prev| if x < prev { x } else { prev },
+        );
+        let maxs = records.fold_axis(
+            Axis(0),
+            F::neg_infinity(),
+            |&x, &prev| if x > prev { x } else { prev },
+        );
+        Zip::from(&mut self.mins)
+            .and(&mut self.maxs)
+            .and(&mins)
+            .and(&maxs)
+            .apply(|min, max, &chunk_min, &chunk_max| {
+                if chunk_min < *min {
+                    *min = chunk_min;
+                }
+                if chunk_max > *max {
+                    *max = chunk_max;
+                }
+            });
+
+        self.n_samples += nsamples;
+    }
 }
 
 impl<F: Float> FittedLinearScaler<F> {
+    fn empty(method: ScalingMethod<F>, nfeatures: usize) -> Self {
+        Self {
+            offsets: Array1::zeros(nfeatures),
+            scales: Array1::ones(nfeatures),
+            method,
+            stats: RunningStatistics::new(nfeatures),
+        }
+    }
+
     pub(crate) fn standard<D: Data<Elem = F>>(
         records: &ArrayBase<D, Ix2>,
         with_mean: bool,
         with_std: bool,
     ) -> Result<Self> {
-        if records.dim().0 == 0 {
-            return Err(Error::NotEnoughSamples);
-        }
-        // safe unwrap because of above zero records check
-        let means = records.mean_axis(Axis(0)).unwrap();
-        let std_devs = if with_std {
-            records.std_axis(Axis(0), F::zero()).mapv(|s| {
-                if abs_diff_eq!(s, F::zero()) {
-                    // if feature is constant then don't scale
-                    F::one()
-                } else {
-                    F::one() / s
-                }
-            })
-        } else {
-            Array1::ones(records.dim().1)
-        };
-        Ok(Self {
-            offsets: means,
-            scales: std_devs,
-            method: ScalingMethod::Standard(with_mean, with_std),
-        })
+        let mut scaler = Self::empty(
+            ScalingMethod::Standard(with_mean, with_std),
+            records.dim().1,
+        );
+        scaler.partial_fit(records)?;
+        Ok(scaler)
     }
 
     pub(crate) fn min_max<D: Data<Elem = F>>(
@@ -181,52 +295,105 @@ impl<F: Float> FittedLinearScaler<F> {
         if min > max {
             return Err(Error::FlippedMinMaxRange);
         }
-        let mins = records.fold_axis(
-            Axis(0),
-            F::infinity(),
-            |&x, &prev| if x < prev { x } else { prev },
-        );
-        let mut scales =
-            records.fold_axis(
-                Axis(0),
-                F::neg_infinity(),
-                |&x, &prev| if x > prev { x } else { prev },
-            );
-        Zip::from(&mut scales).and(&mins).apply(|max, min| {
-            if abs_diff_eq!(*max - *min, F::zero()) {
-                // if feature is constant then don't scale
-                *max = F::one();
-            } else {
-                *max = F::one() / (*max - *min);
-            }
-        });
-        Ok(Self {
-            offsets: mins,
-            scales,
-            method: ScalingMethod::MinMax(min, max),
-        })
+        let mut scaler = Self::empty(ScalingMethod::MinMax(min, max), records.dim().1);
+        scaler.partial_fit(records)?;
+        Ok(scaler)
     }
 
     pub(crate) fn max_abs<D: Data<Elem = F>>(records: &ArrayBase<D, Ix2>) -> Result<Self> {
+        let mut scaler = Self::empty(ScalingMethod::MaxAbs, records.dim().1);
+        scaler.partial_fit(records)?;
+        Ok(scaler)
+    }
+
+    /// Updates the scaler with an additional chunk of samples.
+    ///
+    /// The running statistics of the scaler are merged with the statistics of `records` and the
+    /// offsets and scales are recomputed, such that fitting over several chunks gives the same
+    /// result as fitting over their concatenation. Will return an error if `records` does not contain any samples
+    /// or if its number of features differs from the data seen before.
+    pub fn partial_fit<D: Data<Elem = F>>(&mut self, records: &ArrayBase<D, Ix2>) -> Result<()> {
         if records.dim().0 == 0 {
             return Err(Error::NotEnoughSamples);
         }
-        let scales: Array1<F> = records.map_axis(Axis(0), |col| {
-            let norm_max = F::cast(col.with_lapack().norm_max());
-            if abs_diff_eq!(norm_max, F::zero()) {
-                // if feature is constant at zero then don't scale
-                F::one()
-            } else {
-                F::one() / norm_max
+        if records.dim().1 != self.stats.means.len() {
+            return Err(
+                linfa::Error::MismatchedShapes(self.stats.means.len(), records.dim().1).into(),
+            );
+        }
+
+        self.stats.update(records);
+        self.update_parameters();
+
+        Ok(())
+    }
+
+    /// Discards all samples seen so far.
+    ///
+    /// The scaler keeps its method and number of features, but has to be updated with
+    /// [partial_fit](struct.FittedLinearScaler.html#method.partial_fit) before it can
+    /// meaningfully transform data again.
+    pub fn reset(&mut self) {
+        let nfeatures = self.stats.means.len();
+        self.stats = RunningStatistics::new(nfeatures);
+        self.offsets = Array1::zeros(nfeatures);
+        self.scales = Array1::ones(nfeatures);
+    }
+
+    /// Number of samples seen since the scaler was fitted or last reset
+    pub fn n_samples_seen(&self) -> usize {
+        self.stats.n_samples
+    }
+
+    fn update_parameters(&mut self) {
+        let stats = &self.stats;
+        let (offsets, scales) = match self.method {
+            ScalingMethod::Standard(_, with_std) => {
+                let scales = if with_std {
+                    let n = F::cast(stats.n_samples);
+                    stats.m2.mapv(|m2| {
+                        let s = (m2 / n).sqrt();
+                        if abs_diff_eq!(s, F::zero()) {
+                            // if feature is constant then don't scale
+                            F::one()
+                        } else {
+                            F::one() / s
+                        }
+                    })
+                } else {
+                    Array1::ones(stats.means.len())
+                };
+                (stats.means.clone(), scales)
             }
-        });
+            ScalingMethod::MinMax(_, _) => {
+                let mut scales = stats.maxs.clone();
+                Zip::from(&mut scales).and(&stats.mins).apply(|max, min| {
+                    if abs_diff_eq!(*max - *min, F::zero()) {
+                        // if feature is constant then don't scale
+                        *max = F::one();
+                    } else {
+                        *max = F::one() / (*max - *min);
+                    }
+                });
+                (stats.mins.clone(), scales)
+            }
+            ScalingMethod::MaxAbs => {
+                let mut scales = stats.maxs.clone();
+                Zip::from(&mut scales).and(&stats.mins).apply(|max, min| {
+                    let norm_max = if -*min > *max { -*min } else { *max };
+                    if abs_diff_eq!(norm_max, F::zero()) {
+                        // if feature is constant at zero then don't scale
+                        *max = F::one();
+                    } else {
+                        *max = F::one() / norm_max;
+                    }
+                });
+                (Array1::zeros(stats.means.len()), scales)
+            }
+        };
 
-        let offsets = Array1::zeros(records.dim().1);
-        Ok(Self {
-            offsets,
-            scales,
-            method: ScalingMethod::MaxAbs,
-        })
+        self.offsets = offsets;
+        self.scales = scales;
     }
 
     /// Array of size `n_features` that contains the offset that will be subtracted to each feature
@@ -292,7 +459,7 @@ mod tests {
     use crate::linear_scaling::LinearScaler;
     use approx::assert_abs_diff_eq;
     use linfa::dataset::DatasetBase;
-    use linfa::traits::{Fit, Transformer};
+    use linfa::traits::{Fit, IncrementalFit, Transformer};
     use ndarray::{array, Array2, Axis};
 
     #[test]
@@ -587,6 +754,53 @@ mod tests {
         let _transformed = scaler.transform(wrong_size);
     }
 
+    #[test]
+    fn test_partial_fit_equals_fit() {
+        let records = linfa_datasets::diabetes().records().to_owned();
+        let (first, second) = records.view().split_at(Axis(0), 200);
+
+        for method in vec![
+            ScalingMethod::Standard(true, true),
+            ScalingMethod::MinMax(0., 1.),
+            ScalingMethod::MaxAbs,
+        ] {
+            let params = LinearScaler::new(method);
+            let whole = params.fit(&DatasetBase::from(records.view())).unwrap();
+
+            let scaler = params.fit_with(None, &DatasetBase::from(first)).unwrap();
+            let scaler = params
+                .fit_with(Some(scaler), &DatasetBase::from(second))
+                .unwrap();
+
+            assert_eq!(scaler.n_samples_seen(), records.nrows());
+            assert_abs_diff_eq!(scaler.offsets(), whole.offsets(), epsilon = 1e-10);
+            assert_abs_diff_eq!(scaler.scales(), whole.scales(), epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_reset_and_refit() {
+        let first = array![[1., -1., 2.], [2., 0., 0.], [0., 1., -1.]];
+        let second = array![[10., 5., 3.], [20., 0., 4.]];
+
+        let mut scaler = LinearScaler::standard()
+            .fit(&DatasetBase::from(first.view()))
+            .unwrap();
+        scaler.reset();
+        assert_eq!(scaler.n_samples_seen(), 0);
+
+        scaler.partial_fit(&second).unwrap();
+        let refit = LinearScaler::standard()
+            .fit(&DatasetBase::from(second.view()))
+            .unwrap();
+        assert_abs_diff_eq!(scaler.offsets(), refit.offsets());
+        assert_abs_diff_eq!(scaler.scales(), refit.scales());
+
+        // chunks must have the same number of features
+        assert!(scaler.partial_fit(&array![[1., 2.]]).is_err());
+        assert!(scaler.partial_fit(&Array2::zeros((0, 3))).is_err());
+    }
+
     #[test]
     #[should_panic]
     fn test_min_max_wrong_range() {
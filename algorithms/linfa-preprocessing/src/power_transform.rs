@@ -0,0 +1,424 @@
+//! Power transformations
+//!
+//! Power transformations are a family of monotonic, parametric transformations that map skewed
+//! features to features which are approximately Gaussian distributed. This can help models which
+//! assume normally distributed inputs, for example linear models or Gaussian mixtures. The
+//! parameter `lambda` of each feature is estimated by maximum likelihood.
+
+use crate::error::{Error, Result};
+use linfa::dataset::{AsTargets, DatasetBase, Float};
+use linfa::traits::{Fit, Transformer};
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2, Zip};
+
+/// Bounds of the interval in which the parameter `lambda` is searched
+const LAMBDA_BOUND: f64 = 5.0;
+/// Number of golden section steps used to estimate `lambda`
+const MAX_ITERATIONS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Possible methods for the [PowerTransformer](struct.PowerTransformer.html)
+///
+/// * BoxCox: `(x^lambda - 1) / lambda`, only defined for strictly positive data
+/// * YeoJohnson: a generalization of the Box-Cox transformation which also handles zero and negative values
+pub enum PowerMethod {
+    BoxCox,
+    YeoJohnson,
+}
+
+impl std::fmt::Display for PowerMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PowerMethod::BoxCox => write!(f, "Box-Cox"),
+            PowerMethod::YeoJohnson => write!(f, "Yeo-Johnson"),
+        }
+    }
+}
+
+impl PowerMethod {
+    fn transform<F: Float>(&self, x: F, lambda: F) -> F {
+        let eps = F::cast(1e-8);
+        let two = F::cast(2.);
+        match self {
+            PowerMethod::BoxCox if lambda.abs() < eps => x.ln(),
+            PowerMethod::BoxCox => (x.powf(lambda) - F::one()) / lambda,
+            PowerMethod::YeoJohnson if x >= F::zero() => {
+                if lambda.abs() < eps {
+                    x.ln_1p()
+                } else {
+                    ((x + F::one()).powf(lambda) - F::one()) / lambda
+                }
+            }
+            PowerMethod::YeoJohnson => {
+                if (two - lambda).abs() < eps {
+                    -(-x).ln_1p()
+                } else {
+                    -((F::one() - x).powf(two - lambda) - F::one()) / (two - lambda)
+                }
+            }
+        }
+    }
+
+    fn inverse<F: Float>(&self, y: F, lambda: F) -> F {
+        let eps = F::cast(1e-8);
+        let two = F::cast(2.);
+        match self {
+            PowerMethod::BoxCox if lambda.abs() < eps => y.exp(),
+            PowerMethod::BoxCox => (lambda * y + F::one()).powf(lambda.recip()),
+            PowerMethod::YeoJohnson if y >= F::zero() => {
+                if lambda.abs() < eps {
+                    y.exp_m1()
+                } else {
+                    (lambda * y + F::one()).powf(lambda.recip()) - F::one()
+                }
+            }
+            PowerMethod::YeoJohnson => {
+                if (two - lambda).abs() < eps {
+                    -(-y).exp_m1()
+                } else {
+                    F::one() - (F::one() - (two - lambda) * y).powf((two - lambda).recip())
+                }
+            }
+        }
+    }
+
+    /// Profile log-likelihood of `lambda` under the assumption that the transformed feature is
+    /// normally distributed
+    fn log_likelihood<F: Float>(&self, x: ArrayView1<F>, lambda: F) -> F {
+        let n = F::cast(x.len());
+        let transformed = x.mapv(|x| self.transform(x, lambda));
+        let mean = transformed.sum() / n;
+        let var = transformed.mapv(|y| (y - mean) * (y - mean)).sum() / n;
+
+        let jacobian = match self {
+            PowerMethod::BoxCox => x.mapv(|x| x.ln()).sum(),
+            PowerMethod::YeoJohnson => x.mapv(|x| x.signum() * x.abs().ln_1p()).sum(),
+        };
+
+        let llf = (lambda - F::one()) * jacobian - n / F::cast(2.) * var.ln();
+        if llf.is_nan() {
+            F::neg_infinity()
+        } else {
+            llf
+        }
+    }
+
+    /// Estimates `lambda` by maximizing the log-likelihood with a golden section search
+    fn estimate_lambda<F: Float>(&self, x: ArrayView1<F>) -> F {
+        let ratio = F::cast((5f64.sqrt() - 1.) / 2.);
+        let (mut lower, mut upper) = (F::cast(-LAMBDA_BOUND), F::cast(LAMBDA_BOUND));
+
+        let mut left = upper - ratio * (upper - lower);
+        let mut right = lower + ratio * (upper - lower);
+        let mut llf_left = self.log_likelihood(x, left);
+        let mut llf_right = self.log_likelihood(x, right);
+
+        for _ in 0..MAX_ITERATIONS {
+            if llf_left > llf_right {
+                upper = right;
+                right = left;
+                llf_right = llf_left;
+                left = upper - ratio * (upper - lower);
+                llf_left = self.log_likelihood(x, left);
+            } else {
+                lower = left;
+                left = right;
+                llf_left = llf_right;
+                right = lower + ratio * (upper - lower);
+                llf_right = self.log_likelihood(x, right);
+            }
+        }
+
+        (lower + upper) / F::cast(2.)
+    }
+}
+
+/// Power transformer: estimates the parameter `lambda` of each feature according to the specified
+/// [method](enum.PowerMethod.html), producing a [fitted power transformer](struct.FittedPowerTransformer.html)
+/// that can be used to transform different datasets using the same parameters.
+///
+/// By default the transformed features are standardized to zero mean and unit variance.
+///
+/// ### Example
+///
+/// ```rust
+/// use linfa::traits::{Fit, Transformer};
+/// use linfa_preprocessing::power_transform::PowerTransformer;
+///
+/// // Load dataset
+/// let dataset = linfa_datasets::diabetes();
+/// // Estimate the parameters of the transformation
+/// let transformer = PowerTransformer::yeo_johnson().fit(&dataset).unwrap();
+/// // transform dataset according to the estimated parameters
+/// let dataset = transformer.transform(dataset);
+/// ```
+pub struct PowerTransformer {
+    method: PowerMethod,
+    standardize: bool,
+}
+
+impl PowerTransformer {
+    /// Initializes the transformer with the specified method and standardization of the output
+    pub fn new(method: PowerMethod) -> Self {
+        Self {
+            method,
+            standardize: true,
+        }
+    }
+
+    /// Initializes a Box-Cox transformer
+    pub fn box_cox() -> Self {
+        Self::new(PowerMethod::BoxCox)
+    }
+
+    /// Initializes a Yeo-Johnson transformer
+    pub fn yeo_johnson() -> Self {
+        Self::new(PowerMethod::YeoJohnson)
+    }
+
+    /// Setter for the transformer method
+    pub fn method(mut self, method: PowerMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Whether the transformed features are scaled to zero mean and unit variance
+    pub fn standardize(mut self, standardize: bool) -> Self {
+        self.standardize = standardize;
+        self
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets> Fit<ArrayBase<D, Ix2>, T, Error>
+    for PowerTransformer
+{
+    type Object = FittedPowerTransformer<F>;
+
+    /// Estimates the parameter `lambda` of each feature. Will return an error if the dataset
+    /// does not contain any samples or if the Box-Cox method is used on data which is not
+    /// strictly positive.
+    fn fit(&self, x: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        let records = x.records();
+        if records.nrows() == 0 {
+            return Err(Error::NotEnoughSamples);
+        }
+        if records.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidFloat);
+        }
+        if self.method == PowerMethod::BoxCox && records.iter().any(|x| *x <= F::zero()) {
+            return Err(Error::NonPositiveBoxCox);
+        }
+
+        let lambdas = records
+            .gencolumns()
+            .into_iter()
+            .map(|col| self.method.estimate_lambda(col))
+            .collect::<Array1<F>>();
+
+        let mut transformer = FittedPowerTransformer {
+            method: self.method,
+            lambdas,
+            offsets: Array1::zeros(records.ncols()),
+            scales: Array1::ones(records.ncols()),
+        };
+
+        if self.standardize {
+            let transformed = transformer.transform(records.to_owned());
+            // safe unwrap because of the above zero samples check
+            let offsets = transformed.mean_axis(Axis(0)).unwrap();
+            let scales = transformed.std_axis(Axis(0), F::zero()).mapv(|s| {
+                if s.abs() < F::epsilon() {
+                    // if feature is constant then don't scale
+                    F::one()
+                } else {
+                    F::one() / s
+                }
+            });
+            transformer.offsets = offsets;
+            transformer.scales = scales;
+        }
+
+        Ok(transformer)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The result of fitting a [power transformer](struct.PowerTransformer.html).
+/// Transforms datasets with the parameters estimated during fitting.
+pub struct FittedPowerTransformer<F: Float> {
+    method: PowerMethod,
+    lambdas: Array1<F>,
+    offsets: Array1<F>,
+    scales: Array1<F>,
+}
+
+impl<F: Float> FittedPowerTransformer<F> {
+    /// Array of size `n_features` that contains the estimated `lambda` of each feature
+    pub fn lambdas(&self) -> &Array1<F> {
+        &self.lambdas
+    }
+
+    /// Array of size `n_features` that contains the offset subtracted from each transformed feature
+    ///
+    /// All offsets are zero if the transformer does not standardize its output.
+    pub fn offsets(&self) -> &Array1<F> {
+        &self.offsets
+    }
+
+    /// Array of size `n_features` that contains the scale applied to each transformed feature
+    ///
+    /// All scales are one if the transformer does not standardize its output.
+    pub fn scales(&self) -> &Array1<F> {
+        &self.scales
+    }
+
+    /// Returns the method used for fitting
+    pub fn method(&self) -> PowerMethod {
+        self.method
+    }
+
+    /// Maps transformed data back to the original feature space.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    pub fn inverse_transform(&self, x: Array2<F>) -> Array2<F> {
+        let mut x = x;
+        Zip::from(x.gencolumns_mut())
+            .and(&self.lambdas)
+            .and(&self.offsets)
+            .and(&self.scales)
+            .apply(|mut col, &lambda, &offset, &scale| {
+                col.mapv_inplace(|y| self.method.inverse(y / scale + offset, lambda));
+            });
+        x
+    }
+}
+
+impl<F: Float> Transformer<Array2<F>, Array2<F>> for FittedPowerTransformer<F> {
+    /// Transforms an array of size (nsamples, nfeatures) with the estimated `lambdas`.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    ///
+    /// Values outside of the domain of the transformation, i.e. non-positive values for Box-Cox, are mapped to NaN.
+    fn transform(&self, x: Array2<F>) -> Array2<F> {
+        let mut x = x;
+        Zip::from(x.gencolumns_mut())
+            .and(&self.lambdas)
+            .and(&self.offsets)
+            .and(&self.scales)
+            .apply(|mut col, &lambda, &offset, &scale| {
+                col.mapv_inplace(|x| (self.method.transform(x, lambda) - offset) * scale);
+            });
+        x
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>>
+    for FittedPowerTransformer<F>
+{
+    /// Substitutes the records of the dataset with their transformed version.
+    /// Panics if the shape of the records is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let feature_names = x.feature_names();
+        let (records, targets, weights) = (x.records, x.targets, x.weights);
+        let records = self.transform(records.to_owned());
+        DatasetBase::new(records, targets)
+            .with_weights(weights)
+            .with_feature_names(feature_names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PowerMethod, PowerTransformer};
+    use crate::error::Error;
+    use approx::assert_abs_diff_eq;
+    use linfa::dataset::DatasetBase;
+    use linfa::traits::{Fit, Transformer};
+    use ndarray::{array, Array2, ArrayView1, Axis};
+    use ndarray_rand::{rand::rngs::SmallRng, rand::SeedableRng, rand_distr::LogNormal, RandomExt};
+
+    fn skewness(x: ArrayView1<f64>) -> f64 {
+        let n = x.len() as f64;
+        let mean = x.sum() / n;
+        let m2 = x.mapv(|x| (x - mean).powi(2)).sum() / n;
+        let m3 = x.mapv(|x| (x - mean).powi(3)).sum() / n;
+        m3 / m2.powf(1.5)
+    }
+
+    #[test]
+    fn test_log_normal_skewness() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = Array2::random_using((2000, 1), LogNormal::new(0., 1.).unwrap(), &mut rng);
+        assert!(skewness(records.column(0)) > 2.);
+
+        for method in &[PowerMethod::BoxCox, PowerMethod::YeoJohnson] {
+            let dataset: DatasetBase<_, _> = records.clone().into();
+            let transformer = PowerTransformer::new(*method).fit(&dataset).unwrap();
+            let transformed = transformer.transform(dataset);
+
+            assert!(skewness(transformed.records().column(0)).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_box_cox_lambda() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = Array2::random_using((2000, 1), LogNormal::new(1., 0.5).unwrap(), &mut rng);
+
+        // the logarithm maps log-normal data to normal data
+        let dataset = records.into();
+        let transformer = PowerTransformer::box_cox().fit(&dataset).unwrap();
+        assert_abs_diff_eq!(transformer.lambdas()[0], 0., epsilon = 0.1);
+    }
+
+    #[test]
+    fn test_standardize() {
+        let dataset = array![[1., -3.], [2., 0.], [4., 1.5], [10., 2.], [25., 8.]].into();
+        let transformer = PowerTransformer::yeo_johnson().fit(&dataset).unwrap();
+        let transformed = transformer.transform(dataset);
+
+        let means = transformed.records().mean_axis(Axis(0)).unwrap();
+        let std_devs = transformed.records().std_axis(Axis(0), 0.);
+        assert_abs_diff_eq!(means, array![0., 0.], epsilon = 1e-10);
+        assert_abs_diff_eq!(std_devs, array![1., 1.], epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_inverse_transform() {
+        let records = array![[1., -3.], [2., 0.], [4., 1.5], [10., 2.], [25., 8.]];
+
+        let dataset = records.clone().into();
+        let transformer = PowerTransformer::yeo_johnson().fit(&dataset).unwrap();
+        let transformed = transformer.transform(records.clone());
+        assert_abs_diff_eq!(
+            transformer.inverse_transform(transformed),
+            records,
+            epsilon = 1e-8
+        );
+
+        let records = records.mapv(f64::abs) + 0.5;
+        let dataset = records.clone().into();
+        let transformer = PowerTransformer::box_cox()
+            .standardize(false)
+            .fit(&dataset)
+            .unwrap();
+        let transformed = transformer.transform(records.clone());
+        assert_abs_diff_eq!(
+            transformer.inverse_transform(transformed),
+            records,
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_box_cox_non_positive() {
+        let dataset = array![[1.], [0.], [2.]].into();
+        let res = PowerTransformer::box_cox().fit(&dataset);
+        assert!(matches!(res, Err(Error::NonPositiveBoxCox)));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let dataset: DatasetBase<Array2<f64>, _> = Array2::zeros((0, 2)).into();
+        let res = PowerTransformer::yeo_johnson().fit(&dataset);
+        assert!(matches!(res, Err(Error::NotEnoughSamples)));
+    }
+}
@@ -0,0 +1,433 @@
+//! Quantile transformation
+//!
+//! The quantile transformation maps each feature with its empirical cumulative distribution
+//! function to a uniform distribution and optionally further to a normal distribution. Since only
+//! the ranks of the samples matter, the transformation is robust to outliers and works for
+//! arbitrarily distributed features. It is non-linear and may distort linear correlations between
+//! features.
+
+use crate::error::{Error, Result};
+use linfa::dataset::{AsTargets, DatasetBase, Float};
+use linfa::distribution::{normal_cdf, normal_quantile};
+use linfa::traits::{Fit, Transformer};
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2, Zip};
+
+/// Probabilities are clipped to `[BOUNDS_THRESHOLD, 1 - BOUNDS_THRESHOLD]` before being mapped
+/// to the normal distribution, to avoid infinite values
+const BOUNDS_THRESHOLD: f64 = 1e-7;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Possible output distributions of the [QuantileTransformer](struct.QuantileTransformer.html)
+///
+/// * Uniform: transformed features are uniformly distributed on `[0, 1]`
+/// * Normal: transformed features follow a standard normal distribution
+pub enum OutputDistribution {
+    Uniform,
+    Normal,
+}
+
+impl std::fmt::Display for OutputDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputDistribution::Uniform => write!(f, "Uniform"),
+            OutputDistribution::Normal => write!(f, "Normal"),
+        }
+    }
+}
+
+/// Quantile transformer: learns the quantiles of each feature from a dataset, producing a
+/// [fitted quantile transformer](struct.FittedQuantileTransformer.html) that maps features to the
+/// specified [output distribution](enum.OutputDistribution.html).
+///
+/// The number of quantiles defaults to 1000 and is limited by the number of samples.
+///
+/// ### Example
+///
+/// ```rust
+/// use linfa::traits::{Fit, Transformer};
+/// use linfa_preprocessing::quantile_transform::QuantileTransformer;
+///
+/// // Load dataset
+/// let dataset = linfa_datasets::diabetes();
+/// // Learn the quantiles of each feature
+/// let transformer = QuantileTransformer::normal().n_quantiles(100).fit(&dataset).unwrap();
+/// // transform dataset according to the learned quantiles
+/// let dataset = transformer.transform(dataset);
+/// ```
+pub struct QuantileTransformer {
+    n_quantiles: usize,
+    output_distribution: OutputDistribution,
+}
+
+impl Default for QuantileTransformer {
+    fn default() -> Self {
+        Self::new(OutputDistribution::Uniform)
+    }
+}
+
+impl QuantileTransformer {
+    /// Initializes the transformer with the specified output distribution
+    pub fn new(output_distribution: OutputDistribution) -> Self {
+        Self {
+            n_quantiles: 1000,
+            output_distribution,
+        }
+    }
+
+    /// Initializes a transformer with a uniform output distribution
+    pub fn uniform() -> Self {
+        Self::new(OutputDistribution::Uniform)
+    }
+
+    /// Initializes a transformer with a normal output distribution
+    pub fn normal() -> Self {
+        Self::new(OutputDistribution::Normal)
+    }
+
+    /// Number of quantiles used to approximate the cumulative distribution function. Has to be
+    /// at least two, otherwise fitting will return an error.
+    pub fn n_quantiles(mut self, n_quantiles: usize) -> Self {
+        self.n_quantiles = n_quantiles;
+        self
+    }
+
+    /// Setter for the output distribution
+    pub fn output_distribution(mut self, output_distribution: OutputDistribution) -> Self {
+        self.output_distribution = output_distribution;
+        self
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets> Fit<ArrayBase<D, Ix2>, T, Error>
+    for QuantileTransformer
+{
+    type Object = FittedQuantileTransformer<F>;
+
+    /// Computes the quantiles of each feature. Will return an error if the dataset contains less
+    /// than two samples, contains values which are not finite or if less than two quantiles are
+    /// requested.
+    fn fit(&self, x: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object> {
+        if self.n_quantiles < 2 {
+            return Err(Error::InvalidNQuantiles(self.n_quantiles));
+        }
+        let records = x.records();
+        // the number of quantiles is limited by the number of samples and at least two are
+        // necessary to interpolate between them
+        if records.nrows() < 2 {
+            return Err(Error::NotEnoughSamples);
+        }
+        if records.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidFloat);
+        }
+
+        let n_quantiles = self.n_quantiles.min(records.nrows());
+        let references = Array1::linspace(F::zero(), F::one(), n_quantiles);
+
+        let mut quantiles = Array2::zeros((n_quantiles, records.ncols()));
+        Zip::from(quantiles.gencolumns_mut())
+            .and(records.gencolumns())
+            .apply(|mut quantiles, col| {
+                let mut sorted = col.to_vec();
+                // safe unwrap because all values are finite
+                sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+                let last = F::cast(sorted.len() - 1);
+                for (q, &r) in quantiles.iter_mut().zip(references.iter()) {
+                    let pos = r * last;
+                    let lower = pos.floor();
+                    let idx: usize = lower.as_();
+                    *q = if idx + 1 < sorted.len() {
+                        sorted[idx] + (pos - lower) * (sorted[idx + 1] - sorted[idx])
+                    } else {
+                        sorted[idx]
+                    };
+                }
+            });
+
+        Ok(FittedQuantileTransformer {
+            output_distribution: self.output_distribution,
+            quantiles,
+            references,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The result of fitting a [quantile transformer](struct.QuantileTransformer.html).
+/// Transforms datasets with the quantiles learned during fitting.
+///
+/// Values outside of the range seen during fitting are clipped to the bounds of the output
+/// distribution.
+pub struct FittedQuantileTransformer<F: Float> {
+    output_distribution: OutputDistribution,
+    quantiles: Array2<F>,
+    references: Array1<F>,
+}
+
+impl<F: Float> FittedQuantileTransformer<F> {
+    /// Array of size `(n_quantiles, n_features)` that contains the learned quantiles of each feature
+    pub fn quantiles(&self) -> &Array2<F> {
+        &self.quantiles
+    }
+
+    /// Array of size `n_quantiles` that contains the probabilities of the learned quantiles
+    pub fn references(&self) -> &Array1<F> {
+        &self.references
+    }
+
+    /// Returns the output distribution of the transformer
+    pub fn output_distribution(&self) -> OutputDistribution {
+        self.output_distribution
+    }
+
+    /// Maps transformed data back to the original feature space.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    pub fn inverse_transform(&self, x: Array2<F>) -> Array2<F> {
+        let mut x = x;
+        let last = F::cast(self.references.len() - 1);
+        Zip::from(x.gencolumns_mut())
+            .and(self.quantiles.gencolumns())
+            .apply(|mut col, quantiles| {
+                col.mapv_inplace(|y| {
+                    if y.is_nan() {
+                        return y;
+                    }
+
+                    let p = match self.output_distribution {
+                        OutputDistribution::Uniform => y,
+                        OutputDistribution::Normal => F::cast(normal_cdf(y.to_f64().unwrap())),
+                    };
+                    if p <= F::zero() {
+                        return quantiles[0];
+                    } else if p >= F::one() {
+                        return quantiles[quantiles.len() - 1];
+                    }
+
+                    let pos = p * last;
+                    let lower = pos.floor();
+                    let idx: usize = lower.as_();
+                    // guard against rounding for probabilities close to one
+                    let idx = idx.min(quantiles.len() - 2);
+                    let lower = F::cast(idx);
+                    quantiles[idx] + (pos - lower) * (quantiles[idx + 1] - quantiles[idx])
+                });
+            });
+        x
+    }
+
+    /// Maps a single value of a feature to its empirical cumulative distribution, NaN values are
+    /// passed through
+    fn cdf(&self, x: F, quantiles: ArrayView1<F>) -> F {
+        // NaN fails every comparison and would end up left of the first quantile
+        if x.is_nan() {
+            return x;
+        }
+
+        let n = quantiles.len();
+        if x <= quantiles[0] {
+            return F::zero();
+        } else if x >= quantiles[n - 1] {
+            return F::one();
+        }
+
+        // the quantiles can contain repeated values, interpolate in both directions and average
+        let upper = partition_point(quantiles, |q| q <= x);
+        let forward = self.interpolate(x, quantiles, upper);
+        let first_equal = partition_point(quantiles, |q| q < x);
+        let backward = if quantiles[first_equal] == x {
+            self.references[first_equal]
+        } else {
+            forward
+        };
+
+        (forward + backward) / F::cast(2.)
+    }
+
+    fn interpolate(&self, x: F, quantiles: ArrayView1<F>, upper: usize) -> F {
+        let lower = upper - 1;
+        let (q_lower, q_upper) = (quantiles[lower], quantiles[upper]);
+        let (r_lower, r_upper) = (self.references[lower], self.references[upper]);
+        r_lower + (x - q_lower) / (q_upper - q_lower) * (r_upper - r_lower)
+    }
+}
+
+impl<F: Float> Transformer<Array2<F>, Array2<F>> for FittedQuantileTransformer<F> {
+    /// Transforms an array of size (nsamples, nfeatures) with the learned quantiles.
+    /// Panics if the shape of the input array is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: Array2<F>) -> Array2<F> {
+        let mut x = x;
+        let threshold = F::cast(BOUNDS_THRESHOLD);
+        Zip::from(x.gencolumns_mut())
+            .and(self.quantiles.gencolumns())
+            .apply(|mut col, quantiles| {
+                col.mapv_inplace(|x| {
+                    let p = self.cdf(x, quantiles);
+                    match self.output_distribution {
+                        OutputDistribution::Uniform => p,
+                        // clipping would turn NaN into a finite value
+                        OutputDistribution::Normal if p.is_nan() => p,
+                        OutputDistribution::Normal => {
+                            let p = p.max(threshold).min(F::one() - threshold);
+                            F::cast(normal_quantile(p.to_f64().unwrap()))
+                        }
+                    }
+                });
+            });
+        x
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T: AsTargets>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>>
+    for FittedQuantileTransformer<F>
+{
+    /// Substitutes the records of the dataset with their transformed version.
+    /// Panics if the shape of the records is not compatible with the shape of the dataset used for fitting.
+    fn transform(&self, x: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let feature_names = x.feature_names();
+        let (records, targets, weights) = (x.records, x.targets, x.weights);
+        let records = self.transform(records.to_owned());
+        DatasetBase::new(records, targets)
+            .with_weights(weights)
+            .with_feature_names(feature_names)
+    }
+}
+
+/// Index of the first element of a sorted array for which `pred` is false
+fn partition_point<F: Float, P: Fn(F) -> bool>(values: ArrayView1<F>, pred: P) -> usize {
+    let (mut lower, mut upper) = (0, values.len());
+    while lower < upper {
+        let mid = (lower + upper) / 2;
+        if pred(values[mid]) {
+            lower = mid + 1;
+        } else {
+            upper = mid;
+        }
+    }
+    lower
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutputDistribution, QuantileTransformer};
+    use crate::error::Error;
+    use approx::assert_abs_diff_eq;
+    use linfa::dataset::DatasetBase;
+    use linfa::traits::{Fit, Transformer};
+    use ndarray::{array, Array1, Array2, Axis};
+    use ndarray_rand::{rand::rngs::SmallRng, rand::SeedableRng, rand_distr::LogNormal, RandomExt};
+
+    #[test]
+    fn test_uniform_output() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let mut records =
+            Array2::random_using((5000, 2), LogNormal::new(0., 1.).unwrap(), &mut rng);
+        // add a few outliers
+        records[[0, 0]] = 1e6;
+        records[[1, 1]] = -1e6;
+
+        let dataset: DatasetBase<_, _> = records.into();
+        let transformer = QuantileTransformer::uniform().fit(&dataset).unwrap();
+        assert_eq!(transformer.quantiles().dim(), (1000, 2));
+
+        let transformed = transformer.transform(dataset);
+        for col in transformed.records().gencolumns() {
+            assert!(col.iter().all(|x| (0. ..=1.).contains(x)));
+
+            // every tenth of the unit interval contains a tenth of the samples
+            let mut counts = Array1::<f64>::zeros(10);
+            for x in col.iter() {
+                counts[((x * 10.) as usize).min(9)] += 1.;
+            }
+            assert_abs_diff_eq!(counts / 5000., Array1::from_elem(10, 0.1), epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn test_normal_output() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = Array2::random_using((5000, 1), LogNormal::new(0., 1.).unwrap(), &mut rng);
+
+        let dataset: DatasetBase<_, _> = records.into();
+        let transformer = QuantileTransformer::normal().fit(&dataset).unwrap();
+        let transformed = transformer.transform(dataset);
+
+        let mean = transformed.records().mean_axis(Axis(0)).unwrap();
+        let std = transformed.records().std_axis(Axis(0), 0.);
+        assert_abs_diff_eq!(mean, array![0.], epsilon = 0.05);
+        assert_abs_diff_eq!(std, array![1.], epsilon = 0.05);
+    }
+
+    #[test]
+    fn test_clip_out_of_range() {
+        let dataset = array![[1.], [2.], [3.], [4.], [5.]].into();
+        let transformer = QuantileTransformer::uniform().fit(&dataset).unwrap();
+        let transformed = transformer.transform(array![[-10.], [1.5], [3.], [100.]]);
+        assert_abs_diff_eq!(transformed, array![[0.], [0.125], [0.5], [1.]]);
+    }
+
+    #[test]
+    fn test_repeated_values() {
+        let dataset = array![[1.], [2.], [2.], [2.], [3.]].into();
+        let transformer = QuantileTransformer::uniform().fit(&dataset).unwrap();
+        let transformed = transformer.transform(array![[2.]]);
+        assert_abs_diff_eq!(transformed, array![[0.5]]);
+    }
+
+    #[test]
+    fn test_inverse_transform() {
+        let records = array![[1., -3.], [2., 0.], [4., 1.5], [10., 2.], [25., 8.]];
+        for output_distribution in &[OutputDistribution::Uniform, OutputDistribution::Normal] {
+            let dataset = records.clone().into();
+            let transformer = QuantileTransformer::new(*output_distribution)
+                .fit(&dataset)
+                .unwrap();
+            let query = array![[1.5, -1.], [7., 1.75], [20., 5.]];
+            let transformed = transformer.transform(query.clone());
+            assert_abs_diff_eq!(
+                transformer.inverse_transform(transformed),
+                query,
+                epsilon = 1e-4
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_n_quantiles() {
+        let dataset = array![[1.], [2.]].into();
+        let res = QuantileTransformer::uniform().n_quantiles(1).fit(&dataset);
+        assert!(matches!(res, Err(Error::InvalidNQuantiles(1))));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let dataset: DatasetBase<Array2<f64>, _> = Array2::zeros((0, 2)).into();
+        let res = QuantileTransformer::uniform().fit(&dataset);
+        assert!(matches!(res, Err(Error::NotEnoughSamples)));
+    }
+
+    #[test]
+    fn test_single_sample() {
+        // a single sample would result in a single quantile
+        let dataset = array![[1., 2.]].into();
+        let res = QuantileTransformer::uniform().n_quantiles(10).fit(&dataset);
+        assert!(matches!(res, Err(Error::NotEnoughSamples)));
+    }
+
+    #[test]
+    fn test_nan_passthrough() {
+        let dataset = array![[1.], [2.], [3.]].into();
+        for output_distribution in &[OutputDistribution::Uniform, OutputDistribution::Normal] {
+            let transformer = QuantileTransformer::new(*output_distribution)
+                .fit(&dataset)
+                .unwrap();
+            let transformed = transformer.transform(array![[f64::NAN], [2.]]);
+            assert!(transformed[[0, 0]].is_nan());
+            assert!(transformed[[1, 0]].is_finite());
+
+            let inverse = transformer.inverse_transform(transformed);
+            assert!(inverse[[0, 0]].is_nan());
+            assert_abs_diff_eq!(inverse[[1, 0]], 2., epsilon = 1e-4);
+        }
+    }
+}
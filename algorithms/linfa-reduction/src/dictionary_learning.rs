@@ -0,0 +1,477 @@
+//! Dictionary Learning
+//!
+//! Dictionary learning finds a set of atoms, such that every sample is approximated by a sparse
+//! combination of them. The number of atoms may exceed the number of features, in which case the
+//! dictionary is overcomplete. The records `X` are factorized into sparse codes `C` and a
+//! dictionary `D` with unit-norm atoms by minimizing
+//!
+//! ```text
+//! 0.5 * ||X - C D||^2 + alpha * ||C||_1
+//! ```
+//!
+//! The problem is solved by alternating between the lasso problems of the codes, solved with the
+//! coordinate descent of `linfa-elasticnet`, and a block update of the atoms. The atoms are
+//! initialized with the mixing directions of the independent components found by FastICA on the
+//! whitened records. If the dictionary has more atoms than the records have features, the
+//! remaining atoms are initialized with randomly chosen samples. The records are not centered.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::{Fit, Predict};
+//! use linfa_reduction::DictionaryLearning;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // learn an overcomplete dictionary of six atoms
+//! let dict = DictionaryLearning::params(6)
+//!     .alpha(1.0)
+//!     .fit(&dataset).unwrap();
+//!
+//! // sparse codes of the samples
+//! let codes = dict.predict(dataset.records());
+//! ```
+//!
+use crate::error::{Error, Result};
+use linfa_elasticnet::ElasticNet;
+use linfa_ica::fast_ica::FastIca;
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix2};
+use ndarray_rand::rand::{seq::index, Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use linfa::{
+    dataset::Records,
+    traits::{Fit, PredictRef, Transformer},
+    DatasetBase, Float,
+};
+
+/// Maximal number of coordinate descent sweeps per sparse code
+const MAX_LASSO_ITERATIONS: u32 = 100;
+
+/// Dictionary learning parameters
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct DictionaryLearningParams<R> {
+    n_components: usize,
+    alpha: f64,
+    max_iterations: usize,
+    tolerance: f64,
+    rng: R,
+}
+
+impl<R: Rng + Clone> DictionaryLearningParams<R> {
+    /// Set the L1 penalty of the codes
+    ///
+    /// Larger values lead to sparser codes. Defaults to `1.0`.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+
+        self
+    }
+
+    /// Set the maximal number of alternating updates of codes and dictionary
+    ///
+    /// Defaults to `1000`.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    /// Set the tolerance of the relative change of the objective
+    ///
+    /// Defaults to `1e-8`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    /// Set the random number generator used to initialize the atoms
+    pub fn with_rng<R2: Rng + Clone>(self, rng: R2) -> DictionaryLearningParams<R2> {
+        DictionaryLearningParams {
+            n_components: self.n_components,
+            alpha: self.alpha,
+            max_iterations: self.max_iterations,
+            tolerance: self.tolerance,
+            rng,
+        }
+    }
+
+    /// Validates the parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.n_components == 0 {
+            return Err(linfa::Error::Parameters(
+                "number of components should be larger than zero".into(),
+            )
+            .into());
+        }
+        if !self.alpha.is_finite() || self.alpha < 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "alpha should be positive and finite, but is {}",
+                self.alpha
+            ))
+            .into());
+        }
+        if self.max_iterations == 0 {
+            return Err(linfa::Error::Parameters(
+                "maximal number of iterations should be larger than zero".into(),
+            )
+            .into());
+        }
+        if self.tolerance.is_nan() || self.tolerance <= 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "tolerance should be positive, but is {}",
+                self.tolerance
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn a dictionary given a dataset
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with records in N dimensions, at least as many as atoms
+///
+/// # Returns
+///
+/// The learned dictionary, which encodes samples sparsely
+impl<F: Float, T, D: Data<Elem = F>, R: Rng + Clone> Fit<ArrayBase<D, Ix2>, T, Error>
+    for DictionaryLearningParams<R>
+{
+    type Object = DictionaryLearning<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<DictionaryLearning<F>> {
+        self.validate()?;
+
+        let x = dataset.records();
+        x.check_shape(self.n_components)?;
+
+        let alpha = F::cast(self.alpha);
+        let tolerance = F::cast(self.tolerance);
+        let mut rng = self.rng.clone();
+        let mut dictionary = initial_dictionary(x, self.n_components, &mut rng)?;
+
+        let mut codes = Array2::zeros((x.nsamples(), self.n_components));
+        let mut objective: Option<F> = None;
+        for _ in 0..self.max_iterations {
+            sparse_code(x.view(), &dictionary, &mut codes, alpha, tolerance)?;
+            let residual = update_dictionary(x.view(), &codes, &mut dictionary);
+
+            let new_objective = F::cast(0.5) * residual.mapv(|x| x * x).sum()
+                + alpha * codes.mapv(|x| x.abs()).sum();
+            let converged = objective
+                .map(|objective| (objective - new_objective).abs() <= tolerance * objective.abs())
+                .unwrap_or(false);
+            objective = Some(new_objective);
+
+            if converged {
+                break;
+            }
+        }
+
+        Ok(DictionaryLearning {
+            dictionary,
+            alpha,
+            tolerance,
+        })
+    }
+}
+
+/// Initial atoms with unit norm
+///
+/// The first atoms are the mixing directions of the independent components of the records. As
+/// FastICA projects the records onto their whitened principal components, the sources are
+/// uncorrelated with equal variance and the least squares mixing of the centered records is
+/// proportional to their product with the sources. Atoms which can't be found this way, because the
+/// dictionary is overcomplete or the records are rank-deficient, are randomly chosen samples.
+fn initial_dictionary<F: Float, D: Data<Elem = F>, R: Rng>(
+    x: &ArrayBase<D, Ix2>,
+    n_components: usize,
+    rng: &mut R,
+) -> Result<Array2<F>> {
+    let n_independent = n_components.min(x.nfeatures()).min(x.nsamples());
+    let ica = FastIca::new()
+        .ncomponents(n_independent)
+        .random_state(rng.gen())
+        .fit(&DatasetBase::from(x.view()))?;
+    let sources = ica.predict_ref(x);
+    let centered = x - &x.mean_axis(Axis(0)).unwrap();
+    let mixing = sources.t().dot(&centered);
+
+    let indices = index::sample(rng, x.nsamples(), n_components).into_vec();
+    let mut dictionary = x.select(Axis(0), &indices);
+    for (i, mut atom) in dictionary.genrows_mut().into_iter().enumerate() {
+        if i < n_independent && mixing.row(i).iter().all(|x| x.is_finite()) {
+            atom.assign(&mixing.row(i));
+        }
+
+        let norm = atom.dot(&atom).sqrt();
+        if norm > F::zero() {
+            atom /= norm;
+        }
+    }
+
+    Ok(dictionary)
+}
+
+/// Lasso problem `0.5 * ||x - D^T c||^2 + alpha * ||c||_1` of a single code, solved with the
+/// coordinate descent of the elastic net
+fn lasso_code<F: Float>(
+    record: ArrayView1<F>,
+    dictionary: &Array2<F>,
+    code: ArrayView1<F>,
+    alpha: F,
+    tolerance: F,
+) -> Result<Array1<F>> {
+    // the elastic net scales the squared error by the number of samples, which are the features
+    // of the record here
+    let (code, _) = ElasticNet::lasso()
+        .with_intercept(false)
+        .penalty(alpha / F::cast(dictionary.ncols()))
+        .tolerance(tolerance)
+        .max_iterations(MAX_LASSO_ITERATIONS)
+        .solve(dictionary.t(), record, code.to_owned())?;
+
+    Ok(code)
+}
+
+/// Update the sparse codes of all records, warm-started from the current codes
+fn sparse_code<F: Float>(
+    x: ArrayView2<F>,
+    dictionary: &Array2<F>,
+    codes: &mut Array2<F>,
+    alpha: F,
+    tolerance: F,
+) -> Result<()> {
+    for (record, mut code) in x.genrows().into_iter().zip(codes.genrows_mut()) {
+        let new_code = lasso_code(record, dictionary, code.view(), alpha, tolerance)?;
+        code.assign(&new_code);
+    }
+
+    Ok(())
+}
+
+/// Update the dictionary one atom at a time, keeping every atom at unit norm, and return the
+/// residual `X - C D`
+fn update_dictionary<F: Float>(
+    x: ArrayView2<F>,
+    codes: &Array2<F>,
+    dictionary: &mut Array2<F>,
+) -> Array2<F> {
+    let outer =
+        |a: ArrayView1<F>, b: ArrayView1<F>| a.insert_axis(Axis(1)).dot(&b.insert_axis(Axis(0)));
+
+    let mut residual = &x - &codes.dot(&*dictionary);
+    for (code, mut atom) in codes.gencolumns().into_iter().zip(dictionary.genrows_mut()) {
+        // an atom which is not used by any record keeps its direction
+        if code.dot(&code) <= F::zero() {
+            continue;
+        }
+
+        residual += &outer(code, atom.view());
+        let new_atom = residual.t().dot(&code);
+        let norm = new_atom.dot(&new_atom).sqrt();
+        if norm > F::zero() {
+            atom.assign(&(new_atom / norm));
+        }
+        residual -= &outer(code, atom.view());
+    }
+
+    residual
+}
+
+/// Learned dictionary
+///
+/// The dictionary contains one atom of unit norm per row. Predicting returns the sparse codes of
+/// the records, found with the same penalty as used during fitting.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct DictionaryLearning<F> {
+    dictionary: Array2<F>,
+    alpha: F,
+    tolerance: F,
+}
+
+impl DictionaryLearning<f64> {
+    /// Create default parameter set
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the number of atoms
+    pub fn params(n_components: usize) -> DictionaryLearningParams<Isaac64Rng> {
+        Self::params_with_rng(n_components, Isaac64Rng::seed_from_u64(42))
+    }
+
+    /// Create default parameter set with a random number generator
+    pub fn params_with_rng<R: Rng + Clone>(
+        n_components: usize,
+        rng: R,
+    ) -> DictionaryLearningParams<R> {
+        DictionaryLearningParams {
+            n_components,
+            alpha: 1.0,
+            max_iterations: 1000,
+            tolerance: 1e-8,
+            rng,
+        }
+    }
+}
+
+impl<F: Float> DictionaryLearning<F> {
+    /// Return the atoms with shape `(n_components, n_features)`
+    pub fn dictionary(&self) -> &Array2<F> {
+        &self.dictionary
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>>
+    for DictionaryLearning<F>
+{
+    /// Sparse codes of the records, with shape `(n_samples, n_components)`
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let mut codes = Array2::zeros((records.nrows(), self.dictionary.nrows()));
+        sparse_code(
+            records.view(),
+            &self.dictionary,
+            &mut codes,
+            self.alpha,
+            self.tolerance,
+        )
+        .expect("records should have the dimension of the atoms");
+
+        codes
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>>
+    for DictionaryLearning<F>
+{
+    fn transform(&self, ds: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let DatasetBase {
+            records,
+            targets,
+            weights,
+            ..
+        } = ds;
+
+        let new_records = self.predict_ref(&records);
+
+        DatasetBase::new(new_records, targets).with_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linfa::{traits::Predict, Dataset};
+    use ndarray_rand::{
+        rand_distr::{StandardNormal, Uniform},
+        RandomExt,
+    };
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    /// Samples which are combinations of one or two of four random atoms in eight dimensions
+    fn sparse_combinations(rng: &mut SmallRng) -> (Array2<f64>, Array2<f64>) {
+        let mut atoms = Array2::<f64>::random_using((4, 8), StandardNormal, rng);
+        for mut atom in atoms.genrows_mut() {
+            atom /= atom.dot(&atom).sqrt();
+        }
+
+        let mut codes = Array2::zeros((200, 4));
+        for mut code in codes.genrows_mut() {
+            for _ in 0..rng.gen_range(1..=2) {
+                let sign = if rng.gen::<bool>() { 1. } else { -1. };
+                code[rng.gen_range(0..4)] = sign * rng.gen_range(1.0..3.0);
+            }
+        }
+        let noise = Array2::random_using((200, 8), Uniform::new(-0.01, 0.01), rng);
+
+        (codes.dot(&atoms) + noise, atoms)
+    }
+
+    #[test]
+    fn recovers_known_atoms() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let (records, atoms) = sparse_combinations(&mut rng);
+
+        let dict = DictionaryLearning::params_with_rng(6, rng)
+            .fit(&Dataset::from(records))
+            .unwrap();
+        assert_eq!(dict.dictionary().dim(), (6, 8));
+
+        // every atom is matched by a learned atom, up to its sign
+        let similarity = atoms.dot(&dict.dictionary().t()).mapv(f64::abs);
+        for row in similarity.genrows() {
+            assert!(row.fold(0., |a: f64, b| a.max(*b)) > 0.95);
+        }
+    }
+
+    #[test]
+    fn codes_are_sparse() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let (records, _) = sparse_combinations(&mut rng);
+
+        let dict = DictionaryLearning::params_with_rng(6, rng)
+            .alpha(0.1)
+            .fit(&Dataset::from(records.clone()))
+            .unwrap();
+        let codes = dict.predict(&records);
+        assert_eq!(codes.dim(), (200, 6));
+
+        // only a few of the atoms are active and the records are reconstructed
+        let n_active = codes.iter().filter(|x| **x != 0.).count();
+        assert!(n_active <= 3 * 200);
+        let error = (codes.dot(dict.dictionary()) - &records)
+            .mapv(|x| x * x)
+            .sum();
+        assert!(error < 0.01 * records.mapv(|x| x * x).sum());
+
+        // a larger penalty results in sparser codes
+        let sparse_dict = DictionaryLearning::params(6)
+            .alpha(10.)
+            .fit(&Dataset::from(records.clone()))
+            .unwrap();
+        let n_active_sparse = sparse_dict
+            .predict(&records)
+            .iter()
+            .filter(|x| **x != 0.)
+            .count();
+        assert!(n_active_sparse < n_active);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = Dataset::from(Array2::random((10, 3), Uniform::new(-1.0f64, 1.)));
+
+        assert!(DictionaryLearning::params(0).fit(&dataset).is_err());
+        assert!(DictionaryLearning::params(11).fit(&dataset).is_err());
+        assert!(DictionaryLearning::params(2)
+            .alpha(-1.)
+            .fit(&dataset)
+            .is_err());
+        assert!(DictionaryLearning::params(2)
+            .max_iterations(0)
+            .fit(&dataset)
+            .is_err());
+        assert!(DictionaryLearning::params(5).fit(&dataset).is_ok());
+
+        let empty = Dataset::from(Array2::<f64>::zeros((0, 3)));
+        assert!(DictionaryLearning::params(1).fit(&empty).is_err());
+    }
+}
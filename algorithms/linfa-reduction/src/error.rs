@@ -9,4 +9,8 @@ pub enum Error {
     LinalgError(#[from] ndarray_linalg::error::LinalgError),
     #[error(transparent)]
     LinfaError(#[from] linfa::error::Error),
+    #[error(transparent)]
+    ElasticNetError(#[from] linfa_elasticnet::Error),
+    #[error(transparent)]
+    IcaError(#[from] linfa_ica::error::FastIcaError),
 }
@@ -0,0 +1,458 @@
+//! Factor Analysis
+//!
+//! Factor analysis models the data as samples of the linear-Gaussian latent variable model
+//! `x = W z + mu + e`, where the latent factors `z` are a low-dimensional standard normal vector
+//! and `e` is Gaussian noise with a diagonal covariance `Psi`. Unlike probabilistic PCA, which
+//! assumes the same noise variance for all features, every feature has its own noise variance.
+//! This better describes heteroscedastic data, for example measurements of sensors with differing
+//! precision.
+//!
+//! There is no closed form solution, instead the loadings `W` and the noise variances are
+//! estimated with the Expectation-Maximization algorithm. It alternates between computing the
+//! posterior of the latent factors and updating the parameters, starting from the probabilistic
+//! PCA solution.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::{Fit, Predict, ScoreSamples};
+//! use linfa_reduction::FactorAnalysis;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // model the iris dataset with two latent factors
+//! let fa = FactorAnalysis::params(2)
+//!     .fit(&dataset).unwrap();
+//!
+//! // noise variance of every feature, log-likelihood of the samples and their latent factors
+//! let noise_variance = fa.noise_variance();
+//! let log_likelihood = fa.score_samples(dataset.records());
+//! let embedding = fa.predict(dataset.records());
+//! ```
+//!
+use crate::error::{Error, Result};
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use ndarray_linalg::{eigh::EighInto, Scalar, UPLO};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use linfa::{
+    dataset::{Records, WithLapack, WithoutLapack},
+    traits::{Fit, PredictRef, ScoreSamples, Transformer},
+    DatasetBase, Float,
+};
+
+/// Lower bound of the noise variances, which keeps the precision of the noise finite
+const MIN_NOISE_VARIANCE: f64 = 1e-12;
+
+/// Factor analysis parameters
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorAnalysisParams {
+    n_components: usize,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl FactorAnalysisParams {
+    /// Set the maximal number of EM iterations
+    ///
+    /// Defaults to `1000`.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    /// Set the tolerance of the increase of the average log-likelihood
+    ///
+    /// Defaults to `1e-6`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    /// Validates the parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.max_iterations == 0 {
+            return Err(linfa::Error::Parameters(
+                "maximal number of iterations should be larger than zero".into(),
+            )
+            .into());
+        }
+        if self.tolerance.is_nan() || self.tolerance < 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "tolerance should not be negative, but is {}",
+                self.tolerance
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Fit a factor analysis model given a dataset
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with records in N dimensions
+///
+/// # Returns
+///
+/// The maximum likelihood estimate of the mean, loadings and noise variances
+impl<F: Float, T, D: Data<Elem = F>> Fit<ArrayBase<D, Ix2>, T, Error> for FactorAnalysisParams {
+    type Object = FactorAnalysis<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<FactorAnalysis<F>> {
+        self.validate()?;
+
+        let x = dataset.records();
+        x.check_shape(1)?;
+
+        let nfeatures = x.nfeatures();
+        if self.n_components == 0 || self.n_components >= nfeatures {
+            return Err(linfa::Error::Parameters(format!(
+                "number of components should be between 1 and {}, but is {}",
+                nfeatures - 1,
+                self.n_components
+            ))
+            .into());
+        }
+
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        let centered = x - &mean;
+        let covariance = centered.t().dot(&centered) / F::cast(x.nsamples());
+        let min_noise_variance = F::cast(MIN_NOISE_VARIANCE);
+
+        // start with the probabilistic PCA solution and isotropic noise
+        let (vals, vecs) = covariance.clone().with_lapack().eigh_into(UPLO::Lower)?;
+        let vals: Array1<F> = vals
+            .slice_move(s![..; -1])
+            .mapv(Scalar::from_real)
+            .without_lapack();
+        let vecs: Array2<F> = vecs.slice_move(s![.., ..; -1]).without_lapack();
+
+        let isotropic_variance = vals
+            .slice(s![self.n_components..])
+            .mean()
+            .unwrap()
+            .max(min_noise_variance);
+        let scale = vals
+            .slice(s![..self.n_components])
+            .mapv(|x| (x - isotropic_variance).max(F::zero()).sqrt());
+        let mut loadings = vecs.slice_move(s![.., ..self.n_components]) * &scale;
+        let mut noise_variance = Array1::from_elem(nfeatures, isotropic_variance);
+
+        let mut log_likelihood: Option<F> = None;
+        for _ in 0..self.max_iterations {
+            // E-step: posterior covariance `G = (I + W^T Psi^-1 W)^-1` of the latent factors
+            let scaled_loadings = &loadings / &noise_variance.view().insert_axis(Axis(1));
+            let (posterior_covariance, log_det) = latent_posterior(&loadings, &scaled_loadings)?;
+
+            // average log-likelihood of the current parameters
+            let projected = covariance.dot(&scaled_loadings);
+            let trace = (covariance.diag().to_owned() / &noise_variance).sum()
+                - (posterior_covariance.dot(&scaled_loadings.t().dot(&projected)))
+                    .diag()
+                    .sum();
+            let new_log_likelihood = F::cast(-0.5)
+                * (F::cast(nfeatures) * F::cast(2. * std::f64::consts::PI).ln()
+                    + noise_variance.mapv(|x| x.ln()).sum()
+                    + log_det
+                    + trace);
+            let converged = log_likelihood
+                .map(|log_likelihood| {
+                    new_log_likelihood - log_likelihood <= F::cast(self.tolerance)
+                })
+                .unwrap_or(false);
+            log_likelihood = Some(new_log_likelihood);
+
+            if converged {
+                break;
+            }
+
+            // M-step: update the loadings and the noise variances with the expected statistics
+            // of the latent factors, where `B = G W^T Psi^-1` maps records to posterior means
+            let mapping = posterior_covariance.dot(&scaled_loadings.t());
+            let cross_covariance = covariance.dot(&mapping.t());
+            let mut latent_covariance = mapping.dot(&cross_covariance);
+            latent_covariance += &posterior_covariance;
+
+            let (latent_precision, _) = inverse_symmetric(latent_covariance)?;
+            loadings = cross_covariance.dot(&latent_precision);
+            noise_variance = (covariance.diag().to_owned()
+                - (&loadings * &cross_covariance).sum_axis(Axis(1)))
+            .mapv(|x| x.max(min_noise_variance));
+        }
+
+        let scaled_loadings = &loadings / &noise_variance.view().insert_axis(Axis(1));
+        let (posterior_covariance, log_det) = latent_posterior(&loadings, &scaled_loadings)?;
+
+        Ok(FactorAnalysis {
+            mean,
+            components: loadings.reversed_axes(),
+            noise_variance,
+            posterior_covariance,
+            log_det,
+        })
+    }
+}
+
+/// Compute `(I + W^T Psi^-1 W)^-1` and the logarithm of the determinant of `I + W^T Psi^-1 W`
+fn latent_posterior<F: Float>(
+    loadings: &Array2<F>,
+    scaled_loadings: &Array2<F>,
+) -> Result<(Array2<F>, F)> {
+    let mut precision = loadings.t().dot(scaled_loadings);
+    precision.diag_mut().mapv_inplace(|x| x + F::one());
+
+    inverse_symmetric(precision)
+}
+
+/// Invert a symmetric positive definite matrix and return the logarithm of its determinant
+fn inverse_symmetric<F: Float>(matrix: Array2<F>) -> Result<(Array2<F>, F)> {
+    let (vals, vecs) = matrix.with_lapack().eigh_into(UPLO::Lower)?;
+    let vals: Array1<F> = vals.mapv(Scalar::from_real).without_lapack();
+    let vecs: Array2<F> = vecs.without_lapack();
+
+    let inverse = (&vecs / &vals).dot(&vecs.t());
+    let log_det = vals.mapv(|x| x.ln()).sum();
+
+    Ok((inverse, log_det))
+}
+
+/// Fitted factor analysis model
+///
+/// The model contains the mean, the loadings of the latent factors and the noise variance of
+/// every feature. The data covariance is modelled as `W W^T + Psi`, where `Psi` is diagonal.
+///
+/// Predicting returns the posterior mean of the latent factors for every sample.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct FactorAnalysis<F> {
+    mean: Array1<F>,
+    components: Array2<F>,
+    noise_variance: Array1<F>,
+    posterior_covariance: Array2<F>,
+    log_det: F,
+}
+
+impl FactorAnalysis<f64> {
+    /// Create default parameter set
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the number of latent factors, has to be smaller than the number of
+    ///  features
+    pub fn params(n_components: usize) -> FactorAnalysisParams {
+        FactorAnalysisParams {
+            n_components,
+            max_iterations: 1000,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+impl<F: Float> FactorAnalysis<F> {
+    /// Return the mean of the training records
+    pub fn mean(&self) -> &Array1<F> {
+        &self.mean
+    }
+
+    /// Return the loadings `W^T` with shape `(n_components, n_features)`
+    pub fn components(&self) -> &Array2<F> {
+        &self.components
+    }
+
+    /// Return the estimated noise variance of every feature
+    pub fn noise_variance(&self) -> &Array1<F> {
+        &self.noise_variance
+    }
+
+    /// Return the covariance `W W^T + Psi` of the model
+    pub fn covariance(&self) -> Array2<F> {
+        let mut covariance = self.components.t().dot(&self.components);
+        let mut diag = covariance.diag_mut();
+        diag += &self.noise_variance;
+
+        covariance
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> ScoreSamples<ArrayBase<D, Ix2>, F> for FactorAnalysis<F> {
+    /// Log-likelihood of every sample under the model
+    ///
+    /// The precision matrix and the determinant of the covariance are computed with the Woodbury
+    /// identity from the loadings and the noise variances, without inverting the full covariance
+    /// matrix.
+    fn score_samples(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let nfeatures = self.mean.len();
+        let centered = (x - &self.mean) / &self.noise_variance.mapv(|x| x.sqrt());
+        let projected = (x - &self.mean)
+            .dot(&(&self.components / &self.noise_variance.view().insert_axis(Axis(0))).t());
+
+        // the precision matrix is Psi^-1 - Psi^-1 W G W^T Psi^-1
+        let mahalanobis = centered.mapv(|x| x * x).sum_axis(Axis(1))
+            - (projected.dot(&self.posterior_covariance) * &projected).sum_axis(Axis(1));
+
+        let log_det = self.noise_variance.mapv(|x| x.ln()).sum() + self.log_det;
+        let constant = F::cast(nfeatures) * F::cast(2. * std::f64::consts::PI).ln() + log_det;
+
+        mahalanobis.mapv(|x| F::cast(-0.5) * (constant + x))
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>> for FactorAnalysis<F> {
+    /// Posterior mean `G W^T Psi^-1 (x - mu)` of the latent factors
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let scaled_components = &self.components / &self.noise_variance.view().insert_axis(Axis(0));
+
+        (records - &self.mean)
+            .dot(&scaled_components.t())
+            .dot(&self.posterior_covariance)
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>>
+    for FactorAnalysis<F>
+{
+    fn transform(&self, ds: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let DatasetBase {
+            records,
+            targets,
+            weights,
+            ..
+        } = ds;
+
+        let new_records = self.predict_ref(&records);
+
+        DatasetBase::new(new_records, targets).with_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProbabilisticPca;
+    use approx::assert_abs_diff_eq;
+    use linfa::{traits::Predict, Dataset};
+    use ndarray::array;
+    use ndarray_linalg::{Determinant, Inverse};
+    use ndarray_rand::{
+        rand_distr::{StandardNormal, Uniform},
+        RandomExt,
+    };
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    /// Samples of two latent factors embedded into six dimensions, with noise of standard
+    /// deviation 0.2, 0.4, .., 1.2 for the features
+    fn heteroscedastic_data(nsamples: usize, rng: &mut SmallRng) -> Array2<f64> {
+        let loadings = array![[2., 1., 0., -1., 0.5, 1.], [0., 1., 2., 1., -1.5, -1.]];
+        let latent = Array2::random_using((nsamples, 2), StandardNormal, rng);
+        let noise = Array2::random_using((nsamples, 6), StandardNormal, rng)
+            * &array![0.2, 0.4, 0.6, 0.8, 1.0, 1.2];
+
+        latent.dot(&loadings) + noise + &array![0., 1., 2., 3., 4., 5.]
+    }
+
+    #[test]
+    fn noise_variance_tracks_injected_noise() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::from(heteroscedastic_data(5000, &mut rng));
+
+        let fa = FactorAnalysis::params(2).fit(&dataset).unwrap();
+        assert_eq!(fa.components().dim(), (2, 6));
+        assert_abs_diff_eq!(
+            fa.noise_variance(),
+            &array![0.04, 0.16, 0.36, 0.64, 1.0, 1.44],
+            epsilon = 0.1
+        );
+        assert!(fa
+            .noise_variance()
+            .windows(2)
+            .into_iter()
+            .all(|x| x[0] < x[1]));
+
+        // the model explains the data better than isotropic noise
+        let ppca = ProbabilisticPca::params(2).fit(&dataset).unwrap();
+        let records = dataset.records();
+        assert!(
+            fa.score_samples(records).mean().unwrap() > ppca.score_samples(records).mean().unwrap()
+        );
+    }
+
+    #[test]
+    fn score_samples_matches_gaussian_density() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let data = Array2::random_using((200, 4), Uniform::new(-1.0f64, 1.), &mut rng);
+        let fa = FactorAnalysis::params(1)
+            .fit(&Dataset::from(data.clone()))
+            .unwrap();
+
+        // evaluate the density with the full covariance matrix
+        let covariance = fa.covariance();
+        let precision = covariance.inv().unwrap();
+        let log_det = covariance.det().unwrap().ln();
+        let expected = data
+            .outer_iter()
+            .map(|x| {
+                let centered = &x - fa.mean();
+                -0.5 * (4. * (2. * std::f64::consts::PI).ln()
+                    + log_det
+                    + centered.dot(&precision.dot(&centered)))
+            })
+            .collect::<Array1<_>>();
+
+        assert_abs_diff_eq!(fa.score_samples(&data), expected, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn posterior_mean_of_latent_factors() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::from(heteroscedastic_data(1000, &mut rng));
+        let fa = FactorAnalysis::params(2).fit(&dataset).unwrap();
+
+        let embedding = fa.predict(dataset.records());
+        assert_eq!(embedding.dim(), (1000, 2));
+        // the mean of the posterior means is the prior mean
+        assert_abs_diff_eq!(
+            embedding.mean_axis(Axis(0)).unwrap(),
+            array![0., 0.],
+            epsilon = 1e-8
+        );
+
+        let transformed = fa.transform(dataset);
+        assert_abs_diff_eq!(transformed.records(), &embedding);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = Dataset::from(Array2::random((10, 3), Uniform::new(-1.0f64, 1.)));
+
+        assert!(FactorAnalysis::params(0).fit(&dataset).is_err());
+        assert!(FactorAnalysis::params(3).fit(&dataset).is_err());
+        assert!(FactorAnalysis::params(2)
+            .max_iterations(0)
+            .fit(&dataset)
+            .is_err());
+        assert!(FactorAnalysis::params(2)
+            .tolerance(-1.)
+            .fit(&dataset)
+            .is_err());
+        assert!(FactorAnalysis::params(2).fit(&dataset).is_ok());
+
+        let empty = Dataset::from(Array2::<f64>::zeros((0, 3)));
+        assert!(FactorAnalysis::params(1).fit(&empty).is_err());
+    }
+}
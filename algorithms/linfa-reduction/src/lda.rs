@@ -0,0 +1,423 @@
+//! Linear Discriminant Analysis
+//!
+//! Linear Discriminant Analysis (LDA) is a supervised technique which finds the linear
+//! projection maximizing the ratio of between-class scatter to within-class scatter. The
+//! projection is computed by solving the generalized eigenvalue problem `S_b v = λ S_w v` and can
+//! be used both for dimensionality reduction, retaining at most `n_classes - 1` dimensions, and
+//! for classification under the assumption of gaussian classes with a shared covariance matrix.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::{Fit, Predict, Transformer};
+//! use linfa_reduction::LinearDiscriminantAnalysis;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // find the two most discriminative directions
+//! let lda = LinearDiscriminantAnalysis::params()
+//!     .n_components(2)
+//!     .fit(&dataset).unwrap();
+//!
+//! // project the records onto them
+//! let projection = lda.transform(dataset.records());
+//!
+//! // and classify the records
+//! let predicted = lda.predict(dataset.records());
+//! ```
+//!
+use crate::error::{Error, Result};
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use ndarray_linalg::{cholesky::*, eigh::EighInto, triangular::*, Scalar};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use linfa::{
+    dataset::{AsTargets, Label, Records, WithLapack, WithoutLapack},
+    metrics::{Accuracy, DefaultMetric},
+    traits::{Fit, PredictRef, Transformer},
+    DatasetBase, Float,
+};
+
+/// Linear Discriminant Analysis parameters
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LinearDiscriminantAnalysisParams {
+    n_components: Option<usize>,
+}
+
+impl LinearDiscriminantAnalysisParams {
+    /// Set the dimensionality of the projection
+    ///
+    /// The number of components has to be smaller than the number of classes and not larger
+    /// than the number of features. Defaults to the largest admissible value.
+    pub fn n_components(mut self, n_components: usize) -> Self {
+        self.n_components = Some(n_components);
+
+        self
+    }
+
+    /// Validates the parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.n_components == Some(0) {
+            return Err(linfa::Error::Parameters(
+                "number of components should be larger than zero".into(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Fit a LDA model given a labeled dataset
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with records in N dimensions and a single column of class labels
+///
+/// # Returns
+///
+/// A fitted LDA model with discriminant directions, class means and priors
+impl<F: Float, L: Label, D: Data<Elem = F>, T: AsTargets<Elem = L>> Fit<ArrayBase<D, Ix2>, T, Error>
+    for LinearDiscriminantAnalysisParams
+{
+    type Object = LinearDiscriminantAnalysis<F, L>;
+
+    fn fit(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+    ) -> Result<LinearDiscriminantAnalysis<F, L>> {
+        self.validate()?;
+
+        let records = dataset.records();
+        let targets = dataset.try_single_target()?;
+        let (nsamples, nfeatures) = (dataset.nsamples(), dataset.nfeatures());
+
+        // assign each label an index in order of first appearance
+        let mut classes = Vec::new();
+        let mut index = HashMap::new();
+        let assignment = targets
+            .iter()
+            .map(|label| {
+                *index.entry(label.clone()).or_insert_with(|| {
+                    classes.push(label.clone());
+                    classes.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        let nclasses = classes.len();
+
+        if nclasses < 2 {
+            return Err(
+                linfa::Error::Parameters("at least two classes are required".into()).into(),
+            );
+        }
+        if nsamples <= nclasses {
+            return Err(Error::NotEnoughSamples);
+        }
+
+        let max_components = (nclasses - 1).min(nfeatures);
+        let n_components = self.n_components.unwrap_or(max_components);
+        if n_components > max_components {
+            return Err(linfa::Error::Parameters(format!(
+                "number of components {} exceeds the maximum of {} for {} classes and {} features",
+                n_components, max_components, nclasses, nfeatures
+            ))
+            .into());
+        }
+
+        // per-class means and prior probabilities
+        let mut counts = Array1::<F>::zeros(nclasses);
+        let mut class_means = Array2::<F>::zeros((nclasses, nfeatures));
+        for (row, &class) in records.genrows().into_iter().zip(assignment.iter()) {
+            counts[class] += F::one();
+            let mut mean = class_means.row_mut(class);
+            mean += &row;
+        }
+        for (mut mean, count) in class_means.genrows_mut().into_iter().zip(counts.iter()) {
+            mean /= *count;
+        }
+        let priors = &counts / F::cast(nsamples);
+        let mean = records.mean_axis(Axis(0)).unwrap();
+
+        // within-class scatter
+        let mut centered = records.to_owned();
+        for (mut row, &class) in centered.genrows_mut().into_iter().zip(assignment.iter()) {
+            row -= &class_means.row(class);
+        }
+        let within = centered.t().dot(&centered);
+
+        // between-class scatter
+        let mut deviation = &class_means - &mean;
+        for (mut row, count) in deviation.genrows_mut().into_iter().zip(counts.iter()) {
+            row *= count.sqrt();
+        }
+        let between = deviation.t().dot(&deviation);
+
+        // reduce the generalized eigenproblem to a symmetric one with the cholesky factor of the
+        // within-class scatter: L^-1 S_b L^-T w = λ w, v = L^-T w
+        let cholesky = within.with_lapack().cholesky(UPLO::Lower)?;
+        let tmp = cholesky.solve_triangular(UPLO::Lower, Diag::NonUnit, &between.with_lapack())?;
+        let reduced =
+            cholesky.solve_triangular(UPLO::Lower, Diag::NonUnit, &tmp.reversed_axes())?;
+        let (vals, vecs) = reduced.eigh_into(UPLO::Lower)?;
+
+        // eigenvalues are returned in ascending order, keep the largest ones
+        let vals = vals
+            .slice_move(s![..; -1])
+            .slice_move(s![..max_components])
+            .mapv(Scalar::from_real);
+        let vecs = vecs
+            .slice_move(s![.., ..; -1])
+            .slice_move(s![.., ..max_components]);
+        let scalings = cholesky
+            .t()
+            .to_owned()
+            .solve_triangular(UPLO::Upper, Diag::NonUnit, &vecs)?
+            .without_lapack();
+
+        // scale the directions such that the pooled within-class covariance is unit diagonal
+        let scalings = scalings * F::cast(nsamples - nclasses).sqrt();
+        let eigenvalues: Array1<F> = vals.without_lapack();
+        let explained_variance_ratio = &eigenvalues / eigenvalues.sum();
+
+        Ok(LinearDiscriminantAnalysis {
+            n_components,
+            scalings,
+            eigenvalues,
+            explained_variance_ratio,
+            mean,
+            class_means,
+            priors,
+            classes,
+        })
+    }
+}
+
+/// Fitted Linear Discriminant Analysis model
+///
+/// The model contains the discriminant directions, ordered by decreasing ratio of between-class
+/// to within-class scatter, and the class means and priors needed for classification.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearDiscriminantAnalysis<F, L> {
+    n_components: usize,
+    scalings: Array2<F>,
+    eigenvalues: Array1<F>,
+    explained_variance_ratio: Array1<F>,
+    mean: Array1<F>,
+    class_means: Array2<F>,
+    priors: Array1<F>,
+    classes: Vec<L>,
+}
+
+impl LinearDiscriminantAnalysis<f64, usize> {
+    /// Create default parameter set
+    ///
+    /// The number of components defaults to `min(n_classes - 1, n_features)`.
+    pub fn params() -> LinearDiscriminantAnalysisParams {
+        LinearDiscriminantAnalysisParams::default()
+    }
+}
+
+impl<F: Float, L: Label> LinearDiscriminantAnalysis<F, L> {
+    /// Return the discriminant directions used for the projection, one per column
+    pub fn scalings(&self) -> Array2<F> {
+        self.scalings.slice(s![.., ..self.n_components]).to_owned()
+    }
+
+    /// Return the eigenvalues of the generalized eigenproblem for the projected directions
+    pub fn eigenvalues(&self) -> Array1<F> {
+        self.eigenvalues.slice(s![..self.n_components]).to_owned()
+    }
+
+    /// Return the ratio of between-class variance explained by each projected direction
+    pub fn explained_variance_ratio(&self) -> Array1<F> {
+        self.explained_variance_ratio
+            .slice(s![..self.n_components])
+            .to_owned()
+    }
+
+    /// Return the class labels in order of their first appearance in the training data
+    pub fn classes(&self) -> &[L] {
+        &self.classes
+    }
+
+    /// Return the mean of each class, one per row
+    pub fn class_means(&self) -> &Array2<F> {
+        &self.class_means
+    }
+
+    /// Return the prior probability of each class
+    pub fn priors(&self) -> &Array1<F> {
+        &self.priors
+    }
+}
+
+impl<'a, F: Float, L: Label, D: Data<Elem = F>> Transformer<&'a ArrayBase<D, Ix2>, Array2<F>>
+    for LinearDiscriminantAnalysis<F, L>
+{
+    /// Project records onto the discriminant directions
+    fn transform(&self, records: &'a ArrayBase<D, Ix2>) -> Array2<F> {
+        (records - &self.mean).dot(&self.scalings.slice(s![.., ..self.n_components]))
+    }
+}
+
+impl<F: Float, L: Label, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<L>>
+    for LinearDiscriminantAnalysis<F, L>
+{
+    /// Assign each record to the class with the largest posterior probability
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array1<L> {
+        // in the space spanned by all discriminant directions the pooled covariance is the
+        // identity, the decision reduces to the nearest class mean corrected by the priors
+        let projected = (records - &self.mean).dot(&self.scalings);
+        let centroids = (&self.class_means - &self.mean).dot(&self.scalings);
+        let log_priors = self.priors.mapv(|x| x.ln());
+
+        projected
+            .genrows()
+            .into_iter()
+            .map(|row| {
+                let (best, _) = centroids
+                    .genrows()
+                    .into_iter()
+                    .zip(log_priors.iter())
+                    .map(|(centroid, log_prior)| {
+                        let dist = (&row - &centroid).mapv(|x| x * x).sum();
+                        *log_prior - dist * F::cast(0.5)
+                    })
+                    .enumerate()
+                    .fold((0, F::neg_infinity()), |(best, max), (i, score)| {
+                        if score > max {
+                            (i, score)
+                        } else {
+                            (best, max)
+                        }
+                    });
+
+                self.classes[best].clone()
+            })
+            .collect()
+    }
+}
+
+impl<F: Float, L: Label> DefaultMetric for LinearDiscriminantAnalysis<F, L> {
+    type Metric = Accuracy;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use linfa::{traits::Predict, Dataset};
+    use ndarray::array;
+
+    #[test]
+    fn iris_projection_and_classification() {
+        let dataset = linfa_datasets::iris();
+
+        let lda = LinearDiscriminantAnalysis::params()
+            .n_components(2)
+            .fit(&dataset)
+            .unwrap();
+
+        let projection = lda.transform(dataset.records());
+        assert_eq!(projection.dim(), (150, 2));
+
+        // the projected classes are well separated along the first direction
+        let targets = dataset.try_single_target().unwrap();
+        let class_mean = |class: usize| {
+            let values = projection
+                .column(0)
+                .iter()
+                .zip(targets.iter())
+                .filter(|(_, t)| **t == class)
+                .map(|(x, _)| *x)
+                .collect::<Vec<_>>();
+            values.iter().sum::<f64>() / values.len() as f64
+        };
+        let means = [class_mean(0), class_mean(1), class_mean(2)];
+        assert!((means[0] - means[1]).abs() > 4.0);
+        assert!((means[1] - means[2]).abs() > 4.0);
+
+        // almost all of the between-class variance is retained in the first direction
+        assert!(lda.explained_variance_ratio()[0] > 0.95);
+
+        let predicted = lda.predict(dataset.records());
+        let correct = predicted
+            .iter()
+            .zip(targets.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+        assert!(correct as f64 / 150. > 0.97);
+    }
+
+    #[test]
+    fn projection_whitens_within_class_scatter() {
+        let records = array![
+            [0., 0.],
+            [1., 2.],
+            [2., 0.],
+            [1., -2.],
+            [10., 0.],
+            [11., 2.],
+            [12., 0.],
+            [11., -2.]
+        ];
+        let dataset = Dataset::new(records, array![0usize, 0, 0, 0, 1, 1, 1, 1]);
+
+        let lda = LinearDiscriminantAnalysis::params().fit(&dataset).unwrap();
+        assert_eq!(lda.scalings().dim(), (2, 1));
+
+        // the only discriminant direction is the first axis
+        let scalings = lda.scalings();
+        assert_abs_diff_eq!(scalings[[1, 0]], 0., epsilon = 1e-10);
+
+        // the pooled within-class variance of the projection is one
+        let projection = lda.transform(dataset.records()).remove_axis(Axis(1));
+        let class_means = [
+            projection.slice(s![..4]).sum() / 4.,
+            projection.slice(s![4..]).sum() / 4.,
+        ];
+        let within = projection
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x - class_means[i / 4]).powi(2))
+            .sum::<f64>();
+        assert_abs_diff_eq!(within / 6., 1., epsilon = 1e-10);
+
+        assert_eq!(
+            lda.predict(&array![[1., 1.], [11., -1.]]),
+            array![0usize, 1]
+        );
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = linfa_datasets::iris();
+
+        assert!(LinearDiscriminantAnalysis::params()
+            .n_components(0)
+            .fit(&dataset)
+            .is_err());
+        assert!(LinearDiscriminantAnalysis::params()
+            .n_components(3)
+            .fit(&dataset)
+            .is_err());
+
+        let single_class = Dataset::new(array![[0., 1.], [1., 0.], [2., 2.]], array![1usize, 1, 1]);
+        assert!(LinearDiscriminantAnalysis::params()
+            .fit(&single_class)
+            .is_err());
+    }
+}
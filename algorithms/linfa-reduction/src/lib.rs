@@ -7,15 +7,38 @@
 //! The following implementations are available:
 //!  * Principal Component Analysis - projects data linearily and retains the largest variance
 //!  * Diffusion Map - applies kernel methods and projects close regions together
+//!  * Linear Discriminant Analysis - projects labeled data such that classes are separated
+//!  * Classical Multidimensional Scaling - embeds samples such that pairwise distances are retained
+//!  * Probabilistic Principal Component Analysis - models the data as a low-rank projection with
+//!  isotropic noise
+//!  * Factor Analysis - models the data as a low-rank projection with a separate noise variance per
+//!  feature
+//!  * Sparse Principal Component Analysis - finds components which depend on few features only
+//!  * Dictionary Learning - finds atoms which reconstruct the data with sparse combinations
+//!  * Non-negative Matrix Factorization - factorizes non-negative data into additive parts
 //!
 #[macro_use]
 extern crate ndarray;
 
+pub mod dictionary_learning;
 pub mod diffusion_map;
 pub mod error;
+pub mod factor_analysis;
+pub mod lda;
+pub mod mds;
+pub mod nmf;
 pub mod pca;
+pub mod ppca;
+pub mod sparse_pca;
 pub mod utils;
 
+pub use dictionary_learning::DictionaryLearning;
 pub use diffusion_map::DiffusionMap;
+pub use factor_analysis::FactorAnalysis;
+pub use lda::LinearDiscriminantAnalysis;
+pub use mds::Mds;
+pub use nmf::Nmf;
 pub use pca::Pca;
+pub use ppca::ProbabilisticPca;
+pub use sparse_pca::SparsePca;
 pub use utils::to_gaussian_similarity;
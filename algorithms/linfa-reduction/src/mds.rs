@@ -0,0 +1,262 @@
+//! Classical Multidimensional Scaling
+//!
+//! Classical (or Torgerson) multidimensional scaling finds a low dimensional configuration of
+//! points whose pairwise euclidean distances approximate a given dissimilarity matrix. The
+//! squared distances are double-centered to obtain a Gram matrix, which is then decomposed into
+//! its eigenvalues and eigenvectors. For euclidean input distances the result coincides with a
+//! PCA projection of the data, up to the sign of each axis.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::Fit;
+//! use linfa_reduction::Mds;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // embed the iris dataset into two dimensions
+//! let mds = Mds::params(2)
+//!     .fit(&dataset).unwrap();
+//!
+//! let embedding = mds.embedding();
+//! let eigenvalues = mds.eigenvalues();
+//! ```
+//!
+use crate::error::{Error, Result};
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use ndarray_linalg::{eigh::EighInto, Scalar, UPLO};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use linfa::{
+    dataset::{Records, WithLapack, WithoutLapack},
+    traits::Fit,
+    DatasetBase, Float,
+};
+use linfa_nn::distance::{pairwise_distances, L2Dist};
+
+/// Classical multidimensional scaling parameters
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdsParams {
+    n_components: usize,
+    precomputed: bool,
+}
+
+impl MdsParams {
+    /// Interpret the records as a precomputed distance matrix
+    ///
+    /// By default the records are treated as observations and the euclidean distance between
+    /// them is used as dissimilarity. With this option the records have to be a symmetric square
+    /// matrix of pairwise distances instead.
+    pub fn precomputed(mut self, precomputed: bool) -> Self {
+        self.precomputed = precomputed;
+
+        self
+    }
+
+    /// Validates the parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.n_components == 0 {
+            return Err(linfa::Error::Parameters(
+                "number of components should be larger than zero".into(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Fit a classical MDS embedding given a dataset
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with either observations in N dimensions or a precomputed distance
+/// matrix as records
+///
+/// # Returns
+///
+/// The embedding of every sample together with the eigenvalues of the centered Gram matrix
+impl<F: Float, T, D: Data<Elem = F>> Fit<ArrayBase<D, Ix2>, T, Error> for MdsParams {
+    type Object = Mds<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Mds<F>> {
+        self.validate()?;
+
+        let nsamples = dataset.nsamples();
+        if nsamples == 0 {
+            return Err(Error::NotEnoughSamples);
+        }
+
+        let records = dataset.records();
+        let distances = if self.precomputed {
+            if records.nrows() != records.ncols() {
+                return Err(
+                    linfa::Error::MismatchedShapes(records.nrows(), records.ncols()).into(),
+                );
+            }
+            records.to_owned()
+        } else {
+            pairwise_distances(&records.view(), None, &L2Dist)
+        };
+
+        if self.n_components > nsamples {
+            return Err(linfa::Error::Parameters(format!(
+                "number of components {} exceeds number of samples {}",
+                self.n_components, nsamples
+            ))
+            .into());
+        }
+
+        // double-center the squared distances: B = -1/2 J D^2 J with J = I - 1/n
+        let mut gram = distances.mapv(|x| x * x * F::cast(-0.5));
+        let row_mean = gram.mean_axis(Axis(1)).unwrap();
+        let col_mean = gram.mean_axis(Axis(0)).unwrap();
+        let total_mean = row_mean.sum() / F::cast(nsamples);
+        gram -= &row_mean.insert_axis(Axis(1));
+        gram -= &col_mean;
+        gram.mapv_inplace(|x| x + total_mean);
+
+        // eigenvalues are returned in ascending order, reverse them
+        let (vals, vecs) = gram.with_lapack().eigh_into(UPLO::Lower)?;
+        let (vals, vecs) = (vals.slice_move(s![..; -1]), vecs.slice_move(s![.., ..; -1]));
+        let vals: Array1<F> = vals
+            .slice_move(s![..self.n_components])
+            .mapv(Scalar::from_real)
+            .without_lapack();
+        let mut embedding: Array2<F> = vecs
+            .slice_move(s![.., ..self.n_components])
+            .without_lapack();
+
+        // scale each axis by the square root of its eigenvalue, negative eigenvalues indicate a
+        // non-euclidean distance and are clipped to zero
+        for (mut col, val) in embedding.gencolumns_mut().into_iter().zip(vals.iter()) {
+            col *= val.max(F::zero()).sqrt();
+        }
+
+        Ok(Mds {
+            embedding,
+            eigenvalues: vals,
+        })
+    }
+}
+
+/// Fitted classical multidimensional scaling embedding
+///
+/// The embedding contains one row per sample of the fitted dataset. As classical MDS is not an
+/// inductive method, no projection of unseen samples is available.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mds<F> {
+    embedding: Array2<F>,
+    eigenvalues: Array1<F>,
+}
+
+impl Mds<f64> {
+    /// Create default parameter set
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the target dimensionality
+    pub fn params(n_components: usize) -> MdsParams {
+        MdsParams {
+            n_components,
+            precomputed: false,
+        }
+    }
+}
+
+impl<F: Float> Mds<F> {
+    /// Return the embedding of the fitted samples
+    pub fn embedding(&self) -> &Array2<F> {
+        &self.embedding
+    }
+
+    /// Return the largest eigenvalues of the double-centered Gram matrix
+    ///
+    /// The eigenvalues measure the amount of squared distance captured by each component. Large
+    /// negative eigenvalues indicate that the dissimilarities are far from euclidean.
+    pub fn eigenvalues(&self) -> &Array1<F> {
+        &self.eigenvalues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pca;
+    use approx::assert_abs_diff_eq;
+    use linfa::{traits::Predict, Dataset};
+    use ndarray_rand::{rand_distr::Uniform, RandomExt};
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn reproduces_pca_projection() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let data = Array2::random_using((50, 4), Uniform::new(-1.0f64, 1.), &mut rng);
+        let dataset = Dataset::from(data);
+
+        let mds = Mds::params(2).fit(&dataset).unwrap();
+        let pca = Pca::params(2).fit(&dataset).unwrap();
+        let proj = pca.predict(&dataset);
+
+        // axes are only determined up to their sign
+        for (a, b) in mds
+            .embedding()
+            .gencolumns()
+            .into_iter()
+            .zip(proj.gencolumns())
+        {
+            let sign = a.dot(&b).signum();
+            assert_abs_diff_eq!(a.to_owned() * sign, b, epsilon = 1e-6);
+        }
+
+        // eigenvalues of the Gram matrix equal the squared singular values
+        assert_abs_diff_eq!(
+            mds.eigenvalues(),
+            &pca.singular_values().mapv(|x| x * x),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn precomputed_distances() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let data = Array2::random_using((20, 3), Uniform::new(-1.0f64, 1.), &mut rng);
+        let distances = pairwise_distances(&data.view(), None, &L2Dist);
+
+        let from_data = Mds::params(3).fit(&Dataset::from(data)).unwrap();
+        let from_dist = Mds::params(3)
+            .precomputed(true)
+            .fit(&Dataset::from(distances.clone()))
+            .unwrap();
+
+        assert_abs_diff_eq!(
+            from_data.eigenvalues(),
+            from_dist.eigenvalues(),
+            epsilon = 1e-8
+        );
+
+        // the embedding reproduces the original distances
+        let embedded = pairwise_distances(&from_dist.embedding().view(), None, &L2Dist);
+        assert_abs_diff_eq!(embedded, distances, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = Dataset::from(Array2::<f64>::zeros((5, 2)));
+
+        assert!(Mds::params(0).fit(&dataset).is_err());
+        assert!(Mds::params(6).fit(&dataset).is_err());
+        assert!(Mds::params(2).precomputed(true).fit(&dataset).is_err());
+    }
+}
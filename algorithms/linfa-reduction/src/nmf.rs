@@ -0,0 +1,510 @@
+//! Non-negative Matrix Factorization
+//!
+//! Non-negative matrix factorization approximates non-negative records `X` by the product of two
+//! non-negative matrices, the weights `W` and the components `H`, by minimizing
+//!
+//! ```text
+//! 0.5 * ||X - W H||^2
+//! ```
+//!
+//! As no cancellation between the components is possible, each sample is represented by an
+//! additive combination of parts. This makes the factors easy to interpret, for example as topics
+//! of a document-term matrix. This implementation uses the multiplicative update rules of Lee and
+//! Seung, which keep both factors non-negative and never increase the reconstruction error.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::{Fit, Predict};
+//! use linfa_reduction::Nmf;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // find two non-negative parts of the samples
+//! let nmf = Nmf::params(2)
+//!     .fit(&dataset).unwrap();
+//!
+//! // weights of the parts and the approximated records
+//! let weights = nmf.predict(dataset.records());
+//! let approximation = nmf.inverse_transform(&weights);
+//! ```
+//!
+use crate::error::{Error, Result};
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix2, Zip};
+use ndarray_linalg::{eigh::EighInto, Scalar, UPLO};
+use ndarray_rand::rand::{Rng, SeedableRng};
+use ndarray_rand::rand_distr::Uniform;
+use ndarray_rand::RandomExt;
+use rand_isaac::Isaac64Rng;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use linfa::{
+    dataset::{Records, WithLapack, WithoutLapack},
+    traits::{Fit, PredictRef, Transformer},
+    DatasetBase, Float,
+};
+
+/// A specifier for the initialization of the factors
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NmfInit {
+    /// Both factors are sampled uniformly and scaled to the magnitude of the records
+    Random,
+    /// Non-negative double singular value decomposition, which splits the leading singular
+    /// vectors into their positive and negative parts. Entries which are zero are replaced by the
+    /// mean of the records, so that the multiplicative updates can change them.
+    Nndsvd,
+}
+
+/// Non-negative matrix factorization parameters
+#[derive(Debug, Clone)]
+pub struct NmfParams<R> {
+    n_components: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    init: NmfInit,
+    rng: R,
+}
+
+impl<R: Rng + Clone> NmfParams<R> {
+    /// Set the maximal number of multiplicative updates
+    ///
+    /// Defaults to `200`.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    /// Set the tolerance of the decrease of the reconstruction error
+    ///
+    /// The iteration stops when the error decreases less than `tolerance` times the error of the
+    /// initial factors. Defaults to `1e-4`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    /// Set the initialization of the factors
+    ///
+    /// Defaults to `NmfInit::Nndsvd`.
+    pub fn init(mut self, init: NmfInit) -> Self {
+        self.init = init;
+
+        self
+    }
+
+    /// Set the random number generator used by the random initialization
+    pub fn with_rng<R2: Rng + Clone>(self, rng: R2) -> NmfParams<R2> {
+        NmfParams {
+            n_components: self.n_components,
+            max_iterations: self.max_iterations,
+            tolerance: self.tolerance,
+            init: self.init,
+            rng,
+        }
+    }
+
+    /// Validates the parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.n_components == 0 {
+            return Err(linfa::Error::Parameters(
+                "number of components should be larger than zero".into(),
+            )
+            .into());
+        }
+        if self.max_iterations == 0 {
+            return Err(linfa::Error::Parameters(
+                "maximal number of iterations should be larger than zero".into(),
+            )
+            .into());
+        }
+        if self.tolerance.is_nan() || self.tolerance < 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "tolerance should not be negative, but is {}",
+                self.tolerance
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Factorize a dataset into non-negative weights and components
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with finite, non-negative records in N dimensions
+///
+/// # Returns
+///
+/// A fitted model with non-negative components
+impl<F: Float, T, D: Data<Elem = F>, R: Rng + Clone> Fit<ArrayBase<D, Ix2>, T, Error>
+    for NmfParams<R>
+{
+    type Object = Nmf<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Nmf<F>> {
+        self.validate()?;
+
+        let x = dataset.records();
+        x.check_shape(1)?;
+        if x.iter().any(|x| !x.is_finite() || *x < F::zero()) {
+            return Err(linfa::Error::Parameters(
+                "records of a non-negative matrix factorization should be finite and not negative"
+                    .into(),
+            )
+            .into());
+        }
+
+        let (mut weights, mut components) = match self.init {
+            NmfInit::Random => {
+                let mut rng = self.rng.clone();
+                let scale = (x.mean().unwrap() / F::cast(self.n_components)).sqrt();
+                let weights = Array2::random_using(
+                    (x.nsamples(), self.n_components),
+                    Uniform::new(F::zero(), F::one()),
+                    &mut rng,
+                ) * scale;
+                let components = Array2::random_using(
+                    (self.n_components, x.nfeatures()),
+                    Uniform::new(F::zero(), F::one()),
+                    &mut rng,
+                ) * scale;
+
+                (weights, components)
+            }
+            NmfInit::Nndsvd => {
+                if self.n_components > x.nfeatures().min(x.nsamples()) {
+                    return Err(linfa::Error::Parameters(format!(
+                        "number of components {} exceeds the rank of the records {}, which is \
+                         not supported by the NNDSVD initialization",
+                        self.n_components,
+                        x.nfeatures().min(x.nsamples())
+                    ))
+                    .into());
+                }
+
+                nndsvd(x, self.n_components)?
+            }
+        };
+
+        let initial_error = reconstruction_error(x, &weights, &components);
+        let mut error = initial_error;
+        for _ in 0..self.max_iterations {
+            update_components(x, &weights, &mut components);
+            update_weights(x, &mut weights, &components);
+
+            let new_error = reconstruction_error(x, &weights, &components);
+            let converged = error - new_error <= F::cast(self.tolerance) * initial_error;
+            error = new_error;
+
+            if converged {
+                break;
+            }
+        }
+
+        Ok(Nmf {
+            components,
+            reconstruction_error: error,
+            max_iterations: self.max_iterations,
+            tolerance: F::cast(self.tolerance),
+        })
+    }
+}
+
+/// Initialize both factors with the positive or negative parts of the leading singular vectors
+fn nndsvd<F: Float, D: Data<Elem = F>>(
+    x: &ArrayBase<D, Ix2>,
+    n_components: usize,
+) -> Result<(Array2<F>, Array2<F>)> {
+    let (vals, vecs) = x.t().dot(x).with_lapack().eigh_into(UPLO::Lower)?;
+    let vals: Array1<F> = vals
+        .slice_move(s![..; -1])
+        .mapv(Scalar::from_real)
+        .without_lapack();
+    let vecs: Array2<F> = vecs.slice_move(s![.., ..; -1]).without_lapack();
+
+    let mut weights = Array2::zeros((x.nsamples(), n_components));
+    let mut components = Array2::zeros((n_components, x.nfeatures()));
+    for j in 0..n_components {
+        let singular_value = vals[j].max(F::zero()).sqrt();
+        if singular_value <= F::zero() {
+            continue;
+        }
+        let v = vecs.column(j);
+        let u = x.dot(&v) / singular_value;
+
+        // either the positive or the negative parts of both vectors are retained, depending on
+        // which pair captures more of the rank one approximation
+        let (u_pos, u_neg) = (
+            u.mapv(|x| x.max(F::zero())),
+            u.mapv(|x| (-x).max(F::zero())),
+        );
+        let (v_pos, v_neg) = (
+            v.mapv(|x| x.max(F::zero())),
+            v.mapv(|x| (-x).max(F::zero())),
+        );
+        let norm = |x: &Array1<F>| x.dot(x).sqrt();
+        let (n_u_pos, n_u_neg, n_v_pos, n_v_neg) =
+            (norm(&u_pos), norm(&u_neg), norm(&v_pos), norm(&v_neg));
+
+        let (u, v, n_u, n_v) = if n_u_pos * n_v_pos >= n_u_neg * n_v_neg {
+            (u_pos, v_pos, n_u_pos, n_v_pos)
+        } else {
+            (u_neg, v_neg, n_u_neg, n_v_neg)
+        };
+        if n_u <= F::zero() || n_v <= F::zero() {
+            continue;
+        }
+
+        let scale = (singular_value * n_u * n_v).sqrt();
+        weights.column_mut(j).assign(&(u * (scale / n_u)));
+        components.row_mut(j).assign(&(v * (scale / n_v)));
+    }
+
+    // zero entries are fixed points of the multiplicative updates
+    let mean = x.mean().unwrap();
+    weights.mapv_inplace(|x| if x > F::zero() { x } else { mean });
+    components.mapv_inplace(|x| if x > F::zero() { x } else { mean });
+
+    Ok((weights, components))
+}
+
+/// Frobenius norm of the residual `X - W H`
+fn reconstruction_error<F: Float, D: Data<Elem = F>>(
+    x: &ArrayBase<D, Ix2>,
+    weights: &Array2<F>,
+    components: &Array2<F>,
+) -> F {
+    (x - &weights.dot(components)).mapv(|x| x * x).sum().sqrt()
+}
+
+/// Multiplicative update `H <- H * (W^T X) / (W^T W H)`
+fn update_components<F: Float, D: Data<Elem = F>>(
+    x: &ArrayBase<D, Ix2>,
+    weights: &Array2<F>,
+    components: &mut Array2<F>,
+) {
+    let numerator = weights.t().dot(x);
+    let denominator = weights.t().dot(weights).dot(components);
+
+    Zip::from(components)
+        .and(&numerator)
+        .and(&denominator)
+        .apply(|h, n, d| *h = *h * *n / (*d + F::epsilon()));
+}
+
+/// Multiplicative update `W <- W * (X H^T) / (W H H^T)`
+fn update_weights<F: Float, D: Data<Elem = F>>(
+    x: &ArrayBase<D, Ix2>,
+    weights: &mut Array2<F>,
+    components: &Array2<F>,
+) {
+    let numerator = x.dot(&components.t());
+    let denominator = weights.dot(&components.dot(&components.t()));
+
+    Zip::from(weights)
+        .and(&numerator)
+        .and(&denominator)
+        .apply(|w, n, d| *w = *w * *n / (*d + F::epsilon()));
+}
+
+/// Fitted non-negative matrix factorization
+///
+/// The model contains the non-negative components, one per row. Predicting returns the
+/// non-negative weights of the records, found with the same multiplicative updates and stopping
+/// criterion as used during fitting.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct Nmf<F> {
+    components: Array2<F>,
+    reconstruction_error: F,
+    max_iterations: usize,
+    tolerance: F,
+}
+
+impl Nmf<f64> {
+    /// Create default parameter set
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the number of components
+    pub fn params(n_components: usize) -> NmfParams<Isaac64Rng> {
+        Self::params_with_rng(n_components, Isaac64Rng::seed_from_u64(42))
+    }
+
+    /// Create default parameter set with a random number generator
+    pub fn params_with_rng<R: Rng + Clone>(n_components: usize, rng: R) -> NmfParams<R> {
+        NmfParams {
+            n_components,
+            max_iterations: 200,
+            tolerance: 1e-4,
+            init: NmfInit::Nndsvd,
+            rng,
+        }
+    }
+}
+
+impl<F: Float> Nmf<F> {
+    /// Return the components with shape `(n_components, n_features)`
+    pub fn components(&self) -> &Array2<F> {
+        &self.components
+    }
+
+    /// Return the Frobenius norm of the residual of the training records
+    pub fn reconstruction_error(&self) -> F {
+        self.reconstruction_error
+    }
+
+    /// Approximate records from their weights
+    pub fn inverse_transform<D: Data<Elem = F>>(&self, weights: &ArrayBase<D, Ix2>) -> Array2<F> {
+        weights.dot(&self.components)
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>> for Nmf<F> {
+    /// Non-negative weights of the records, with shape `(n_samples, n_components)`
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let n_components = self.components.nrows();
+        let scale = records
+            .mean()
+            .map(|mean| (mean.max(F::zero()) / F::cast(n_components)).sqrt())
+            .unwrap_or_else(F::zero);
+        let mut weights = Array2::from_elem((records.nrows(), n_components), scale);
+
+        let initial_error = reconstruction_error(records, &weights, &self.components);
+        let mut error = initial_error;
+        for _ in 0..self.max_iterations {
+            update_weights(records, &mut weights, &self.components);
+
+            let new_error = reconstruction_error(records, &weights, &self.components);
+            let converged = error - new_error <= self.tolerance * initial_error;
+            error = new_error;
+
+            if converged {
+                break;
+            }
+        }
+
+        weights
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>> for Nmf<F>
+{
+    fn transform(&self, ds: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let DatasetBase {
+            records,
+            targets,
+            weights,
+            ..
+        } = ds;
+
+        let new_records = self.predict_ref(&records);
+
+        DatasetBase::new(new_records, targets).with_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linfa::{traits::Predict, Dataset};
+    use rand::rngs::SmallRng;
+
+    /// Records which are exact products of three random non-negative factors
+    fn low_rank(rng: &mut SmallRng) -> Array2<f64> {
+        let weights = Array2::random_using((100, 3), Uniform::new(0., 1.), rng);
+        let components = Array2::random_using((3, 10), Uniform::new(0., 1.), rng);
+
+        weights.dot(&components)
+    }
+
+    #[test]
+    fn error_decreases() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::from(low_rank(&mut rng));
+        let norm = dataset.records().mapv(|x| x * x).sum().sqrt();
+
+        for init in &[NmfInit::Random, NmfInit::Nndsvd] {
+            let errors = [1, 10, 100, 1000]
+                .iter()
+                .map(|max_iterations| {
+                    let nmf = Nmf::params(3)
+                        .init(*init)
+                        .max_iterations(*max_iterations)
+                        .tolerance(0.)
+                        .fit(&dataset)
+                        .unwrap();
+                    assert!(nmf.components().iter().all(|x| *x >= 0.));
+
+                    nmf.reconstruction_error()
+                })
+                .collect::<Vec<_>>();
+
+            assert!(errors.windows(2).all(|x| x[1] < x[0]));
+            assert!(errors[3] < 0.05 * norm);
+        }
+    }
+
+    #[test]
+    fn transform_reconstructs() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = low_rank(&mut rng);
+
+        let nmf = Nmf::params(3)
+            .max_iterations(1000)
+            .tolerance(0.)
+            .fit(&Dataset::from(records.clone()))
+            .unwrap();
+
+        let weights = nmf.predict(&records);
+        assert_eq!(weights.dim(), (100, 3));
+        assert!(weights.iter().all(|x| *x >= 0.));
+
+        let error = (nmf.inverse_transform(&weights) - &records)
+            .mapv(|x| x * x)
+            .sum()
+            .sqrt();
+        assert!(error < 0.1 * records.mapv(|x| x * x).sum().sqrt());
+    }
+
+    #[test]
+    fn invalid_params() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::from(low_rank(&mut rng));
+
+        assert!(Nmf::params(0).fit(&dataset).is_err());
+        assert!(Nmf::params(2).max_iterations(0).fit(&dataset).is_err());
+        assert!(Nmf::params(2).tolerance(-1.).fit(&dataset).is_err());
+        assert!(Nmf::params(11).fit(&dataset).is_err());
+        assert!(Nmf::params(11).init(NmfInit::Random).fit(&dataset).is_ok());
+
+        let negative = Dataset::from(-dataset.records());
+        assert!(Nmf::params(2).fit(&negative).is_err());
+
+        for value in &[f64::NAN, f64::INFINITY] {
+            let mut records = dataset.records().clone();
+            records[(3, 2)] = *value;
+            let dataset = Dataset::from(records);
+            assert!(Nmf::params(2).fit(&dataset).is_err());
+            assert!(Nmf::params(2).init(NmfInit::Random).fit(&dataset).is_err());
+        }
+
+        let empty = Dataset::from(Array2::<f64>::zeros((0, 3)));
+        assert!(Nmf::params(1).fit(&empty).is_err());
+    }
+}
@@ -72,10 +72,23 @@ impl<T, D: Data<Elem = f64>> Fit<ArrayBase<D, Ix2>, T, Error> for PcaParams {
     type Object = Pca<f64>;
 
     fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Pca<f64>> {
-        if dataset.nsamples() == 0 {
-            return Err(Error::NotEnoughSamples);
-        }
         let x = dataset.records();
+        x.check_shape(self.embedding_size)?;
+        if self.embedding_size == 0 || self.embedding_size > x.ncols() {
+            return Err(linfa::Error::Parameters(format!(
+                "The embedding size has to be between 1 and the number of features {}, but is {}",
+                x.ncols(),
+                self.embedding_size
+            ))
+            .into());
+        }
+        if x.outer_iter().all(|row| row == x.row(0)) {
+            return Err(linfa::Error::Parameters(
+                "All samples are identical, the data has no variance".to_string(),
+            )
+            .into());
+        }
+
         // calculate mean of data and subtract it
         // safe because of above 0 samples check
         let mean = x.mean_axis(Axis(0)).unwrap();
@@ -198,7 +211,7 @@ mod tests {
     use super::*;
     use approx::assert_abs_diff_eq;
     use linfa::{traits::Predict, Dataset};
-    use ndarray::{array, Array2};
+    use ndarray::{array, s, Array2};
     use ndarray_rand::{
         rand_distr::{StandardNormal, Uniform},
         RandomExt,
@@ -335,6 +348,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_non_contiguous_view() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let data = Array2::random_using((100, 8), Uniform::new(-1.0f64, 1.), &mut rng);
+
+        // every second feature, without copying the data
+        let view = data.slice(s![.., ..;2]);
+        assert!(!view.is_standard_layout());
+        let owned = view.to_owned();
+
+        let from_view = Pca::params(2).fit(&DatasetBase::from(view)).unwrap();
+        let from_owned = Pca::params(2)
+            .fit(&DatasetBase::from(owned.clone()))
+            .unwrap();
+
+        assert_abs_diff_eq!(
+            from_view.explained_variance(),
+            from_owned.explained_variance(),
+            epsilon = 1e-8
+        );
+        // the components are only unique up to their sign
+        assert_abs_diff_eq!(
+            from_view.predict(&view).mapv(f64::abs),
+            from_owned.predict(&owned).mapv(f64::abs),
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_degenerate_records() {
+        let is_parameters_error = |result: Result<Pca<f64>>| {
+            matches!(result, Err(Error::LinfaError(linfa::Error::Parameters(_))))
+        };
+
+        let empty = Dataset::from(Array2::<f64>::zeros((0, 3)));
+        assert!(is_parameters_error(Pca::params(1).fit(&empty)));
+
+        let no_features = Dataset::from(Array2::<f64>::zeros((3, 0)));
+        assert!(is_parameters_error(Pca::params(1).fit(&no_features)));
+
+        let dataset = Dataset::from(array![[0., 1., 2.], [1., 0., 2.]]);
+        assert!(is_parameters_error(Pca::params(0).fit(&dataset)));
+        assert!(is_parameters_error(Pca::params(3).fit(&dataset)));
+        assert!(is_parameters_error(Pca::params(4).fit(&dataset)));
+
+        let dataset = Dataset::from(array![
+            [0., 1., 2.],
+            [1., 0., 2.],
+            [2., 2., 0.],
+            [1., 1., 1.]
+        ]);
+        assert!(Pca::params(2).fit(&dataset).is_ok());
+
+        let identical = Dataset::from(Array2::<f64>::ones((5, 2)));
+        assert!(is_parameters_error(Pca::params(1).fit(&identical)));
+    }
+
     #[test]
     fn test_explained_variance_diag() {
         let dataset = Dataset::from(Array2::from_diag(&array![1., 1., 1., 1.]));
@@ -0,0 +1,348 @@
+//! Probabilistic Principal Component Analysis
+//!
+//! Probabilistic PCA interprets the data as samples of a linear-Gaussian latent variable model
+//! `x = W z + mu + e`, where the latent variable `z` is a low-dimensional standard normal vector
+//! and `e` is isotropic Gaussian noise with variance `sigma^2`. The maximum likelihood solution
+//! has a closed form (Tipping & Bishop, 1999): the columns of `W` span the principal subspace of
+//! the sample covariance and the noise variance is the average variance of the discarded
+//! directions. Unlike standard PCA the model defines a proper density, such that samples can be
+//! scored by their log-likelihood, for example to detect anomalies.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::{Fit, Predict, ScoreSamples};
+//! use linfa_reduction::ProbabilisticPca;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // model the iris dataset with two latent dimensions
+//! let ppca = ProbabilisticPca::params(2)
+//!     .fit(&dataset).unwrap();
+//!
+//! // log-likelihood of every sample and posterior mean of the latent variables
+//! let log_likelihood = ppca.score_samples(dataset.records());
+//! let embedding = ppca.predict(dataset.records());
+//! ```
+//!
+use crate::error::{Error, Result};
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use ndarray_linalg::{eigh::EighInto, Scalar, UPLO};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use linfa::{
+    dataset::{Records, WithLapack, WithoutLapack},
+    traits::{Fit, PredictRef, ScoreSamples, Transformer},
+    DatasetBase, Float,
+};
+
+/// Probabilistic principal component analysis parameters
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbabilisticPcaParams {
+    n_components: usize,
+}
+
+/// Fit a probabilistic PCA model given a dataset
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with records in N dimensions
+///
+/// # Returns
+///
+/// The maximum likelihood estimate of the mean, principal subspace and noise variance
+impl<F: Float, T, D: Data<Elem = F>> Fit<ArrayBase<D, Ix2>, T, Error> for ProbabilisticPcaParams {
+    type Object = ProbabilisticPca<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<ProbabilisticPca<F>> {
+        let x = dataset.records();
+        x.check_shape(1)?;
+
+        // at least one discarded direction is needed to estimate the noise
+        let nfeatures = x.nfeatures();
+        if self.n_components == 0 || self.n_components >= nfeatures {
+            return Err(linfa::Error::Parameters(format!(
+                "number of components should be between 1 and {}, but is {}",
+                nfeatures - 1,
+                self.n_components
+            ))
+            .into());
+        }
+
+        // maximum likelihood estimate of the covariance
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        let centered = x - &mean;
+        let covariance = centered.t().dot(&centered) / F::cast(x.nsamples());
+
+        // eigenvalues are returned in ascending order, reverse them
+        let (vals, vecs) = covariance.with_lapack().eigh_into(UPLO::Lower)?;
+        let vals: Array1<F> = vals
+            .slice_move(s![..; -1])
+            .mapv(Scalar::from_real)
+            .without_lapack();
+        let vecs: Array2<F> = vecs.slice_move(s![.., ..; -1]).without_lapack();
+
+        // the variance of the discarded directions is attributed to the noise
+        let noise_variance = vals.slice(s![self.n_components..]).mean().unwrap();
+        if noise_variance <= F::zero() {
+            return Err(linfa::Error::Parameters(format!(
+                "the data has no variance outside of the first {} principal directions",
+                self.n_components
+            ))
+            .into());
+        }
+
+        Ok(ProbabilisticPca {
+            mean,
+            components: vecs.slice_move(s![.., ..self.n_components]).reversed_axes(),
+            explained_variance: vals.slice_move(s![..self.n_components]),
+            noise_variance,
+        })
+    }
+}
+
+/// Fitted probabilistic principal component analysis model
+///
+/// The model contains the mean, the principal directions with their variance and the variance of
+/// the isotropic noise. The data covariance is modelled as `W W^T + sigma^2 I` with the loadings
+/// `W = U (L - sigma^2 I)^(1/2)`, where `U` are the principal directions and `L` their variance.
+///
+/// Predicting returns the posterior mean of the latent variables for every sample.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct ProbabilisticPca<F> {
+    mean: Array1<F>,
+    components: Array2<F>,
+    explained_variance: Array1<F>,
+    noise_variance: F,
+}
+
+impl ProbabilisticPca<f64> {
+    /// Create default parameter set
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the dimensionality of the latent variables, has to be smaller than the
+    ///  number of features
+    pub fn params(n_components: usize) -> ProbabilisticPcaParams {
+        ProbabilisticPcaParams { n_components }
+    }
+}
+
+impl<F: Float> ProbabilisticPca<F> {
+    /// Return the mean of the training records
+    pub fn mean(&self) -> &Array1<F> {
+        &self.mean
+    }
+
+    /// Return the principal directions with shape `(n_components, n_features)`
+    pub fn components(&self) -> &Array2<F> {
+        &self.components
+    }
+
+    /// Return the variance of the training records along each principal direction
+    pub fn explained_variance(&self) -> &Array1<F> {
+        &self.explained_variance
+    }
+
+    /// Return the estimated variance of the isotropic noise
+    pub fn noise_variance(&self) -> F {
+        self.noise_variance
+    }
+
+    /// Return the covariance `W W^T + sigma^2 I` of the model
+    pub fn covariance(&self) -> Array2<F> {
+        let scale = self
+            .explained_variance
+            .mapv(|x| x - self.noise_variance)
+            .insert_axis(Axis(1));
+        let mut covariance = self.components.t().dot(&(&self.components * &scale));
+        covariance
+            .diag_mut()
+            .mapv_inplace(|x| x + self.noise_variance);
+
+        covariance
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> ScoreSamples<ArrayBase<D, Ix2>, F> for ProbabilisticPca<F> {
+    /// Log-likelihood of every sample under the model
+    ///
+    /// The precision matrix and the determinant of the covariance are computed from the
+    /// principal directions, without inverting the full covariance matrix. Samples far from the
+    /// principal subspace have a low log-likelihood.
+    fn score_samples(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let nfeatures = self.mean.len();
+        let centered = x - &self.mean;
+        let projected = centered.dot(&self.components.t());
+
+        // the precision matrix is (I - U diag(1 - sigma^2 / l) U^T) / sigma^2
+        let shrinkage = self
+            .explained_variance
+            .mapv(|x| (x - self.noise_variance) / x);
+        let mahalanobis = (centered.mapv(|x| x * x).sum_axis(Axis(1))
+            - (projected.mapv(|x| x * x) * &shrinkage).sum_axis(Axis(1)))
+            / self.noise_variance;
+
+        let log_det = self.explained_variance.mapv(|x| x.ln()).sum()
+            + F::cast(nfeatures - self.explained_variance.len()) * self.noise_variance.ln();
+        let constant = F::cast(nfeatures) * F::cast(2. * std::f64::consts::PI).ln() + log_det;
+
+        mahalanobis.mapv(|x| F::cast(-0.5) * (constant + x))
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>> for ProbabilisticPca<F> {
+    /// Posterior mean `(W^T W + sigma^2 I)^-1 W^T (x - mu)` of the latent variables
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let scale = self
+            .explained_variance
+            .mapv(|x| (x - self.noise_variance).sqrt() / x);
+
+        (records - &self.mean).dot(&self.components.t()) * &scale
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>>
+    for ProbabilisticPca<F>
+{
+    fn transform(&self, ds: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let DatasetBase {
+            records,
+            targets,
+            weights,
+            ..
+        } = ds;
+
+        let new_records = self.predict_ref(&records);
+
+        DatasetBase::new(new_records, targets).with_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use linfa::{traits::Predict, Dataset};
+    use ndarray::{array, Array2};
+    use ndarray_linalg::{Determinant, Inverse};
+    use ndarray_rand::{
+        rand_distr::{StandardNormal, Uniform},
+        RandomExt,
+    };
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    /// Samples of a two-dimensional latent variable embedded into five dimensions, with isotropic
+    /// noise of standard deviation 0.5
+    fn low_rank_data(nsamples: usize, rng: &mut SmallRng) -> Array2<f64> {
+        let loadings = array![[2., 1., 0., -1., 0.5], [0., 1., 2., 1., -1.5]];
+        let latent = Array2::random_using((nsamples, 2), StandardNormal, rng);
+        let noise = Array2::random_using((nsamples, 5), StandardNormal, rng) * 0.5;
+
+        latent.dot(&loadings) + noise + &array![1., -2., 0., 3., 0.5]
+    }
+
+    #[test]
+    fn noise_variance_matches_injected_noise() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::from(low_rank_data(5000, &mut rng));
+
+        let ppca = ProbabilisticPca::params(2).fit(&dataset).unwrap();
+        assert_eq!(ppca.components().dim(), (2, 5));
+        assert_abs_diff_eq!(ppca.noise_variance(), 0.25, epsilon = 0.02);
+        assert_abs_diff_eq!(ppca.mean(), &array![1., -2., 0., 3., 0.5], epsilon = 0.1);
+
+        // the principal directions are orthonormal
+        assert_abs_diff_eq!(
+            ppca.components().dot(&ppca.components().t()),
+            Array2::eye(2),
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn score_samples_matches_gaussian_density() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let data = Array2::random_using((200, 4), Uniform::new(-1.0f64, 1.), &mut rng);
+        let ppca = ProbabilisticPca::params(1)
+            .fit(&Dataset::from(data.clone()))
+            .unwrap();
+
+        // evaluate the density with the full covariance matrix
+        let covariance = ppca.covariance();
+        let precision = covariance.inv().unwrap();
+        let log_det = covariance.det().unwrap().ln();
+        let expected = data
+            .outer_iter()
+            .map(|x| {
+                let centered = &x - ppca.mean();
+                -0.5 * (4. * (2. * std::f64::consts::PI).ln()
+                    + log_det
+                    + centered.dot(&precision.dot(&centered)))
+            })
+            .collect::<Array1<_>>();
+
+        assert_abs_diff_eq!(ppca.score_samples(&data), expected, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn outliers_have_low_likelihood() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let data = low_rank_data(1000, &mut rng);
+        let ppca = ProbabilisticPca::params(2)
+            .fit(&Dataset::from(data.clone()))
+            .unwrap();
+
+        // a sample at the mean is more likely than any training sample, one far off the principal
+        // subspace less likely
+        let mean = ppca.mean().clone().insert_axis(Axis(0));
+        let outlier =
+            &mean + &(ppca.components().row(0).to_owned() * 3.) + &array![[10., 0., 0., 0., 0.]];
+        let scores = ppca.score_samples(&data);
+        assert!(scores.iter().all(|x| *x < ppca.score_samples(&mean)[0]));
+        assert!(scores.iter().all(|x| *x > ppca.score_samples(&outlier)[0]));
+    }
+
+    #[test]
+    fn posterior_mean_of_latent_variables() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::from(low_rank_data(1000, &mut rng));
+        let ppca = ProbabilisticPca::params(2).fit(&dataset).unwrap();
+
+        let embedding = ppca.predict(dataset.records());
+        assert_eq!(embedding.dim(), (1000, 2));
+        // the mean of the posterior means is the prior mean
+        assert_abs_diff_eq!(
+            embedding.mean_axis(Axis(0)).unwrap(),
+            array![0., 0.],
+            epsilon = 1e-8
+        );
+
+        let transformed = ppca.transform(dataset);
+        assert_abs_diff_eq!(transformed.records(), &embedding);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = Dataset::from(Array2::random((10, 3), Uniform::new(-1.0f64, 1.)));
+
+        assert!(ProbabilisticPca::params(0).fit(&dataset).is_err());
+        assert!(ProbabilisticPca::params(3).fit(&dataset).is_err());
+        assert!(ProbabilisticPca::params(2).fit(&dataset).is_ok());
+
+        let empty = Dataset::from(Array2::<f64>::zeros((0, 3)));
+        assert!(ProbabilisticPca::params(1).fit(&empty).is_err());
+    }
+}
@@ -0,0 +1,470 @@
+//! Sparse Principal Component Analysis
+//!
+//! The components of a Principal Component Analysis are dense combinations of all features,
+//! which makes them hard to interpret. Sparse PCA adds an L1 penalty on the loadings of the
+//! components, such that each component depends only on a few features. The centered records `X`
+//! are factorized into a code `U` with unit-norm columns and components `V` by minimizing
+//!
+//! ```text
+//! 0.5 * ||X - U V||^2 + alpha * ||V||_1
+//! ```
+//!
+//! The problem is solved by alternating between the lasso problems of the components, solved with
+//! the coordinate descent of `linfa-elasticnet`, and a block update of the code, starting from the
+//! principal components. For `alpha = 0` the result is equivalent to PCA.
+//!
+//! # Example
+//!
+//! ```
+//! use linfa::traits::{Fit, Predict};
+//! use linfa_reduction::SparsePca;
+//!
+//! let dataset = linfa_datasets::iris();
+//!
+//! // find two components with sparse loadings
+//! let spca = SparsePca::params(2)
+//!     .alpha(10.0)
+//!     .fit(&dataset).unwrap();
+//!
+//! let loadings = spca.components();
+//! let embedding = spca.predict(dataset.records());
+//! ```
+//!
+use crate::error::{Error, Result};
+use linfa_elasticnet::ElasticNet;
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2};
+use ndarray_linalg::{eigh::EighInto, Inverse, Scalar, UPLO};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use linfa::{
+    dataset::{Records, WithLapack, WithoutLapack},
+    traits::{Fit, PredictRef, Transformer},
+    DatasetBase, Float,
+};
+
+/// Maximal number of coordinate descent sweeps per update of the components
+const MAX_LASSO_ITERATIONS: u32 = 100;
+
+/// Sparse principal component analysis parameters
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparsePcaParams {
+    n_components: usize,
+    alpha: f64,
+    ridge_alpha: f64,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl SparsePcaParams {
+    /// Set the L1 penalty of the loadings
+    ///
+    /// Larger values lead to more zero loadings. As the reconstruction error is summed over all
+    /// samples, the penalty has to grow with the number of samples to have the same effect.
+    /// Defaults to `1.0`.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+
+        self
+    }
+
+    /// Set the ridge penalty used when projecting records onto the components
+    ///
+    /// As the sparse components are in general not orthogonal, records are embedded by a ridge
+    /// regression on the components. The penalty keeps the projection well-defined if
+    /// components are (nearly) collinear or vanished completely. Defaults to `0.01`.
+    pub fn ridge_alpha(mut self, ridge_alpha: f64) -> Self {
+        self.ridge_alpha = ridge_alpha;
+
+        self
+    }
+
+    /// Set the maximal number of alternating updates of code and components
+    ///
+    /// Defaults to `1000`.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    /// Set the tolerance of the relative change of the objective
+    ///
+    /// Defaults to `1e-8`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    /// Validates the parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.n_components == 0 {
+            return Err(linfa::Error::Parameters(
+                "number of components should be larger than zero".into(),
+            )
+            .into());
+        }
+        if !self.alpha.is_finite() || self.alpha < 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "alpha should be positive and finite, but is {}",
+                self.alpha
+            ))
+            .into());
+        }
+        if !self.ridge_alpha.is_finite() || self.ridge_alpha < 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "ridge alpha should be positive and finite, but is {}",
+                self.ridge_alpha
+            ))
+            .into());
+        }
+        if self.max_iterations == 0 {
+            return Err(linfa::Error::Parameters(
+                "maximal number of iterations should be larger than zero".into(),
+            )
+            .into());
+        }
+        if self.tolerance.is_nan() || self.tolerance <= 0. {
+            return Err(linfa::Error::Parameters(format!(
+                "tolerance should be positive, but is {}",
+                self.tolerance
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Fit a sparse PCA model given a dataset
+///
+/// # Parameters
+///
+/// * `dataset`: A dataset with records in N dimensions
+///
+/// # Returns
+///
+/// A fitted sparse PCA model with mean and sparse components
+impl<F: Float, T, D: Data<Elem = F>> Fit<ArrayBase<D, Ix2>, T, Error> for SparsePcaParams {
+    type Object = SparsePca<F>;
+
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<SparsePca<F>> {
+        self.validate()?;
+
+        let x = dataset.records();
+        x.check_shape(1)?;
+        if self.n_components > x.nfeatures() {
+            return Err(linfa::Error::Parameters(format!(
+                "number of components {} exceeds number of features {}",
+                self.n_components,
+                x.nfeatures()
+            ))
+            .into());
+        }
+
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        let x = x - &mean;
+
+        // start with the principal components, which are the solution for `alpha = 0`
+        let (vals, vecs) = x.t().dot(&x).with_lapack().eigh_into(UPLO::Lower)?;
+        let vals: Array1<F> = vals
+            .slice_move(s![..; -1])
+            .mapv(Scalar::from_real)
+            .without_lapack();
+        let vecs: Array2<F> = vecs.slice_move(s![.., ..; -1]).without_lapack();
+        let vecs = vecs.slice_move(s![.., ..self.n_components]);
+
+        let singular_values = vals
+            .slice_move(s![..self.n_components])
+            .mapv(|x| x.max(F::zero()).sqrt());
+        let mut components = &vecs.t() * &singular_values.view().insert_axis(Axis(1));
+        let mut code = x.dot(&vecs);
+        for (mut atom, value) in code
+            .gencolumns_mut()
+            .into_iter()
+            .zip(singular_values.iter())
+        {
+            if *value > F::zero() {
+                atom /= *value;
+            } else {
+                atom.fill(F::zero());
+            }
+        }
+
+        sparse_factorization(
+            &x,
+            &mut code,
+            &mut components,
+            F::cast(self.alpha),
+            self.max_iterations,
+            F::cast(self.tolerance),
+        )?;
+
+        // normalize the loadings, components which vanished completely remain zero
+        for mut component in components.genrows_mut() {
+            let norm = component.dot(&component).sqrt();
+            if norm > F::zero() {
+                component /= norm;
+            }
+        }
+
+        // records are projected onto the components with a slightly regularized least squares
+        let mut gram = components.dot(&components.t());
+        gram.diag_mut()
+            .mapv_inplace(|x| x + F::cast(self.ridge_alpha));
+        let projection = components
+            .t()
+            .dot(&gram.with_lapack().inv()?.without_lapack());
+
+        Ok(SparsePca {
+            mean,
+            components,
+            projection,
+        })
+    }
+}
+
+/// Minimize `0.5 * ||X - U V||^2 + alpha * ||V||_1`, with unit-norm columns of the code `U`
+///
+/// The components `V` and the code are updated alternately, starting from the given values, until
+/// the relative change of the objective falls below the tolerance.
+fn sparse_factorization<F: Float>(
+    x: &Array2<F>,
+    code: &mut Array2<F>,
+    components: &mut Array2<F>,
+    alpha: F,
+    max_iterations: usize,
+    tolerance: F,
+) -> Result<()> {
+    // the elastic net scales the squared error by the number of samples
+    let lasso = ElasticNet::lasso()
+        .with_intercept(false)
+        .penalty(alpha / F::cast(x.nrows()))
+        .tolerance(tolerance)
+        .max_iterations(MAX_LASSO_ITERATIONS);
+
+    let mut objective: Option<F> = None;
+    for _ in 0..max_iterations {
+        // the lasso problems `0.5 * ||x_j - U v_j||^2 + alpha * ||v_j||_1` of the features are
+        // independent and warm-started from the current components
+        for (feature, mut loadings) in x.gencolumns().into_iter().zip(components.gencolumns_mut()) {
+            let (new_loadings, _) = lasso.solve(code.view(), feature, loadings.to_owned())?;
+            loadings.assign(&new_loadings);
+        }
+        let residual = update_code(x, code, components);
+
+        let new_objective = F::cast(0.5) * residual.mapv(|x| x * x).sum()
+            + alpha * components.mapv(|x| x.abs()).sum();
+        let converged = objective
+            .map(|objective| (objective - new_objective).abs() <= tolerance * objective.abs())
+            .unwrap_or(false);
+        objective = Some(new_objective);
+
+        if converged {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the code one column at a time, keeping every column at unit norm, and return the
+/// residual `X - U V`
+fn update_code<F: Float>(x: &Array2<F>, code: &mut Array2<F>, components: &Array2<F>) -> Array2<F> {
+    let outer =
+        |a: ArrayView1<F>, b: ArrayView1<F>| a.insert_axis(Axis(1)).dot(&b.insert_axis(Axis(0)));
+
+    let mut residual = x - &code.dot(components);
+    for (mut atom, component) in code.gencolumns_mut().into_iter().zip(components.genrows()) {
+        // the atom of a vanished component cannot be updated
+        if component.dot(&component) <= F::zero() {
+            continue;
+        }
+
+        residual += &outer(atom.view(), component);
+        let new_atom = residual.dot(&component);
+        let norm = new_atom.dot(&new_atom).sqrt();
+        if norm > F::zero() {
+            atom.assign(&(new_atom / norm));
+        }
+        residual -= &outer(atom.view(), component);
+    }
+
+    residual
+}
+
+/// Fitted sparse principal component analysis model
+///
+/// The model contains the mean of the training records and the components with unit norm.
+/// Predicting projects the centered records onto the components by ridge regression, as the
+/// sparse components are in general not orthogonal.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone)]
+pub struct SparsePca<F> {
+    mean: Array1<F>,
+    components: Array2<F>,
+    projection: Array2<F>,
+}
+
+impl SparsePca<f64> {
+    /// Create default parameter set
+    ///
+    /// # Parameters
+    ///
+    ///  * `n_components`: the target dimensionality
+    pub fn params(n_components: usize) -> SparsePcaParams {
+        SparsePcaParams {
+            n_components,
+            alpha: 1.0,
+            ridge_alpha: 0.01,
+            max_iterations: 1000,
+            tolerance: 1e-8,
+        }
+    }
+}
+
+impl<F: Float> SparsePca<F> {
+    /// Return the mean of the training records
+    pub fn mean(&self) -> &Array1<F> {
+        &self.mean
+    }
+
+    /// Return the sparse components with shape `(n_components, n_features)`
+    pub fn components(&self) -> &Array2<F> {
+        &self.components
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<F>> for SparsePca<F> {
+    fn predict_ref(&self, records: &ArrayBase<D, Ix2>) -> Array2<F> {
+        (records - &self.mean).dot(&self.projection)
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T>
+    Transformer<DatasetBase<ArrayBase<D, Ix2>, T>, DatasetBase<Array2<F>, T>> for SparsePca<F>
+{
+    fn transform(&self, ds: DatasetBase<ArrayBase<D, Ix2>, T>) -> DatasetBase<Array2<F>, T> {
+        let DatasetBase {
+            records,
+            targets,
+            weights,
+            ..
+        } = ds;
+
+        let new_records = self.predict_ref(&records);
+
+        DatasetBase::new(new_records, targets).with_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pca;
+    use approx::assert_abs_diff_eq;
+    use linfa::{traits::Predict, Dataset};
+    use ndarray_rand::{
+        rand_distr::{StandardNormal, Uniform},
+        RandomExt,
+    };
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    /// Three latent factors of different strength, each driving a block of three features
+    fn block_data(rng: &mut SmallRng) -> Array2<f64> {
+        let latent =
+            Array2::<f64>::random_using((300, 3), StandardNormal, rng) * &array![2., 1.5, 1.];
+        let noise = Array2::<f64>::random_using((300, 9), StandardNormal, rng) * 0.3;
+
+        Array2::from_shape_fn((300, 9), |(i, j)| latent[(i, j / 3)]) + noise
+    }
+
+    /// Fraction of the variance retained when reconstructing the records from the embedding
+    fn explained_variance(model: &SparsePca<f64>, records: &Array2<f64>) -> f64 {
+        let centered = records - model.mean();
+        let reconstructed = model.predict(records).dot(model.components());
+
+        1. - (&centered - &reconstructed).mapv(|x| x * x).sum() / centered.mapv(|x| x * x).sum()
+    }
+
+    #[test]
+    fn alpha_increases_sparsity() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = block_data(&mut rng);
+        let dataset = Dataset::from(records.clone());
+
+        let zeros = [0., 0.2, 1., 5.]
+            .iter()
+            .map(|&alpha| {
+                let spca = SparsePca::params(3).alpha(alpha).fit(&dataset).unwrap();
+                assert!(explained_variance(&spca, &records) > 0.95);
+
+                spca.components().iter().filter(|x| **x == 0.).count()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(zeros[0], 0);
+        assert!(zeros[0] < zeros[1]);
+        assert!(zeros[1] < zeros[2]);
+        assert!(zeros[2] <= zeros[3]);
+
+        // with a strong penalty every component loads on a single block of features
+        let spca = SparsePca::params(3).alpha(5.).fit(&dataset).unwrap();
+        for component in spca.components().genrows() {
+            let blocks = component
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| **x != 0.)
+                .map(|(j, _)| j / 3)
+                .collect::<Vec<_>>();
+            assert!(!blocks.is_empty());
+            assert!(blocks.iter().all(|block| *block == blocks[0]));
+        }
+    }
+
+    #[test]
+    fn without_penalty_matches_pca() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let records = Array2::random_using((100, 4), Uniform::new(-1.0f64, 1.), &mut rng)
+            * &array![3., 2., 1., 0.5];
+        let dataset = Dataset::from(records);
+
+        let spca = SparsePca::params(2).alpha(0.).fit(&dataset).unwrap();
+        let pca = Pca::params(2).fit(&dataset).unwrap();
+
+        // the components are only determined up to their sign
+        let pca_components =
+            (pca.predict(&Array2::eye(4)) - pca.predict(&Array2::zeros((1, 4)))).reversed_axes();
+        let overlap = spca.components().dot(&pca_components.t());
+        assert_abs_diff_eq!(overlap.mapv(f64::abs), Array2::eye(2), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dataset = Dataset::from(Array2::random((10, 3), Uniform::new(-1.0f64, 1.)));
+
+        assert!(SparsePca::params(0).fit(&dataset).is_err());
+        assert!(SparsePca::params(4).fit(&dataset).is_err());
+        assert!(SparsePca::params(2).alpha(-1.).fit(&dataset).is_err());
+        assert!(SparsePca::params(2)
+            .ridge_alpha(f64::NAN)
+            .fit(&dataset)
+            .is_err());
+        assert!(SparsePca::params(2).tolerance(0.).fit(&dataset).is_err());
+        assert!(SparsePca::params(2)
+            .max_iterations(0)
+            .fit(&dataset)
+            .is_err());
+        assert!(SparsePca::params(2).fit(&dataset).is_ok());
+    }
+}
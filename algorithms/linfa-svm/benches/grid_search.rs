@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use linfa::prelude::*;
+use linfa_kernel::{Gamma, Kernel, KernelMethod};
+use linfa_svm::Svm;
+use ndarray::{Array1, Array2};
+use ndarray_rand::rand::SeedableRng;
+use ndarray_rand::rand_distr::StandardNormal;
+use ndarray_rand::RandomExt;
+use rand_isaac::Isaac64Rng;
+
+/// Penalty values tried in every grid search
+const PENALTIES: [f64; 5] = [0.01, 0.1, 1.0, 10.0, 100.0];
+
+fn grid_search_bench(c: &mut Criterion) {
+    let mut rng = Isaac64Rng::seed_from_u64(42);
+    let n_features = 10;
+
+    let mut group = c.benchmark_group("svm_grid_search");
+    group.sample_size(10);
+
+    for n in &[100, 500, 1000] {
+        let records = Array2::random_using((*n, n_features), StandardNormal, &mut rng);
+        let targets = records
+            .outer_iter()
+            .map(|x| x.dot(&x) > n_features as f64)
+            .collect::<Array1<_>>();
+        let dataset = Dataset::new(records, targets);
+
+        group.bench_with_input(BenchmarkId::new("recompute", n), &dataset, |b, d| {
+            b.iter(|| {
+                for c in &PENALTIES {
+                    Svm::<_, bool>::params()
+                        .pos_neg_weights(*c, *c)
+                        .rbf_kernel(Gamma::Scale)
+                        .fit(d)
+                        .unwrap();
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("reuse_kernel", n), &dataset, |b, d| {
+            b.iter(|| {
+                let kernel = Kernel::params()
+                    .method(KernelMethod::Rbf(Gamma::Scale))
+                    .transform(d.records());
+
+                for c in &PENALTIES {
+                    Svm::<_, bool>::params()
+                        .pos_neg_weights(*c, *c)
+                        .fit_with_kernel(d, &kernel)
+                        .unwrap();
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, grid_search_bench);
+criterion_main!(benches);
@@ -2,6 +2,7 @@ use linfa::prelude::Transformer;
 use linfa::{
     composing::platt_scaling::{platt_newton_method, platt_predict, PlattParams},
     dataset::{AsTargets, CountedTargets, DatasetBase, Pr},
+    metrics::{Accuracy, DefaultMetric},
     traits::Fit,
     traits::{Predict, PredictRef},
 };
@@ -13,7 +14,7 @@ use super::permutable_kernel::{PermutableKernel, PermutableKernelOneClass};
 use super::solver_smo::SolverState;
 use super::SolverParams;
 use super::{Float, Svm, SvmParams};
-use linfa_kernel::Kernel;
+use linfa_kernel::{Kernel, KernelView};
 
 fn calibrate_with_platt<F: Float, D: Data<Elem = F>, T: AsTargets<Elem = bool>>(
     mut obj: Svm<F, F>,
@@ -51,7 +52,7 @@ fn calibrate_with_platt<F: Float, D: Data<Elem = F>, T: AsTargets<Elem = bool>>(
 pub fn fit_c<F: Float>(
     params: SolverParams<F>,
     dataset: ArrayView2<F>,
-    kernel: Kernel<F>,
+    kernel: KernelView<F>,
     targets: &[bool],
     cpos: F,
     cneg: F,
@@ -104,7 +105,7 @@ pub fn fit_c<F: Float>(
 pub fn fit_nu<F: Float>(
     params: SolverParams<F>,
     dataset: ArrayView2<F>,
-    kernel: Kernel<F>,
+    kernel: KernelView<F>,
     targets: &[bool],
     nu: F,
 ) -> Svm<F, F> {
@@ -168,7 +169,7 @@ pub fn fit_nu<F: Float>(
 pub fn fit_one_class<F: Float + num_traits::ToPrimitive>(
     params: SolverParams<F>,
     dataset: ArrayView2<F>,
-    kernel: Kernel<F>,
+    kernel: KernelView<F>,
     nu: F,
 ) -> Svm<F, F> {
     let size = kernel.size();
@@ -198,6 +199,89 @@ pub fn fit_one_class<F: Float + num_traits::ToPrimitive>(
     solver.solve()
 }
 
+/// Solve a binary classification problem with either the C or Nu formulation
+fn fit_binary<F: Float, P, D: Data<Elem = F>, T: AsTargets<Elem = bool>>(
+    params: &SvmParams<F, P>,
+    dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+    kernel: KernelView<F>,
+) -> Result<Svm<F, F>> {
+    let target = dataset.try_single_target()?;
+    let target = target.as_slice().unwrap();
+
+    let ret = match (params.c, params.nu) {
+        (Some((c_p, c_n)), _) => fit_c(
+            params.solver_params.clone(),
+            dataset.records().view(),
+            kernel,
+            target,
+            c_p,
+            c_n,
+        ),
+        (None, Some((nu, _))) => fit_nu(
+            params.solver_params.clone(),
+            dataset.records().view(),
+            kernel,
+            target,
+            nu,
+        ),
+        _ => panic!("Set either C value or Nu value"),
+    };
+
+    Ok(ret)
+}
+
+/// Check that a precomputed kernel matrix matches the samples of a dataset
+fn check_kernel_size<F: Float, D: Data<Elem = F>, T>(
+    dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+    kernel: &Kernel<F>,
+) -> Result<()> {
+    let nsamples = dataset.records().nrows();
+    if kernel.size() != nsamples {
+        return Err(linfa::Error::MismatchedShapes(kernel.size(), nsamples).into());
+    }
+
+    Ok(())
+}
+
+impl<F: Float> SvmParams<F, Pr> {
+    /// Fit a binary classifier with a precomputed kernel matrix
+    ///
+    /// The kernel matrix has to be computed from the records of `dataset`, for example with
+    /// `Kernel::params().transform(dataset.records())`. It is only borrowed, so the same matrix can
+    /// be reused when searching for the best penalty parameters, and only the dual optimization is
+    /// repeated for every fit. The kernel parameters of `self` are ignored and the inner product of
+    /// `kernel` is used for predictions instead.
+    ///
+    /// Fails if the size of the kernel matrix does not match the number of samples.
+    pub fn fit_with_kernel<D: Data<Elem = F>, T: AsTargets<Elem = bool>>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+        kernel: &Kernel<F>,
+    ) -> Result<Svm<F, Pr>> {
+        check_kernel_size(dataset, kernel)?;
+        let ret = fit_binary(self, dataset, kernel.view())?;
+
+        calibrate_with_platt(ret, &self.platt, dataset)
+    }
+}
+
+impl<F: Float> SvmParams<F, bool> {
+    /// Fit a binary classifier with a precomputed kernel matrix
+    ///
+    /// See [`SvmParams::<F, Pr>::fit_with_kernel`](struct.SvmParams.html#method.fit_with_kernel),
+    /// the returned model predicts boolean labels instead of probabilities.
+    pub fn fit_with_kernel<D: Data<Elem = F>, T: AsTargets<Elem = bool>>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+        kernel: &Kernel<F>,
+    ) -> Result<Svm<F, bool>> {
+        check_kernel_size(dataset, kernel)?;
+        let ret = fit_binary(self, dataset, kernel.view())?;
+
+        Ok(ret.with_phantom())
+    }
+}
+
 /// Fit binary classification problem
 ///
 /// For a given dataset with kernel matrix as records and two class problem as targets this fits
@@ -210,27 +294,7 @@ macro_rules! impl_classification {
 
             fn fit(&self, dataset: &DatasetBase<$records, $targets>) -> Result<Self::Object> {
                 let kernel = self.kernel.transform(dataset.records());
-                let target = dataset.try_single_target()?;
-                let target = target.as_slice().unwrap();
-
-                let ret = match (self.c, self.nu) {
-                    (Some((c_p, c_n)), _) => fit_c(
-                        self.solver_params.clone(),
-                        dataset.records().view(),
-                        kernel,
-                        target,
-                        c_p,
-                        c_n,
-                    ),
-                    (None, Some((nu, _))) => fit_nu(
-                        self.solver_params.clone(),
-                        dataset.records().view(),
-                        kernel,
-                        target,
-                        nu,
-                    ),
-                    _ => panic!("Set either C value or Nu value"),
-                };
+                let ret = fit_binary(self, dataset, kernel.view())?;
 
                 calibrate_with_platt(ret, &self.platt, dataset)
             }
@@ -241,27 +305,7 @@ macro_rules! impl_classification {
 
             fn fit(&self, dataset: &DatasetBase<$records, $targets>) -> Result<Self::Object> {
                 let kernel = self.kernel.transform(dataset.records());
-                let target = dataset.try_single_target()?;
-                let target = target.as_slice().unwrap();
-
-                let ret = match (self.c, self.nu) {
-                    (Some((c_p, c_n)), _) => fit_c(
-                        self.solver_params.clone(),
-                        dataset.records().view(),
-                        kernel,
-                        target,
-                        c_p,
-                        c_n,
-                    ),
-                    (None, Some((nu, _))) => fit_nu(
-                        self.solver_params.clone(),
-                        dataset.records().view(),
-                        kernel,
-                        target,
-                        nu,
-                    ),
-                    _ => panic!("Set either C value or Nu value"),
-                };
+                let ret = fit_binary(self, dataset, kernel.view())?;
 
                 Ok(ret.with_phantom())
             }
@@ -289,7 +333,9 @@ macro_rules! impl_oneclass {
                 let records = dataset.records().view();
 
                 let ret = match self.nu {
-                    Some((nu, _)) => fit_one_class(self.solver_params.clone(), records, kernel, nu),
+                    Some((nu, _)) => {
+                        fit_one_class(self.solver_params.clone(), records, kernel.view(), nu)
+                    }
                     None => panic!("One class needs Nu value"),
                 };
 
@@ -350,12 +396,8 @@ impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<Pr>> for
     fn predict_ref(&self, data: &ArrayBase<D, Ix2>) -> Array1<Pr> {
         let (a, b) = self.probability_coeffs.unwrap();
 
-        data.outer_iter()
-            .map(|data| {
-                let val = self.weighted_sum(&data) - self.rho;
-                platt_predict(val, a, b)
-            })
-            .collect()
+        self.decision_function(data)
+            .mapv(|val| platt_predict(val, a, b))
     }
 }
 
@@ -365,15 +407,13 @@ impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<Pr>> for
 /// the positive class.
 impl<F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1<bool>> for Svm<F, bool> {
     fn predict_ref(&self, data: &ArrayBase<D, Ix2>) -> Array1<bool> {
-        data.outer_iter()
-            .map(|data| {
-                let val = self.weighted_sum(&data) - self.rho;
-
-                val >= F::zero()
-            })
-            .collect()
+        self.decision_function(data).mapv(|val| val >= F::zero())
     }
 }
+
+impl<F: Float> DefaultMetric for Svm<F, bool> {
+    type Metric = Accuracy;
+}
 #[cfg(test)]
 mod tests {
     use super::Svm;
@@ -381,9 +421,10 @@ mod tests {
     use approx::assert_abs_diff_eq;
     use linfa::dataset::{Dataset, DatasetBase};
     use linfa::prelude::ToConfusionMatrix;
-    use linfa::traits::{Fit, Predict};
+    use linfa::traits::{Fit, Predict, Transformer};
+    use linfa_kernel::{Gamma, Kernel, KernelMethod, Nystroem};
 
-    use ndarray::{Array, Array1, Array2, Axis};
+    use ndarray::{s, Array, Array1, Array2, Axis};
     use ndarray_rand::rand::SeedableRng;
     use ndarray_rand::rand_distr::Uniform;
     use ndarray_rand::RandomExt;
@@ -408,6 +449,29 @@ mod tests {
         out
     }
 
+    pub fn generate_two_moons(
+        n_points: usize,
+        rng: &mut Isaac64Rng,
+    ) -> (Array2<f64>, Array1<bool>) {
+        let phi = Array::random_using(n_points * 2, Uniform::new(0., std::f64::consts::PI), rng);
+        let noise = Array::random_using((n_points * 2, 2), Uniform::new(-0.1, 0.1), rng);
+
+        let mut records = Array2::zeros((n_points * 2, 2));
+        for (i, (mut row, phi)) in records.outer_iter_mut().zip(phi.iter()).enumerate() {
+            if i < n_points {
+                row[0] = phi.cos();
+                row[1] = phi.sin();
+            } else {
+                row[0] = 1.0 - phi.cos();
+                row[1] = 0.5 - phi.sin();
+            }
+        }
+
+        let targets = (0..n_points * 2).map(|x| x < n_points).collect();
+
+        (records + noise, targets)
+    }
+
     #[test]
     fn test_linear_classification() -> Result<()> {
         let entries: Array2<f64> = ndarray::concatenate(
@@ -443,6 +507,13 @@ mod tests {
         let cm = valid.confusion_matrix(&dataset)?;
         assert_abs_diff_eq!(cm.accuracy(), 1.0);
 
+        // predictions are thresholded values of the decision function
+        let decision = model.decision_function(dataset.records());
+        assert_eq!(decision.len(), 20);
+        assert_eq!(decision.mapv(|x| x >= 0.), valid);
+        assert!(decision.iter().take(10).all(|x| *x > 0.));
+        assert!(decision.iter().skip(10).all(|x| *x < 0.));
+
         Ok(())
     }
 
@@ -498,6 +569,96 @@ mod tests {
         let cm = y_est.confusion_matrix(&dataset)?;
         assert!(cm.accuracy() > 0.9);
 
+        // train model with a bandwidth estimated from the data
+        let model = Svm::<_, bool>::params()
+            .pos_neg_weights(1.0, 1.0)
+            .rbf_kernel(Gamma::Scale)
+            .fit(&dataset)?;
+
+        let y_est = model.predict(&dataset);
+
+        let cm = y_est.confusion_matrix(&dataset)?;
+        assert!(cm.accuracy() > 0.9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nystroem_approximates_kernel_svm() -> Result<()> {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let (records, targets) = generate_two_moons(100, &mut rng);
+        let dataset = Dataset::new(records, targets);
+
+        // exact kernel SVM as reference
+        let model = Svm::<_, bool>::params()
+            .pos_neg_weights(10.0, 10.0)
+            .rbf_kernel(Gamma::Value(2.0))
+            .fit(&dataset)?;
+        let acc_kernel = model
+            .predict(&dataset)
+            .confusion_matrix(&dataset)?
+            .accuracy();
+
+        // linear SVM on the approximated feature map with a quarter of the samples as landmarks
+        let nystroem = Nystroem::params(50)
+            .method(KernelMethod::Rbf(Gamma::Value(2.0)))
+            .fit(&dataset)
+            .unwrap();
+        let features = Dataset::new(
+            nystroem.predict(dataset.records()),
+            dataset.targets().clone(),
+        );
+
+        let model = Svm::<_, bool>::params()
+            .pos_neg_weights(10.0, 10.0)
+            .linear_kernel()
+            .fit(&features)?;
+        let acc_nystroem = model
+            .predict(&features)
+            .confusion_matrix(&features)?
+            .accuracy();
+
+        assert!(acc_kernel > 0.95);
+        assert!(acc_nystroem > 0.9);
+        assert!((acc_kernel - acc_nystroem).abs() < 0.05);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_with_kernel() -> Result<()> {
+        let mut rng = Isaac64Rng::seed_from_u64(42);
+        let (records, targets) = generate_two_moons(50, &mut rng);
+        let dataset = Dataset::new(records, targets);
+
+        // compute the kernel matrix once and reuse it for every penalty
+        let kernel = Kernel::params()
+            .method(KernelMethod::Rbf(Gamma::Value(2.0)))
+            .transform(dataset.records());
+
+        for c in &[0.1, 1.0, 10.0] {
+            let params = Svm::<_, bool>::params()
+                .pos_neg_weights(*c, *c)
+                .rbf_kernel(Gamma::Value(2.0));
+
+            let model = params.fit(&dataset)?;
+            let model_cached = params.fit_with_kernel(&dataset, &kernel)?;
+
+            assert_abs_diff_eq!(model.rho, model_cached.rho, epsilon = 1e-10);
+            assert_abs_diff_eq!(
+                Array1::from(model.alpha.clone()),
+                Array1::from(model_cached.alpha.clone()),
+                epsilon = 1e-10
+            );
+            assert_eq!(model.predict(&dataset), model_cached.predict(&dataset));
+        }
+
+        // the kernel matrix has to match the number of samples
+        let smaller = Kernel::params().transform(&dataset.records().slice(s![..10, ..]));
+        assert!(Svm::<_, bool>::params()
+            .fit_with_kernel(&dataset, &smaller)
+            .is_err());
+
         Ok(())
     }
 
@@ -68,7 +68,7 @@
 //! accuracy 0.8867925, MCC 0.40720797
 //! ```
 use linfa::{composing::PlattParams, Float};
-use ndarray::{ArrayBase, Data, Ix1};
+use ndarray::{Array1, ArrayBase, Data, Ix1, Ix2};
 
 use std::fmt;
 use std::marker::PhantomData;
@@ -82,7 +82,7 @@ mod permutable_kernel;
 mod regression;
 pub mod solver_smo;
 
-use linfa_kernel::{Kernel, KernelMethod, KernelParams};
+use linfa_kernel::{Gamma, Kernel, KernelMethod, KernelParams};
 pub use solver_smo::{SeparatingHyperplane, SolverParams};
 
 use std::ops::Mul;
@@ -170,6 +170,17 @@ impl<F: Float, T> SvmParams<F, T> {
         self
     }
 
+    /// Sets the model to use the RBF kernel. For this kernel the distance between two points is
+    /// computed as: `d(x, x') = exp(-gamma * norm(x - x'))`
+    ///
+    /// With `Gamma::Scale` or `Gamma::Auto` the value of `gamma` is estimated from the training
+    /// data at fit time and reused for predictions.
+    pub fn rbf_kernel(mut self, gamma: Gamma<F>) -> Self {
+        self.kernel = Kernel::params().method(KernelMethod::Rbf(gamma));
+
+        self
+    }
+
     /// Sets the model to use the Polynomial kernel. For this kernel the
     /// distance between two points is computed as: `d(x, x') = (<x, x'> + costant)^(degree)`
     pub fn polynomial_kernel(mut self, constant: F, degree: F) -> Self {
@@ -349,6 +360,28 @@ impl<F: Float, T> Svm<F, T> {
                 .sum(),
         }
     }
+
+    /// Evaluates the decision function for every observation
+    ///
+    /// The decision function is the weighted sum of an observation minus the offset `rho` and is
+    /// proportional to the signed distance of the observation to the separating hyperplane.
+    /// Binary classifiers predict the positive class for non-negative values and the magnitude is
+    /// a measure of confidence, which makes the values useful for ROC curves or to tune the
+    /// classification threshold.
+    ///
+    /// ## Panics
+    ///
+    /// If the number of features of `observations` is not compatible with the
+    /// shape of the support vectors
+    pub fn decision_function<D: Data<Elem = F>>(
+        &self,
+        observations: &ArrayBase<D, Ix2>,
+    ) -> Array1<F> {
+        observations
+            .outer_iter()
+            .map(|x| self.weighted_sum(&x) - self.rho)
+            .collect()
+    }
 }
 
 /// Display solution
@@ -1,28 +1,27 @@
 use crate::Float;
-use linfa_kernel::Kernel;
+use linfa_kernel::{KernelMethod, KernelView};
 use ndarray::Array1;
 
 pub trait Permutable<F: Float> {
     fn swap_indices(&mut self, i: usize, j: usize);
     fn distances(&self, idx: usize, length: usize) -> Vec<F>;
     fn self_distance(&self, idx: usize) -> F;
-    fn inner(&self) -> &Kernel<F>;
-    fn to_inner(self) -> Kernel<F>;
+    fn method(&self) -> &KernelMethod<F>;
 }
 
 /// KernelView matrix with permutable columns
 ///
 /// This struct wraps a kernel matrix with access indices. The working set can shrink during the
 /// optimization and it is therefore necessary to reorder entries.
-pub struct PermutableKernel<F: Float> {
-    kernel: Kernel<F>,
+pub struct PermutableKernel<'a, F: Float> {
+    kernel: KernelView<'a, F>,
     kernel_diag: Array1<F>,
     kernel_indices: Vec<usize>,
     targets: Vec<bool>,
 }
 
-impl<F: Float> PermutableKernel<F> {
-    pub fn new(kernel: Kernel<F>, targets: Vec<bool>) -> PermutableKernel<F> {
+impl<'a, F: Float> PermutableKernel<'a, F> {
+    pub fn new(kernel: KernelView<'a, F>, targets: Vec<bool>) -> PermutableKernel<'a, F> {
         let kernel_diag = kernel.diagonal();
         let kernel_indices = (0..kernel.size()).collect::<Vec<_>>();
 
@@ -35,7 +34,7 @@ impl<F: Float> PermutableKernel<F> {
     }
 }
 
-impl<F: Float> Permutable<F> for PermutableKernel<F> {
+impl<'a, F: Float> Permutable<F> for PermutableKernel<'a, F> {
     /// Swap two indices
     fn swap_indices(&mut self, i: usize, j: usize) {
         self.kernel_indices.swap(i, j);
@@ -63,14 +62,9 @@ impl<F: Float> Permutable<F> for PermutableKernel<F> {
             .collect()
     }
 
-    /// Return internal kernel
-    fn inner(&self) -> &Kernel<F> {
-        &self.kernel
-    }
-
-    /// Return internal kernel
-    fn to_inner(self) -> Kernel<F> {
-        self.kernel
+    /// Return the inner product of the kernel
+    fn method(&self) -> &KernelMethod<F> {
+        &self.kernel.method
     }
 
     /// Return distance to itself
@@ -81,14 +75,14 @@ impl<F: Float> Permutable<F> for PermutableKernel<F> {
     }
 }
 
-pub struct PermutableKernelOneClass<F: Float> {
-    kernel: Kernel<F>,
+pub struct PermutableKernelOneClass<'a, F: Float> {
+    kernel: KernelView<'a, F>,
     kernel_diag: Array1<F>,
     kernel_indices: Vec<usize>,
 }
 
-impl<F: Float> PermutableKernelOneClass<F> {
-    pub fn new(kernel: Kernel<F>) -> PermutableKernelOneClass<F> {
+impl<'a, F: Float> PermutableKernelOneClass<'a, F> {
+    pub fn new(kernel: KernelView<'a, F>) -> PermutableKernelOneClass<'a, F> {
         let kernel_diag = kernel.diagonal();
         let kernel_indices = (0..kernel.size()).collect::<Vec<_>>();
 
@@ -100,7 +94,7 @@ impl<F: Float> PermutableKernelOneClass<F> {
     }
 }
 
-impl<F: Float> Permutable<F> for PermutableKernelOneClass<F> {
+impl<'a, F: Float> Permutable<F> for PermutableKernelOneClass<'a, F> {
     /// Swap two indices
     fn swap_indices(&mut self, i: usize, j: usize) {
         self.kernel_indices.swap(i, j);
@@ -118,14 +112,9 @@ impl<F: Float> Permutable<F> for PermutableKernelOneClass<F> {
             .collect()
     }
 
-    /// Return internal kernel
-    fn inner(&self) -> &Kernel<F> {
-        &self.kernel
-    }
-
-    /// Return internal kernel
-    fn to_inner(self) -> Kernel<F> {
-        self.kernel
+    /// Return the inner product of the kernel
+    fn method(&self) -> &KernelMethod<F> {
+        &self.kernel.method
     }
 
     /// Return distance to itself
@@ -136,15 +125,15 @@ impl<F: Float> Permutable<F> for PermutableKernelOneClass<F> {
     }
 }
 
-pub struct PermutableKernelRegression<F: Float> {
-    kernel: Kernel<F>,
+pub struct PermutableKernelRegression<'a, F: Float> {
+    kernel: KernelView<'a, F>,
     kernel_diag: Array1<F>,
     kernel_indices: Vec<usize>,
     signs: Vec<bool>,
 }
 
-impl<'a, F: Float> PermutableKernelRegression<F> {
-    pub fn new(kernel: Kernel<F>) -> PermutableKernelRegression<F> {
+impl<'a, F: Float> PermutableKernelRegression<'a, F> {
+    pub fn new(kernel: KernelView<'a, F>) -> PermutableKernelRegression<'a, F> {
         let kernel_diag = kernel.diagonal();
         let kernel_indices = (0..2 * kernel.size())
             .map(|x| {
@@ -168,7 +157,7 @@ impl<'a, F: Float> PermutableKernelRegression<F> {
     }
 }
 
-impl<'a, F: Float> Permutable<F> for PermutableKernelRegression<F> {
+impl<'a, F: Float> Permutable<F> for PermutableKernelRegression<'a, F> {
     /// Swap two indices
     fn swap_indices(&mut self, i: usize, j: usize) {
         self.kernel_indices.swap(i, j);
@@ -195,14 +184,9 @@ impl<'a, F: Float> Permutable<F> for PermutableKernelRegression<F> {
             .collect()
     }
 
-    /// Return internal kernel
-    fn inner(&self) -> &Kernel<F> {
-        &self.kernel
-    }
-
-    /// Return internal kernel
-    fn to_inner(self) -> Kernel<F> {
-        self.kernel
+    /// Return the inner product of the kernel
+    fn method(&self) -> &KernelMethod<F> {
+        &self.kernel.method
     }
 
     /// Return distance to itself
@@ -229,7 +213,7 @@ mod tests {
             method: KernelMethod::Linear,
         };
 
-        let mut kernel = PermutableKernel::new(dist, targets);
+        let mut kernel = PermutableKernel::new(dist.view(), targets);
 
         assert_abs_diff_eq!(*kernel.distances(0, 3), [1.0, 0.3, 0.1]);
         assert_abs_diff_eq!(*kernel.distances(1, 3), [0.3, 1.0, 0.5]);
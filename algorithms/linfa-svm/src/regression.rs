@@ -1,11 +1,12 @@
 //! Support Vector Regression
 use linfa::{
     dataset::{AsTargets, DatasetBase},
+    metrics::{DefaultMetric, R2},
     traits::Fit,
     traits::Transformer,
     traits::{Predict, PredictRef},
 };
-use linfa_kernel::Kernel;
+use linfa_kernel::KernelView;
 use ndarray::{Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Data, Ix2};
 
 use super::error::{Result, SvmResult};
@@ -28,7 +29,7 @@ use super::{Float, Svm, SvmParams};
 pub fn fit_epsilon<F: Float>(
     params: SolverParams<F>,
     dataset: ArrayView2<F>,
-    kernel: Kernel<F>,
+    kernel: KernelView<F>,
     target: &[F],
     c: F,
     p: F,
@@ -75,7 +76,7 @@ pub fn fit_epsilon<F: Float>(
 pub fn fit_nu<F: Float>(
     params: SolverParams<F>,
     dataset: ArrayView2<F>,
-    kernel: Kernel<F>,
+    kernel: KernelView<F>,
     target: &[F],
     c: F,
     nu: F,
@@ -131,7 +132,7 @@ macro_rules! impl_regression {
                     (Some((c, eps)), _) => fit_epsilon(
                         self.solver_params.clone(),
                         dataset.records().view(),
-                        kernel,
+                        kernel.view(),
                         target,
                         c,
                         eps,
@@ -139,7 +140,7 @@ macro_rules! impl_regression {
                     (None, Some((nu, eps))) => fit_nu(
                         self.solver_params.clone(),
                         dataset.records().view(),
-                        kernel,
+                        kernel.view(),
                         target,
                         nu,
                         eps,
@@ -192,6 +193,10 @@ macro_rules! impl_predict {
             }
         }
 
+        impl DefaultMetric for Svm<$t, $t> {
+            type Metric = R2;
+        }
+
     ) *
     }
 }
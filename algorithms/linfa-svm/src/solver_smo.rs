@@ -869,7 +869,7 @@ impl<'a, F: Float, K: 'a + Permutable<F>> SolverState<'a, F, K> {
         // and we only need to store the vector given by their combination. If the kernel
         // is non linear then we need to store all support vectors so that we are able to
         // compute distances between them and new samples when making predictions.
-        let sep_hyperplane = if self.kernel.inner().is_linear() {
+        let sep_hyperplane = if self.kernel.method().is_linear() {
             let mut tmp = Array1::zeros(self.dataset.len_of(Axis(1)));
 
             for (i, elm) in self.dataset.outer_iter().enumerate() {
@@ -898,7 +898,7 @@ impl<'a, F: Float, K: 'a + Permutable<F>> SolverState<'a, F, K> {
             obj,
             iterations: iter,
             sep_hyperplane,
-            kernel_method: self.kernel.to_inner().method,
+            kernel_method: self.kernel.method().clone(),
             probability_coeffs: None,
             phantom: PhantomData,
         }
@@ -13,6 +13,7 @@ use linfa::{
     dataset::{AsTargets, Labels, Records},
     error::Error,
     error::Result,
+    metrics::{Accuracy, DefaultMetric},
     traits::*,
     DatasetBase, Float, Label,
 };
@@ -501,6 +502,10 @@ impl<F: Float, L: Label, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array1
     }
 }
 
+impl<F: Float, L: Label> DefaultMetric for DecisionTree<F, L> {
+    type Metric = Accuracy;
+}
+
 impl<'a, F: Float, L: Label + 'a + std::fmt::Debug, D, T> Fit<ArrayBase<D, Ix2>, T, Error>
     for DecisionTreeParams<F, L>
 where
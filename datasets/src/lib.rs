@@ -32,12 +32,130 @@
 //! let (train, valid) = linfa_datasets::winequality()
 //!     .split_with_ratio(0.8);
 //! ```
+//!
+//! Other CSV data, compressed or not, can be read with [dataset_from_csv] or [array_from_csv],
+//! which return an error instead of panicking on malformed input. Data which does not fit into
+//! memory can be read in chunks with a [DatasetStream].
 
-use csv::ReaderBuilder;
+use csv::{Reader, ReaderBuilder};
 use flate2::read::GzDecoder;
 use linfa::Dataset;
 use ndarray::prelude::*;
-use ndarray_csv::Array2Reader;
+use ndarray_csv::{Array2Reader, ReadError};
+use std::io::Read;
+use thiserror::Error;
+
+mod stream;
+
+pub use stream::DatasetStream;
+
+pub type Result<T> = std::result::Result<T, DatasetError>;
+
+/// Errors occuring while loading a dataset
+#[derive(Error, Debug)]
+pub enum DatasetError {
+    #[error("decompression failed: {0}")]
+    Decompression(std::io::Error),
+    #[error("invalid CSV data: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("invalid shape: {0}")]
+    Shape(String),
+    #[error("invalid number: {0}")]
+    Number(#[from] std::num::ParseFloatError),
+}
+
+impl From<ReadError> for DatasetError {
+    fn from(err: ReadError) -> Self {
+        match err {
+            ReadError::Csv(err) => DatasetError::Csv(err),
+            err => DatasetError::Shape(err.to_string()),
+        }
+    }
+}
+
+impl DatasetError {
+    /// Convert an error of the CSV parser, whose I/O errors stem from the decompression of
+    /// gzip compressed input
+    pub(crate) fn from_csv(err: csv::Error, gzip: bool) -> Self {
+        let io_err = match err.kind() {
+            csv::ErrorKind::Io(io_err) if gzip => {
+                Some(std::io::Error::new(io_err.kind(), io_err.to_string()))
+            }
+            _ => None,
+        };
+
+        match io_err {
+            Some(io_err) => DatasetError::Decompression(io_err),
+            None => DatasetError::Csv(err),
+        }
+    }
+}
+
+/// Read a two-dimensional array from CSV data
+///
+/// The input is decompressed on the fly if `gzip` is set and fields are separated by `delimiter`.
+/// If `has_headers` is set, the first line is skipped. Every line has to contain the same number
+/// of fields.
+pub fn array_from_csv<R: Read>(
+    input: R,
+    has_headers: bool,
+    delimiter: u8,
+    gzip: bool,
+) -> Result<Array2<f64>> {
+    fn read_array<R: Read>(mut reader: Reader<R>, gzip: bool) -> Result<Array2<f64>> {
+        reader
+            .deserialize_array2_dynamic()
+            .map_err(|err| match err {
+                ReadError::Csv(err) => DatasetError::from_csv(err, gzip),
+                err => err.into(),
+            })
+    }
+
+    // create a CSV reader with the given header setting and delimiter
+    let mut builder = ReaderBuilder::new();
+    builder.has_headers(has_headers).delimiter(delimiter);
+
+    // extract ndarray, unzipping the input if necessary
+    if gzip {
+        read_array(builder.from_reader(GzDecoder::new(input)), gzip)
+    } else {
+        read_array(builder.from_reader(input), gzip)
+    }
+}
+
+/// Read a dataset from CSV data
+///
+/// The input is parsed like in [array_from_csv] and the last `ntargets` columns are used as
+/// targets. If `has_headers` is set, the first line contains the feature names.
+///
+/// Fails if the data is malformed, if it contains no samples or if there are more targets than
+/// columns.
+///
+/// # Example
+///
+/// ```rust
+/// let data = "a,b,y\n1,2,0\n3,4,1\n";
+/// let dataset = linfa_datasets::dataset_from_csv(data.as_bytes(), true, b',', false, 1).unwrap();
+///
+/// assert_eq!(dataset.feature_names(), &["a", "b"]);
+/// ```
+pub fn dataset_from_csv<R: Read>(
+    input: R,
+    has_headers: bool,
+    delimiter: u8,
+    gzip: bool,
+    ntargets: usize,
+) -> Result<Dataset<f64, f64>> {
+    // a single chunk containing all samples
+    DatasetStream::new(input, has_headers, delimiter, gzip, usize::MAX)?
+        .with_targets(ntargets)
+        .next()
+        .unwrap_or_else(|| {
+            Err(DatasetError::Shape(
+                "CSV data should contain at least one sample".to_string(),
+            ))
+        })
+}
 
 #[cfg(any(
     feature = "iris",
@@ -46,16 +164,8 @@ use ndarray_csv::Array2Reader;
     feature = "linnerud"
 ))]
 fn array_from_buf(buf: &[u8]) -> Array2<f64> {
-    // unzip file
-    let file = GzDecoder::new(buf);
-    // create a CSV reader with headers and `,` as delimiter
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .delimiter(b',')
-        .from_reader(file);
-
-    // extract ndarray
-    reader.deserialize_array2_dynamic().unwrap()
+    // the bundled datasets are gzip compressed with headers and `,` as delimiter
+    array_from_csv(buf, true, b',', true).expect("bundled dataset is valid")
 }
 
 #[cfg(feature = "iris")]
@@ -75,6 +185,7 @@ pub fn iris() -> Dataset<f64, usize> {
     Dataset::new(data, targets)
         .map_targets(|x| *x as usize)
         .with_feature_names(feature_names)
+        .with_target_names(vec!["setosa", "versicolor", "virginica"])
 }
 
 #[cfg(feature = "diabetes")]
@@ -163,6 +274,50 @@ mod tests {
     use approx::assert_abs_diff_eq;
     use linfa::prelude::*;
 
+    #[test]
+    fn test_array_from_csv() {
+        let data = "a;b;c\n1.0;2.0;3.0\n4.0;5.0;6.0\n";
+        let array = array_from_csv(data.as_bytes(), true, b';', false).unwrap();
+        assert_abs_diff_eq!(array, array![[1., 2., 3.], [4., 5., 6.]]);
+
+        // without headers the first line is a sample
+        let array = array_from_csv("1;2\n3;4\n".as_bytes(), false, b';', false).unwrap();
+        assert_abs_diff_eq!(array, array![[1., 2.], [3., 4.]]);
+    }
+
+    #[test]
+    fn test_malformed_csv() {
+        // not a gzip stream
+        let res = array_from_csv("1,2\n3,4\n".as_bytes(), false, b',', true);
+        assert!(matches!(res, Err(DatasetError::Decompression(_))));
+
+        // fields which are not numbers
+        let res = array_from_csv("1,2\n3,a\n".as_bytes(), false, b',', false);
+        assert!(matches!(res, Err(DatasetError::Csv(_))));
+
+        // rows of different length
+        let res = array_from_csv("1,2\n3,4,5\n".as_bytes(), false, b',', false);
+        assert!(res.is_err());
+
+        // the same errors are returned when reading a dataset
+        let res = dataset_from_csv("1,2\n3,4\n".as_bytes(), false, b',', true, 1);
+        assert!(matches!(res, Err(DatasetError::Decompression(_))));
+        let res = dataset_from_csv("1,2\n3,a\n".as_bytes(), false, b',', false, 1);
+        assert!(matches!(res, Err(DatasetError::Number(_))));
+        let res = dataset_from_csv("a,b\n".as_bytes(), true, b',', false, 1);
+        assert!(matches!(res, Err(DatasetError::Shape(_))));
+    }
+
+    #[test]
+    fn test_dataset_from_csv() {
+        let data = "a;b;y\n1.0;2.0;0\n4.0;5.0;1\n";
+        let dataset = dataset_from_csv(data.as_bytes(), true, b';', false, 1).unwrap();
+
+        assert_eq!(dataset.feature_names(), &["a", "b"]);
+        assert_abs_diff_eq!(dataset.records(), &array![[1., 2.], [4., 5.]]);
+        assert_abs_diff_eq!(dataset.targets(), &array![[0.], [1.]]);
+    }
+
     #[cfg(feature = "iris")]
     #[test]
     fn test_iris() {
@@ -177,6 +332,9 @@ mod tests {
             &["sepal length", "sepal width", "petal length", "petal width"]
         );
 
+        // check for target names
+        assert_eq!(ds.target_names(), &["setosa", "versicolor", "virginica"]);
+
         // check label frequency
         assert_abs_diff_eq!(
             ds.label_frequencies()
@@ -258,13 +416,18 @@ mod tests {
         ];
 
         let freqs = ds.label_frequencies();
-        assert!(compare_to.into_iter().all(|(key, val)| {
+        assert!(compare_to.iter().all(|(key, val)| {
             freqs
-                .get(&key)
-                .map(|x| abs_diff_eq!(*x, val))
+                .get(key)
+                .map(|x| abs_diff_eq!(*x, *val))
                 .unwrap_or(false)
         }));
 
+        // sorted label frequencies are in ascending order of the labels
+        let mut compare_to = compare_to;
+        compare_to.sort_by_key(|x| x.0);
+        assert_eq!(ds.label_frequencies_sorted(), compare_to);
+
         // perform correlation analysis and assert that fixed acidity and citric acid are
         // correlated
         let _pcc = ds.pearson_correlation_with_p_value(100);
@@ -0,0 +1,291 @@
+use crate::{DatasetError, Result};
+use csv::{Reader, ReaderBuilder, StringRecord};
+use flate2::read::GzDecoder;
+use linfa::Dataset;
+use ndarray::Array2;
+use std::io::Read;
+
+/// Read CSV data in chunks of samples
+///
+/// Instead of loading all data into memory, as [`array_from_csv`](crate::array_from_csv) does,
+/// the stream parses at most `chunk_size` lines at a time and yields them as a dataset. This
+/// allows fitting incremental models on data which does not fit into memory. The header line is
+/// parsed once and used as feature names of every chunk. The buffers of the parser are reused
+/// between chunks.
+///
+/// By default all columns are records. The last columns can be used as targets with
+/// [`with_targets`](DatasetStream::with_targets).
+///
+/// # Example
+///
+/// ```rust
+/// use linfa_datasets::DatasetStream;
+///
+/// let data = "a,b,y\n1,2,0\n3,4,1\n5,6,0\n";
+/// let stream = DatasetStream::new(data.as_bytes(), true, b',', false, 2)
+///     .unwrap()
+///     .with_targets(1);
+///
+/// for chunk in stream {
+///     let chunk = chunk.unwrap();
+///     assert_eq!(chunk.feature_names(), &["a", "b"]);
+/// }
+/// ```
+pub struct DatasetStream<'a> {
+    reader: Reader<Box<dyn Read + 'a>>,
+    record: StringRecord,
+    records: Vec<f64>,
+    targets: Vec<f64>,
+    headers: Vec<String>,
+    chunk_size: usize,
+    ntargets: usize,
+    gzip: bool,
+    finished: bool,
+}
+
+impl<'a> DatasetStream<'a> {
+    /// Create a stream of CSV data
+    ///
+    /// The input is decompressed on the fly if `gzip` is set and fields are separated by
+    /// `delimiter`. If `has_headers` is set, the first line contains the feature names. Every line
+    /// has to contain the same number of fields.
+    pub fn new<R: Read + 'a>(
+        input: R,
+        has_headers: bool,
+        delimiter: u8,
+        gzip: bool,
+        chunk_size: usize,
+    ) -> Result<Self> {
+        if chunk_size == 0 {
+            return Err(DatasetError::Shape(
+                "chunk size should be larger than zero".to_string(),
+            ));
+        }
+
+        let input: Box<dyn Read + 'a> = if gzip {
+            Box::new(GzDecoder::new(input))
+        } else {
+            Box::new(input)
+        };
+
+        let mut reader = ReaderBuilder::new()
+            .has_headers(has_headers)
+            .delimiter(delimiter)
+            .from_reader(input);
+
+        // parse the header line once, it is reused for every chunk
+        let headers = if has_headers {
+            reader
+                .headers()
+                .map_err(|err| DatasetError::from_csv(err, gzip))?
+                .iter()
+                .map(|x| x.to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(DatasetStream {
+            reader,
+            record: StringRecord::new(),
+            records: Vec::new(),
+            targets: Vec::new(),
+            headers,
+            chunk_size,
+            ntargets: 0,
+            gzip,
+            finished: false,
+        })
+    }
+
+    /// Use the last `ntargets` columns as targets
+    pub fn with_targets(mut self, ntargets: usize) -> Self {
+        self.ntargets = ntargets;
+
+        self
+    }
+
+    /// Return the names of the features, which are empty if the data has no header line
+    pub fn feature_names(&self) -> &[String] {
+        &self.headers[..self.headers.len().saturating_sub(self.ntargets)]
+    }
+
+    /// Parse up to `chunk_size` lines into the buffers and return the number of samples
+    fn read_chunk(&mut self) -> Result<usize> {
+        self.records.clear();
+        self.targets.clear();
+
+        let mut nsamples = 0;
+        while nsamples < self.chunk_size
+            && self
+                .reader
+                .read_record(&mut self.record)
+                .map_err(|err| DatasetError::from_csv(err, self.gzip))?
+        {
+            let nfeatures = self
+                .record
+                .len()
+                .checked_sub(self.ntargets)
+                .ok_or_else(|| {
+                    DatasetError::Shape(format!(
+                        "{} targets exceed the number of columns {}",
+                        self.ntargets,
+                        self.record.len()
+                    ))
+                })?;
+
+            for (i, field) in self.record.iter().enumerate() {
+                let value = field.parse()?;
+                if i < nfeatures {
+                    self.records.push(value);
+                } else {
+                    self.targets.push(value);
+                }
+            }
+
+            nsamples += 1;
+        }
+
+        Ok(nsamples)
+    }
+}
+
+impl Iterator for DatasetStream<'_> {
+    type Item = Result<Dataset<f64, f64>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let nsamples = match self.read_chunk() {
+            Ok(0) => {
+                self.finished = true;
+                return None;
+            }
+            Ok(nsamples) => nsamples,
+            Err(err) => {
+                self.finished = true;
+                return Some(Err(err));
+            }
+        };
+
+        // the buffers keep their capacity for the next chunk
+        let records = Array2::from_shape_vec(
+            (nsamples, self.records.len() / nsamples),
+            self.records.clone(),
+        )
+        .unwrap();
+        let targets =
+            Array2::from_shape_vec((nsamples, self.ntargets), self.targets.clone()).unwrap();
+
+        let dataset =
+            Dataset::new(records, targets).with_feature_names(self.feature_names().to_vec());
+
+        Some(Ok(dataset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use flate2::{write::GzEncoder, Compression};
+    use linfa::dataset::Records;
+    use ndarray::{concatenate, s, Axis};
+    use std::io::Write;
+
+    /// CSV data with a header line, four features and a target column
+    fn synthetic_csv(nsamples: usize) -> (String, Array2<f64>) {
+        let array = Array2::from_shape_fn((nsamples, 5), |(i, j)| (i * 5 + j) as f64 / 8.);
+
+        let mut csv = "a,b,c,d,y\n".to_string();
+        for row in array.genrows() {
+            let fields = row.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        (csv, array)
+    }
+
+    /// Concatenate the records and targets of all chunks
+    fn collect_chunks(stream: DatasetStream) -> (Vec<usize>, Array2<f64>, Array2<f64>) {
+        let chunks = stream.collect::<Result<Vec<_>>>().unwrap();
+        let sizes = chunks.iter().map(|x| x.nsamples()).collect();
+        let records = chunks
+            .iter()
+            .map(|x| x.records().view())
+            .collect::<Vec<_>>();
+        let targets = chunks
+            .iter()
+            .map(|x| x.targets().view())
+            .collect::<Vec<_>>();
+
+        (
+            sizes,
+            concatenate(Axis(0), &records).unwrap(),
+            concatenate(Axis(0), &targets).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_reconstruct_chunks() {
+        let (csv, expected) = synthetic_csv(10_000);
+
+        let stream = DatasetStream::new(csv.as_bytes(), true, b',', false, 777)
+            .unwrap()
+            .with_targets(1);
+        assert_eq!(stream.feature_names(), &["a", "b", "c", "d"]);
+
+        let (sizes, records, targets) = collect_chunks(stream);
+        assert_eq!(sizes.len(), 13);
+        assert!(sizes[..12].iter().all(|x| *x == 777));
+        assert_eq!(sizes[12], 10_000 - 12 * 777);
+
+        assert_abs_diff_eq!(records, expected.slice(s![.., ..4]));
+        assert_abs_diff_eq!(targets, expected.slice(s![.., 4..]));
+    }
+
+    #[test]
+    fn test_gzip_stream() {
+        let (csv, expected) = synthetic_csv(1000);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(csv.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // without targets all columns are records
+        let mut stream = DatasetStream::new(compressed.as_slice(), true, b',', true, 100).unwrap();
+        let chunk = stream.next().unwrap().unwrap();
+        assert_eq!(chunk.feature_names(), vec!["a", "b", "c", "d", "y"]);
+        assert_eq!(chunk.targets().dim(), (100, 0));
+
+        let stream = DatasetStream::new(compressed.as_slice(), true, b',', true, 100).unwrap();
+        let (sizes, records, _) = collect_chunks(stream);
+        assert_eq!(sizes, vec![100; 10]);
+        assert_abs_diff_eq!(records, expected);
+    }
+
+    #[test]
+    fn test_malformed_stream() {
+        assert!(DatasetStream::new("1,2\n".as_bytes(), false, b',', false, 0).is_err());
+
+        // the error is returned with the chunk containing the malformed line, then the stream ends
+        let mut stream =
+            DatasetStream::new("1,2\n3,4\n5,a\n".as_bytes(), false, b',', false, 2).unwrap();
+        assert!(stream.next().unwrap().is_ok());
+        assert!(matches!(stream.next(), Some(Err(DatasetError::Number(_)))));
+        assert!(stream.next().is_none());
+
+        // rows of different length
+        let mut stream =
+            DatasetStream::new("1,2\n3,4,5\n".as_bytes(), false, b',', false, 10).unwrap();
+        assert!(matches!(stream.next(), Some(Err(DatasetError::Csv(_)))));
+
+        // more targets than columns
+        let mut stream = DatasetStream::new("1,2\n".as_bytes(), false, b',', false, 10)
+            .unwrap()
+            .with_targets(3);
+        assert!(matches!(stream.next(), Some(Err(DatasetError::Shape(_)))));
+    }
+}
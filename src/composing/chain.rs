@@ -0,0 +1,457 @@
+//! Chain single-target models to predict correlated targets
+//!
+//! A `MultiTargetModel` predicts every target independently. If the targets depend on each other
+//! a chain can perform better: one model is fitted per target and the targets earlier in the chain
+//! are appended to the records of all later models. A `ClassifierChain` predicts class indices and
+//! a `RegressorChain` continuous targets, both result in a `FittedChain`.
+use crate::composing::MultiTargetModel;
+use crate::dataset::{AsTargets, DatasetBase, Label};
+use crate::error::Error;
+use crate::traits::{Fit, PredictRef};
+use crate::Float;
+use ndarray::{concatenate, Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use num_traits::NumCast;
+
+/// Order of the targets shared by both chains
+struct ChainParams<P> {
+    inner: P,
+    order: Option<Vec<usize>>,
+}
+
+impl<P> ChainParams<P> {
+    /// Fit a model for every target in the order of the chain
+    fn fit_chain<F, D, L, T, E>(
+        &self,
+        dataset: &DatasetBase<ArrayBase<D, Ix2>, T>,
+    ) -> Result<FittedChain<F, L>, E>
+    where
+        F: Float,
+        D: Data<Elem = F>,
+        L: Copy + NumCast,
+        T: AsTargets<Elem = L>,
+        E: std::error::Error + From<Error>,
+        P: Fit<Array2<F>, Array2<L>, E>,
+        P::Object: PredictRef<Array2<F>, Array1<L>> + 'static,
+    {
+        let targets = dataset.as_multi_targets();
+        let ntargets = targets.ncols();
+
+        let order = match &self.order {
+            Some(order) => {
+                let mut sorted = order.clone();
+                sorted.sort_unstable();
+                if sorted != (0..ntargets).collect::<Vec<_>>() {
+                    return Err(Error::Parameters(format!(
+                        "order {:?} is not a permutation of the {} targets",
+                        order, ntargets
+                    ))
+                    .into());
+                }
+
+                order.clone()
+            }
+            None => (0..ntargets).collect(),
+        };
+
+        let mut records = dataset.records().to_owned();
+        let mut models = Vec::with_capacity(ntargets);
+        for &idx in &order {
+            let target = targets.column(idx);
+            let subset = DatasetBase::new(records.clone(), target.to_owned().insert_axis(Axis(1)))
+                .with_weights(dataset.weights.clone());
+            let model = self.inner.fit(&subset)?;
+            models.push(Box::new(model) as Box<dyn PredictRef<Array2<F>, Array1<L>>>);
+
+            // later models see the true targets during training
+            let feature = target.mapv(|x| F::cast(x)).insert_axis(Axis(1));
+            records = concatenate![Axis(1), records, feature];
+        }
+
+        Ok(FittedChain {
+            models: MultiTargetModel::new(models),
+            order,
+        })
+    }
+}
+
+/// Classifier chain for multi-target classification
+///
+/// The chain fits one classifier per target column, in the order given by
+/// [`with_order`](#method.with_order) or in the order of the columns otherwise. Each classifier
+/// is fitted on the records extended by the targets of all previous classifiers in the chain. When
+/// predicting, the predicted classes of the earlier classifiers are fed forward instead. The
+/// classes are used as features, so they have to be convertible to floats, like class indices.
+///
+/// # Example
+///
+/// ```ignore
+/// use linfa::composing::ClassifierChain;
+///
+/// // predict the second target first and use it to predict the first target
+/// let model = ClassifierChain::new(DecisionTree::params())
+///     .with_order(vec![1, 0])
+///     .fit(&dataset)?;
+/// let prediction = model.predict(&dataset);
+/// ```
+pub struct ClassifierChain<P>(ChainParams<P>);
+
+impl<P> ClassifierChain<P> {
+    /// Wrap the parameters of a single-target classifier into a chain
+    pub fn new(inner: P) -> Self {
+        ClassifierChain(ChainParams { inner, order: None })
+    }
+
+    /// Set the order in which the targets are fitted and predicted
+    ///
+    /// The order has to contain every target index exactly once.
+    pub fn with_order(mut self, order: Vec<usize>) -> Self {
+        self.0.order = Some(order);
+        self
+    }
+}
+
+impl<F, D, L, T, E, P> Fit<ArrayBase<D, Ix2>, T, E> for ClassifierChain<P>
+where
+    F: Float,
+    D: Data<Elem = F>,
+    L: Label + Copy + NumCast,
+    T: AsTargets<Elem = L>,
+    E: std::error::Error + From<Error>,
+    P: Fit<Array2<F>, Array2<L>, E>,
+    P::Object: PredictRef<Array2<F>, Array1<L>> + 'static,
+{
+    type Object = FittedChain<F, L>;
+
+    /// Fit a classifier for every target in the order of the chain
+    ///
+    /// Fails if the order is not a permutation of the target indices or if fitting a classifier
+    /// fails.
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object, E> {
+        self.0.fit_chain(dataset)
+    }
+}
+
+/// Regressor chain for multi-target regression
+///
+/// The chain fits one regressor per target column, in the order given by
+/// [`with_order`](#method.with_order) or in the order of the columns otherwise. Each regressor is
+/// fitted on the records extended by the targets of all previous regressors in the chain. When
+/// predicting, the predictions of the earlier regressors are fed forward instead.
+///
+/// # Example
+///
+/// ```ignore
+/// use linfa::composing::RegressorChain;
+///
+/// // predict the second target first and use it to predict the first target
+/// let model = RegressorChain::new(LinearRegression::new())
+///     .with_order(vec![1, 0])
+///     .fit(&dataset)?;
+/// let prediction = model.predict(&dataset);
+/// ```
+pub struct RegressorChain<P>(ChainParams<P>);
+
+impl<P> RegressorChain<P> {
+    /// Wrap the parameters of a single-target regressor into a chain
+    pub fn new(inner: P) -> Self {
+        RegressorChain(ChainParams { inner, order: None })
+    }
+
+    /// Set the order in which the targets are fitted and predicted
+    ///
+    /// The order has to contain every target index exactly once.
+    pub fn with_order(mut self, order: Vec<usize>) -> Self {
+        self.0.order = Some(order);
+        self
+    }
+}
+
+impl<F, D, T, E, P> Fit<ArrayBase<D, Ix2>, T, E> for RegressorChain<P>
+where
+    F: Float,
+    D: Data<Elem = F>,
+    T: AsTargets<Elem = F>,
+    E: std::error::Error + From<Error>,
+    P: Fit<Array2<F>, Array2<F>, E>,
+    P::Object: PredictRef<Array2<F>, Array1<F>> + 'static,
+{
+    type Object = FittedChain<F, F>;
+
+    /// Fit a regressor for every target in the order of the chain
+    ///
+    /// Fails if the order is not a permutation of the target indices or if fitting a regressor
+    /// fails.
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object, E> {
+        self.0.fit_chain(dataset)
+    }
+}
+
+/// Fitted chain of single-target models
+///
+/// The models are kept in a `MultiTargetModel` in the order of the chain.
+pub struct FittedChain<F: Float, L> {
+    models: MultiTargetModel<Array2<F>, L>,
+    order: Vec<usize>,
+}
+
+impl<F: Float, L> FittedChain<F, L> {
+    /// Return the fitted models in the order of the chain
+    pub fn models(&self) -> &MultiTargetModel<Array2<F>, L> {
+        &self.models
+    }
+
+    /// Return the target indices in the order of the chain
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+}
+
+impl<F, D, L> PredictRef<ArrayBase<D, Ix2>, Array2<L>> for FittedChain<F, L>
+where
+    F: Float,
+    D: Data<Elem = F>,
+    L: Copy + Default + NumCast,
+{
+    /// Predict the targets in the order of the chain, feeding predictions to later models
+    ///
+    /// The columns of the result are in the order of the targets of the training dataset.
+    fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array2<L> {
+        let mut records = x.to_owned();
+        let mut targets = Array2::from_elem((x.nrows(), self.order.len()), L::default());
+
+        for (model, &idx) in self.models.models().iter().zip(self.order.iter()) {
+            let prediction = model.predict_ref(&records);
+
+            let feature = prediction.mapv(|v| F::cast(v)).insert_axis(Axis(1));
+            records = concatenate![Axis(1), records, feature];
+            targets.column_mut(idx).assign(&prediction);
+        }
+
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClassifierChain, RegressorChain};
+    use crate::traits::{Fit, Predict, PredictRef};
+    use crate::{DatasetBase, MultiTargetModel};
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1, Array2, ArrayBase, Axis, Data, Ix2};
+
+    /// Perceptron classifier for the labels zero and one
+    struct PerceptronParams;
+
+    struct Perceptron {
+        weights: Array1<f64>,
+        bias: f64,
+    }
+
+    impl Fit<Array2<f64>, Array2<usize>, crate::Error> for PerceptronParams {
+        type Object = Perceptron;
+
+        fn fit(
+            &self,
+            dataset: &DatasetBase<Array2<f64>, Array2<usize>>,
+        ) -> Result<Perceptron, crate::Error> {
+            let mut model = Perceptron {
+                weights: Array1::zeros(dataset.records().ncols()),
+                bias: 0.,
+            };
+
+            // update on every mistake until the classes are separated
+            for _ in 0..100 {
+                let mut mistakes = 0;
+                for (x, y) in dataset.records().outer_iter().zip(dataset.targets().iter()) {
+                    let predicted = model.weights.dot(&x) + model.bias > 0.;
+                    if predicted != (*y == 1) {
+                        let sign = if *y == 1 { 1. } else { -1. };
+                        model.weights.scaled_add(sign, &x);
+                        model.bias += sign;
+                        mistakes += 1;
+                    }
+                }
+
+                if mistakes == 0 {
+                    break;
+                }
+            }
+
+            Ok(model)
+        }
+    }
+
+    impl<D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<usize>> for Perceptron {
+        fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<usize> {
+            x.dot(&self.weights)
+                .mapv(|v| if v + self.bias > 0. { 1 } else { 0 })
+        }
+    }
+
+    /// The first target is linear in the records and the second target is linear in the
+    /// records and the first target, but not in the records alone
+    fn dependent_targets() -> DatasetBase<Array2<f64>, Array2<usize>> {
+        let values = (0..9).map(|i| -1. + 0.25 * i as f64).collect::<Vec<_>>();
+
+        let mut records = Vec::new();
+        let mut targets = Vec::new();
+        for &x0 in &values {
+            for &x1 in &values {
+                for &x2 in &values {
+                    // skip samples on the decision boundaries
+                    let y0 = if x0 + x1 > 0. { 1 } else { 0 };
+                    if (x0 + x1).abs() < 0.1 || (x2 + y0 as f64 - 0.5).abs() < 0.1 {
+                        continue;
+                    }
+                    let y1 = if x2 + y0 as f64 > 0.5 { 1 } else { 0 };
+
+                    records.extend_from_slice(&[x0, x1, x2]);
+                    targets.extend_from_slice(&[y0, y1]);
+                }
+            }
+        }
+
+        let nsamples = targets.len() / 2;
+        DatasetBase::new(
+            Array2::from_shape_vec((nsamples, 3), records).unwrap(),
+            Array2::from_shape_vec((nsamples, 2), targets).unwrap(),
+        )
+    }
+
+    fn accuracy(predicted: &Array2<usize>, targets: &Array2<usize>, idx: usize) -> f64 {
+        let correct = predicted
+            .column(idx)
+            .iter()
+            .zip(targets.column(idx).iter())
+            .filter(|(a, b)| a == b)
+            .count();
+
+        correct as f64 / targets.nrows() as f64
+    }
+
+    #[test]
+    fn chain_beats_independent_models() {
+        let dataset = dependent_targets();
+
+        // independent models for every target
+        let independent = (0..2)
+            .map(|idx| {
+                let target = dataset
+                    .targets()
+                    .column(idx)
+                    .to_owned()
+                    .insert_axis(Axis(1));
+                PerceptronParams
+                    .fit(&DatasetBase::new(dataset.records().clone(), target))
+                    .unwrap()
+            })
+            .collect::<MultiTargetModel<Array2<f64>, usize>>();
+        let predicted_independent = independent.predict(dataset.records());
+
+        let chain = ClassifierChain::new(PerceptronParams)
+            .fit(&dataset)
+            .unwrap();
+        assert_eq!(chain.order(), &[0, 1]);
+        let predicted_chain = chain.predict(dataset.records());
+
+        // the first target is linearly separable for both
+        assert_eq!(accuracy(&predicted_independent, dataset.targets(), 0), 1.);
+        assert_eq!(accuracy(&predicted_chain, dataset.targets(), 0), 1.);
+
+        // the second target only with the first target as feature
+        assert!(accuracy(&predicted_independent, dataset.targets(), 1) < 0.95);
+        assert_eq!(accuracy(&predicted_chain, dataset.targets(), 1), 1.);
+    }
+
+    #[test]
+    fn chain_order() {
+        let dataset = dependent_targets();
+
+        // predictions are returned in the order of the targets
+        let chain = ClassifierChain::new(PerceptronParams)
+            .with_order(vec![1, 0])
+            .fit(&dataset)
+            .unwrap();
+        assert_eq!(chain.order(), &[1, 0]);
+        let predicted = chain.predict(dataset.records());
+        assert_eq!(predicted.dim(), dataset.targets().dim());
+        assert_eq!(accuracy(&predicted, dataset.targets(), 0), 1.);
+
+        assert!(ClassifierChain::new(PerceptronParams)
+            .with_order(vec![0, 0])
+            .fit(&dataset)
+            .is_err());
+        assert!(ClassifierChain::new(PerceptronParams)
+            .with_order(vec![0])
+            .fit(&dataset)
+            .is_err());
+
+        let dataset = DatasetBase::new(array![[1., 2.]], array![[0usize, 1, 1]]);
+        assert!(ClassifierChain::new(PerceptronParams)
+            .with_order(vec![2, 0, 1])
+            .fit(&dataset)
+            .is_ok());
+    }
+
+    /// Least squares regressor through the origin on the last feature only
+    struct LastFeatureParams;
+
+    struct LastFeature {
+        coefficient: f64,
+    }
+
+    impl Fit<Array2<f64>, Array2<f64>, crate::Error> for LastFeatureParams {
+        type Object = LastFeature;
+
+        fn fit(
+            &self,
+            dataset: &DatasetBase<Array2<f64>, Array2<f64>>,
+        ) -> Result<LastFeature, crate::Error> {
+            let x = dataset.records().column(dataset.records().ncols() - 1);
+            let y = dataset.targets().column(0);
+
+            Ok(LastFeature {
+                coefficient: x.dot(&y) / x.dot(&x),
+            })
+        }
+    }
+
+    impl<D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<f64>> for LastFeature {
+        fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<f64> {
+            x.column(x.ncols() - 1).mapv(|v| v * self.coefficient)
+        }
+    }
+
+    #[test]
+    fn regressor_chain_feeds_predictions_forward() {
+        // the first target is unrelated to the last feature, but the second target is a multiple
+        // of the first one
+        let records = array![[1., 1.], [2., -1.], [3., 1.], [4., -1.]];
+        let targets = array![[1., 3.], [2., 6.], [3., 9.], [4., 12.]];
+        let dataset = DatasetBase::new(records.clone(), targets.clone());
+
+        let chain = RegressorChain::new(LastFeatureParams)
+            .with_order(vec![0, 1])
+            .fit(&dataset)
+            .unwrap();
+        let predicted = chain.predict(&records);
+
+        // the first model only sees the records and misses the first target
+        let first = LastFeatureParams
+            .fit(&DatasetBase::new(
+                records.clone(),
+                targets.column(0).to_owned().insert_axis(Axis(1)),
+            ))
+            .unwrap()
+            .predict(&records);
+        assert_abs_diff_eq!(predicted.column(0), first);
+
+        // the second model uses the predicted first target as feature
+        assert_abs_diff_eq!(predicted.column(1), first.mapv(|x| 3. * x), epsilon = 1e-12);
+
+        assert!(RegressorChain::new(LastFeatureParams)
+            .with_order(vec![1])
+            .fit(&dataset)
+            .is_err());
+    }
+}
@@ -1,13 +1,22 @@
 //! Composition models
 //!
-//! This module contains three composition models:
+//! This module contains five composition models:
 //!  * `MultiClassModel`: combine multiple binary decision models to a single multi-class model
 //!  * `MultiTargetModel`: combine multiple univariate models to a single multi-target model
+//!  * `ClassifierChain` and `RegressorChain`: fit univariate models to multiple targets, feeding
+//!    earlier targets to later models
 //!  * `Platt`: calibrate a classifier (i.e. SVC) to predicted posterior probabilities
+//!  * `TransformedTargetRegressor`: fit a regression model on transformed targets
+mod chain;
 mod multi_class_model;
 mod multi_target_model;
 pub mod platt_scaling;
+mod transformed_target;
 
+pub use chain::{ClassifierChain, FittedChain, RegressorChain};
 pub use multi_class_model::MultiClassModel;
 pub use multi_target_model::MultiTargetModel;
 pub use platt_scaling::{Platt, PlattNewtonResult, PlattParams};
+pub use transformed_target::{
+    FittedTransformedTargetRegressor, TargetTransform, TransformedTargetRegressor,
+};
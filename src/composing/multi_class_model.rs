@@ -1,6 +1,7 @@
 //! Merge models with binary to multi-class classification
 //!
 use crate::dataset::{Pr, Records};
+use crate::metrics::{Accuracy, DefaultMetric};
 use crate::traits::PredictRef;
 use crate::Float;
 use ndarray::{Array1, ArrayBase, Data, Ix2};
@@ -52,6 +53,10 @@ impl<L: Clone + Default, F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix
     }
 }
 
+impl<R: Records, L> DefaultMetric for MultiClassModel<R, L> {
+    type Metric = Accuracy;
+}
+
 impl<F: Float, D: Data<Elem = F>, L, P: PredictRef<ArrayBase<D, Ix2>, Array1<Pr>> + 'static>
     FromIterator<(L, P)> for MultiClassModel<ArrayBase<D, Ix2>, L>
 {
@@ -30,6 +30,11 @@ impl<R: Records, L> MultiTargetModel<R, L> {
     pub fn new(models: Vec<Box<dyn PredictRef<R, Array1<L>>>>) -> Self {
         MultiTargetModel { models }
     }
+
+    /// Return the single-target models in the order of the targets
+    pub(crate) fn models(&self) -> &[Box<dyn PredictRef<R, Array1<L>>>] {
+        &self.models
+    }
 }
 
 impl<L, F: Float, D: Data<Elem = F>> PredictRef<ArrayBase<D, Ix2>, Array2<L>>
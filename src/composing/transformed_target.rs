@@ -0,0 +1,232 @@
+//! Fit regression models on transformed targets
+//!
+//! Linear models often perform poorly on skewed targets, for example when the target is
+//! log-normally distributed. This wrapper applies a transformation to the targets before the
+//! inner model is fitted and maps the predictions back with the inverse transformation.
+use crate::dataset::{AsTargets, DatasetBase};
+use crate::error::Error;
+use crate::metrics::{DefaultMetric, R2};
+use crate::traits::{Fit, PredictRef};
+use crate::Float;
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix2};
+
+/// Invertible transformation applied to the targets
+#[derive(Debug, Clone, Copy)]
+pub enum TargetTransform<F> {
+    /// Natural logarithm, inverted by the exponential function
+    Log,
+    /// Box-Cox transformation `(y^lambda - 1) / lambda` with parameter `lambda`
+    ///
+    /// For `lambda = 0` this is equal to the natural logarithm.
+    BoxCox(F),
+    /// Custom transformation given by a forward function and its inverse
+    Custom {
+        forward: fn(F) -> F,
+        inverse: fn(F) -> F,
+    },
+}
+
+impl<F: Float> TargetTransform<F> {
+    /// Apply the transformation to a single target value
+    pub fn forward(&self, y: F) -> F {
+        match self {
+            TargetTransform::Log => y.ln(),
+            TargetTransform::BoxCox(lambda) if *lambda == F::zero() => y.ln(),
+            TargetTransform::BoxCox(lambda) => (y.powf(*lambda) - F::one()) / *lambda,
+            TargetTransform::Custom { forward, .. } => forward(y),
+        }
+    }
+
+    /// Map a transformed value back to the scale of the original targets
+    pub fn inverse(&self, z: F) -> F {
+        match self {
+            TargetTransform::Log => z.exp(),
+            TargetTransform::BoxCox(lambda) if *lambda == F::zero() => z.exp(),
+            TargetTransform::BoxCox(lambda) => (*lambda * z + F::one()).powf(lambda.recip()),
+            TargetTransform::Custom { inverse, .. } => inverse(z),
+        }
+    }
+}
+
+/// Regression model fitted on transformed targets
+///
+/// The targets are transformed with a [`TargetTransform`] before the inner model is fitted and
+/// the predictions of the fitted model are mapped back to the original scale. The wrapper is
+/// generic over the inner model and only requires that its parameters implement `Fit` for
+/// owned records and targets.
+///
+/// # Example
+///
+/// ```ignore
+/// use linfa::composing::{TargetTransform, TransformedTargetRegressor};
+///
+/// let model = TransformedTargetRegressor::new(LinearRegression::new(), TargetTransform::Log)
+///     .fit(&dataset)?;
+/// let prediction = model.predict(&dataset);
+/// ```
+pub struct TransformedTargetRegressor<P, F> {
+    inner: P,
+    transform: TargetTransform<F>,
+}
+
+impl<P, F: Float> TransformedTargetRegressor<P, F> {
+    /// Wrap the parameters of a regression model with a target transformation
+    pub fn new(inner: P, transform: TargetTransform<F>) -> Self {
+        TransformedTargetRegressor { inner, transform }
+    }
+
+    /// Return the target transformation
+    pub fn transform(&self) -> &TargetTransform<F> {
+        &self.transform
+    }
+}
+
+impl<F, D, T, E, P> Fit<ArrayBase<D, Ix2>, T, E> for TransformedTargetRegressor<P, F>
+where
+    F: Float,
+    D: Data<Elem = F>,
+    T: AsTargets<Elem = F>,
+    E: std::error::Error + From<Error>,
+    P: Fit<Array2<F>, Array2<F>, E>,
+{
+    type Object = FittedTransformedTargetRegressor<P::Object, F>;
+
+    /// Transform the targets and fit the inner model
+    ///
+    /// Fails if a transformed target is not finite, for example for non-positive targets and the
+    /// logarithm, or if fitting the inner model fails.
+    fn fit(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Object, E> {
+        let targets = dataset
+            .as_multi_targets()
+            .mapv(|y| self.transform.forward(y));
+
+        if targets.iter().any(|y| !y.is_finite()) {
+            return Err(Error::Parameters(
+                "targets are out of the domain of the transformation".to_string(),
+            )
+            .into());
+        }
+
+        let transformed = DatasetBase::new(dataset.records().to_owned(), targets)
+            .with_weights(dataset.weights.clone())
+            .with_feature_names(dataset.feature_names());
+
+        Ok(FittedTransformedTargetRegressor {
+            model: self.inner.fit(&transformed)?,
+            transform: self.transform,
+        })
+    }
+}
+
+/// Fitted regression model with a target transformation
+pub struct FittedTransformedTargetRegressor<M, F> {
+    model: M,
+    transform: TargetTransform<F>,
+}
+
+impl<M, F: Float> FittedTransformedTargetRegressor<M, F> {
+    /// Return the fitted inner model, which predicts transformed targets
+    pub fn inner(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, M: PredictRef<ArrayBase<D, Ix2>, Array1<F>>>
+    PredictRef<ArrayBase<D, Ix2>, Array1<F>> for FittedTransformedTargetRegressor<M, F>
+{
+    /// Predict with the inner model and apply the inverse transformation
+    fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        self.model
+            .predict_ref(x)
+            .mapv(|z| self.transform.inverse(z))
+    }
+}
+
+impl<M, F: Float> DefaultMetric for FittedTransformedTargetRegressor<M, F> {
+    type Metric = R2;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TargetTransform, TransformedTargetRegressor};
+    use crate::traits::{Fit, Predict, PredictRef};
+    use crate::{Dataset, DatasetBase};
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1, Array2, ArrayBase, Axis, Data, Ix2};
+
+    /// Predicts the mean of the targets seen during fitting
+    struct MeanParams;
+
+    struct MeanModel {
+        mean: f64,
+    }
+
+    impl Fit<Array2<f64>, Array2<f64>, crate::Error> for MeanParams {
+        type Object = MeanModel;
+
+        fn fit(
+            &self,
+            dataset: &DatasetBase<Array2<f64>, Array2<f64>>,
+        ) -> Result<MeanModel, crate::Error> {
+            Ok(MeanModel {
+                mean: dataset.targets().mean_axis(Axis(0)).unwrap()[0],
+            })
+        }
+    }
+
+    impl<D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<f64>> for MeanModel {
+        fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<f64> {
+            Array1::from_elem(x.nrows(), self.mean)
+        }
+    }
+
+    #[test]
+    fn transforms_are_inverted() {
+        let transforms: Vec<TargetTransform<f64>> = vec![
+            TargetTransform::Log,
+            TargetTransform::BoxCox(0.0),
+            TargetTransform::BoxCox(0.5),
+            TargetTransform::BoxCox(-1.5),
+            TargetTransform::Custom {
+                forward: |y: f64| y.sqrt(),
+                inverse: |z: f64| z * z,
+            },
+        ];
+
+        for transform in transforms {
+            for y in &[0.1, 1.0, 3.5, 100.0] {
+                assert_abs_diff_eq!(transform.inverse(transform.forward(*y)), *y, epsilon = 1e-9);
+            }
+        }
+
+        assert_abs_diff_eq!(TargetTransform::BoxCox(1.0).forward(3.0), 2.0);
+    }
+
+    #[test]
+    fn predicts_on_original_scale() {
+        let dataset = Dataset::new(array![[0.], [1.], [2.]], array![1.0, 10.0, 100.0]);
+
+        let model = TransformedTargetRegressor::new(MeanParams, TargetTransform::Log)
+            .fit(&dataset)
+            .unwrap();
+
+        // the geometric mean is the inverse of the mean of the logarithms
+        assert_abs_diff_eq!(model.inner().mean, 10f64.ln(), epsilon = 1e-12);
+        assert_abs_diff_eq!(
+            model.predict(dataset.records()),
+            array![10.0, 10.0, 10.0],
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn rejects_targets_out_of_domain() {
+        let dataset = Dataset::new(array![[0.], [1.]], array![1.0, -1.0]);
+
+        assert!(
+            TransformedTargetRegressor::new(MeanParams, TargetTransform::Log)
+                .fit(&dataset)
+                .is_err()
+        );
+    }
+}
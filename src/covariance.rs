@@ -0,0 +1,188 @@
+//! Covariance estimation for dataset features
+//!
+//! # Implementations
+//!
+//! * Ledoit-Wolf shrinkage - well-conditioned covariance estimate for small sample sizes
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix1, Ix2};
+
+use crate::Float;
+
+/// Estimate the covariance matrix with Ledoit-Wolf shrinkage
+///
+/// The empirical covariance matrix is singular when there are fewer observations than features
+/// and ill-conditioned when both numbers are close. The Ledoit-Wolf estimator blends the empirical
+/// covariance `S` towards a scaled identity matrix as
+///
+/// ```text
+/// (1 - shrinkage) * S + shrinkage * trace(S) / nfeatures * I
+/// ```
+///
+/// with the shrinkage intensity minimizing the expected squared error of the estimate. The
+/// observations are centered and the empirical covariance is normalized by the number of
+/// observations.
+///
+/// Returns the shrunk covariance matrix and the shrinkage intensity in `[0, 1]`.
+///
+/// # Panics
+///
+/// If there are no observations
+pub fn ledoit_wolf<F: Float, D: Data<Elem = F>>(
+    observations: &ArrayBase<D, Ix2>,
+) -> (Array2<F>, F) {
+    let nobservations = observations.nrows();
+    assert!(nobservations > 0, "ledoit_wolf requires observations");
+
+    shrunk_covariance(observations, &Array1::ones(nobservations))
+}
+
+/// Estimate the covariance matrix of weighted observations with Ledoit-Wolf shrinkage
+///
+/// This is the weighted version of [`ledoit_wolf`], observations are centered by their weighted
+/// mean and contribute to the empirical covariance in proportion to their weight. The weights are
+/// frequencies, an observation with weight two gives the same estimate as a duplicated
+/// observation and unit weights give the same result as [`ledoit_wolf`].
+///
+/// # Panics
+///
+/// If the number of weights differs from the number of observations or the weights sum to zero
+pub fn weighted_ledoit_wolf<F: Float, D: Data<Elem = F>, E: Data<Elem = F>>(
+    observations: &ArrayBase<D, Ix2>,
+    weights: &ArrayBase<E, Ix1>,
+) -> (Array2<F>, F) {
+    assert_eq!(
+        observations.nrows(),
+        weights.len(),
+        "every observation needs a weight"
+    );
+    assert!(weights.sum() > F::zero(), "weights must not sum to zero");
+
+    shrunk_covariance(observations, weights)
+}
+
+// Ledoit-Wolf estimate for observations weighted with frequencies `weights`
+fn shrunk_covariance<F: Float, D: Data<Elem = F>, E: Data<Elem = F>>(
+    observations: &ArrayBase<D, Ix2>,
+    weights: &ArrayBase<E, Ix1>,
+) -> (Array2<F>, F) {
+    let nfeatures = F::cast(observations.ncols());
+    let total_weight = weights.sum();
+    let weights = weights / total_weight;
+    let weights_col = weights.view().insert_axis(Axis(1));
+
+    // center by the weighted mean and compute the empirical covariance
+    let mean = (observations * &weights_col).sum_axis(Axis(0));
+    let centered = observations - &mean.insert_axis(Axis(0));
+    let covariance = (&centered * &weights_col).t().dot(&centered);
+
+    // the shrinkage target is the identity scaled by the average variance
+    let mu = covariance.diag().sum() / nfeatures;
+    let covariance_norm = covariance.iter().map(|x| *x * *x).sum::<F>();
+
+    // distance between the empirical covariance and the shrinkage target
+    let delta = (covariance_norm - nfeatures * mu * mu) / nfeatures;
+
+    // variance of the empirical covariance, from the squared distances between every
+    // observation's outer product and the empirical covariance
+    let beta = centered
+        .outer_iter()
+        .zip(centered.dot(&covariance).outer_iter())
+        .zip(weights.iter())
+        .map(|((x, xs), &w)| {
+            let sq_norm = x.dot(&x);
+            w * (sq_norm * sq_norm - F::cast(2.) * x.dot(&xs) + covariance_norm)
+        })
+        .sum::<F>()
+        / (total_weight * nfeatures);
+
+    let shrinkage = if delta > F::zero() {
+        beta.min(delta) / delta
+    } else {
+        F::zero()
+    };
+
+    let mut shrunk = covariance * (F::one() - shrinkage);
+    shrunk.diag_mut().mapv_inplace(|x| x + shrinkage * mu);
+
+    (shrunk, shrinkage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array};
+
+    #[test]
+    fn test_ledoit_wolf() {
+        let observations = array![
+            [0.5, -0.1, 1.0],
+            [-0.3, 0.1, 0.2],
+            [0.2, 0.3, 0.0],
+            [0.3, 1.1, -0.4],
+            [-1.8, -3.2, -0.2],
+            [-0.3, -0.3, -1.3],
+            [0.0, 0.1, -0.8],
+            [-1.0, -1.0, -2.2],
+            [-1.1, -3.5, 0.8],
+            [0.2, 0.8, 0.5],
+            [1.0, 1.9, -0.6],
+            [-1.0, -2.2, 1.1]
+        ];
+
+        let (covariance, shrinkage) = ledoit_wolf(&observations);
+        assert_abs_diff_eq!(shrinkage, 0.2785684985891195, epsilon = 1e-10);
+        assert_abs_diff_eq!(
+            covariance,
+            array![
+                [0.806333592877462, 0.8188247541013494, 0.01608191055228423],
+                [0.8188247541013494, 2.240629596619969, -0.2573105688365474],
+                [0.01608191055228423, -0.2573105688365474, 1.0123423660581246]
+            ],
+            epsilon = 1e-10
+        );
+
+        // unit weights give the same estimate
+        let (weighted, weighted_shrinkage) = weighted_ledoit_wolf(&observations, &Array::ones(12));
+        assert_abs_diff_eq!(weighted_shrinkage, shrinkage, epsilon = 1e-10);
+        assert_abs_diff_eq!(weighted, covariance, epsilon = 1e-10);
+
+        // integer weights are the same as duplicated observations
+        let duplicated = ndarray::concatenate(
+            Axis(0),
+            &[
+                observations.view(),
+                observations.slice(ndarray::s![..1, ..]),
+            ],
+        )
+        .unwrap();
+        let mut weights = Array::ones(12);
+        weights[0] = 2.;
+        let (weighted, weighted_shrinkage) = weighted_ledoit_wolf(&observations, &weights);
+        let (covariance, shrinkage) = ledoit_wolf(&duplicated);
+        assert_abs_diff_eq!(weighted_shrinkage, shrinkage, epsilon = 1e-10);
+        assert_abs_diff_eq!(weighted, covariance, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_ledoit_wolf_well_conditioned() {
+        // two observations in three dimensions give a rank one empirical covariance
+        let observations = array![[1., 2., -1.], [-1., 0., 2.]];
+        let centered = &observations - &observations.mean_axis(Axis(0)).unwrap();
+        let empirical = centered.t().dot(&centered) / 2.;
+
+        // this direction is orthogonal to the difference of both observations
+        let direction = array![1., -1., 0.];
+        assert_abs_diff_eq!(direction.dot(&empirical.dot(&direction)), 0.);
+
+        let (covariance, shrinkage) = ledoit_wolf(&observations);
+        assert!(shrinkage > 0. && shrinkage <= 1.);
+
+        // all eigenvalues are at least the shrinkage times the average variance
+        let mu = empirical.diag().sum() / 3.;
+        assert!(
+            direction.dot(&covariance.dot(&direction))
+                >= shrinkage * mu * direction.dot(&direction) - 1e-12
+        );
+        assert!(direction.dot(&covariance.dot(&direction)) > 0.);
+    }
+}
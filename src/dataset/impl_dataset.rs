@@ -1,15 +1,18 @@
 use super::{
-    super::traits::{Predict, PredictRef},
+    super::traits::{Predict, PredictOne, PredictRef},
     iter::{ChunksIter, DatasetIter, Iter},
     AsTargets, AsTargetsMut, CountedTargets, Dataset, DatasetBase, DatasetView, Float,
-    FromTargetArray, Label, Labels, Records, Result,
+    FromTargetArray, Label, Labels, Records, Result, SampleSize,
 };
 use crate::traits::Fit;
 use ndarray::{
     concatenate, s, Array, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, ArrayViewMut2, Axis,
     Data, DataMut, Dimension, Ix1, Ix2, OwnedRepr,
 };
-use rand::{seq::SliceRandom, Rng};
+use rand::{
+    seq::{index, SliceRandom},
+    Rng,
+};
 use std::collections::HashMap;
 use std::ops::AddAssign;
 
@@ -33,6 +36,7 @@ impl<R: Records, S> DatasetBase<R, S> {
             targets,
             weights: Array1::zeros(0),
             feature_names: Vec::new(),
+            target_names: Vec::new(),
         }
     }
 
@@ -84,25 +88,28 @@ impl<R: Records, S> DatasetBase<R, S> {
     /// Updates the records of a dataset
     ///
     /// This function overwrites the records in a dataset. It also invalidates the weights and
-    /// feature names.
+    /// feature names, while the target names are kept.
     pub fn with_records<T: Records>(self, records: T) -> DatasetBase<T, S> {
         DatasetBase {
             records,
             targets: self.targets,
             weights: Array1::zeros(0),
             feature_names: Vec::new(),
+            target_names: self.target_names,
         }
     }
 
     /// Updates the targets of a dataset
     ///
-    /// This function overwrites the targets in a dataset.
+    /// This function overwrites the targets in a dataset. Weights, feature names and target names
+    /// are kept.
     pub fn with_targets<T>(self, targets: T) -> DatasetBase<R, T> {
         DatasetBase {
             records: self.records,
             targets,
             weights: self.weights,
             feature_names: self.feature_names,
+            target_names: self.target_names,
         }
     }
 
@@ -114,10 +121,82 @@ impl<R: Records, S> DatasetBase<R, S> {
     }
 
     /// Updates the feature names of a dataset
+    ///
+    /// An empty vector removes the feature names, in which case default names are generated by
+    /// [`feature_names`](DatasetBase::feature_names).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of names is neither zero nor equal to the number of features.
     pub fn with_feature_names<I: Into<String>>(mut self, names: Vec<I>) -> DatasetBase<R, S> {
-        let feature_names = names.into_iter().map(|x| x.into()).collect();
+        self.set_feature_names(names);
+
+        self
+    }
+
+    /// Replaces the feature names of a dataset in place
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of names is neither zero nor equal to the number of features.
+    pub fn set_feature_names<I: Into<String>>(&mut self, names: Vec<I>) {
+        let nfeatures = self.records.nfeatures();
+        assert!(
+            names.is_empty() || names.len() == nfeatures,
+            "number of feature names ({}) does not match number of features ({})",
+            names.len(),
+            nfeatures
+        );
+
+        self.feature_names = names.into_iter().map(|x| x.into()).collect();
+    }
+
+    /// Renames a single feature
+    ///
+    /// If no feature names were set before, all other features keep their default names.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than the number of features.
+    pub fn rename_feature<I: Into<String>>(&mut self, idx: usize, name: I) {
+        let nfeatures = self.records.nfeatures();
+        assert!(
+            idx < nfeatures,
+            "feature index {} out of range for {} features",
+            idx,
+            nfeatures
+        );
+
+        if self.feature_names.is_empty() {
+            self.feature_names = self.feature_names();
+        }
+        self.feature_names[idx] = name.into();
+    }
+
+    /// Returns target names
+    ///
+    /// Target names are human-readable descriptions of the classes in the targets. For class
+    /// indices of type `usize` the name of class `i` is found at position `i`. Returns an empty
+    /// vector if no target names were set.
+    pub fn target_names(&self) -> Vec<String> {
+        self.target_names.clone()
+    }
 
-        self.feature_names = feature_names;
+    /// Updates the target names of a dataset
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linfa::Dataset;
+    /// use ndarray::array;
+    ///
+    /// let dataset = Dataset::new(array![[1.], [2.]], array![0, 1])
+    ///     .with_target_names(vec!["negative", "positive"]);
+    ///
+    /// assert_eq!(dataset.target_names(), vec!["negative", "positive"]);
+    /// ```
+    pub fn with_target_names<I: Into<String>>(mut self, names: Vec<I>) -> DatasetBase<R, S> {
+        self.target_names = names.into_iter().map(|x| x.into()).collect();
 
         self
     }
@@ -156,6 +235,7 @@ impl<L, R: Records, T: AsTargets<Elem = L>> DatasetBase<R, T> {
             targets: targets.map(fnc),
             weights,
             feature_names,
+            target_names: Vec::new(),
         }
     }
 
@@ -210,6 +290,7 @@ where
 
         DatasetBase::new(records, targets)
             .with_feature_names(self.feature_names.clone())
+            .with_target_names(self.target_names.clone())
             .with_weights(self.weights.clone())
     }
 
@@ -248,6 +329,34 @@ impl<L, R: Records, T: AsTargetsMut<Elem = L>> AsTargetsMut for DatasetBase<R, T
     }
 }
 
+/// Implementation for datasets owning their records
+///
+/// Records and targets can be modified in place, for example when scaling or clipping the data,
+/// without cloning the dataset. Their shape cannot change, so the samples stay aligned with their
+/// targets and weights.
+impl<F, E, T: AsTargetsMut<Elem = E>> DatasetBase<Array2<F>, T> {
+    /// Return a mutable view on the records of a dataset
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut dataset = linfa_datasets::iris();
+    ///
+    /// // convert the sepal length from centimeters to millimeters
+    /// dataset.records_mut().column_mut(0).mapv_inplace(|x| x * 10.);
+    /// ```
+    pub fn records_mut(&mut self) -> ArrayViewMut2<'_, F> {
+        self.records.view_mut()
+    }
+
+    /// Return a mutable view on the targets of a dataset
+    ///
+    /// The targets are returned as two-dimensional array, with a column for each target.
+    pub fn targets_mut(&mut self) -> ArrayViewMut2<'_, E> {
+        self.targets.as_multi_targets_mut()
+    }
+}
+
 #[allow(clippy::type_complexity)]
 impl<'a, L: 'a, F: Float, T> DatasetBase<ArrayView2<'a, F>, T>
 where
@@ -283,11 +392,13 @@ where
         };
         let dataset1 = DatasetBase::new(records_first, targets_first)
             .with_weights(first_weights)
-            .with_feature_names(self.feature_names.clone());
+            .with_feature_names(self.feature_names.clone())
+            .with_target_names(self.target_names.clone());
 
         let dataset2 = DatasetBase::new(records_second, targets_second)
             .with_weights(second_weights)
-            .with_feature_names(self.feature_names.clone());
+            .with_feature_names(self.feature_names.clone())
+            .with_target_names(self.target_names.clone());
 
         (dataset1, dataset2)
     }
@@ -381,6 +492,24 @@ impl<L: Label, R: Records, S: AsTargets<Elem = L>> DatasetBase<R, S> {
     pub fn label_frequencies(&self) -> HashMap<L, f32> {
         self.label_frequencies_with_mask(&[])
     }
+
+    /// Calculates label frequencies from a dataset, sorted by label
+    ///
+    /// In contrast to [`label_frequencies`](DatasetBase::label_frequencies) the order of the
+    /// returned frequencies is deterministic, which is useful for reports and logs.
+    ///
+    /// ### Returns
+    ///
+    /// A vector of labels and their frequencies in ascending order of the labels
+    pub fn label_frequencies_sorted(&self) -> Vec<(L, f32)>
+    where
+        L: Ord,
+    {
+        let mut freqs = self.label_frequencies().into_iter().collect::<Vec<_>>();
+        freqs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        freqs
+    }
 }
 
 impl<F: Float, D: Data<Elem = F>, I: Dimension> From<ArrayBase<D, I>>
@@ -393,6 +522,7 @@ impl<F: Float, D: Data<Elem = F>, I: Dimension> From<ArrayBase<D, I>>
             targets: empty_targets,
             weights: Array1::zeros(0),
             feature_names: Vec::new(),
+            target_names: Vec::new(),
         }
     }
 }
@@ -409,6 +539,7 @@ where
             targets: rec_tar.1,
             weights: Array1::zeros(0),
             feature_names: Vec::new(),
+            target_names: Vec::new(),
         }
     }
 }
@@ -425,6 +556,7 @@ where
             targets: rec_tar.1.insert_axis(Axis(1)),
             weights: Array1::zeros(0),
             feature_names: Vec::new(),
+            target_names: Vec::new(),
         }
     }
 }
@@ -566,6 +698,128 @@ where
         DatasetBase::new(records, targets)
     }
 
+    /// Draws a random subset of the samples without replacement
+    ///
+    /// The selected samples keep their original order, weights, feature names and target names
+    /// are retained.
+    ///
+    /// ### Parameters
+    ///
+    /// * `size`: the number of samples, or the fraction of the dataset, to draw
+    /// * `rng`: the random number generator used to select the samples
+    ///
+    /// ### Returns
+    ///
+    /// A new dataset with the selected samples, or an error if more samples are requested than
+    /// available
+    pub fn random_sample<R: Rng, S: Into<SampleSize>>(
+        &self,
+        size: S,
+        rng: &mut R,
+    ) -> Result<DatasetBase<Array2<F>, T::Owned>> {
+        let n = size.into().resolve(self.nsamples())?;
+
+        let mut indices = index::sample(rng, self.nsamples(), n).into_vec();
+        indices.sort_unstable();
+
+        Ok(self.select_samples(&indices))
+    }
+
+    /// Draws a class-proportional random subset of the samples without replacement
+    ///
+    /// Each class contributes samples in proportion to its frequency in the dataset, remaining
+    /// samples are assigned to the classes with the largest fractional share. The selected samples
+    /// keep their original order, weights, feature names and target names are retained.
+    ///
+    /// ### Parameters
+    ///
+    /// * `size`: the number of samples, or the fraction of the dataset, to draw
+    /// * `rng`: the random number generator used to select the samples
+    ///
+    /// ### Returns
+    ///
+    /// A new dataset with the selected samples, or an error if more samples are requested than
+    /// available or the dataset has multiple targets
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use rand::{rngs::SmallRng, SeedableRng};
+    ///
+    /// let dataset = linfa_datasets::iris();
+    /// let mut rng = SmallRng::seed_from_u64(42);
+    ///
+    /// // draw ten samples from each of the three classes
+    /// let subset = dataset.stratified_sample(0.2f32, &mut rng).unwrap();
+    /// assert_eq!(subset.label_frequencies_sorted(), vec![(0, 10.), (1, 10.), (2, 10.)]);
+    /// ```
+    pub fn stratified_sample<R: Rng, S: Into<SampleSize>>(
+        &self,
+        size: S,
+        rng: &mut R,
+    ) -> Result<DatasetBase<Array2<F>, T::Owned>>
+    where
+        E: Label,
+    {
+        let nsamples = self.nsamples();
+        let n = size.into().resolve(nsamples)?;
+        let targets = self.try_single_target()?;
+
+        // group samples by class in order of first appearance
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        let mut class_index = HashMap::new();
+        for (i, label) in targets.iter().enumerate() {
+            let idx = *class_index.entry(label).or_insert_with(|| {
+                classes.push(Vec::new());
+                classes.len() - 1
+            });
+            classes[idx].push(i);
+        }
+
+        // allocate samples to classes with the largest remainder method
+        let mut counts = classes
+            .iter()
+            .map(|members| members.len() * n / nsamples)
+            .collect::<Vec<_>>();
+        let mut remainders = classes
+            .iter()
+            .map(|members| members.len() * n % nsamples)
+            .enumerate()
+            .collect::<Vec<_>>();
+        remainders.sort_by(|a, b| b.1.cmp(&a.1));
+        let missing = n - counts.iter().sum::<usize>();
+        for (idx, _) in remainders.into_iter().take(missing) {
+            counts[idx] += 1;
+        }
+
+        let mut indices = Vec::with_capacity(n);
+        for (members, count) in classes.iter().zip(counts.into_iter()) {
+            indices.extend(
+                index::sample(rng, members.len(), count)
+                    .into_iter()
+                    .map(|i| members[i]),
+            );
+        }
+        indices.sort_unstable();
+
+        Ok(self.select_samples(&indices))
+    }
+
+    fn select_samples(&self, indices: &[usize]) -> DatasetBase<Array2<F>, T::Owned> {
+        let records = self.records().select(Axis(0), indices);
+        let targets = T::new_targets(self.as_multi_targets().select(Axis(0), indices));
+        let weights = if self.weights.len() == self.nsamples() {
+            indices.iter().map(|&i| self.weights[i]).collect()
+        } else {
+            Array1::zeros(0)
+        };
+
+        DatasetBase::new(records, targets)
+            .with_weights(weights)
+            .with_feature_names(self.feature_names.clone())
+            .with_target_names(self.target_names.clone())
+    }
+
     #[allow(clippy::type_complexity)]
     /// Performs K-folding on the dataset.
     /// The dataset is divided into `k` "fold", each containing
@@ -1041,13 +1295,162 @@ impl<F: Float, E> Dataset<F, E> {
         // create new datasets with attached weights
         let dataset1 = Dataset::new(first, first_targets)
             .with_weights(self.weights)
-            .with_feature_names(feature_names.clone());
+            .with_feature_names(feature_names.clone())
+            .with_target_names(self.target_names.clone());
         let dataset2 = Dataset::new(second, second_targets)
             .with_weights(second_weights)
-            .with_feature_names(feature_names);
+            .with_feature_names(feature_names)
+            .with_target_names(self.target_names);
 
         (dataset1, dataset2)
     }
+
+    /// Shuffle the dataset and split it into two disjoint chunks
+    ///
+    /// Same as [`split_with_ratio`](#method.split_with_ratio), but the samples are permuted
+    /// before splitting. For datasets ordered by their targets, like the Iris dataset, this avoids
+    /// chunks which contain only a subset of the classes. Weights, feature and target names are
+    /// retained.
+    ///
+    /// ### Parameters
+    ///
+    /// * `ratio`: the ratio of samples in the input Dataset to include in the first output one
+    /// * `rng`: the random number generator used to shuffle the samples
+    ///
+    /// ### Returns
+    ///
+    /// The shuffled input Dataset split into two according to the input ratio.
+    pub fn split_with_ratio_shuffled<R: Rng>(self, ratio: f32, rng: &mut R) -> (Self, Self)
+    where
+        E: Clone,
+    {
+        let nsamples = self.nsamples();
+        let mut indices = (0..nsamples).collect::<Vec<_>>();
+        indices.shuffle(rng);
+
+        let weights = if self.weights.len() == nsamples {
+            self.weights.select(Axis(0), &indices)
+        } else {
+            self.weights
+        };
+
+        let dataset = DatasetBase {
+            records: self.records.select(Axis(0), &indices),
+            targets: self.targets.select(Axis(0), &indices),
+            weights,
+            feature_names: self.feature_names,
+            target_names: self.target_names,
+        };
+
+        dataset.split_with_ratio(ratio)
+    }
+
+    /// Permutation test for the significance of a cross validated score
+    ///
+    /// The model is evaluated with k-fold cross validation, once on the dataset and
+    /// `n_permutations` times on copies of the dataset with shuffled targets. Shuffling removes any
+    /// dependency between records and targets, so the permuted scores estimate the distribution of
+    /// the score under the null hypothesis that the model performs no better than chance. Higher
+    /// scores are assumed to be better.
+    ///
+    /// ### Parameters
+    ///
+    /// - `params`: the parameters of the fittable algorithm
+    /// - `k`: the number of folds to apply
+    /// - `n_permutations`: the number of times the targets are shuffled
+    /// - `rng`: the random number generator used to shuffle the targets
+    /// - `eval`: closure used to evaluate the predictions against the targets of each fold
+    ///
+    /// ### Returns
+    ///
+    /// The cross validated score on the dataset, the cross validated scores on the permuted
+    /// datasets and the p-value `(C + 1) / (n_permutations + 1)`, where `C` is the number of
+    /// permutations scoring at least as well as the dataset. A small p-value indicates that the
+    /// model found a real dependency between records and targets.
+    ///
+    /// It returns an Error in one of the following cases:
+    ///
+    /// - The dataset has more than one target or `n_permutations` is zero
+    /// - An error occurred during the fitting of the model
+    /// - An error occurred inside the evaluation closure
+    ///
+    /// ### Example
+    ///
+    /// ```rust, ignore
+    ///
+    /// use linfa::prelude::*;
+    ///
+    /// let dataset = linfa_datasets::diabetes();
+    ///
+    /// let (score, permutation_scores, p_value) = dataset.permutation_test_score(
+    ///     &params,
+    ///     5,
+    ///     100,
+    ///     &mut rng,
+    ///     |prediction, truth| prediction.r2(truth),
+    /// )?;
+    /// ```
+    pub fn permutation_test_score<O, ER, M, FACC, C, R>(
+        &self,
+        params: &M,
+        k: usize,
+        n_permutations: usize,
+        rng: &mut R,
+        eval: C,
+    ) -> std::result::Result<(FACC, Array1<FACC>, FACC), ER>
+    where
+        E: Copy,
+        ER: std::error::Error + std::convert::From<crate::error::Error>,
+        M: for<'c> Fit<ArrayView2<'c, F>, ArrayView2<'c, E>, ER, Object = O>,
+        O: for<'c> PredictRef<ArrayView2<'c, F>, Array1<E>>,
+        FACC: Float,
+        C: Fn(&ArrayView1<E>, &ArrayView1<E>) -> std::result::Result<FACC, crate::error::Error>,
+        R: Rng,
+    {
+        if self.ntargets() != 1 {
+            return Err(crate::error::Error::Parameters(format!(
+                "permutation test requires a single target, but the dataset has {}",
+                self.ntargets()
+            ))
+            .into());
+        }
+        if n_permutations == 0 {
+            return Err(crate::error::Error::Parameters(
+                "permutation test requires at least one permutation".to_string(),
+            )
+            .into());
+        }
+
+        // cross validate on a copy of the dataset with the targets in the given order
+        let cv_score = |indices: &[usize]| -> std::result::Result<FACC, ER> {
+            let mut dataset =
+                Dataset::new(self.records.clone(), self.targets.select(Axis(0), indices))
+                    .with_weights(self.weights.clone());
+
+            let mut score = FACC::zero();
+            for (model, valid) in dataset.iter_fold(k, |train| params.fit(train)) {
+                let predicted: Array1<E> = model?.predict(valid.records());
+                score += eval(&predicted.view(), &valid.targets().column(0))?;
+            }
+
+            Ok(score / FACC::cast(k))
+        };
+
+        let mut indices = (0..self.nsamples()).collect::<Vec<_>>();
+        let score = cv_score(&indices)?;
+
+        let permutation_scores = (0..n_permutations)
+            .map(|_| {
+                indices.shuffle(rng);
+                cv_score(&indices)
+            })
+            .collect::<std::result::Result<Array1<FACC>, ER>>()?;
+
+        let nbetter = permutation_scores.iter().filter(|s| **s >= score).count();
+        let p_value = FACC::cast(nbetter + 1) / FACC::cast(n_permutations + 1);
+
+        Ok((score, permutation_scores, p_value))
+    }
 }
 
 impl<F: Float, D, T, O> Predict<ArrayBase<D, Ix2>, DatasetBase<ArrayBase<D, Ix2>, T>> for O
@@ -1066,9 +1469,13 @@ where
     R: Records<Elem = F>,
     O: PredictRef<R, S>,
 {
+    /// Predict targets for a dataset
+    ///
+    /// The predictions replace the targets of the dataset, while records, weights and feature
+    /// names are preserved.
     fn predict(&self, ds: DatasetBase<R, T>) -> DatasetBase<R, S> {
         let new_targets = self.predict_ref(&ds.records);
-        DatasetBase::new(ds.records, new_targets)
+        ds.with_targets(new_targets)
     }
 }
 impl<'a, F: Float, R, T, S, O> Predict<&'a DatasetBase<R, T>, S> for O
@@ -1091,6 +1498,16 @@ where
     }
 }
 
+impl<F: Float, T: Clone, O> PredictOne<F, T> for O
+where
+    O: for<'a> PredictRef<ArrayView2<'a, F>, Array1<T>>,
+{
+    fn predict_one(&self, sample: ArrayView1<F>) -> T {
+        let records = sample.insert_axis(Axis(0));
+        self.predict_ref(&records)[0].clone()
+    }
+}
+
 impl<L: Label, S: Labels<Elem = L>> CountedTargets<L, S> {
     pub fn new(targets: S) -> Self {
         let labels = targets.label_count();
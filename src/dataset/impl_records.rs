@@ -1,5 +1,7 @@
 use super::{DatasetBase, Float, Records};
 use ndarray::{ArrayBase, Axis, Data, Dimension};
+use sprs::{CsMatBase, SpIndex};
+use std::ops::Deref;
 
 /// Implement records for NdArrays
 impl<F: Float, S: Data<Elem = F>, I: Dimension> Records for ArrayBase<S, I> {
@@ -14,6 +16,27 @@ impl<F: Float, S: Data<Elem = F>, I: Dimension> Records for ArrayBase<S, I> {
     }
 }
 
+/// Implement records for sparse matrices, samples are stored in rows
+impl<F, I, Iptr, IptrS, IndS, DataS> Records for CsMatBase<F, I, IptrS, IndS, DataS, Iptr>
+where
+    F: Float,
+    I: SpIndex,
+    Iptr: SpIndex,
+    IptrS: Deref<Target = [Iptr]>,
+    IndS: Deref<Target = [I]>,
+    DataS: Deref<Target = [F]>,
+{
+    type Elem = F;
+
+    fn nsamples(&self) -> usize {
+        self.rows()
+    }
+
+    fn nfeatures(&self) -> usize {
+        self.cols()
+    }
+}
+
 /// Implement records for a DatasetBase
 impl<F: Float, D: Records<Elem = F>, T> Records for DatasetBase<D, T> {
     type Elem = F;
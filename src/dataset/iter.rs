@@ -100,6 +100,7 @@ where
             targets,
             weights,
             feature_names,
+            target_names: self.dataset.target_names.clone(),
         };
 
         Some(dataset_view)
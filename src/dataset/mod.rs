@@ -80,10 +80,22 @@ impl Float for f64 {
 ///
 /// Labels are countable, comparable and hashable. Currently null-type (no targets),
 /// boolean (binary task) and usize, strings (multi-label tasks) are supported.
-pub trait Label: PartialEq + Eq + Hash + Clone {}
+pub trait Label: PartialEq + Eq + Hash + Clone {
+    /// Look up the human-readable name of a label in a list of target names
+    ///
+    /// Labels which are class indices resolve to the name at their position, all other labels
+    /// have no name.
+    fn target_name<'a>(&self, _names: &'a [String]) -> Option<&'a str> {
+        None
+    }
+}
 
 impl Label for bool {}
-impl Label for usize {}
+impl Label for usize {
+    fn target_name<'a>(&self, names: &'a [String]) -> Option<&'a str> {
+        names.get(*self).map(|x| x.as_str())
+    }
+}
 impl Label for String {}
 impl Label for () {}
 impl Label for &str {}
@@ -138,6 +150,7 @@ impl Deref for Pr {
 /// * `targets`: a two-/one-dimension matrix with dimensionality (nsamples, ntargets)
 /// * `weights`: optional weights for each sample with dimensionality (nsamples)
 /// * `feature_names`: optional descriptive feature names with dimensionality (nfeatures)
+/// * `target_names`: optional descriptive names of the classes in the targets
 ///
 /// # Trait bounds
 ///
@@ -153,6 +166,7 @@ where
 
     pub weights: Array1<f32>,
     feature_names: Vec<String>,
+    target_names: Vec<String>,
 }
 
 /// Targets with precomputed, counted labels
@@ -190,12 +204,77 @@ pub type DatasetView<'a, D, T> = DatasetBase<ArrayView<'a, D, Ix2>, ArrayView<'a
 pub type DatasetPr<D, L> =
     DatasetBase<ArrayBase<OwnedRepr<D>, Ix2>, CountedTargets<L, ArrayBase<OwnedRepr<Pr>, Ix3>>>;
 
+/// Size of a random subsample
+///
+/// The size is either given as an absolute number of samples or as a fraction of the samples in
+/// the dataset. Both `usize` and `f32` convert into a sample size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleSize {
+    Count(usize),
+    Fraction(f32),
+}
+
+impl SampleSize {
+    /// Resolve the number of samples drawn from a dataset with `nsamples` samples
+    pub fn resolve(self, nsamples: usize) -> Result<usize> {
+        match self {
+            SampleSize::Count(n) if n > nsamples => Err(Error::Parameters(format!(
+                "requested {} samples, but the dataset contains only {}",
+                n, nsamples
+            ))),
+            SampleSize::Count(n) => Ok(n),
+            SampleSize::Fraction(ratio) if !(0.0..=1.0).contains(&ratio) => Err(Error::Parameters(
+                format!("sample fraction {} not in range [0, 1]", ratio),
+            )),
+            SampleSize::Fraction(ratio) => Ok((nsamples as f32 * ratio).round() as usize),
+        }
+    }
+}
+
+impl From<usize> for SampleSize {
+    fn from(n: usize) -> SampleSize {
+        SampleSize::Count(n)
+    }
+}
+
+impl From<f32> for SampleSize {
+    fn from(ratio: f32) -> SampleSize {
+        SampleSize::Fraction(ratio)
+    }
+}
+
 /// Record trait
 pub trait Records: Sized {
     type Elem;
 
     fn nsamples(&self) -> usize;
     fn nfeatures(&self) -> usize;
+
+    /// Check that the records are large enough to fit a model
+    ///
+    /// Returns an `Error::Parameters` if the records have no samples, no features or fewer than
+    /// `min_samples` samples.
+    fn check_shape(&self, min_samples: usize) -> Result<()> {
+        if self.nsamples() == 0 {
+            return Err(Error::Parameters(
+                "The records do not contain any samples".to_string(),
+            ));
+        }
+        if self.nfeatures() == 0 {
+            return Err(Error::Parameters(
+                "The records do not contain any features".to_string(),
+            ));
+        }
+        if self.nsamples() < min_samples {
+            return Err(Error::Parameters(format!(
+                "At least {} samples are required, but the records contain only {}",
+                min_samples,
+                self.nsamples()
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 /// Return a reference to single or multiple target variables
@@ -373,6 +452,12 @@ mod tests {
         assert_eq!(*freqs.get(&0).unwrap() as usize, 1);
         assert_eq!(*freqs.get(&1).unwrap() as usize, 1);
         assert_eq!(*freqs.get(&2).unwrap() as usize, 1);
+
+        // Sorted frequencies have a deterministic order
+        let freqs = dataset_multiclass
+            .map_targets(|x| 2 - *x)
+            .label_frequencies_sorted();
+        assert_eq!(freqs, vec![(0, 2.), (1, 1.), (2, 1.)]);
     }
 
     #[test]
@@ -524,8 +609,8 @@ mod tests {
         );
     }
 
-    use crate::traits::{Fit, PredictRef};
-    use ndarray::ArrayView2;
+    use crate::traits::{Fit, Predict, PredictOne, PredictRef};
+    use ndarray::{ArrayBase, ArrayView2, Axis, Data, Ix2};
     use thiserror::Error;
 
     struct MockFittable {
@@ -573,6 +658,186 @@ mod tests {
         }
     }
 
+    struct MockRowSum;
+
+    impl<D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<f64>> for MockRowSum {
+        fn predict_ref<'a>(&'a self, x: &'a ArrayBase<D, Ix2>) -> Array1<f64> {
+            x.sum_axis(Axis(1))
+        }
+    }
+
+    #[test]
+    fn test_predict_dataset_replaces_targets() {
+        let dataset = Dataset::new(array![[1., 2.], [3., 4.], [5., 6.]], array![0., 0., 0.])
+            .with_weights(array![1., 2., 3.])
+            .with_feature_names(vec!["a", "b"]);
+
+        let predicted: DatasetBase<Array2<f64>, Array1<f64>> = MockRowSum.predict(dataset);
+
+        assert_abs_diff_eq!(predicted.records(), &array![[1., 2.], [3., 4.], [5., 6.]]);
+        assert_abs_diff_eq!(predicted.targets(), &array![3., 7., 11.]);
+        assert_eq!(predicted.weights(), Some(&[1., 2., 3.][..]));
+        assert_eq!(predicted.feature_names(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_predict_one() {
+        let records = array![[1., 2.], [3., 4.]];
+
+        for row in records.genrows() {
+            let prediction: Array1<f64> = MockRowSum.predict(&row.insert_axis(Axis(0)));
+            assert_abs_diff_eq!(MockRowSum.predict_one(row), prediction[0]);
+        }
+    }
+
+    #[test]
+    fn test_stratified_sample() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = linfa_datasets::winequality();
+
+        let subset = dataset.stratified_sample(0.2f32, &mut rng).unwrap();
+        assert_eq!(subset.nsamples(), 320);
+        assert_eq!(subset.feature_names(), dataset.feature_names());
+
+        // class proportions are retained up to a single sample
+        let (n, n_sub) = (dataset.nsamples() as f32, subset.nsamples() as f32);
+        let freqs = dataset.label_frequencies();
+        let sub_freqs = subset.label_frequencies();
+        assert_eq!(freqs.len(), sub_freqs.len());
+        for (label, freq) in freqs {
+            assert_abs_diff_eq!(sub_freqs[&label] / n_sub, freq / n, epsilon = 1. / n_sub);
+        }
+
+        // the same seed selects the same samples
+        let mut rng = SmallRng::seed_from_u64(42);
+        let subset2 = dataset.stratified_sample(0.2f32, &mut rng).unwrap();
+        assert_eq!(subset.records(), subset2.records());
+
+        assert!(dataset.stratified_sample(1600usize, &mut rng).is_err());
+        assert!(dataset.stratified_sample(1.5f32, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_records_targets_mut() {
+        let mut dataset = Dataset::new(array![[1., 2.], [3., 4.], [5., 6.]], array![0., 1., 2.]);
+
+        dataset
+            .records_mut()
+            .column_mut(1)
+            .mapv_inplace(|x| x.min(4.));
+        dataset.targets_mut().mapv_inplace(|x| x * 2.);
+
+        assert_abs_diff_eq!(dataset.records(), &array![[1., 2.], [3., 4.], [5., 4.]]);
+        assert_abs_diff_eq!(dataset.targets(), &array![[0.], [2.], [4.]]);
+    }
+
+    #[test]
+    fn test_split_with_ratio_shuffled() {
+        let mut rng = SmallRng::seed_from_u64(42);
+
+        // the iris dataset is ordered by species, every split should contain all of them
+        let dataset = linfa_datasets::iris();
+        let feature_names = dataset.feature_names();
+        let (train, valid) = dataset.split_with_ratio_shuffled(0.8, &mut rng);
+        assert_eq!((train.nsamples(), valid.nsamples()), (120, 30));
+        assert_eq!(train.label_frequencies().len(), 3);
+        assert_eq!(valid.label_frequencies().len(), 3);
+        assert_eq!(train.feature_names(), feature_names);
+        assert_eq!(valid.feature_names(), feature_names);
+
+        // weights stay aligned with their samples
+        let dataset = Dataset::new(
+            Array2::from_shape_fn((10, 1), |(i, _)| i as f64),
+            Array1::from_shape_fn(10, |i| i as f64),
+        )
+        .with_weights(Array1::from_shape_fn(10, |i| i as f32));
+        let (train, valid) = dataset.split_with_ratio_shuffled(0.7, &mut rng);
+        assert_eq!((train.nsamples(), valid.nsamples()), (7, 3));
+        for ds in &[train, valid] {
+            for ((record, target), weight) in ds
+                .records()
+                .iter()
+                .zip(ds.targets().iter())
+                .zip(ds.weights().unwrap().iter())
+            {
+                assert_abs_diff_eq!(*record, *target);
+                assert_abs_diff_eq!(*record as f32, *weight);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_sample() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = linfa_datasets::diabetes();
+
+        let subset = dataset.random_sample(100usize, &mut rng).unwrap();
+        assert_eq!(subset.nsamples(), 100);
+        assert_eq!(subset.feature_names(), dataset.feature_names());
+
+        // every selected sample is contained in the original dataset
+        for row in subset.records().genrows() {
+            assert!(dataset.records().genrows().into_iter().any(|x| x == row));
+        }
+
+        assert!(dataset
+            .random_sample(dataset.nsamples() + 1, &mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn test_rename_feature() {
+        let mut dataset = Dataset::new(array![[1., 2., 3.]], array![0.]);
+        dataset.rename_feature(1, "b");
+        assert_eq!(dataset.feature_names(), vec!["feature-0", "b", "feature-2"]);
+
+        dataset.set_feature_names(vec!["x", "y", "z"]);
+        dataset.rename_feature(2, "w");
+        assert_eq!(dataset.feature_names(), vec!["x", "y", "w"]);
+
+        // an empty vector restores the default names
+        dataset.set_feature_names(Vec::<String>::new());
+        assert_eq!(
+            dataset.feature_names(),
+            vec!["feature-0", "feature-1", "feature-2"]
+        );
+    }
+
+    #[test]
+    fn test_target_names_round_trip() {
+        let dataset = Dataset::new(
+            array![[1., 2.], [3., 4.], [5., 6.], [7., 8.]],
+            array![0, 1, 1, 0],
+        )
+        .with_target_names(vec!["negative", "positive"]);
+        assert_eq!(dataset.target_names(), vec!["negative", "positive"]);
+
+        // target names survive views and splits
+        let view = dataset.view();
+        assert_eq!(view.target_names(), vec!["negative", "positive"]);
+        let (train, valid) = view.split_with_ratio(0.5);
+        assert_eq!(train.target_names(), vec!["negative", "positive"]);
+        assert_eq!(valid.target_names(), vec!["negative", "positive"]);
+
+        // but not a mapping to new targets
+        let dataset = dataset.map_targets(|x| *x == 1);
+        assert!(dataset.target_names().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "number of feature names (2) does not match number of features (3)")]
+    fn test_feature_names_wrong_length() {
+        let _dataset =
+            Dataset::new(array![[1., 2., 3.]], array![0.]).with_feature_names(vec!["a", "b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "feature index 3 out of range for 3 features")]
+    fn test_rename_feature_out_of_range() {
+        let mut dataset = Dataset::new(array![[1., 2., 3.]], array![0.]);
+        dataset.rename_feature(3, "d");
+    }
+
     #[test]
     fn test_iter_fold() {
         let records =
@@ -768,6 +1033,103 @@ mod tests {
         assert_eq!(err.to_string(), "invalid parameter eval".to_string());
     }
 
+    /// Predicts the mean target of the training samples with the same sign of the first feature
+    struct MockSignMean;
+
+    struct MockSignMeanResult {
+        positive: f64,
+        negative: f64,
+    }
+
+    impl<'a> Fit<ArrayView2<'a, f64>, ArrayView2<'a, f64>, MockError> for MockSignMean {
+        type Object = MockSignMeanResult;
+
+        fn fit(
+            &self,
+            training_data: &DatasetView<f64, f64>,
+        ) -> std::result::Result<Self::Object, MockError> {
+            let mean = |positive: bool| {
+                let targets = training_data
+                    .records()
+                    .column(0)
+                    .iter()
+                    .zip(training_data.targets().iter())
+                    .filter(|(x, _)| (**x > 0.) == positive)
+                    .map(|(_, y)| *y)
+                    .collect::<Vec<_>>();
+
+                targets.iter().sum::<f64>() / targets.len() as f64
+            };
+
+            Ok(MockSignMeanResult {
+                positive: mean(true),
+                negative: mean(false),
+            })
+        }
+    }
+
+    impl<'b> PredictRef<ArrayView2<'b, f64>, Array1<f64>> for MockSignMeanResult {
+        fn predict_ref<'a>(&'a self, x: &'a ArrayView2<'b, f64>) -> Array1<f64> {
+            x.column(0)
+                .mapv(|x| if x > 0. { self.positive } else { self.negative })
+        }
+    }
+
+    // records alternate in sign, so that every training set contains both signs
+    fn alternating_signs() -> Array2<f64> {
+        Array1::from_iter((1..=10).flat_map(|x| vec![x as f64, -x as f64])).insert_axis(Axis(1))
+    }
+
+    fn negative_mse(
+        predicted: &ArrayView1<f64>,
+        truth: &ArrayView1<f64>,
+    ) -> std::result::Result<f64, Error> {
+        Ok(-(predicted - truth).mapv(|x| x * x).mean().unwrap())
+    }
+
+    #[test]
+    fn test_permutation_test_score() {
+        let mut rng = SmallRng::seed_from_u64(42);
+
+        // the targets are given by the sign of the records
+        let records = alternating_signs();
+        let targets = records.column(0).mapv(|x| if x > 0. { 1. } else { 0. });
+        let dataset = Dataset::new(records, targets);
+
+        let (score, permutation_scores, p_value): (f64, _, _) = dataset
+            .permutation_test_score(&MockSignMean, 5, 100, &mut rng, negative_mse)
+            .unwrap();
+        assert_abs_diff_eq!(score, 0.);
+        assert_eq!(permutation_scores.len(), 100);
+        assert!(permutation_scores.iter().all(|s| *s < 0.));
+        assert_abs_diff_eq!(p_value, 1. / 101.);
+
+        // the targets do not depend on the records
+        let targets =
+            array![0., 1., 0., 0., 0., 1., 0., 0., 1., 0., 1., 0., 0., 0., 0., 1., 1., 0., 1., 1.];
+        let dataset = Dataset::new(alternating_signs(), targets);
+
+        let (_, _, p_value) = dataset
+            .permutation_test_score(&MockSignMean, 5, 100, &mut rng, negative_mse)
+            .unwrap();
+        assert!(p_value > 0.5);
+    }
+
+    #[test]
+    fn test_permutation_test_score_invalid() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let dataset = Dataset::new(alternating_signs(), Array1::<f64>::zeros(20));
+
+        let res: MockResult<(f64, _, _)> =
+            dataset.permutation_test_score(&MockSignMean, 5, 0, &mut rng, negative_mse);
+        assert!(res.is_err());
+
+        let dataset = Dataset::new(alternating_signs(), Array2::<f64>::zeros((20, 2)));
+        let res: MockResult<(f64, _, _)> =
+            dataset.permutation_test_score(&MockSignMean, 5, 10, &mut rng, negative_mse);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn test_with_labels_st() {
         let records = array![
@@ -0,0 +1,104 @@
+//! Distribution functions
+//!
+//! Approximations of the cumulative distribution and quantile functions of the standard normal
+//! distribution, shared by algorithms which need them for confidence intervals or
+//! transformations to a normal distribution.
+
+/// Cumulative distribution function of the standard normal distribution
+///
+/// Uses the Chebyshev approximation of the complementary error function with a fractional
+/// error below 1.2e-7.
+pub fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1. / (1. + 0.5 * z);
+    let poly = -1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let erfc = t * (-z * z + poly).exp();
+
+    if x >= 0. {
+        1. - 0.5 * erfc
+    } else {
+        0.5 * erfc
+    }
+}
+
+/// Quantile function of the standard normal distribution
+///
+/// Uses the rational approximation of Acklam with a relative error below 1.2e-9.
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.383577518672690e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+
+    if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p > 1. - P_LOW {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normal_cdf, normal_quantile};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_normal_quantile() {
+        assert_abs_diff_eq!(normal_quantile(0.5), 0., epsilon = 1e-9);
+        assert_abs_diff_eq!(normal_quantile(0.975), 1.959963985, epsilon = 1e-8);
+        assert_abs_diff_eq!(normal_quantile(0.01), -2.326347874, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert_abs_diff_eq!(normal_cdf(0.), 0.5, epsilon = 1e-7);
+        assert_abs_diff_eq!(normal_cdf(1.959964), 0.975, epsilon = 1e-6);
+        assert_abs_diff_eq!(normal_cdf(-1.), 0.158655, epsilon = 1e-6);
+        for p in &[0.001, 0.1, 0.5, 0.7, 0.99] {
+            assert_abs_diff_eq!(normal_cdf(normal_quantile(*p)), *p, epsilon = 1e-6);
+        }
+    }
+}
@@ -36,4 +36,6 @@ pub enum Error {
     Platt(PlattNewtonResult),
     #[error("The number of samples do not match: {0} - {1}")]
     MismatchedShapes(usize, usize),
+    #[error("model persistence failed: {0}")]
+    Persistence(String),
 }
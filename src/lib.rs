@@ -44,12 +44,18 @@
 
 pub mod composing;
 pub mod correlation;
+pub mod covariance;
 pub mod dataset;
+pub mod distribution;
 pub mod error;
 mod metrics_classification;
 mod metrics_clustering;
 mod metrics_regression;
+mod metrics_score;
+#[cfg(feature = "serde")]
+pub mod persistence;
 pub mod prelude;
+pub mod summary;
 pub mod traits;
 
 pub use composing::*;
@@ -72,8 +78,15 @@ extern crate netblas_src;
 /// Common metrics functions for classification and regression
 pub mod metrics {
     pub use crate::metrics_classification::{
-        BinaryClassification, ConfusionMatrix, ReceiverOperatingCharacteristic, ToConfusionMatrix,
+        auc, brier_score, cumulative_gain_curve, lift_at, misclassified_indices,
+        multiclass_brier_score, optimal_threshold, roc_curve, worst_misclassifications,
+        BinaryClassification, ConfusionMatrix, Misclassification, ReceiverOperatingCharacteristic,
+        ThresholdCriterion, ToConfusionMatrix,
+    };
+    pub use crate::metrics_clustering::{
+        contingency_matrix, fowlkes_mallows_score, homogeneity_completeness_v_measure,
+        ContingencyMatrix, SilhouetteScore,
     };
-    pub use crate::metrics_clustering::SilhouetteScore;
     pub use crate::metrics_regression::{MultiTargetRegression, SingleTargetRegression};
+    pub use crate::metrics_score::{Accuracy, DefaultMetric, Metric, R2};
 }
@@ -11,6 +11,7 @@ use ndarray::Data;
 
 use crate::dataset::{AsTargets, DatasetBase, Label, Labels, Pr, Records};
 use crate::error::{Error, Result};
+use crate::Float;
 
 /// Return tuple of class index for each element of prediction and ground_truth
 fn map_prediction_to_idx<L: Label>(
@@ -41,6 +42,7 @@ fn map_prediction_to_idx<L: Label>(
 pub struct ConfusionMatrix<A> {
     matrix: Array2<f32>,
     members: Array1<A>,
+    target_names: Vec<String>,
 }
 
 impl<A> ConfusionMatrix<A> {
@@ -48,6 +50,17 @@ impl<A> ConfusionMatrix<A> {
         self.matrix.shape() == [2, 2]
     }
 
+    /// Attach human-readable class names to the confusion matrix
+    ///
+    /// The names are looked up with [`Label::target_name`], for class indices the name of class
+    /// `i` is found at position `i`. They replace the raw labels when the confusion matrix is
+    /// printed. Confusion matrices computed from datasets carry their target names already.
+    pub fn with_target_names<I: Into<String>>(mut self, names: Vec<I>) -> Self {
+        self.target_names = names.into_iter().map(|x| x.into()).collect();
+
+        self
+    }
+
     /// Precision score, the number of correct classifications for the first class divided by total
     /// number of items in the first class
     ///
@@ -202,6 +215,7 @@ impl<A> ConfusionMatrix<A> {
                 ConfusionMatrix {
                     matrix: array![[tp, fp], [_fn, tn]],
                     members: Array1::from(vec![true, false]),
+                    target_names: Vec::new(),
                 }
             })
             .collect()
@@ -222,6 +236,7 @@ impl<A> ConfusionMatrix<A> {
                 cms.push(ConfusionMatrix {
                     matrix: array![[tp, fp], [_fn, tn]],
                     members: Array1::from(vec![true, false]),
+                    target_names: Vec::new(),
                 });
             }
         }
@@ -231,18 +246,27 @@ impl<A> ConfusionMatrix<A> {
 }
 
 /// Print a confusion matrix
-impl<A: fmt::Display> fmt::Debug for ConfusionMatrix<A> {
+impl<A: fmt::Display + Label> fmt::Debug for ConfusionMatrix<A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = self
+            .members
+            .iter()
+            .map(|x| match x.target_name(&self.target_names) {
+                Some(name) => name.to_string(),
+                None => x.to_string(),
+            })
+            .collect::<Vec<_>>();
+
         let len = self.matrix.len_of(Axis(0));
         writeln!(f)?;
         write!(f, "{: <10}", "classes")?;
-        for i in 0..len {
-            write!(f, " | {: <10}", self.members[i])?;
+        for name in &names {
+            write!(f, " | {: <10}", name)?;
         }
         writeln!(f)?;
 
         for i in 0..len {
-            write!(f, "{: <10}", self.members[i])?;
+            write!(f, "{: <10}", names[i])?;
 
             for j in 0..len {
                 write!(f, " | {: <10}", self.matrix[(i, j)])?;
@@ -299,6 +323,7 @@ where
         Ok(ConfusionMatrix {
             matrix: confusion_matrix,
             members: Array1::from(classes),
+            target_names: Vec::new(),
         })
     }
 }
@@ -311,8 +336,16 @@ where
     T2: AsTargets<Elem = L> + Labels<Elem = L>,
 {
     fn confusion_matrix(&self, ground_truth: &DatasetBase<R, T>) -> Result<ConfusionMatrix<L>> {
-        self.targets()
-            .confusion_matrix(ground_truth.try_single_target()?)
+        let target_names = if ground_truth.target_names().is_empty() {
+            self.target_names()
+        } else {
+            ground_truth.target_names()
+        };
+
+        Ok(self
+            .targets()
+            .confusion_matrix(ground_truth.try_single_target()?)?
+            .with_target_names(target_names))
     }
 }
 
@@ -320,7 +353,9 @@ impl<L: Label, S: Data<Elem = L>, T: AsTargets<Elem = L> + Labels<Elem = L>, R:
     ToConfusionMatrix<L, &DatasetBase<R, T>> for ArrayBase<S, Ix1>
 {
     fn confusion_matrix(&self, ground_truth: &DatasetBase<R, T>) -> Result<ConfusionMatrix<L>> {
-        ground_truth.confusion_matrix(self.view())
+        Ok(ground_truth
+            .confusion_matrix(self.view())?
+            .with_target_names(ground_truth.target_names()))
     }
 }
 
@@ -379,22 +414,45 @@ impl IntoConfusionMatrix<bool, OwnedRepr<bool>> for Array1<bool> {
 }*/
 
 /// Integration using the trapezoidal rule.
-fn trapezoidal<A: NdFloat>(vals: &[(A, A)]) -> A {
-    let mut prev_x = vals[0].0;
-    let mut prev_y = vals[0].1;
-    let mut integral = A::zero();
+///
+/// The area of an empty curve or a single point is zero.
+fn trapezoidal<A: num_traits::Float>(vals: &[(A, A)]) -> A {
+    let two = A::one() + A::one();
 
-    for (x, y) in vals.iter().skip(1) {
-        integral += (*x - prev_x) * (prev_y + *y) / A::from(2.0).unwrap();
-        prev_x = *x;
-        prev_y = *y;
+    vals.windows(2)
+        .map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / two)
+        .fold(A::zero(), |integral, x| integral + x)
+}
+
+/// Pair every score with its label and sort them by decreasing score
+///
+/// Fails if the number of labels and scores differ or if any score is NaN, as NaN can not be
+/// ranked.
+fn rank_by_score<F: Float>(y_true: &[bool], scores: &[F]) -> Result<Vec<(F, bool)>> {
+    if y_true.len() != scores.len() {
+        return Err(Error::MismatchedShapes(y_true.len(), scores.len()));
     }
-    integral
+    if scores.iter().any(|x| x.is_nan()) {
+        return Err(Error::Parameters("scores should not be NaN".to_string()));
+    }
+
+    let mut tuples = scores
+        .iter()
+        .zip(y_true.iter())
+        .map(|(s, t)| (*s, *t))
+        .collect::<Vec<_>>();
+
+    // all scores are comparable after excluding NaN
+    tuples.sort_unstable_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(tuples)
 }
 
 /// A Receiver Operating Characteristic for binary-label classification
 ///
-/// The ROC curve gives insight about the seperability of a binary classification task.
+/// The ROC curve gives insight about the seperability of a binary classification task. The
+/// curve and its thresholds are also available as arrays from `roc_curve`, both integrate the
+/// curve with the same trapezoidal rule.
 pub struct ReceiverOperatingCharacteristic {
     curve: Vec<(f32, f32)>,
     thresholds: Vec<f32>,
@@ -417,6 +475,386 @@ impl ReceiverOperatingCharacteristic {
     }
 }
 
+/// Compute the Receiver Operating Characteristic curve of a binary classification
+///
+/// The samples are ranked by their `scores` in decreasing order, every distinct score is used as
+/// a threshold and samples with a score greater or equal to it are classified as positive. Tied
+/// scores are therefore grouped into a single point of the curve.
+///
+/// Returns the false-positive rates, true-positive rates and the corresponding thresholds. The
+/// curve always starts at `(0, 0)` with an infinite threshold and ends at `(1, 1)` with the
+/// smallest score as threshold.
+///
+/// Fails if the number of labels and scores differ, if any score is NaN or if `y_true` contains
+/// only a single class.
+pub fn roc_curve<F: Float>(
+    y_true: &[bool],
+    scores: &[F],
+) -> Result<(Array1<F>, Array1<F>, Array1<F>)> {
+    let tuples = rank_by_score(y_true, scores)?;
+
+    let (mut tp, mut fp) = (F::zero(), F::zero());
+    let mut tps_fps = vec![(F::zero(), F::zero())];
+    let mut thresholds = vec![F::infinity()];
+
+    for (i, (s, t)) in tuples.iter().enumerate() {
+        if *t {
+            tp += F::one();
+        } else {
+            fp += F::one();
+        }
+
+        // emit a point only after the last sample of a group of tied scores
+        if tuples.get(i + 1).map(|next| next.0 != *s).unwrap_or(true) {
+            tps_fps.push((tp, fp));
+            thresholds.push(*s);
+        }
+    }
+
+    if tp == F::zero() || fp == F::zero() {
+        return Err(Error::Parameters(
+            "ROC curve requires both positive and negative samples".to_string(),
+        ));
+    }
+
+    let fpr = tps_fps.iter().map(|(_, x)| *x / fp).collect();
+    let tpr = tps_fps.iter().map(|(x, _)| *x / tp).collect();
+
+    Ok((fpr, tpr, Array1::from(thresholds)))
+}
+
+/// Compute the area under a curve with the trapezoidal rule
+///
+/// The points are integrated in the given order, for example the false-positive and
+/// true-positive rates returned by `roc_curve`.
+pub fn auc<F: Float>(x: ArrayView1<F>, y: ArrayView1<F>) -> Result<F> {
+    if x.len() != y.len() {
+        return Err(Error::MismatchedShapes(x.len(), y.len()));
+    }
+
+    let points = x
+        .iter()
+        .zip(y.iter())
+        .map(|(x, y)| (*x, *y))
+        .collect::<Vec<_>>();
+
+    Ok(trapezoidal(&points))
+}
+
+/// Criterion for selecting the decision threshold of a binary classifier
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdCriterion {
+    /// Maximize Youden's J statistic, the difference of true-positive and false-positive rate
+    YoudensJ,
+    /// Maximize the F1-score of the positive class
+    MaxF1,
+}
+
+/// Find the decision threshold optimizing a criterion
+///
+/// Every point of the ROC curve, see `roc_curve`, is evaluated and the threshold with the largest
+/// value of `criterion` is selected. Samples with a score greater or equal to the threshold are
+/// classified as positive.
+///
+/// Returns the threshold and the achieved value of the criterion.
+pub fn optimal_threshold<F: Float>(
+    y_true: &[bool],
+    scores: &[F],
+    criterion: ThresholdCriterion,
+) -> Result<(F, F)> {
+    let (fpr, tpr, thresholds) = roc_curve(y_true, scores)?;
+
+    let npositives = F::cast(y_true.iter().filter(|x| **x).count());
+    let nnegatives = F::cast(y_true.len()) - npositives;
+
+    // skip the first point, as its threshold is infinite
+    let (threshold, value) = fpr
+        .iter()
+        .zip(tpr.iter())
+        .zip(thresholds.iter())
+        .skip(1)
+        .map(|((fpr, tpr), threshold)| {
+            let value = match criterion {
+                ThresholdCriterion::YoudensJ => *tpr - *fpr,
+                ThresholdCriterion::MaxF1 => {
+                    let tp = *tpr * npositives;
+                    let fp = *fpr * nnegatives;
+                    F::cast(2.0) * tp / (tp + fp + npositives)
+                }
+            };
+
+            (*threshold, value)
+        })
+        .fold((F::infinity(), F::neg_infinity()), |best, x| {
+            if x.1 > best.1 {
+                x
+            } else {
+                best
+            }
+        });
+
+    Ok((threshold, value))
+}
+
+/// Compute the cumulative gains curve of a ranking
+///
+/// The samples are ranked by their `scores` in decreasing order and targeted one after another.
+/// For every prefix of the ranking the curve contains the fraction of the population targeted
+/// and the fraction of all positive samples captured by it. As in `roc_curve`, tied scores are
+/// grouped into a single point. The curve starts at `(0, 0)` and ends at `(1, 1)`, a perfect
+/// ranking rises with the largest possible slope until all positives are captured.
+///
+/// Returns the targeted fractions and the captured fractions of positives.
+///
+/// Fails if the number of labels and scores differ, if any score is NaN or if `y_true` contains no
+/// positive sample.
+pub fn cumulative_gain_curve<F: Float>(
+    y_true: &[bool],
+    scores: &[F],
+) -> Result<(Array1<F>, Array1<F>)> {
+    let tuples = rank_by_score(y_true, scores)?;
+
+    let (mut targeted, mut captured) = (F::zero(), F::zero());
+    let mut points = vec![(F::zero(), F::zero())];
+
+    for (i, (s, t)) in tuples.iter().enumerate() {
+        targeted += F::one();
+        if *t {
+            captured += F::one();
+        }
+
+        // emit a point only after the last sample of a group of tied scores
+        if tuples.get(i + 1).map(|next| next.0 != *s).unwrap_or(true) {
+            points.push((targeted, captured));
+        }
+    }
+
+    if captured == F::zero() {
+        return Err(Error::Parameters(
+            "cumulative gains require at least one positive sample".to_string(),
+        ));
+    }
+
+    let fractions = points.iter().map(|(x, _)| *x / targeted).collect();
+    let gains = points.iter().map(|(_, x)| *x / captured).collect();
+
+    Ok((fractions, gains))
+}
+
+/// Compute the lift when targeting the top `fraction` of a ranking
+///
+/// The lift is the fraction of positives captured, see `cumulative_gain_curve`, divided by the
+/// fraction of the population targeted. It measures how many times better the ranking is than
+/// targeting at random, which has a lift of one. Between two points of the gains curve, for
+/// example inside a group of tied scores, the gain is interpolated linearly.
+///
+/// Fails if `fraction` is not in `(0, 1]` or if the gains curve can not be computed.
+pub fn lift_at<F: Float>(y_true: &[bool], scores: &[F], fraction: F) -> Result<F> {
+    if !(fraction > F::zero() && fraction <= F::one()) {
+        return Err(Error::Parameters(format!(
+            "fraction of the population should be in (0, 1], but is {}",
+            fraction
+        )));
+    }
+
+    let (fractions, gains) = cumulative_gain_curve(y_true, scores)?;
+
+    // find the segment of the curve containing the fraction
+    let idx = fractions
+        .iter()
+        .position(|x| *x >= fraction)
+        .unwrap_or(fractions.len() - 1);
+    let gain = if fractions[idx] == fraction {
+        gains[idx]
+    } else {
+        let (x0, x1) = (fractions[idx - 1], fractions[idx]);
+        let (y0, y1) = (gains[idx - 1], gains[idx]);
+        y0 + (y1 - y0) * (fraction - x0) / (x1 - x0)
+    };
+
+    Ok(gain / fraction)
+}
+
+/// Compute the Brier score of predicted probabilities for the positive class
+///
+/// The Brier score is the mean squared difference between the predicted probabilities and the
+/// outcomes, encoded as one for positive and zero for negative samples. It is a proper scoring
+/// rule bounded in `[0, 1]`, lower values indicate better calibrated and sharper predictions.
+///
+/// Fails if the number of labels and probabilities differ or if no sample is given.
+pub fn brier_score<F: Float>(y_true: &[bool], probs: &[F]) -> Result<F> {
+    if y_true.len() != probs.len() {
+        return Err(Error::MismatchedShapes(y_true.len(), probs.len()));
+    }
+    if y_true.is_empty() {
+        return Err(Error::NotEnoughSamples);
+    }
+
+    let sum = y_true
+        .iter()
+        .zip(probs.iter())
+        .map(|(t, p)| {
+            let diff = if *t { F::one() - *p } else { *p };
+            diff * diff
+        })
+        .sum::<F>();
+
+    Ok(sum / F::cast(y_true.len()))
+}
+
+/// Compute the Brier score of predicted class probabilities
+///
+/// Generalizes `brier_score` to multiple classes. The probabilities are given as a matrix with
+/// shape `(n_samples, n_classes)` and `y_true` contains the index of the true class for each
+/// sample. The squared differences to the one-hot encoded outcomes are summed over the classes
+/// and averaged over the samples, which gives a score in `[0, 2]`.
+///
+/// Fails if the number of labels and rows differ, if no sample is given or if a label is not a
+/// valid class index.
+pub fn multiclass_brier_score<F: Float>(y_true: &[usize], probs: ArrayView2<F>) -> Result<F> {
+    if y_true.len() != probs.nrows() {
+        return Err(Error::MismatchedShapes(y_true.len(), probs.nrows()));
+    }
+    if y_true.is_empty() {
+        return Err(Error::NotEnoughSamples);
+    }
+    if let Some(label) = y_true.iter().find(|x| **x >= probs.ncols()) {
+        return Err(Error::Parameters(format!(
+            "class index {} out of range for {} classes",
+            label,
+            probs.ncols()
+        )));
+    }
+
+    let sum = y_true
+        .iter()
+        .zip(probs.genrows())
+        .map(|(t, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(k, p)| {
+                    let diff = if k == *t { F::one() - *p } else { *p };
+                    diff * diff
+                })
+                .sum::<F>()
+        })
+        .sum::<F>();
+
+    Ok(sum / F::cast(y_true.len()))
+}
+
+/// Find the samples whose predicted labels differ from the true labels
+///
+/// Returns the indices of the misclassified samples in ascending order, they can be used to
+/// select the corresponding records of a dataset.
+///
+/// Fails if the number of true and predicted labels differ.
+pub fn misclassified_indices<L: PartialEq>(y_true: &[L], predicted: &[L]) -> Result<Vec<usize>> {
+    if y_true.len() != predicted.len() {
+        return Err(Error::MismatchedShapes(y_true.len(), predicted.len()));
+    }
+
+    Ok(y_true
+        .iter()
+        .zip(predicted.iter())
+        .enumerate()
+        .filter(|(_, (t, p))| t != p)
+        .map(|(i, _)| i)
+        .collect())
+}
+
+/// A sample which was assigned to the wrong class, see `worst_misclassifications`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Misclassification<F> {
+    /// Position of the sample in the dataset
+    pub index: usize,
+    /// Index of the true class
+    pub expected: usize,
+    /// Index of the predicted class, which has the largest probability
+    pub predicted: usize,
+    /// Probability of the predicted class
+    pub confidence: F,
+}
+
+/// Find the misclassified samples which were predicted with the largest confidence
+///
+/// The probabilities are given as a matrix with shape `(n_samples, n_classes)` and the ground
+/// truth contains the index of the true class for each sample, as in `multiclass_brier_score`. It
+/// can be a single target array or a dataset, whose targets are then used. Every sample is
+/// assigned to the class with the largest probability, and samples assigned to the wrong class are
+/// returned in descending order of the probability of the wrong class. Ties are kept in the order
+/// of the samples. If `top_k` is given, at most `top_k` samples are returned.
+///
+/// Fails if the ground truth has multiple targets, if the number of labels and rows differ, if
+/// there are no classes, if a label is not a valid class index or if a probability is NaN.
+pub fn worst_misclassifications<F: Float, T: AsTargets<Elem = usize>>(
+    ground_truth: &T,
+    probs: ArrayView2<F>,
+    top_k: Option<usize>,
+) -> Result<Vec<Misclassification<F>>> {
+    let y_true = ground_truth.try_single_target()?;
+    if y_true.len() != probs.nrows() {
+        return Err(Error::MismatchedShapes(y_true.len(), probs.nrows()));
+    }
+    if probs.ncols() == 0 {
+        return Err(Error::Parameters(
+            "probabilities should contain at least one class".to_string(),
+        ));
+    }
+    if let Some(label) = y_true.iter().find(|x| **x >= probs.ncols()) {
+        return Err(Error::Parameters(format!(
+            "class index {} out of range for {} classes",
+            label,
+            probs.ncols()
+        )));
+    }
+    if probs.iter().any(|x| x.is_nan()) {
+        return Err(Error::Parameters(
+            "probabilities should not be NaN".to_string(),
+        ));
+    }
+
+    let mut misclassified = y_true
+        .iter()
+        .zip(probs.genrows())
+        .enumerate()
+        .filter_map(|(index, (expected, row))| {
+            // the first class with the largest probability is predicted
+            let (predicted, confidence) =
+                row.iter()
+                    .enumerate()
+                    .fold(
+                        (0, row[0]),
+                        |(best, max), (k, p)| if *p > max { (k, *p) } else { (best, max) },
+                    );
+
+            if predicted != *expected {
+                Some(Misclassification {
+                    index,
+                    expected: *expected,
+                    predicted,
+                    confidence,
+                })
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // stable sort keeps tied samples in their original order, all confidences are comparable
+    // after excluding NaN
+    misclassified.sort_by(|a, b| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    if let Some(top_k) = top_k {
+        misclassified.truncate(top_k);
+    }
+
+    Ok(misclassified)
+}
+
 /// Classification for binary-labels
 ///
 /// This contains Receiver-Operating-Characterstics curves as these only work for binary
@@ -492,10 +930,14 @@ impl<R: Records, R2: Records, T: AsTargets<Elem = bool>, T2: AsTargets<Elem = Pr
 
 #[cfg(test)]
 mod tests {
-    use super::{BinaryClassification, ConfusionMatrix, ToConfusionMatrix};
-    use super::{Label, Pr};
+    use super::{
+        auc, brier_score, cumulative_gain_curve, lift_at, multiclass_brier_score,
+        optimal_threshold, roc_curve, BinaryClassification, ConfusionMatrix, ThresholdCriterion,
+        ToConfusionMatrix,
+    };
+    use super::{DatasetBase, Label, Pr};
     use approx::assert_abs_diff_eq;
-    use ndarray::{array, Array1, Array2, ArrayView1};
+    use ndarray::{array, s, Array1, Array2, ArrayView1};
     use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
     use std::collections::HashMap;
 
@@ -558,6 +1000,24 @@ mod tests {
         assert_cm_eq(&cm, &expected, &labels);
     }
 
+    #[test]
+    fn test_confusion_matrix_target_names() {
+        let ground_truth = DatasetBase::new((), array![1usize, 1, 0, 1, 0, 2])
+            .with_target_names(vec!["setosa", "versicolor", "virginica"]);
+        let predicted = array![0usize, 1, 0, 1, 0, 2];
+
+        let cm = predicted.confusion_matrix(&ground_truth).unwrap();
+        let report = format!("{:?}", cm);
+
+        assert!(report.contains("setosa"));
+        assert!(report.contains("versicolor"));
+        assert!(report.contains("virginica"));
+
+        // labels are printed without target names
+        let cm = predicted.confusion_matrix(ground_truth.targets()).unwrap();
+        assert!(!format!("{:?}", cm).contains("setosa"));
+    }
+
     #[test]
     fn test_cm_metrices() {
         let ground_truth = Array1::from(vec![1, 1, 0, 1, 0, 1]);
@@ -630,6 +1090,252 @@ mod tests {
         assert!((roc.area_under_curve() - 0.5) < 0.04);
     }
 
+    #[test]
+    fn test_roc_curve_points() {
+        let scores = &[0.1, 0.4, 0.35, 0.8];
+        let y_true = &[false, false, true, true];
+
+        let (fpr, tpr, thresholds) = roc_curve(y_true, scores).unwrap();
+
+        assert_abs_diff_eq!(fpr, array![0.0, 0.0, 0.5, 0.5, 1.0]);
+        assert_abs_diff_eq!(tpr, array![0.0, 0.5, 0.5, 1.0, 1.0]);
+        assert_eq!(thresholds[0], f64::INFINITY);
+        assert_abs_diff_eq!(thresholds.slice(s![1..]), array![0.8, 0.4, 0.35, 0.1]);
+        assert_abs_diff_eq!(auc(fpr.view(), tpr.view()).unwrap(), 0.75);
+    }
+
+    #[test]
+    fn test_cumulative_gain_perfect_ranking() {
+        let scores = &[0.9, 0.2, 0.8, 0.1, 0.3];
+        let y_true = &[true, false, true, false, false];
+
+        let (fractions, gains) = cumulative_gain_curve(y_true, scores).unwrap();
+
+        // all positives are captured after targeting the first two samples
+        assert_abs_diff_eq!(fractions, array![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+        assert_abs_diff_eq!(gains, array![0.0, 0.5, 1.0, 1.0, 1.0, 1.0]);
+
+        assert_abs_diff_eq!(lift_at(y_true, scores, 0.4).unwrap(), 2.5);
+        assert_abs_diff_eq!(lift_at(y_true, scores, 0.3).unwrap(), 2.5);
+        assert_abs_diff_eq!(lift_at(y_true, scores, 0.8).unwrap(), 1.25);
+        assert_abs_diff_eq!(lift_at(y_true, scores, 1.0).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_cumulative_gain_ties() {
+        // a ranking without information is equivalent to targeting at random
+        let scores = &[0.5, 0.5, 0.5, 0.5];
+        let y_true = &[true, false, false, true];
+
+        let (fractions, gains) = cumulative_gain_curve(y_true, scores).unwrap();
+
+        assert_abs_diff_eq!(fractions, array![0.0, 1.0]);
+        assert_abs_diff_eq!(gains, array![0.0, 1.0]);
+        assert_abs_diff_eq!(lift_at(y_true, scores, 0.25).unwrap(), 1.0);
+
+        assert!(lift_at(y_true, scores, 0.0).is_err());
+        assert!(lift_at(y_true, scores, 1.5).is_err());
+        assert!(cumulative_gain_curve(&[false, false], &[0.1, 0.2]).is_err());
+    }
+
+    #[test]
+    fn test_brier_score() {
+        let y_true = &[true, false, true, false];
+
+        // confident and correct predictions
+        assert_abs_diff_eq!(brier_score(y_true, &[1.0, 0.0, 1.0, 0.0]).unwrap(), 0.0);
+        // maximally uncertain predictions
+        assert_abs_diff_eq!(brier_score(y_true, &[0.5, 0.5, 0.5, 0.5]).unwrap(), 0.25);
+        // confident and wrong predictions
+        assert_abs_diff_eq!(brier_score(y_true, &[0.0, 1.0, 0.0, 1.0]).unwrap(), 1.0);
+
+        assert_abs_diff_eq!(
+            brier_score(y_true, &[0.9, 0.2, 0.6, 0.0]).unwrap(),
+            (0.01 + 0.04 + 0.16) / 4.0,
+            epsilon = 1e-12
+        );
+
+        assert!(brier_score(y_true, &[0.5]).is_err());
+        assert!(brier_score::<f64>(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_misclassified_indices() {
+        let y_true = &["a", "b", "b", "c", "a"];
+        let predicted = &["a", "c", "b", "c", "b"];
+        assert_eq!(
+            misclassified_indices(y_true, predicted).unwrap(),
+            vec![1, 4]
+        );
+        assert_eq!(
+            misclassified_indices(y_true, y_true).unwrap(),
+            Vec::<usize>::new()
+        );
+        assert!(misclassified_indices(y_true, &["a"]).is_err());
+    }
+
+    #[test]
+    fn test_worst_misclassifications() {
+        let y_true = array![0, 1, 2, 0, 1, 2];
+        let probs = array![
+            [0.8, 0.1, 0.1],
+            [0.6, 0.3, 0.1],
+            [0.1, 0.1, 0.8],
+            [0.2, 0.1, 0.7],
+            [0.1, 0.5, 0.4],
+            [0.5, 0.5, 0.0]
+        ];
+
+        let worst = worst_misclassifications(&y_true, probs.view(), None).unwrap();
+        let indices = worst.iter().map(|m| m.index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![3, 1, 5]);
+        assert_eq!(
+            worst[0],
+            Misclassification {
+                index: 3,
+                expected: 0,
+                predicted: 2,
+                confidence: 0.7
+            }
+        );
+        // ties in the probabilities predict the first class
+        assert_eq!(worst[2].predicted, 0);
+
+        // the same samples as comparing the predicted labels
+        let predicted = probs
+            .genrows()
+            .into_iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .fold((0, 0.0), |a, (k, p)| if *p > a.1 { (k, *p) } else { a })
+                    .0
+            })
+            .collect::<Vec<_>>();
+        let mut sorted = indices.clone();
+        sorted.sort_unstable();
+        assert_eq!(
+            misclassified_indices(y_true.as_slice().unwrap(), &predicted).unwrap(),
+            sorted
+        );
+
+        let top = worst_misclassifications(&y_true, probs.view(), Some(2)).unwrap();
+        assert_eq!(top, worst[..2].to_vec());
+
+        // the targets of a dataset are used as ground truth
+        let dataset = DatasetBase::new(Array2::<f64>::zeros((6, 1)), y_true.clone());
+        assert_eq!(
+            worst_misclassifications(&dataset, probs.view(), None).unwrap(),
+            worst
+        );
+
+        assert!(worst_misclassifications(&array![0, 3, 1, 0, 0, 0], probs.view(), None).is_err());
+        assert!(worst_misclassifications(&array![0, 1], probs.view(), None).is_err());
+
+        let mut nan_probs = probs.clone();
+        nan_probs[(4, 1)] = f64::NAN;
+        assert!(worst_misclassifications(&y_true, nan_probs.view(), None).is_err());
+    }
+
+    #[test]
+    fn test_multiclass_brier_score() {
+        let y_true = &[0, 2, 1];
+
+        let confident = array![[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+        assert_abs_diff_eq!(
+            multiclass_brier_score(y_true, confident.view()).unwrap(),
+            0.0
+        );
+
+        // uniform probabilities give (K - 1) / K for K classes
+        let uniform = Array2::from_elem((3, 3), 1.0 / 3.0);
+        assert_abs_diff_eq!(
+            multiclass_brier_score(y_true, uniform.view()).unwrap(),
+            2.0 / 3.0,
+            epsilon = 1e-12
+        );
+
+        // the binary score is half of the multiclass score with two classes
+        let binary = array![[0.1, 0.9], [0.8, 0.2], [0.4, 0.6]];
+        assert_abs_diff_eq!(
+            multiclass_brier_score(&[1, 0, 1], binary.view()).unwrap(),
+            2.0 * brier_score(&[true, false, true], &[0.9, 0.2, 0.6]).unwrap(),
+            epsilon = 1e-12
+        );
+
+        assert!(multiclass_brier_score(&[0, 3, 1], uniform.view()).is_err());
+        assert!(multiclass_brier_score(&[0, 1], uniform.view()).is_err());
+    }
+
+    #[test]
+    fn test_roc_curve_ties() {
+        // the two samples with score 0.5 are merged into a single point
+        let scores = &[0.5, 0.5, 0.2, 0.9];
+        let y_true = &[true, false, false, true];
+
+        let (fpr, tpr, thresholds) = roc_curve(y_true, scores).unwrap();
+
+        assert_abs_diff_eq!(fpr, array![0.0, 0.0, 0.5, 1.0]);
+        assert_abs_diff_eq!(tpr, array![0.0, 0.5, 1.0, 1.0]);
+        assert_abs_diff_eq!(thresholds.slice(s![1..]), array![0.9, 0.5, 0.2]);
+        assert_abs_diff_eq!(auc(fpr.view(), tpr.view()).unwrap(), 0.875);
+    }
+
+    #[test]
+    fn test_roc_curve_invalid() {
+        assert!(roc_curve(&[true, false], &[0.5]).is_err());
+        assert!(roc_curve(&[true, true], &[0.5, 0.2]).is_err());
+        assert!(roc_curve(&[true, false], &[0.5, f64::NAN]).is_err());
+        assert!(cumulative_gain_curve(&[true, false], &[f64::NAN, 0.2]).is_err());
+    }
+
+    #[test]
+    fn test_optimal_threshold() {
+        let mut rng = SmallRng::seed_from_u64(42);
+
+        // imbalanced dataset with 10% positives, which score lower than 0.5
+        let y_true = (0..1000).map(|i| i % 10 == 0).collect::<Vec<_>>();
+        let scores = y_true
+            .iter()
+            .map(|t| {
+                if *t {
+                    rng.gen_range(0.25..0.55)
+                } else {
+                    rng.gen_range(0.0..0.4)
+                }
+            })
+            .collect::<Vec<f64>>();
+
+        // rates and F1-score when classifying with score >= threshold
+        let evaluate = |threshold: f64| {
+            let (mut tp, mut fp) = (0.0, 0.0);
+            for (t, s) in y_true.iter().zip(scores.iter()) {
+                if *s >= threshold {
+                    if *t {
+                        tp += 1.0;
+                    } else {
+                        fp += 1.0;
+                    }
+                }
+            }
+            let j = tp / 100.0 - fp / 900.0;
+            let f1 = 2.0 * tp / (tp + fp + 100.0);
+            (j, f1)
+        };
+
+        let (threshold, j) =
+            optimal_threshold(&y_true, &scores, ThresholdCriterion::YoudensJ).unwrap();
+        assert!(threshold < 0.5);
+        assert_abs_diff_eq!(j, evaluate(threshold).0, epsilon = 1e-10);
+        assert!(j > evaluate(0.5).0);
+
+        let (threshold, f1) =
+            optimal_threshold(&y_true, &scores, ThresholdCriterion::MaxF1).unwrap();
+        assert!(threshold < 0.5);
+        assert_abs_diff_eq!(f1, evaluate(threshold).1, epsilon = 1e-10);
+        assert!(f1 > evaluate(0.5).1);
+    }
+
     #[test]
     fn split_one_vs_all() {
         let ground_truth = array![0, 2, 3, 0, 1, 2, 1, 2, 3, 2];
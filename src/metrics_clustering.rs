@@ -2,7 +2,7 @@
 use crate::dataset::{AsTargets, DatasetBase, Label, Labels, Records};
 use crate::error::{Error, Result};
 use crate::Float;
-use ndarray::{ArrayBase, ArrayView1, Data, Ix2};
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2};
 use std::collections::HashMap;
 use std::ops::Sub;
 
@@ -140,13 +140,258 @@ impl<'a, F: Float, L: 'a + Label, D: Data<Elem = F>, T: AsTargets<Elem = L> + La
     }
 }
 
+/// Contingency table of a clustering and the true classes
+///
+/// Counts the samples for every pair of predicted cluster and true class. Rows correspond to the
+/// clusters and columns to the classes, both ordered by their first occurence in the labelings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContingencyMatrix<T, P> {
+    matrix: Array2<usize>,
+    classes: Vec<T>,
+    clusters: Vec<P>,
+}
+
+impl<T, P> ContingencyMatrix<T, P> {
+    /// Return the number of samples for every cluster (rows) and class (columns)
+    pub fn matrix(&self) -> &Array2<usize> {
+        &self.matrix
+    }
+
+    /// Return the true class labels in the order of the columns
+    pub fn classes(&self) -> &[T] {
+        &self.classes
+    }
+
+    /// Return the predicted cluster labels in the order of the rows
+    pub fn clusters(&self) -> &[P] {
+        &self.clusters
+    }
+
+    /// Return the number of samples in every cluster
+    pub fn cluster_sizes(&self) -> Array1<usize> {
+        self.matrix.sum_axis(Axis(1))
+    }
+
+    /// Return the number of samples in every class
+    pub fn class_sizes(&self) -> Array1<usize> {
+        self.matrix.sum_axis(Axis(0))
+    }
+}
+
+/// Cross-tabulate predicted clusters with true classes
+///
+/// The resulting contingency table shows which clusters correspond to which classes. It is the
+/// basis of the external clustering metrics, like the V-measure or the Fowlkes-Mallows score.
+///
+/// Fails if the number of true and predicted labels differ or if no sample is given.
+///
+/// # Example
+///
+/// ```
+/// use linfa::metrics::contingency_matrix;
+/// use ndarray::array;
+///
+/// let labels_true = vec!["setosa".to_string(), "setosa".to_string(), "virginica".to_string()];
+/// let cm = contingency_matrix(&labels_true, &[1usize, 1, 0]).unwrap();
+///
+/// // cluster 1 contains both samples of setosa
+/// assert_eq!(cm.clusters(), &[1, 0]);
+/// assert_eq!(cm.classes(), &["setosa", "virginica"]);
+/// assert_eq!(cm.matrix(), &array![[2, 0], [0, 1]]);
+/// ```
+pub fn contingency_matrix<T: Label, P: Label>(
+    labels_true: &[T],
+    labels_pred: &[P],
+) -> Result<ContingencyMatrix<T, P>> {
+    if labels_true.len() != labels_pred.len() {
+        return Err(Error::MismatchedShapes(
+            labels_true.len(),
+            labels_pred.len(),
+        ));
+    }
+    if labels_true.is_empty() {
+        return Err(Error::NotEnoughSamples);
+    }
+
+    let mut classes = Vec::new();
+    let mut clusters = Vec::new();
+    let mut class_indices = HashMap::new();
+    let mut cluster_indices = HashMap::new();
+    let indices = labels_true
+        .iter()
+        .zip(labels_pred.iter())
+        .map(|(t, p)| {
+            let class = *class_indices.entry(t).or_insert_with(|| {
+                classes.push(t.clone());
+                classes.len() - 1
+            });
+            let cluster = *cluster_indices.entry(p).or_insert_with(|| {
+                clusters.push(p.clone());
+                clusters.len() - 1
+            });
+
+            (cluster, class)
+        })
+        .collect::<Vec<_>>();
+
+    let mut matrix = Array2::zeros((clusters.len(), classes.len()));
+    for idx in indices {
+        matrix[idx] += 1;
+    }
+
+    Ok(ContingencyMatrix {
+        matrix,
+        classes,
+        clusters,
+    })
+}
+
+/// Entropy of a labeling given by the number of samples with each label
+fn entropy<F: Float>(counts: ArrayView1<usize>) -> F {
+    let total = F::cast(counts.sum());
+
+    counts
+        .iter()
+        .filter(|x| **x > 0)
+        .map(|x| {
+            let p = F::cast(*x) / total;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// Compute the homogeneity, completeness and V-measure of a clustering
+///
+/// A clustering is homogeneous if every cluster contains only samples of a single class and
+/// complete if all samples of a class are assigned to the same cluster. With `C` the true classes
+/// and `K` the predicted clusters both are defined from the conditional entropies as
+///
+/// ```text
+/// homogeneity = 1 - H(C|K) / H(C)
+/// completeness = 1 - H(K|C) / H(K)
+/// ```
+///
+/// and the V-measure is their harmonic mean. All scores are in `[0, 1]` and independent of the
+/// actual label values. A labeling with a single class is always homogeneous and a single cluster
+/// is always complete.
+///
+/// Fails if the number of true and predicted labels differ or if no sample is given.
+///
+/// # Example
+///
+/// ```
+/// use linfa::metrics::homogeneity_completeness_v_measure;
+///
+/// // splitting a class into two clusters is homogeneous, but not complete
+/// let (h, c, v) =
+///     homogeneity_completeness_v_measure::<f64, usize, usize>(&[0, 0, 1, 1], &[0, 0, 1, 2])
+///         .unwrap();
+///
+/// assert!((h - 1.0).abs() < 1e-10);
+/// assert!((c - 2. / 3.).abs() < 1e-10);
+/// assert!((v - 0.8).abs() < 1e-10);
+/// ```
+pub fn homogeneity_completeness_v_measure<F: Float, T: Label, P: Label>(
+    labels_true: &[T],
+    labels_pred: &[P],
+) -> Result<(F, F, F)> {
+    let counts = contingency_matrix(labels_true, labels_pred)?;
+    let (cluster_sizes, class_sizes) = (counts.cluster_sizes(), counts.class_sizes());
+
+    let entropy_classes = entropy::<F>(class_sizes.view());
+    let entropy_clusters = entropy::<F>(cluster_sizes.view());
+
+    // mutual information between classes and clusters
+    let total = F::cast(labels_true.len());
+    let mutual_info = counts
+        .matrix()
+        .indexed_iter()
+        .filter(|(_, n)| **n > 0)
+        .map(|((i, j), n)| {
+            let n = F::cast(*n);
+            let expected = F::cast(cluster_sizes[i]) * F::cast(class_sizes[j]);
+            n / total * (n * total / expected).ln()
+        })
+        .sum::<F>();
+
+    let homogeneity = if entropy_classes > F::zero() {
+        mutual_info / entropy_classes
+    } else {
+        F::one()
+    };
+    let completeness = if entropy_clusters > F::zero() {
+        mutual_info / entropy_clusters
+    } else {
+        F::one()
+    };
+    let v_measure = if homogeneity + completeness > F::zero() {
+        F::cast(2.) * homogeneity * completeness / (homogeneity + completeness)
+    } else {
+        F::zero()
+    };
+
+    Ok((homogeneity, completeness, v_measure))
+}
+
+/// Number of pairs of distinct samples in groups of the given sizes
+fn count_pairs<'a>(sizes: impl Iterator<Item = &'a usize>) -> usize {
+    sizes.map(|n| n * n.saturating_sub(1) / 2).sum()
+}
+
+/// Compute the Fowlkes-Mallows score of a clustering
+///
+/// The score is computed from all pairs of samples. A pair is a true positive if both samples
+/// belong to the same class and are assigned to the same cluster. With `TP`, `FP` and `FN` the
+/// true positive, false positive and false negative pairs the score is the geometric mean of the
+/// pairwise precision and recall
+///
+/// ```text
+/// FMI = TP / sqrt((TP + FP) * (TP + FN))
+/// ```
+///
+/// The score is in `[0, 1]` and independent of the actual label values, identical clusterings
+/// score one. If no pair of samples shares a class or a cluster, the score is zero.
+///
+/// Fails if the number of true and predicted labels differ or if no sample is given.
+///
+/// # Example
+///
+/// ```
+/// use linfa::metrics::fowlkes_mallows_score;
+///
+/// let score: f64 =
+///     fowlkes_mallows_score::<_, usize, usize>(&[0, 0, 0, 1, 1, 1], &[0, 0, 1, 1, 2, 2]).unwrap();
+///
+/// assert!((score - 0.4714).abs() < 1e-4);
+/// ```
+pub fn fowlkes_mallows_score<F: Float, T: Label, P: Label>(
+    labels_true: &[T],
+    labels_pred: &[P],
+) -> Result<F> {
+    let counts = contingency_matrix(labels_true, labels_pred)?;
+
+    // number of pairs sharing a class and a cluster, a cluster or a class
+    let tp = count_pairs(counts.matrix().iter());
+    let tp_fp = count_pairs(counts.cluster_sizes().iter());
+    let tp_fn = count_pairs(counts.class_sizes().iter());
+
+    if tp == 0 {
+        return Ok(F::zero());
+    }
+
+    Ok(F::cast(tp) / (F::cast(tp_fp) * F::cast(tp_fn)).sqrt())
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::metrics_clustering::SilhouetteScore;
+    use crate::metrics_clustering::{
+        contingency_matrix, fowlkes_mallows_score, homogeneity_completeness_v_measure,
+        SilhouetteScore,
+    };
     use crate::{Dataset, DatasetBase};
     use approx::assert_abs_diff_eq;
-    use ndarray::{concatenate, Array, Array1, Axis};
+    use ndarray::{array, concatenate, Array, Array1, Axis};
     use num_traits::ToPrimitive;
 
     #[test]
@@ -215,4 +460,97 @@ mod tests {
         let score_res = dataset.silhouette_score();
         assert!(score_res.is_err());
     }
+
+    #[test]
+    fn test_homogeneity_completeness_v_measure() {
+        // a perfect clustering with permuted cluster labels
+        let labels_true = ["a", "a", "b", "b", "c", "c"]
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        let labels_pred = [2usize, 2, 0, 0, 1, 1];
+        let (h, c, v) =
+            homogeneity_completeness_v_measure::<f64, _, _>(&labels_true, &labels_pred).unwrap();
+        assert_abs_diff_eq!(h, 1., epsilon = 1e-10);
+        assert_abs_diff_eq!(c, 1., epsilon = 1e-10);
+        assert_abs_diff_eq!(v, 1., epsilon = 1e-10);
+
+        // clusters independent of the classes
+        let (h, c, v) =
+            homogeneity_completeness_v_measure::<f64, usize, usize>(&[0, 0, 1, 1], &[0, 1, 0, 1])
+                .unwrap();
+        assert_abs_diff_eq!(h, 0.);
+        assert_abs_diff_eq!(c, 0.);
+        assert_abs_diff_eq!(v, 0.);
+
+        // a random clustering scores low
+        let labels_true = Array1::from_shape_fn(300, |i| i % 3).to_vec();
+        let labels_pred = Array1::from_shape_fn(300, |i| (i * 7 + i / 5) % 4).to_vec();
+        let (_, _, v) =
+            homogeneity_completeness_v_measure::<f64, _, _>(&labels_true, &labels_pred).unwrap();
+        assert!(v < 0.1);
+
+        // merging two classes into a single cluster is complete, but not homogeneous
+        let (h, c, v) = homogeneity_completeness_v_measure::<f64, usize, usize>(
+            &[0, 0, 1, 1, 2, 2],
+            &[0, 0, 0, 0, 1, 1],
+        )
+        .unwrap();
+        assert_abs_diff_eq!(h, 0.579380164285695, epsilon = 1e-10);
+        assert_abs_diff_eq!(c, 1., epsilon = 1e-10);
+        assert!(v > h && v < c);
+
+        // single class and single cluster are degenerate cases
+        let (h, c, _) =
+            homogeneity_completeness_v_measure::<f64, usize, usize>(&[0, 0, 0], &[0, 1, 2])
+                .unwrap();
+        assert_abs_diff_eq!(h, 1.);
+        assert_abs_diff_eq!(c, 0.);
+        let (h, c, _) =
+            homogeneity_completeness_v_measure::<f64, usize, usize>(&[0, 1, 2], &[0, 0, 0])
+                .unwrap();
+        assert_abs_diff_eq!(h, 0.);
+        assert_abs_diff_eq!(c, 1.);
+
+        assert!(homogeneity_completeness_v_measure::<f64, usize, usize>(&[0, 1], &[0]).is_err());
+        assert!(homogeneity_completeness_v_measure::<f64, usize, usize>(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_fowlkes_mallows_score() {
+        // identical clusterings up to permuted labels
+        let score: f64 =
+            fowlkes_mallows_score::<_, usize, usize>(&[0, 0, 1, 1, 2], &[1, 1, 2, 2, 0]).unwrap();
+        assert_abs_diff_eq!(score, 1., epsilon = 1e-10);
+
+        // independent clusterings score close to the chance value sqrt(1/3 * 1/4)
+        let labels_true = Array1::from_shape_fn(120, |i| i % 3).to_vec();
+        let labels_pred = Array1::from_shape_fn(120, |i| (i / 3) % 4).to_vec();
+        let score: f64 = fowlkes_mallows_score(&labels_true, &labels_pred).unwrap();
+        assert_abs_diff_eq!(score, 0.26761546505252365, epsilon = 1e-10);
+        assert_abs_diff_eq!(score, (1. / 12f64).sqrt(), epsilon = 0.03);
+
+        // no shared pairs
+        let score: f64 = fowlkes_mallows_score::<_, usize, usize>(&[0, 1, 2], &[0, 0, 0]).unwrap();
+        assert_abs_diff_eq!(score, 0.);
+
+        assert!(fowlkes_mallows_score::<f64, usize, usize>(&[0, 1], &[0]).is_err());
+    }
+
+    #[test]
+    fn test_contingency_matrix() {
+        // a near-perfect clustering with a single sample assigned to the wrong cluster
+        let labels_true = [0usize, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2];
+        let labels_pred = [5usize, 5, 5, 5, 3, 3, 3, 7, 7, 7, 7, 7];
+        let cm = contingency_matrix(&labels_true, &labels_pred).unwrap();
+
+        assert_eq!(cm.clusters(), &[5, 3, 7]);
+        assert_eq!(cm.classes(), &[0, 1, 2]);
+        assert_eq!(cm.matrix(), &array![[4, 0, 0], [0, 3, 0], [0, 1, 4]]);
+        assert_eq!(cm.cluster_sizes(), array![4, 3, 5]);
+        assert_eq!(cm.class_sizes(), array![4, 4, 4]);
+
+        assert!(contingency_matrix(&[0usize, 1], &[0usize]).is_err());
+        assert!(contingency_matrix::<usize, usize>(&[], &[]).is_err());
+    }
 }
@@ -0,0 +1,206 @@
+//! Default metrics used to score models
+//!
+use crate::dataset::{AsTargets, DatasetBase, Float};
+use crate::error::{Error, Result};
+use crate::metrics_regression::SingleTargetRegression;
+use crate::traits::{PredictRef, Score};
+use ndarray::{Array1, ArrayBase, ArrayView1, Data, Ix2};
+
+/// Accuracy, the fraction of correctly predicted samples
+///
+/// This is the default metric of classifiers and supports any label type which can be compared
+/// for equality, including floating point labels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accuracy;
+
+/// Coefficient of determination (R²)
+///
+/// This is the default metric of regressors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct R2;
+
+/// Metric comparing predicted targets of type `L` with the ground truth
+pub trait Metric<L> {
+    type Output;
+
+    /// Compare predicted targets with the ground truth
+    fn compute(predicted: ArrayView1<L>, ground_truth: ArrayView1<L>) -> Result<Self::Output>;
+}
+
+impl<L: PartialEq> Metric<L> for Accuracy {
+    type Output = f32;
+
+    fn compute(predicted: ArrayView1<L>, ground_truth: ArrayView1<L>) -> Result<f32> {
+        if ground_truth.is_empty() {
+            return Err(Error::NotEnoughSamples);
+        }
+
+        let correct = predicted
+            .iter()
+            .zip(ground_truth.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+
+        Ok(correct as f32 / ground_truth.len() as f32)
+    }
+}
+
+impl<F: Float> Metric<F> for R2 {
+    type Output = F;
+
+    fn compute(predicted: ArrayView1<F>, ground_truth: ArrayView1<F>) -> Result<F> {
+        predicted.r2(&ground_truth)
+    }
+}
+
+/// Default metric of a model
+///
+/// Every model which implements this trait can be evaluated with [`Score::score`]. The metric is
+/// chosen by the model and not by the type of its targets: classifiers select [`Accuracy`] and
+/// regressors [`R2`].
+///
+/// ```ignore
+/// impl<F: Float> DefaultMetric for MyClassifier<F> {
+///     type Metric = Accuracy;
+/// }
+/// ```
+pub trait DefaultMetric {
+    type Metric;
+}
+
+impl<F, D, T, L, O> Score<ArrayBase<D, Ix2>, T> for O
+where
+    F: Float,
+    D: Data<Elem = F>,
+    T: AsTargets<Elem = L>,
+    O: DefaultMetric + PredictRef<ArrayBase<D, Ix2>, Array1<L>>,
+    O::Metric: Metric<L>,
+{
+    type Output = <O::Metric as Metric<L>>::Output;
+
+    /// Predict the targets of the dataset and compare them with the default metric
+    ///
+    /// Fails if the dataset has more than one target.
+    fn score(&self, dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Result<Self::Output> {
+        let ground_truth = dataset.try_single_target()?;
+        let predicted = self.predict_ref(dataset.records());
+        if predicted.len() != ground_truth.len() {
+            return Err(Error::MismatchedShapes(predicted.len(), ground_truth.len()));
+        }
+
+        O::Metric::compute(predicted.view(), ground_truth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Accuracy, DefaultMetric, Metric};
+    use crate::dataset::{Dataset, DatasetBase};
+    use crate::metrics::{SingleTargetRegression, ToConfusionMatrix};
+    use crate::traits::{Predict, PredictRef, Score};
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1, ArrayBase, Axis, Data, Ix2};
+
+    /// Predicts the class from the sign of the first feature
+    struct SignClassifier;
+
+    impl<D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<usize>> for SignClassifier {
+        fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<usize> {
+            x.column(0).mapv(|x| if x > 0. { 1 } else { 0 })
+        }
+    }
+
+    impl DefaultMetric for SignClassifier {
+        type Metric = Accuracy;
+    }
+
+    /// Predicts the sign of the first feature as label of any type
+    struct LabelClassifier<L>(L, L);
+
+    impl<L: Clone, D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<L>>
+        for LabelClassifier<L>
+    {
+        fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<L> {
+            x.column(0).mapv(|x| {
+                if x > 0. {
+                    self.1.clone()
+                } else {
+                    self.0.clone()
+                }
+            })
+        }
+    }
+
+    impl<L> DefaultMetric for LabelClassifier<L> {
+        type Metric = Accuracy;
+    }
+
+    /// Predicts the sum of the features
+    struct SumRegressor;
+
+    impl<D: Data<Elem = f64>> PredictRef<ArrayBase<D, Ix2>, Array1<f64>> for SumRegressor {
+        fn predict_ref(&self, x: &ArrayBase<D, Ix2>) -> Array1<f64> {
+            x.sum_axis(Axis(1))
+        }
+    }
+
+    impl DefaultMetric for SumRegressor {
+        type Metric = super::R2;
+    }
+
+    #[test]
+    fn test_score_classifier() {
+        let dataset = Dataset::new(
+            array![[-1., 2.], [2., 0.], [0.5, 1.], [-0.5, 3.], [1., 1.]],
+            array![0, 1, 0, 0, 1],
+        );
+
+        let score = SignClassifier.score(&dataset).unwrap();
+        assert_abs_diff_eq!(score, 0.8);
+
+        let predicted: Array1<usize> = SignClassifier.predict(dataset.records());
+        let cm = predicted.confusion_matrix(&dataset).unwrap();
+        assert_abs_diff_eq!(score, cm.accuracy());
+    }
+
+    #[test]
+    fn test_score_label_types() {
+        let records = array![[-1., 2.], [2., 0.], [0.5, 1.], [-0.5, 3.], [1., 1.]];
+
+        // classifiers with float labels are evaluated with the accuracy, not with R²
+        let dataset = Dataset::new(records.clone(), array![-1., 1., -1., -1., 1.]);
+        let score = LabelClassifier(-1., 1.).score(&dataset).unwrap();
+        assert_abs_diff_eq!(score, 0.8);
+
+        let dataset = Dataset::new(records.clone(), array![-3, 7, -3, -3, 7]);
+        let score = LabelClassifier(-3, 7).score(&dataset).unwrap();
+        assert_abs_diff_eq!(score, 0.8);
+
+        let dataset = Dataset::new(records, array!["cat", "dog", "dog", "cat", "dog"]);
+        let score = LabelClassifier("cat", "dog").score(&dataset).unwrap();
+        assert_abs_diff_eq!(score, 1.);
+    }
+
+    #[test]
+    fn test_score_regressor() {
+        let dataset = Dataset::new(
+            array![[1., 0.], [2., 1.], [0., 0.5], [3., 3.]],
+            array![1.2, 2.5, 0.4, 6.5],
+        );
+
+        let score = SumRegressor.score(&dataset).unwrap();
+        let predicted: Array1<f64> = SumRegressor.predict(dataset.records());
+        assert_abs_diff_eq!(score, predicted.r2(dataset.targets()).unwrap());
+        assert!(score > 0.9 && score < 1.);
+    }
+
+    #[test]
+    fn test_score_invalid() {
+        // multiple targets
+        let dataset = DatasetBase::new(array![[1.], [2.]], array![[1., 2.], [3., 4.]]);
+        assert!(SumRegressor.score(&dataset).is_err());
+
+        let empty = Array1::<usize>::zeros(0);
+        assert!(Accuracy::compute(empty.view(), empty.view()).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Persistence of fitted models
+//!
+//! Fitted models which implement [`SaveModel`] and [`LoadModel`] can be written to a single file
+//! and restored from it again. The models are encoded with `bincode`, a compact binary format.
+//! Other serializable values can be stored with [`save_to_file`] and [`load_from_file`]. This
+//! module is only available with the `serde` feature enabled.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use linfa::persistence::{LoadModel, SaveModel};
+//!
+//! // fit a model, for example a K-Means clustering with serde support
+//! let model = KMeans::params(3).fit(&dataset)?;
+//! model.save_model("kmeans.bin")?;
+//!
+//! let model: KMeans<f64, L2Dist> = KMeans::load_model("kmeans.bin")?;
+//! ```
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde_crate::{de::DeserializeOwned, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Serialize a model and write it to `path`, overwriting any existing file
+///
+/// This is the generic function behind [`SaveModel`], it accepts any serializable value.
+pub fn save_to_file<T: Serialize, P: AsRef<Path>>(model: &T, path: P) -> Result<()> {
+    let file = File::create(path).map_err(|e| Error::Persistence(e.to_string()))?;
+
+    bincode::serialize_into(BufWriter::new(file), model)
+        .map_err(|e| Error::Persistence(e.to_string()))
+}
+
+/// Read a value from `path`, which has been written with [`save_to_file`]
+///
+/// This is the generic function behind [`LoadModel`], it accepts any deserializable value.
+pub fn load_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
+    let file = File::open(path).map_err(|e| Error::Persistence(e.to_string()))?;
+
+    bincode::deserialize_from(BufReader::new(file)).map_err(|e| Error::Persistence(e.to_string()))
+}
+
+/// Save a fitted model to a file
+///
+/// The trait is implemented by the fitted models of the algorithm crates, when their `serde`
+/// feature is enabled.
+pub trait SaveModel: Serialize {
+    /// Serialize the model and write it to `path`, overwriting any existing file
+    fn save_model<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        save_to_file(self, path)
+    }
+}
+
+/// Load a fitted model from a file
+///
+/// The trait is implemented by the fitted models of the algorithm crates, when their `serde`
+/// feature is enabled.
+pub trait LoadModel: DeserializeOwned {
+    /// Read a model from `path`, which has been written with [`SaveModel::save_model`]
+    fn load_model<P: AsRef<Path>>(path: P) -> Result<Self> {
+        load_from_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{array, Array2};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn round_trip() {
+        let file = NamedTempFile::new().unwrap();
+        let params = array![[1., 2.], [3., 4.]];
+
+        save_to_file(&params, file.path()).unwrap();
+        let loaded: Array2<f64> = load_from_file(file.path()).unwrap();
+
+        assert_eq!(loaded, params);
+    }
+
+    #[test]
+    fn missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let res: Result<Vec<f64>> = load_from_file(dir.path().join("does_not_exist.bin"));
+
+        assert!(matches!(res, Err(Error::Persistence(_))));
+    }
+}
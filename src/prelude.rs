@@ -24,3 +24,7 @@ pub use crate::metrics_clustering::SilhouetteScore;
 
 #[doc(no_inline)]
 pub use crate::correlation::PearsonCorrelation;
+
+#[cfg(feature = "serde")]
+#[doc(no_inline)]
+pub use crate::persistence::{LoadModel, SaveModel};
@@ -0,0 +1,391 @@
+//! Per-feature summary statistics
+//!
+//! This module provides descriptive statistics for each feature of a dataset, similar to the
+//! `describe` function of data frame libraries, and detects outliers based on them.
+use std::fmt;
+
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Data, Ix2, Zip};
+
+use crate::dataset::{DatasetBase, Float};
+
+/// Summary statistics of every feature in a dataset
+///
+/// For each feature the number of non-NaN values, the mean, standard deviation, minimum,
+/// quartiles and maximum are calculated. NaN values are excluded from all statistics. Statistics
+/// which are not defined for the number of values, for example the standard deviation of a single
+/// value, are NaN. This type also supports printing the statistics as a table together with the
+/// feature names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary<F> {
+    feature_names: Vec<String>,
+    count: Array1<usize>,
+    mean: Array1<F>,
+    std: Array1<F>,
+    min: Array1<F>,
+    lower_quartile: Array1<F>,
+    median: Array1<F>,
+    upper_quartile: Array1<F>,
+    max: Array1<F>,
+}
+
+/// Statistics of a single feature
+struct FeatureSummary<F> {
+    count: usize,
+    mean: F,
+    std: F,
+    quantiles: [F; 5],
+}
+
+impl<F: Float> FeatureSummary<F> {
+    fn from_values(values: ArrayView1<F>) -> Self {
+        let mut values = values
+            .iter()
+            .copied()
+            .filter(|x| !x.is_nan())
+            .collect::<Vec<_>>();
+        // safe unwrap because NaN values are removed
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let count = values.len();
+        if count == 0 {
+            return FeatureSummary {
+                count,
+                mean: F::nan(),
+                std: F::nan(),
+                quantiles: [F::nan(); 5],
+            };
+        }
+
+        let mean = values.iter().copied().sum::<F>() / F::cast(count);
+        // use the unbiased estimator of the variance
+        let std = if count > 1 {
+            (values.iter().map(|x| (*x - mean) * (*x - mean)).sum::<F>() / F::cast(count - 1))
+                .sqrt()
+        } else {
+            F::nan()
+        };
+
+        let mut quantiles = [F::zero(); 5];
+        for (quantile, q) in quantiles.iter_mut().zip(&[0.0, 0.25, 0.5, 0.75, 1.0]) {
+            *quantile = interpolated_quantile(&values, *q);
+        }
+
+        FeatureSummary {
+            count,
+            mean,
+            std,
+            quantiles,
+        }
+    }
+}
+
+/// Quantile of sorted values with linear interpolation between neighbouring values
+fn interpolated_quantile<F: Float>(sorted: &[F], q: f64) -> F {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+    let frac = F::cast(pos - lower as f64);
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * frac
+}
+
+impl<F: Float> Summary<F> {
+    /// Calculate the summary statistics of every feature in a dataset
+    pub fn from_dataset<D: Data<Elem = F>, T>(dataset: &DatasetBase<ArrayBase<D, Ix2>, T>) -> Self {
+        let features = dataset
+            .records()
+            .gencolumns()
+            .into_iter()
+            .map(FeatureSummary::from_values)
+            .collect::<Vec<_>>();
+
+        let collect =
+            |f: fn(&FeatureSummary<F>) -> F| -> Array1<F> { features.iter().map(f).collect() };
+
+        Summary {
+            feature_names: dataset.feature_names(),
+            count: features.iter().map(|x| x.count).collect(),
+            mean: collect(|x| x.mean),
+            std: collect(|x| x.std),
+            min: collect(|x| x.quantiles[0]),
+            lower_quartile: collect(|x| x.quantiles[1]),
+            median: collect(|x| x.quantiles[2]),
+            upper_quartile: collect(|x| x.quantiles[3]),
+            max: collect(|x| x.quantiles[4]),
+        }
+    }
+
+    /// Return the names of the summarized features
+    pub fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    /// Return the number of non-NaN values per feature
+    pub fn count(&self) -> &Array1<usize> {
+        &self.count
+    }
+
+    /// Return the mean per feature
+    pub fn mean(&self) -> &Array1<F> {
+        &self.mean
+    }
+
+    /// Return the sample standard deviation per feature
+    pub fn std(&self) -> &Array1<F> {
+        &self.std
+    }
+
+    /// Return the minimum per feature
+    pub fn min(&self) -> &Array1<F> {
+        &self.min
+    }
+
+    /// Return the 25% quantile per feature
+    pub fn lower_quartile(&self) -> &Array1<F> {
+        &self.lower_quartile
+    }
+
+    /// Return the median per feature
+    pub fn median(&self) -> &Array1<F> {
+        &self.median
+    }
+
+    /// Return the 75% quantile per feature
+    pub fn upper_quartile(&self) -> &Array1<F> {
+        &self.upper_quartile
+    }
+
+    /// Return the maximum per feature
+    pub fn max(&self) -> &Array1<F> {
+        &self.max
+    }
+}
+
+/// Criterion to detect outliers in a single feature
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierMethod<F> {
+    /// Values further than `factor` times the interquartile range below the lower quartile or above
+    /// the upper quartile are outliers, a common choice is `1.5`
+    Iqr(F),
+    /// Values further than `threshold` standard deviations away from the mean are outliers, a
+    /// common choice is `3.0`
+    ZScore(F),
+}
+
+impl<F: Float> Summary<F> {
+    /// Return the range of non-outlier values for every feature
+    ///
+    /// Features with a zero interquartile range or standard deviation, for example constant
+    /// features, have an unbounded range.
+    fn outlier_bounds(&self, method: OutlierMethod<F>) -> (Array1<F>, Array1<F>) {
+        let (center_lower, center_upper, spread, factor) = match method {
+            OutlierMethod::Iqr(factor) => (
+                &self.lower_quartile,
+                &self.upper_quartile,
+                &self.upper_quartile - &self.lower_quartile,
+                factor,
+            ),
+            OutlierMethod::ZScore(threshold) => {
+                (&self.mean, &self.mean, self.std.clone(), threshold)
+            }
+        };
+
+        let mut lower = Array1::zeros(spread.len());
+        let mut upper = Array1::zeros(spread.len());
+        Zip::from(&mut lower)
+            .and(&mut upper)
+            .and(center_lower)
+            .and(center_upper)
+            .and(&spread)
+            .apply(|lower, upper, &center_lower, &center_upper, &spread| {
+                if spread > F::zero() {
+                    *lower = center_lower - spread * factor;
+                    *upper = center_upper + spread * factor;
+                } else {
+                    *lower = F::neg_infinity();
+                    *upper = F::infinity();
+                }
+            });
+
+        (lower, upper)
+    }
+}
+
+impl<F: Float, D: Data<Elem = F>, T> DatasetBase<ArrayBase<D, Ix2>, T> {
+    /// Calculate summary statistics for every feature
+    ///
+    /// NaN values are excluded from the statistics and the number of remaining values is reported
+    /// for each feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let summary = linfa_datasets::iris().describe();
+    ///
+    /// println!("{}", summary);
+    /// ```
+    ///
+    /// The output looks like this:
+    ///
+    /// ```ignore
+    /// feature       count  mean     std      min      25%      50%      75%      max
+    /// sepal length  150    5.8433   0.8281   4.3000   5.1000   5.8000   6.4000   7.9000
+    /// sepal width   150    3.0573   0.4359   2.0000   2.8000   3.0000   3.3000   4.4000
+    /// petal length  150    3.7580   1.7653   1.0000   1.6000   4.3500   5.1000   6.9000
+    /// petal width   150    1.1993   0.7622   0.1000   0.3000   1.3000   1.8000   2.5000
+    /// ```
+    pub fn describe(&self) -> Summary<F> {
+        Summary::from_dataset(self)
+    }
+
+    /// Mark outlying values of every feature
+    ///
+    /// The bounds of each feature are calculated from the [summary statistics](Summary) with the
+    /// given `method`. Features with a zero interquartile range or standard deviation, for example
+    /// constant features, contain no outliers. NaN values are never marked.
+    ///
+    /// # Returns
+    ///
+    /// A boolean matrix with the same shape as the records, in which `true` marks an outlier.
+    /// Samples containing any outlier can be found with `mask.map_axis(Axis(1), |x| x.iter().any(|x| *x))`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linfa::summary::OutlierMethod;
+    ///
+    /// let dataset = linfa_datasets::diabetes();
+    /// let mask = dataset.outlier_mask(OutlierMethod::Iqr(1.5));
+    ///
+    /// println!("{} outliers", mask.iter().filter(|x| **x).count());
+    /// ```
+    pub fn outlier_mask(&self, method: OutlierMethod<F>) -> Array2<bool> {
+        let (lower, upper) = self.describe().outlier_bounds(method);
+
+        let mut mask = Array2::from_elem(self.records().dim(), false);
+        for ((mut mask, values), (lower, upper)) in mask
+            .gencolumns_mut()
+            .into_iter()
+            .zip(self.records().gencolumns())
+            .zip(lower.iter().zip(upper.iter()))
+        {
+            Zip::from(&mut mask).and(&values).apply(|mask, &value| {
+                *mask = value < *lower || value > *upper;
+            });
+        }
+
+        mask
+    }
+}
+
+/// Display the summary statistics as a table with one row per feature
+impl<F: Float> fmt::Display for Summary<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let longest = self
+            .feature_names
+            .iter()
+            .map(|x| x.len())
+            .max()
+            .unwrap_or(0)
+            .max("feature".len());
+
+        write!(
+            f,
+            "{: <width$}  {: <6}",
+            "feature",
+            "count",
+            width = longest
+        )?;
+        for name in &["mean", "std", "min", "25%", "50%", "75%", "max"] {
+            write!(f, " {: <8}", name)?;
+        }
+        writeln!(f)?;
+
+        for (i, name) in self.feature_names.iter().enumerate() {
+            write!(
+                f,
+                "{: <width$}  {: <6}",
+                name,
+                self.count[i],
+                width = longest
+            )?;
+            for stat in &[
+                &self.mean,
+                &self.std,
+                &self.min,
+                &self.lower_quartile,
+                &self.median,
+                &self.upper_quartile,
+                &self.max,
+            ] {
+                write!(f, " {: <8.4}", stat[i])?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutlierMethod;
+    use crate::DatasetBase;
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array2};
+
+    #[test]
+    fn iris_means() {
+        let summary = linfa_datasets::iris().describe();
+
+        assert_eq!(summary.count(), &array![150, 150, 150, 150]);
+        assert_eq!(summary.feature_names()[0], "sepal length");
+        assert_abs_diff_eq!(summary.mean()[0], 5.8433, epsilon = 1e-4);
+        assert_abs_diff_eq!(summary.mean()[2], 3.758, epsilon = 1e-4);
+        assert_abs_diff_eq!(summary.min()[0], 4.3);
+        assert_abs_diff_eq!(summary.max()[0], 7.9);
+        assert_abs_diff_eq!(summary.median()[2], 4.35, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn flags_injected_outlier() {
+        let mut records = Array2::zeros((20, 2));
+        for (i, mut row) in records.genrows_mut().into_iter().enumerate() {
+            row[0] = (i + 1) as f64;
+            row[1] = 3.;
+        }
+        records[[5, 0]] = 1000.;
+        let dataset = DatasetBase::from(records);
+
+        let mut expected = Array2::from_elem((20, 2), false);
+        expected[[5, 0]] = true;
+
+        // the constant second feature contains no outliers
+        assert_eq!(dataset.outlier_mask(OutlierMethod::Iqr(1.5)), expected);
+        assert_eq!(dataset.outlier_mask(OutlierMethod::ZScore(3.)), expected);
+    }
+
+    #[test]
+    fn excludes_nan() {
+        let dataset = DatasetBase::from(array![
+            [1., f64::NAN],
+            [2., f64::NAN],
+            [f64::NAN, f64::NAN],
+            [3., 5.],
+            [4., f64::NAN]
+        ]);
+        let summary = dataset.describe();
+
+        assert_eq!(summary.count(), &array![4, 1]);
+        assert_abs_diff_eq!(summary.mean(), &array![2.5, 5.]);
+        assert_abs_diff_eq!(summary.std()[0], (5. / 3f64).sqrt(), epsilon = 1e-10);
+        assert!(summary.std()[1].is_nan());
+        assert_abs_diff_eq!(summary.lower_quartile(), &array![1.75, 5.], epsilon = 1e-10);
+        assert_abs_diff_eq!(summary.median(), &array![2.5, 5.], epsilon = 1e-10);
+        assert_abs_diff_eq!(summary.upper_quartile(), &array![3.25, 5.], epsilon = 1e-10);
+
+        // the table contains a row per feature
+        let table = summary.to_string();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(1).unwrap().starts_with("feature-0"));
+    }
+}
@@ -1,7 +1,8 @@
 //! Provide traits for different classes of algorithms
 //!
 
-use crate::dataset::{DatasetBase, Records};
+use crate::dataset::{DatasetBase, Float, Records};
+use ndarray::{Array1, ArrayView1};
 use std::convert::From;
 
 /// Transformation algorithms
@@ -57,3 +58,48 @@ pub trait Predict<R: Records, T> {
 pub trait PredictRef<R: Records, T> {
     fn predict_ref<'a>(&'a self, x: &'a R) -> T;
 }
+
+/// Predict a single sample
+///
+/// This trait is implemented for every model which predicts a one-dimensional array of targets
+/// from two-dimensional records. The sample is wrapped into a matrix with a single row, which
+/// avoids building it at the call site when samples arrive one at a time.
+pub trait PredictOne<F, T> {
+    fn predict_one(&self, sample: ArrayView1<F>) -> T;
+}
+
+/// Evaluate a model with its default metric
+///
+/// The predictions of the model are compared with the targets of the dataset. Classifiers are
+/// evaluated with the accuracy and regressors with the coefficient of determination (R²), see
+/// [`DefaultMetric`](crate::metrics::DefaultMetric). This trait is implemented for every model
+/// which predicts single targets and selects its default metric.
+pub trait Score<R: Records, T> {
+    type Output;
+
+    fn score(&self, dataset: &DatasetBase<R, T>) -> Result<Self::Output, crate::error::Error>;
+}
+
+/// Log-likelihood of samples under a density model
+///
+/// Probabilistic models define a density over the records, which can be used to rank samples by
+/// how likely they are under the model. Samples with a low log-likelihood are candidates for
+/// outliers. This trait is implemented for mixture models, naive Bayes classifiers and linear
+/// latent variable models.
+pub trait ScoreSamples<R: Records, F> {
+    /// Log-likelihood of every sample
+    fn score_samples(&self, x: &R) -> Array1<F>;
+
+    /// Mean log-likelihood of all samples
+    ///
+    /// This is not called `score`, as classifiers with a density also implement [`Score`], which
+    /// compares their predictions with the targets. Returns `NaN` if there are no samples.
+    fn mean_log_likelihood(&self, x: &R) -> F
+    where
+        F: Float,
+    {
+        let scores = self.score_samples(x);
+
+        scores.sum() / F::cast(scores.len())
+    }
+}